@@ -0,0 +1,117 @@
+use serde::Serialize;
+use type_safe_id::{StaticType, TypeSafeId};
+
+/// TypeID prefix of audit events
+#[derive(Default)]
+pub struct AuditEventType;
+
+impl StaticType for AuditEventType {
+    const TYPE: &'static str = "audit";
+}
+
+pub type AuditEventId = TypeSafeId<AuditEventType>;
+
+/// Actor recorded for changes made from the command line
+pub const CLI_ACTOR: &str = "cli";
+
+/// Change made to an API key or an owner budget, as recorded in the audit trail
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub created_at: i64,
+    /// Id of the admin key that made the change, `cli`, or `budget` for spend events
+    pub actor: String,
+    /// `key.created`, `key.updated`, `key.rotated`, `key.revoked`, `owner.budgets_updated`,
+    /// `budget.threshold` or `budget.exceeded`
+    pub action: String,
+    /// Key the event is about, empty for changes to owner budgets
+    pub key_id: String,
+    /// Changed fields and their new values, or the budget and spend of a spend event
+    pub details: serde_json::Value,
+}
+
+impl AuditEvent {
+    fn from_row(row: &turso::Row) -> Result<Self, turso::Error> {
+        let details: String = row.get(5)?;
+        Ok(Self {
+            id: row.get(0)?,
+            created_at: row.get(1)?,
+            actor: row.get(2)?,
+            action: row.get(3)?,
+            key_id: row.get(4)?,
+            details: serde_json::from_str(&details).unwrap_or_default(),
+        })
+    }
+}
+
+/// Append an event to the audit trail
+pub(super) async fn record(
+    conn: &turso::Connection,
+    actor: &str,
+    action: &str,
+    key_id: &str,
+    details: serde_json::Value,
+) -> Result<AuditEvent, turso::Error> {
+    let event = AuditEvent {
+        id: AuditEventId::new().to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        actor: actor.to_string(),
+        action: action.to_string(),
+        key_id: key_id.to_string(),
+        details,
+    };
+    conn.execute(
+        "INSERT INTO audit_log (id, created_at, actor, action, key_id, details) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        (
+            event.id.as_str(),
+            event.created_at,
+            event.actor.as_str(),
+            event.action.as_str(),
+            event.key_id.as_str(),
+            event.details.to_string(),
+        ),
+    )
+    .await?;
+    tracing::info!(
+        target: "audit",
+        actor = %event.actor,
+        action = %event.action,
+        key_id = %event.key_id,
+        "{}",
+        event.details
+    );
+    Ok(event)
+}
+
+/// Most recent events first, optionally for a single key
+pub(super) async fn list(
+    conn: &turso::Connection,
+    key_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<AuditEvent>, turso::Error> {
+    const COLUMNS: &str = "id, created_at, actor, action, key_id, details";
+    let mut rows = match key_id {
+        Some(key_id) => {
+            conn.query(
+                format!(
+                    "SELECT {} FROM audit_log WHERE key_id = ?1 ORDER BY rowid DESC LIMIT ?2",
+                    COLUMNS
+                ),
+                (key_id, limit as i64),
+            )
+            .await?
+        }
+        None => {
+            conn.query(
+                format!("SELECT {} FROM audit_log ORDER BY rowid DESC LIMIT ?1", COLUMNS),
+                [limit as i64],
+            )
+            .await?
+        }
+    };
+    let mut events = Vec::new();
+    while let Some(row) = rows.next().await? {
+        events.push(AuditEvent::from_row(&row)?);
+    }
+    Ok(events)
+}
@@ -0,0 +1,682 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::audit;
+use super::keys::{ApiKeyRecord, AuthError};
+use crate::config::AuthConfig;
+use crate::database::{Database, DatabaseError};
+use crate::http::response::ErrorCode;
+use crate::http::schemas::completions::{ProviderAttempt, UsageInfo};
+use crate::providers::{ChatCompletionStream, ModelCatalog};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+/// Actor recorded for the events emitted when spend reaches a threshold
+const BUDGET_ACTOR: &str = "budget";
+/// Days in a rolling window when none are given
+const DEFAULT_ROLLING_DAYS: u32 = 30;
+/// Longest rolling window
+const MAX_ROLLING_DAYS: u32 = 366;
+
+/// What a budget is expressed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetUnit {
+    /// US dollars, priced from the model catalog
+    #[default]
+    Usd,
+    /// Prompt and completion tokens
+    Tokens,
+}
+
+/// Window spend is counted over, in UTC days
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    /// The current day
+    Daily,
+    /// The current calendar month
+    #[default]
+    Monthly,
+    /// The current day and the `days - 1` before it
+    Rolling,
+}
+
+/// Spend an API key or an owner may reach within a period
+///
+/// Requests are refused once the spend of the period reaches the limit. Reaching
+/// a soft limit, a percentage of the limit, only emits a `budget.threshold` event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub limit: f64,
+    #[serde(default)]
+    pub unit: BudgetUnit,
+    #[serde(default)]
+    pub period: BudgetPeriod,
+    /// Length of a rolling window, ignored by the other periods
+    #[serde(default = "default_rolling_days")]
+    pub days: u32,
+    /// Percentages of the limit emitting an event when reached
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub soft_limits: Vec<u32>,
+}
+
+fn default_rolling_days() -> u32 {
+    DEFAULT_ROLLING_DAYS
+}
+
+impl Budget {
+    /// First day of the window containing `now` and the time the window next moves
+    fn window(&self, now: DateTime<Utc>) -> (i64, i64) {
+        let today = now.timestamp().div_euclid(SECONDS_PER_DAY);
+        let tomorrow = (today + 1) * SECONDS_PER_DAY;
+        match self.period {
+            BudgetPeriod::Daily => (today, tomorrow),
+            BudgetPeriod::Rolling => (today - i64::from(self.days.max(1)) + 1, tomorrow),
+            BudgetPeriod::Monthly => {
+                let first = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap_or_default();
+                let next = first.checked_add_months(Months::new(1)).unwrap_or(first);
+                let start = first.and_time(Default::default()).and_utc().timestamp();
+                let reset = next.and_time(Default::default()).and_utc().timestamp();
+                (start.div_euclid(SECONDS_PER_DAY), reset)
+            }
+        }
+    }
+
+    /// Part of a spend this budget counts
+    fn amount(&self, spend: &SpendTotal) -> f64 {
+        match self.unit {
+            BudgetUnit::Usd => spend.cost,
+            BudgetUnit::Tokens => spend.tokens as f64,
+        }
+    }
+
+    /// Key telling budgets apart in the events already emitted
+    fn label(&self) -> String {
+        let unit = match self.unit {
+            BudgetUnit::Usd => "usd",
+            BudgetUnit::Tokens => "tokens",
+        };
+        match self.period {
+            BudgetPeriod::Daily => format!("{}:{}:daily", self.limit, unit),
+            BudgetPeriod::Monthly => format!("{}:{}:monthly", self.limit, unit),
+            BudgetPeriod::Rolling => format!("{}:{}:rolling:{}", self.limit, unit, self.days),
+        }
+    }
+
+    fn validate(&self) -> Result<(), AuthError> {
+        let invalid = |message: &str| Err(AuthError::InvalidInput(message.to_string()));
+        if !self.limit.is_finite() || self.limit <= 0.0 {
+            return invalid("Budget limit must be a positive number");
+        }
+        if self.period == BudgetPeriod::Rolling && !(1..=MAX_ROLLING_DAYS).contains(&self.days) {
+            return invalid("Rolling budgets must span between 1 and 366 days");
+        }
+        if self.soft_limits.iter().any(|percent| !(1..100).contains(percent)) {
+            return invalid("Soft limits must be percentages between 1 and 99");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Amount(self.unit, self.limit))?;
+        match self.period {
+            BudgetPeriod::Daily => f.write_str(" per day"),
+            BudgetPeriod::Monthly => f.write_str(" per month"),
+            BudgetPeriod::Rolling => write!(f, " per {} days", self.days),
+        }
+    }
+}
+
+/// Amount of a unit, dollars rounded to the micro-dollar
+struct Amount(BudgetUnit, f64);
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            BudgetUnit::Usd => write!(f, "{} USD", (self.1 * 1e6).round() / 1e6),
+            BudgetUnit::Tokens => write!(f, "{} tokens", self.1 as u64),
+        }
+    }
+}
+
+/// Whose spend a budget caps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetScope {
+    /// A single API key
+    Key,
+    /// Every key of an owner, such as a product team
+    Owner,
+}
+
+/// Requests, tokens and cost summed over a window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SpendTotal {
+    pub requests: u64,
+    pub tokens: u64,
+    /// US dollars
+    pub cost: f64,
+}
+
+/// Spend of the current window of a budget
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    pub budget: Budget,
+    /// Spend counted by the budget, in its unit
+    pub spent: f64,
+    pub remaining: f64,
+    /// Unix timestamps in seconds
+    pub window_start: i64,
+    pub resets_at: i64,
+}
+
+/// Budgets of an API key or an owner along with their current spend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    pub owner: String,
+    pub budgets: Vec<BudgetStatus>,
+    /// Spend of the current calendar month, whether or not a budget covers it
+    pub month: SpendTotal,
+}
+
+/// Request refused because a budget of its key or owner is spent
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "Budget of {budget} exceeded for {} ({} spent). Please try again in {}s.",
+    self.subject(),
+    Amount(budget.unit, *spent),
+    self.retry_after.as_secs().max(1)
+)]
+pub struct QuotaExceeded {
+    pub scope: BudgetScope,
+    /// Name of the key or the owner
+    pub name: String,
+    pub budget: Budget,
+    pub spent: f64,
+    /// Time until the window of the budget moves on
+    pub retry_after: Duration,
+}
+
+impl QuotaExceeded {
+    fn subject(&self) -> String {
+        match self.scope {
+            BudgetScope::Key => format!("API key '{}'", self.name),
+            BudgetScope::Owner => format!("owner '{}'", self.name),
+        }
+    }
+}
+
+/// Errors produced while enforcing budgets
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BudgetError {
+    #[error(transparent)]
+    Exceeded(#[from] QuotaExceeded),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl From<turso::Error> for BudgetError {
+    fn from(err: turso::Error) -> Self {
+        BudgetError::Database(err.into())
+    }
+}
+
+impl BudgetError {
+    /// HTTP status code the error maps to
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            BudgetError::Exceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            BudgetError::Database(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Error code reported in the response envelope
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            BudgetError::Exceeded(_) => ErrorCode::QuotaError,
+            BudgetError::Database(_) => ErrorCode::ServiceError,
+        }
+    }
+
+    /// `Retry-After` header of a refused request
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let BudgetError::Exceeded(e) = self {
+            headers.insert(RETRY_AFTER, HeaderValue::from(e.retry_after.as_secs().max(1)));
+        }
+        headers
+    }
+}
+
+/// Owner budgets read from the database, see [`BudgetTracker`]
+struct CachedBudgets {
+    budgets: Vec<Budget>,
+    cached_at: Instant,
+}
+
+/// BudgetTracker records what API keys spend and enforces their budgets
+///
+/// Spend of the keys a budget applies to is kept in the database as daily totals
+/// per key and owner, priced from the model catalog with the usage providers report. A request is let through
+/// while the spend of its key and owner is under every budget, so requests in
+/// flight when a budget is reached may take it slightly over.
+///
+/// Owner budgets are cached in memory for the key cache TTL; changes made
+/// through the tracker clear the cache right away.
+#[derive(Clone)]
+pub struct BudgetTracker {
+    database: Database,
+    cache_ttl: Duration,
+    owners: Arc<RwLock<HashMap<String, CachedBudgets>>>,
+}
+
+impl BudgetTracker {
+    pub fn new(database: Database, config: &AuthConfig) -> Self {
+        Self {
+            database,
+            cache_ttl: Duration::from_secs(config.cache_ttl),
+            owners: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Refuse a request when a budget of the key or of its owner is spent
+    ///
+    /// Requests of keys without a budget, on their own or through their owner,
+    /// get a charge that records nothing.
+    pub async fn check(&self, key: &ApiKeyRecord) -> Result<BudgetCharge, BudgetError> {
+        let conn = self.database.connect().await?;
+        let owner_budgets = self.owner_budgets(&conn, &key.owner).await?;
+        if key.budgets.is_empty() && owner_budgets.is_empty() {
+            return Ok(BudgetCharge {
+                tracker: None,
+                key: key.clone(),
+                usd: false,
+            });
+        }
+        let usd = key
+            .budgets
+            .iter()
+            .chain(&owner_budgets)
+            .any(|budget| budget.unit == BudgetUnit::Usd);
+
+        let now = Utc::now();
+        let scopes = [
+            (BudgetScope::Key, key.name.as_str(), &key.budgets),
+            (BudgetScope::Owner, key.owner.as_str(), &owner_budgets),
+        ];
+        for (scope, name, budgets) in scopes {
+            for status in statuses(&conn, scope, key, budgets, now).await? {
+                if status.remaining <= 0.0 {
+                    return Err(QuotaExceeded {
+                        scope,
+                        name: name.to_string(),
+                        retry_after: Duration::from_secs((status.resets_at - now.timestamp()).max(0) as u64),
+                        budget: status.budget,
+                        spent: status.spent,
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(BudgetCharge {
+            tracker: Some(self.clone()),
+            key: key.clone(),
+            usd,
+        })
+    }
+
+    /// Budgets and spend of a key
+    pub async fn key_report(&self, key: &ApiKeyRecord) -> Result<BudgetReport, AuthError> {
+        let conn = self.database.connect().await?;
+        let now = Utc::now();
+        Ok(BudgetReport {
+            key_id: Some(key.id.clone()),
+            owner: key.owner.clone(),
+            budgets: statuses(&conn, BudgetScope::Key, key, &key.budgets, now).await?,
+            month: month_total(&conn, "key_id", &key.id, now).await?,
+        })
+    }
+
+    /// Budgets and spend of an owner, across all of its keys
+    pub async fn owner_report(&self, owner: &str) -> Result<BudgetReport, AuthError> {
+        let conn = self.database.connect().await?;
+        let budgets = self.owner_budgets(&conn, owner).await?;
+        self.report(&conn, owner, &budgets).await
+    }
+
+    /// Replace the budgets of an owner, an empty list lifting them
+    pub async fn set_owner_budgets(
+        &self,
+        actor: &str,
+        owner: &str,
+        budgets: Vec<Budget>,
+    ) -> Result<BudgetReport, AuthError> {
+        let owner = owner.trim();
+        if owner.is_empty() {
+            return Err(AuthError::InvalidInput("Owner is required".to_string()));
+        }
+        validate(&budgets)?;
+        let conn = self.database.connect().await?;
+        conn.execute(
+            "INSERT INTO owner_budgets (owner, budgets, updated_at) VALUES (?1, ?2, ?3) \
+             ON CONFLICT (owner) DO UPDATE SET budgets = excluded.budgets, updated_at = excluded.updated_at",
+            (owner, json!(budgets).to_string(), Utc::now().timestamp()),
+        )
+        .await?;
+        self.owners.write().unwrap_or_else(|e| e.into_inner()).clear();
+        audit::record(
+            &conn,
+            actor,
+            "owner.budgets_updated",
+            "",
+            json!({ "owner": owner, "budgets": budgets }),
+        )
+        .await?;
+        self.report(&conn, owner, &budgets).await
+    }
+
+    async fn report(
+        &self,
+        conn: &turso::Connection,
+        owner: &str,
+        budgets: &[Budget],
+    ) -> Result<BudgetReport, AuthError> {
+        let now = Utc::now();
+        Ok(BudgetReport {
+            key_id: None,
+            owner: owner.to_string(),
+            budgets: window_statuses(conn, "owner", owner, budgets, now).await?,
+            month: month_total(conn, "owner", owner, now).await?,
+        })
+    }
+
+    /// Add a priced request to the daily totals, then emit the events of the thresholds it reached
+    async fn record(&self, key: &ApiKeyRecord, tokens: u64, cost: f64) -> Result<(), BudgetError> {
+        let conn = self.database.connect().await?;
+        let now = Utc::now();
+        conn.execute(
+            "INSERT INTO spend (key_id, owner, day, requests, tokens, cost) VALUES (?1, ?2, ?3, 1, ?4, ?5) \
+             ON CONFLICT (key_id, owner, day) DO UPDATE SET requests = requests + 1, \
+             tokens = tokens + excluded.tokens, cost = cost + excluded.cost",
+            (
+                key.id.as_str(),
+                key.owner.as_str(),
+                now.timestamp().div_euclid(SECONDS_PER_DAY),
+                tokens as i64,
+                cost,
+            ),
+        )
+        .await?;
+
+        let owner_budgets = self.owner_budgets(&conn, &key.owner).await?;
+        for (scope, budgets) in [(BudgetScope::Key, &key.budgets), (BudgetScope::Owner, &owner_budgets)] {
+            for status in statuses(&conn, scope, key, budgets, now).await? {
+                let limits = status
+                    .budget
+                    .soft_limits
+                    .iter()
+                    .map(|percent| ("budget.threshold", *percent));
+                for (action, percent) in limits.chain([("budget.exceeded", 100)]) {
+                    if status.spent >= status.budget.limit * f64::from(percent) / 100.0 {
+                        emit(&conn, scope, key, &status, action, percent).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Budgets of an owner, none for keys without an owner
+    async fn owner_budgets(&self, conn: &turso::Connection, owner: &str) -> Result<Vec<Budget>, DatabaseError> {
+        if owner.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(cached) = self.owners.read().unwrap_or_else(|e| e.into_inner()).get(owner)
+            && cached.cached_at.elapsed() < self.cache_ttl
+        {
+            return Ok(cached.budgets.clone());
+        }
+
+        let mut rows = conn
+            .query("SELECT budgets FROM owner_budgets WHERE owner = ?1", [owner])
+            .await?;
+        let budgets = match rows.next().await? {
+            // Unreadable budgets must not lift the ceiling of the owner
+            Some(row) => serde_json::from_str(&row.get::<String>(0)?)
+                .map_err(|e| DatabaseError::Query(format!("Invalid budgets stored for owner '{}': {}", owner, e)))?,
+            None => Vec::new(),
+        };
+        if !self.cache_ttl.is_zero() {
+            self.owners.write().unwrap_or_else(|e| e.into_inner()).insert(
+                owner.to_string(),
+                CachedBudgets {
+                    budgets: budgets.clone(),
+                    cached_at: Instant::now(),
+                },
+            );
+        }
+        Ok(budgets)
+    }
+}
+
+/// Budget check passed by a request, to be charged with its usage
+///
+/// Requests that fail, or streams whose provider reports no usage, are not charged.
+pub struct BudgetCharge {
+    /// Tracker recording the spend, none when no budget applies to the key
+    tracker: Option<BudgetTracker>,
+    key: ApiKeyRecord,
+    /// Whether a USD budget applies to the request
+    usd: bool,
+}
+
+impl BudgetCharge {
+    /// Charge the usage a provider reported, priced for the provider and model that answered
+    pub async fn settle(self, catalog: &ModelCatalog, attempts: &[ProviderAttempt], usage: Option<&UsageInfo>) {
+        let (Some(tracker), Some(answered), Some(usage)) = (&self.tracker, attempts.last(), usage) else {
+            return;
+        };
+        let prompt = usage.prompt_tokens.max(0) as u64;
+        let completion = usage.completion_tokens.max(0) as u64;
+        let tokens = (usage.total_tokens.max(0) as u64).max(prompt + completion);
+        let cost = match catalog
+            .get(&answered.provider, &answered.model)
+            .and_then(|info| info.pricing)
+        {
+            Some(pricing) => (prompt as f64 * pricing.input + completion as f64 * pricing.output) / 1e6,
+            None if self.usd => {
+                tracing::warn!(
+                    "No pricing for model '{}/{}' under a USD budget of API key '{}', only its tokens are charged",
+                    answered.provider,
+                    answered.model,
+                    self.key.prefix
+                );
+                0.0
+            }
+            None => {
+                tracing::debug!(
+                    "No pricing for model '{}/{}', only its tokens are charged",
+                    answered.provider,
+                    answered.model
+                );
+                0.0
+            }
+        };
+        if let Err(e) = tracker.record(&self.key, tokens, cost).await {
+            tracing::warn!("Failed to record the spend of API key '{}': {}", self.key.prefix, e);
+        }
+    }
+
+    /// Charge the usage reported on the final chunk of a stream, in the background
+    pub fn settle_stream(
+        self,
+        catalog: &ModelCatalog,
+        attempts: &[ProviderAttempt],
+        stream: ChatCompletionStream,
+    ) -> ChatCompletionStream {
+        let catalog = catalog.clone();
+        let attempts = attempts.to_vec();
+        let mut charge = Some(self);
+        Box::pin(stream.inspect(move |item| {
+            if let Ok(chunk) = item
+                && let Some(usage) = chunk.usage.clone()
+                && let Some(charge) = charge.take()
+            {
+                let catalog = catalog.clone();
+                let attempts = attempts.clone();
+                tokio::spawn(async move { charge.settle(&catalog, &attempts, Some(&usage)).await });
+            }
+        }))
+    }
+}
+
+pub(super) fn validate(budgets: &[Budget]) -> Result<(), AuthError> {
+    budgets.iter().try_for_each(Budget::validate)
+}
+
+/// Spend of the current window of each budget of a key or of its owner
+async fn statuses(
+    conn: &turso::Connection,
+    scope: BudgetScope,
+    key: &ApiKeyRecord,
+    budgets: &[Budget],
+    now: DateTime<Utc>,
+) -> Result<Vec<BudgetStatus>, DatabaseError> {
+    match scope {
+        BudgetScope::Key => window_statuses(conn, "key_id", &key.id, budgets, now).await,
+        BudgetScope::Owner => window_statuses(conn, "owner", &key.owner, budgets, now).await,
+    }
+}
+
+/// Spend of the current window of each budget, for a key id or an owner
+async fn window_statuses(
+    conn: &turso::Connection,
+    column: &str,
+    value: &str,
+    budgets: &[Budget],
+    now: DateTime<Utc>,
+) -> Result<Vec<BudgetStatus>, DatabaseError> {
+    let mut statuses = Vec::with_capacity(budgets.len());
+    for budget in budgets {
+        let (start, resets_at) = budget.window(now);
+        let spent = budget.amount(&total(conn, column, value, start).await?);
+        statuses.push(BudgetStatus {
+            budget: budget.clone(),
+            spent,
+            remaining: (budget.limit - spent).max(0.0),
+            window_start: start * SECONDS_PER_DAY,
+            resets_at,
+        });
+    }
+    Ok(statuses)
+}
+
+async fn month_total(
+    conn: &turso::Connection,
+    column: &str,
+    value: &str,
+    now: DateTime<Utc>,
+) -> Result<SpendTotal, DatabaseError> {
+    let month = Budget {
+        limit: 0.0,
+        unit: BudgetUnit::Usd,
+        period: BudgetPeriod::Monthly,
+        days: DEFAULT_ROLLING_DAYS,
+        soft_limits: Vec::new(),
+    };
+    total(conn, column, value, month.window(now).0).await
+}
+
+/// Spend recorded since a day, for a key id or an owner
+async fn total(conn: &turso::Connection, column: &str, value: &str, since: i64) -> Result<SpendTotal, DatabaseError> {
+    let mut rows = conn
+        .query(
+            format!(
+                "SELECT COALESCE(SUM(requests), 0), COALESCE(SUM(tokens), 0), COALESCE(SUM(cost), 0.0) \
+                 FROM spend WHERE {} = ?1 AND day >= ?2",
+                column
+            ),
+            (value, since),
+        )
+        .await?;
+    match rows.next().await? {
+        Some(row) => Ok(SpendTotal {
+            requests: row.get::<i64>(0)?.max(0) as u64,
+            tokens: row.get::<i64>(1)?.max(0) as u64,
+            cost: row.get(2)?,
+        }),
+        None => Ok(SpendTotal::default()),
+    }
+}
+
+/// Record a threshold event, once per budget and window
+async fn emit(
+    conn: &turso::Connection,
+    scope: BudgetScope,
+    key: &ApiKeyRecord,
+    status: &BudgetStatus,
+    action: &str,
+    percent: u32,
+) -> Result<(), BudgetError> {
+    let subject = match scope {
+        BudgetScope::Key => format!("key:{}", key.id),
+        BudgetScope::Owner => format!("owner:{}", key.owner),
+    };
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO budget_events (subject, budget, percent, window_start, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            (
+                subject.as_str(),
+                status.budget.label(),
+                i64::from(percent),
+                status.window_start,
+                Utc::now().timestamp(),
+            ),
+        )
+        .await?;
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    tracing::warn!(
+        target: "budget",
+        scope = ?scope,
+        key_id = %key.id,
+        owner = %key.owner,
+        "Spend reached {}% of the budget of {} ({} spent)",
+        percent,
+        status.budget,
+        Amount(status.budget.unit, status.spent)
+    );
+    audit::record(
+        conn,
+        BUDGET_ACTOR,
+        action,
+        &key.id,
+        json!({
+            "scope": scope,
+            "owner": key.owner,
+            "budget": status.budget,
+            "percent": percent,
+            "spent": status.spent,
+            "window_start": status.window_start,
+        }),
+    )
+    .await?;
+    Ok(())
+}
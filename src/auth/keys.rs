@@ -0,0 +1,715 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+use ring::digest::{SHA256, digest};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+#[cfg(feature = "test-util")]
+use tokio::sync::OnceCell;
+use type_safe_id::{StaticType, TypeSafeId};
+
+use super::audit::{self, AuditEvent};
+use super::budgets::{self, Budget};
+use super::limits::RateLimits;
+use super::scopes::ApiKeyScopes;
+use crate::config::AuthConfig;
+use crate::database::{Database, DatabaseError};
+use crate::http::response::ErrorCode;
+
+/// Leading characters of a key stored in clear, at most half of the key
+const PREFIX_LEN: usize = 12;
+/// Random bytes in a generated key
+const KEY_BYTES: usize = 24;
+/// Random bytes in the salt of a stored key hash
+const SALT_BYTES: usize = 16;
+/// Seconds `last_used_at` may lag behind before it is written again
+const LAST_USED_RESOLUTION: i64 = 60;
+/// Cached lookups kept before expired entries are dropped
+const CACHE_CAPACITY: usize = 10_000;
+/// Actor recorded for keys added with [`ApiKeyStore::with_key`]
+#[cfg(feature = "test-util")]
+const IMPORT_ACTOR: &str = "import";
+
+const COLUMNS: &str = "id, name, owner, prefix, created_at, last_used_at, disabled, admin, revoked_at, previous_expires_at, scopes, \
+     rate_limits, budgets";
+
+/// TypeID prefix of API key ids
+#[derive(Default)]
+pub struct ApiKeyType;
+
+impl StaticType for ApiKeyType {
+    const TYPE: &'static str = "key";
+}
+
+/// Identifier of a stored API key, such as `key_01h2xcejqtf2nbrexx3vqjhp41`
+pub type ApiKeyId = TypeSafeId<ApiKeyType>;
+
+/// Errors produced while resolving or managing API keys
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AuthError {
+    #[error("Invalid API key")]
+    InvalidKey,
+    #[error("API key is disabled")]
+    Disabled,
+    #[error("API key has been revoked")]
+    Revoked,
+    #[error("API key '{0}' not found")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl From<turso::Error> for AuthError {
+    fn from(err: turso::Error) -> Self {
+        AuthError::Database(err.into())
+    }
+}
+
+impl AuthError {
+    /// HTTP status code the error maps to
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::InvalidKey | AuthError::Disabled | AuthError::Revoked => StatusCode::UNAUTHORIZED,
+            AuthError::NotFound(_) => StatusCode::NOT_FOUND,
+            AuthError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            AuthError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AuthError::Database(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Error code reported in the response envelope
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            AuthError::InvalidKey | AuthError::Disabled | AuthError::Revoked => ErrorCode::AuthenticationError,
+            AuthError::NotFound(_) | AuthError::InvalidInput(_) => ErrorCode::InvalidRequest,
+            AuthError::Forbidden(_) => ErrorCode::AuthorizationError,
+            AuthError::Database(_) => ErrorCode::ServiceError,
+        }
+    }
+}
+
+/// Stored API key, without its secret
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    /// Leading characters of the key, kept in clear to tell keys apart
+    pub prefix: String,
+    /// Unix timestamps in seconds
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+    pub disabled: bool,
+    /// Whether the key may manage other keys through the admin API
+    pub admin: bool,
+    pub revoked_at: Option<i64>,
+    /// End of the grace window in which the secret replaced by the last rotation still works
+    pub previous_expires_at: Option<i64>,
+    /// Providers, models and endpoint families the key may use
+    pub scopes: ApiKeyScopes,
+    /// Requests and tokens per minute the key may use
+    pub rate_limits: RateLimits,
+    /// Spend the key may reach, on top of the budgets of its owner
+    pub budgets: Vec<Budget>,
+}
+
+impl ApiKeyRecord {
+    fn from_row(row: &turso::Row) -> Result<Self, AuthError> {
+        let id: String = row.get(0)?;
+        // Unreadable scopes or limits must not leave the key unrestricted
+        let scopes = serde_json::from_str(&row.get::<String>(10)?)
+            .map_err(|e| DatabaseError::Query(format!("Invalid scopes stored for API key '{}': {}", id, e)))?;
+        let rate_limits = serde_json::from_str(&row.get::<String>(11)?)
+            .map_err(|e| DatabaseError::Query(format!("Invalid rate limits stored for API key '{}': {}", id, e)))?;
+        let budgets = serde_json::from_str(&row.get::<String>(12)?)
+            .map_err(|e| DatabaseError::Query(format!("Invalid budgets stored for API key '{}': {}", id, e)))?;
+        Ok(Self {
+            id,
+            name: row.get(1)?,
+            owner: row.get(2)?,
+            prefix: row.get(3)?,
+            created_at: row.get(4)?,
+            last_used_at: row.get(5)?,
+            disabled: row.get::<i64>(6)? != 0,
+            admin: row.get::<i64>(7)? != 0,
+            revoked_at: row.get(8)?,
+            previous_expires_at: row.get(9)?,
+            scopes,
+            rate_limits,
+            budgets,
+        })
+    }
+}
+
+/// Newly created or rotated API key, the only place its plaintext is available
+#[derive(Debug, Clone, Serialize)]
+pub struct NewApiKey {
+    #[serde(flatten)]
+    pub record: ApiKeyRecord,
+    pub key: String,
+}
+
+/// Parameters of a new API key
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateApiKey {
+    pub name: String,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub scopes: ApiKeyScopes,
+    #[serde(default)]
+    pub rate_limits: RateLimits,
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+}
+
+/// Changes to the metadata of an API key, absent fields are left untouched
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateApiKey {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<ApiKeyScopes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<RateLimits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budgets: Option<Vec<Budget>>,
+}
+
+/// Usage recorded for an API key
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiKeyUsage {
+    pub id: String,
+    /// Authenticated requests, written to the database along with `last_used_at`
+    pub request_count: u64,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+/// Plaintext key to store on first use, see [`ApiKeyStore::with_key`]
+#[cfg(feature = "test-util")]
+struct ImportedKey {
+    key: String,
+    name: String,
+    owner: String,
+}
+
+/// Key matched by a lookup, with the end of the grace window when an outgoing secret matched
+#[derive(Clone)]
+struct ResolvedKey {
+    record: ApiKeyRecord,
+    expires_at: Option<i64>,
+}
+
+struct CachedKey {
+    resolved: Option<ResolvedKey>,
+    cached_at: Instant,
+}
+
+/// ApiKeyStore resolves and manages the virtual API keys clients authenticate with
+///
+/// Keys are stored as a salted HMAC-SHA256 hash next to a short prefix kept in
+/// clear, so a key is looked up by its prefix and then verified against the hash.
+/// Lookups, including failed ones, are cached in memory for the configured TTL;
+/// changes made through the store clear the cache right away, changes made by
+/// another instance are picked up once the cached entries expire.
+///
+/// Rotating a key replaces its secret but keeps its id, and the outgoing secret
+/// keeps working until the grace window ends. Every change is recorded in the
+/// audit trail along with the actor that made it.
+#[derive(Clone)]
+pub struct ApiKeyStore {
+    database: Database,
+    cache_ttl: Duration,
+    rotation_grace: u64,
+    /// Cached lookups keyed by the SHA-256 digest of the plaintext key
+    cache: Arc<RwLock<HashMap<String, CachedKey>>>,
+    /// Requests per key id not yet written to the database
+    uses: Arc<Mutex<HashMap<String, u64>>>,
+    #[cfg(feature = "test-util")]
+    imports: Arc<Mutex<Vec<ImportedKey>>>,
+    #[cfg(feature = "test-util")]
+    imported: Arc<OnceCell<()>>,
+}
+
+impl ApiKeyStore {
+    pub fn new(database: Database, config: &AuthConfig) -> Self {
+        Self {
+            database,
+            cache_ttl: Duration::from_secs(config.cache_ttl),
+            rotation_grace: config.rotation_grace,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            uses: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "test-util")]
+            imports: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(feature = "test-util")]
+            imported: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Add a key whose plaintext is already known, stored on first use unless present
+    ///
+    /// Only built with the `test-util` feature, so tests can authenticate with a fixed key.
+    #[cfg(feature = "test-util")]
+    pub fn with_key(self, key: impl Into<String>, name: impl Into<String>, owner: impl Into<String>) -> Self {
+        self.imports
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ImportedKey {
+                key: key.into(),
+                name: name.into(),
+                owner: owner.into(),
+            });
+        self
+    }
+
+    pub fn database(&self) -> &Database {
+        &self.database
+    }
+
+    /// Resolve the key a client sent, failing for unknown, disabled and revoked keys
+    pub async fn authenticate(&self, key: &str) -> Result<ApiKeyRecord, AuthError> {
+        let cache_key = hex::encode(digest(&SHA256, key.as_bytes()));
+        let resolved = match self.cached(&cache_key) {
+            Some(resolved) => resolved,
+            None => {
+                let resolved = self.lookup(key).await?;
+                self.cache_put(cache_key.clone(), resolved.clone());
+                resolved
+            }
+        };
+
+        let ResolvedKey { mut record, expires_at } = resolved.ok_or(AuthError::InvalidKey)?;
+        let now = chrono::Utc::now().timestamp();
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AuthError::InvalidKey);
+        }
+        if record.revoked_at.is_some() {
+            return Err(AuthError::Revoked);
+        }
+        if record.disabled {
+            return Err(AuthError::Disabled);
+        }
+
+        // Uses are counted in memory and written along with `last_used_at`
+        let flush = {
+            let mut uses = self.uses.lock().unwrap_or_else(|e| e.into_inner());
+            *uses.entry(record.id.clone()).or_default() += 1;
+            record
+                .last_used_at
+                .is_none_or(|last_used| now - last_used >= LAST_USED_RESOLUTION)
+                .then(|| uses.remove(&record.id).unwrap_or_default())
+        };
+        if let Some(count) = flush {
+            record.last_used_at = Some(now);
+            self.cache_put(
+                cache_key,
+                Some(ResolvedKey {
+                    record: record.clone(),
+                    expires_at,
+                }),
+            );
+            if let Err(e) = self.touch(&record.id, now, count).await {
+                tracing::warn!("Failed to record the use of API key '{}': {}", record.prefix, e);
+                let mut uses = self.uses.lock().unwrap_or_else(|e| e.into_inner());
+                *uses.entry(record.id.clone()).or_default() += count;
+            }
+        }
+        Ok(record)
+    }
+
+    /// Create a key, returning its plaintext along with the stored record
+    pub async fn create(&self, actor: &str, params: &CreateApiKey) -> Result<NewApiKey, AuthError> {
+        let name = required_name(&params.name)?;
+        budgets::validate(&params.budgets)?;
+        let key = generate_key()?;
+        let conn = self.connect().await?;
+        let record = self.insert(&conn, &key, name, params).await?;
+        audit::record(&conn, actor, "key.created", &record.id, created_details(&record)).await?;
+        Ok(NewApiKey { record, key })
+    }
+
+    /// Look up a key by id
+    pub async fn get(&self, id: &str) -> Result<ApiKeyRecord, AuthError> {
+        let conn = self.connect().await?;
+        self.fetch(&conn, id).await
+    }
+
+    /// All keys, oldest first
+    pub async fn list(&self) -> Result<Vec<ApiKeyRecord>, AuthError> {
+        let conn = self.connect().await?;
+        let mut rows = conn
+            .query(format!("SELECT {} FROM api_keys ORDER BY created_at, id", COLUMNS), ())
+            .await?;
+        let mut records = Vec::new();
+        while let Some(row) = rows.next().await? {
+            records.push(ApiKeyRecord::from_row(&row)?);
+        }
+        Ok(records)
+    }
+
+    /// Change the metadata of a key
+    pub async fn update(&self, actor: &str, id: &str, changes: &UpdateApiKey) -> Result<ApiKeyRecord, AuthError> {
+        if let Some(name) = &changes.name {
+            required_name(name)?;
+        }
+        if let Some(budgets) = &changes.budgets {
+            budgets::validate(budgets)?;
+        }
+        let conn = self.connect().await?;
+        let changed = conn
+            .execute(
+                "UPDATE api_keys SET name = COALESCE(?1, name), owner = COALESCE(?2, owner), \
+                 admin = COALESCE(?3, admin), disabled = COALESCE(?4, disabled), scopes = COALESCE(?5, scopes), \
+                 rate_limits = COALESCE(?6, rate_limits), budgets = COALESCE(?7, budgets) WHERE id = ?8",
+                (
+                    changes.name.as_deref().map(str::trim),
+                    changes.owner.as_deref(),
+                    changes.admin,
+                    changes.disabled,
+                    changes.scopes.as_ref().map(|scopes| json!(scopes).to_string()),
+                    changes.rate_limits.as_ref().map(|limits| json!(limits).to_string()),
+                    changes.budgets.as_ref().map(|budgets| json!(budgets).to_string()),
+                    id,
+                ),
+            )
+            .await?;
+        if changed == 0 {
+            return Err(AuthError::NotFound(id.to_string()));
+        }
+        self.clear_cache();
+        audit::record(&conn, actor, "key.updated", id, json!(changes)).await?;
+        self.fetch(&conn, id).await
+    }
+
+    /// Disable or re-enable a key
+    pub async fn set_disabled(&self, actor: &str, id: &str, disabled: bool) -> Result<ApiKeyRecord, AuthError> {
+        let changes = UpdateApiKey {
+            disabled: Some(disabled),
+            ..Default::default()
+        };
+        self.update(actor, id, &changes).await
+    }
+
+    /// Revoke a key for good, revoking it again is a no-op
+    pub async fn revoke(&self, actor: &str, id: &str) -> Result<ApiKeyRecord, AuthError> {
+        let conn = self.connect().await?;
+        let record = self.fetch(&conn, id).await?;
+        if record.revoked_at.is_some() {
+            return Ok(record);
+        }
+        conn.execute(
+            "UPDATE api_keys SET revoked_at = ?1 WHERE id = ?2",
+            (chrono::Utc::now().timestamp(), id),
+        )
+        .await?;
+        self.clear_cache();
+        audit::record(&conn, actor, "key.revoked", id, json!({})).await?;
+        self.fetch(&conn, id).await
+    }
+
+    /// Replace the secret of a key, the outgoing one working for `grace` seconds more
+    ///
+    /// Without a grace period the store default is used. Rotating again within the
+    /// grace window ends it for the secret rotated out first.
+    pub async fn rotate(&self, actor: &str, id: &str, grace: Option<u64>) -> Result<NewApiKey, AuthError> {
+        let conn = self.connect().await?;
+        let record = self.fetch(&conn, id).await?;
+        if record.revoked_at.is_some() {
+            return Err(AuthError::InvalidInput(format!("API key '{}' has been revoked", id)));
+        }
+
+        let grace = grace.unwrap_or(self.rotation_grace);
+        let expires_at = chrono::Utc::now().timestamp().saturating_add_unsigned(grace);
+        let key = generate_key()?;
+        let salt = random_bytes::<SALT_BYTES>()?;
+        conn.execute(
+            "UPDATE api_keys SET previous_prefix = prefix, previous_hash = key_hash, previous_salt = salt, \
+             previous_expires_at = ?1, prefix = ?2, key_hash = ?3, salt = ?4 WHERE id = ?5",
+            (
+                expires_at,
+                prefix_of(&key),
+                hash_key(&key, &salt),
+                hex::encode(salt),
+                id,
+            ),
+        )
+        .await?;
+        self.clear_cache();
+        audit::record(
+            &conn,
+            actor,
+            "key.rotated",
+            id,
+            json!({ "grace_seconds": grace, "previous_expires_at": expires_at }),
+        )
+        .await?;
+
+        let record = self.fetch(&conn, id).await?;
+        Ok(NewApiKey { record, key })
+    }
+
+    /// Requests and last use of a key, including uses not yet written by this instance
+    pub async fn usage(&self, id: &str) -> Result<ApiKeyUsage, AuthError> {
+        let conn = self.connect().await?;
+        let record = self.fetch(&conn, id).await?;
+        let mut rows = conn
+            .query("SELECT request_count FROM api_keys WHERE id = ?1", [id])
+            .await?;
+        let stored = match rows.next().await? {
+            Some(row) => row.get::<i64>(0)?.max(0) as u64,
+            None => 0,
+        };
+        let pending = self
+            .uses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+            .copied()
+            .unwrap_or_default();
+        Ok(ApiKeyUsage {
+            id: record.id,
+            request_count: stored + pending,
+            created_at: record.created_at,
+            last_used_at: record.last_used_at,
+        })
+    }
+
+    /// Audit trail, most recent first, optionally for a single key
+    pub async fn audit(&self, key_id: Option<&str>, limit: u32) -> Result<Vec<AuditEvent>, AuthError> {
+        let conn = self.connect().await?;
+        Ok(audit::list(&conn, key_id, limit).await?)
+    }
+
+    /// Drop every cached lookup
+    pub fn clear_cache(&self) {
+        self.cache.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    async fn connect(&self) -> Result<turso::Connection, AuthError> {
+        let conn = self.database.connect().await?;
+        #[cfg(feature = "test-util")]
+        self.store_imports(&conn).await?;
+        Ok(conn)
+    }
+
+    /// Store the keys added with [`ApiKeyStore::with_key`] on first use
+    #[cfg(feature = "test-util")]
+    async fn store_imports(&self, conn: &turso::Connection) -> Result<(), AuthError> {
+        self.imported
+            .get_or_try_init(|| async {
+                let imports = std::mem::take(&mut *self.imports.lock().unwrap_or_else(|e| e.into_inner()));
+                for import in imports {
+                    if self.find(conn, &import.key).await?.is_none() {
+                        let params = CreateApiKey {
+                            owner: import.owner,
+                            ..Default::default()
+                        };
+                        let record = self.insert(conn, &import.key, &import.name, &params).await?;
+                        audit::record(conn, IMPORT_ACTOR, "key.created", &record.id, created_details(&record)).await?;
+                    }
+                }
+                Ok::<_, AuthError>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn fetch(&self, conn: &turso::Connection, id: &str) -> Result<ApiKeyRecord, AuthError> {
+        let mut rows = conn
+            .query(format!("SELECT {} FROM api_keys WHERE id = ?1", COLUMNS), [id])
+            .await?;
+        match rows.next().await? {
+            Some(row) => Ok(ApiKeyRecord::from_row(&row)?),
+            None => Err(AuthError::NotFound(id.to_string())),
+        }
+    }
+
+    async fn lookup(&self, key: &str) -> Result<Option<ResolvedKey>, AuthError> {
+        let conn = self.connect().await?;
+        self.find(&conn, key).await
+    }
+
+    async fn find(&self, conn: &turso::Connection, key: &str) -> Result<Option<ResolvedKey>, AuthError> {
+        let prefix = prefix_of(key);
+        let mut rows = conn
+            .query(
+                format!(
+                    "SELECT {}, key_hash, salt, previous_prefix, previous_hash, previous_salt \
+                     FROM api_keys WHERE prefix = ?1 OR previous_prefix = ?1",
+                    COLUMNS
+                ),
+                [prefix],
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let record = ApiKeyRecord::from_row(&row)?;
+            if record.prefix == prefix && verify_key(key, &row.get::<String>(14)?, &row.get::<String>(13)?) {
+                return Ok(Some(ResolvedKey {
+                    record,
+                    expires_at: None,
+                }));
+            }
+            if let (Some(previous_prefix), Some(hash), Some(salt)) = (
+                row.get::<Option<String>>(15)?,
+                row.get::<Option<String>>(16)?,
+                row.get::<Option<String>>(17)?,
+            ) && previous_prefix == prefix
+                && verify_key(key, &salt, &hash)
+            {
+                return Ok(Some(ResolvedKey {
+                    expires_at: record.previous_expires_at,
+                    record,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn insert(
+        &self,
+        conn: &turso::Connection,
+        key: &str,
+        name: &str,
+        params: &CreateApiKey,
+    ) -> Result<ApiKeyRecord, AuthError> {
+        let salt = random_bytes::<SALT_BYTES>()?;
+        let record = ApiKeyRecord {
+            id: ApiKeyId::new().to_string(),
+            name: name.to_string(),
+            owner: params.owner.clone(),
+            prefix: prefix_of(key).to_string(),
+            created_at: chrono::Utc::now().timestamp(),
+            last_used_at: None,
+            disabled: false,
+            admin: params.admin,
+            revoked_at: None,
+            previous_expires_at: None,
+            scopes: params.scopes.clone(),
+            rate_limits: params.rate_limits.clone(),
+            budgets: params.budgets.clone(),
+        };
+        conn.execute(
+            "INSERT INTO api_keys (id, name, owner, prefix, key_hash, salt, created_at, admin, scopes, \
+             rate_limits, budgets) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            (
+                record.id.as_str(),
+                record.name.as_str(),
+                record.owner.as_str(),
+                record.prefix.as_str(),
+                hash_key(key, &salt),
+                hex::encode(salt),
+                record.created_at,
+                record.admin,
+                json!(record.scopes).to_string(),
+                json!(record.rate_limits).to_string(),
+                json!(record.budgets).to_string(),
+            ),
+        )
+        .await?;
+        self.clear_cache();
+        Ok(record)
+    }
+
+    async fn touch(&self, id: &str, now: i64, uses: u64) -> Result<(), AuthError> {
+        let conn = self.connect().await?;
+        conn.execute(
+            "UPDATE api_keys SET last_used_at = ?1, request_count = request_count + ?2 WHERE id = ?3",
+            (now, uses as i64, id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn cached(&self, cache_key: &str) -> Option<Option<ResolvedKey>> {
+        let cache = self.cache.read().unwrap_or_else(|e| e.into_inner());
+        let entry = cache.get(cache_key)?;
+        (entry.cached_at.elapsed() < self.cache_ttl).then(|| entry.resolved.clone())
+    }
+
+    fn cache_put(&self, cache_key: String, resolved: Option<ResolvedKey>) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= CACHE_CAPACITY {
+            cache.retain(|_, entry| entry.cached_at.elapsed() < self.cache_ttl);
+            if cache.len() >= CACHE_CAPACITY {
+                cache.clear();
+            }
+        }
+        cache.insert(
+            cache_key,
+            CachedKey {
+                resolved,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+}
+
+fn created_details(record: &ApiKeyRecord) -> serde_json::Value {
+    json!({
+        "name": record.name,
+        "owner": record.owner,
+        "admin": record.admin,
+        "scopes": record.scopes,
+        "rate_limits": record.rate_limits,
+        "budgets": record.budgets,
+    })
+}
+
+fn required_name(name: &str) -> Result<&str, AuthError> {
+    match name.trim() {
+        "" => Err(AuthError::InvalidInput("API key name is required".to_string())),
+        name => Ok(name),
+    }
+}
+
+/// Generate a new key: `sk-` followed by 48 random hex characters
+fn generate_key() -> Result<String, AuthError> {
+    Ok(format!("sk-{}", hex::encode(random_bytes::<KEY_BYTES>()?)))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], AuthError> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| DatabaseError::Query("Failed to generate random bytes".to_string()))?;
+    Ok(bytes)
+}
+
+/// Part of a key stored in clear: up to [`PREFIX_LEN`] characters, never more than half of it
+fn prefix_of(key: &str) -> &str {
+    let len = (key.chars().count() / 2).min(PREFIX_LEN);
+    let end = key.char_indices().nth(len).map_or(key.len(), |(index, _)| index);
+    &key[..end]
+}
+
+fn hash_key(key: &str, salt: &[u8]) -> String {
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, salt), key.as_bytes());
+    hex::encode(tag.as_ref())
+}
+
+fn verify_key(key: &str, salt: &str, hash: &str) -> bool {
+    match (hex::decode(salt), hex::decode(hash)) {
+        (Ok(salt), Ok(hash)) => hmac::verify(&hmac::Key::new(hmac::HMAC_SHA256, &salt), key.as_bytes(), &hash).is_ok(),
+        _ => false,
+    }
+}
@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use super::keys::ApiKeyRecord;
+use super::scopes::model_matches;
+use crate::config::AuthConfig;
+use crate::http::schemas::completions::{ContentPart, RequestMessage, UsageInfo};
+use crate::providers::{ChatCompletionStream, ProviderError};
+
+/// Buckets kept before full ones are dropped
+const BUCKET_CAPACITY: usize = 10_000;
+
+/// Requests and tokens per minute an API key may use
+///
+/// Unset limits fall back to `SORAI_RATE_LIMIT_RPM` and `SORAI_RATE_LIMIT_TPM`,
+/// and a limit of `0` means no limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tpm: Option<u64>,
+    /// Limits of the models matching a pattern, applied on top of the key limits;
+    /// the first matching entry applies
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelRateLimit>,
+}
+
+/// Requests and tokens per minute a key may send to the models matching a pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelRateLimit {
+    /// Model pattern, matched the same way as model scopes
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tpm: Option<u64>,
+}
+
+/// What a limit counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Requests,
+    Tokens,
+}
+
+impl LimitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitKind::Requests => "requests",
+            LimitKind::Tokens => "tokens",
+        }
+    }
+}
+
+impl fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// State of the tightest limit of a kind, as reported in `x-ratelimit-*` headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitStatus {
+    pub kind: LimitKind,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Time until the limit is fully replenished
+    pub reset: Duration,
+}
+
+/// Request refused because it would exceed a rate limit
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Rate limit of {limit} {kind} per minute exceeded. Please try again in {}s.", retry_after_secs(*.retry_after))]
+pub struct RateLimitExceeded {
+    /// What the limit that refused the request counts
+    pub kind: LimitKind,
+    pub limit: u64,
+    /// Time until the request would be accepted
+    pub retry_after: Duration,
+    pub status: Vec<RateLimitStatus>,
+}
+
+impl RateLimitExceeded {
+    /// `Retry-After` and `x-ratelimit-*` headers of the 429 response
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from(retry_after_secs(self.retry_after)));
+        for status in &self.status {
+            let kind = status.kind.as_str();
+            let values = [
+                ("limit", status.limit.to_string()),
+                ("remaining", status.remaining.to_string()),
+                ("reset", format!("{}s", retry_after_secs(status.reset))),
+            ];
+            for (name, value) in values {
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::try_from(format!("x-ratelimit-{}-{}", name, kind)),
+                    HeaderValue::try_from(value),
+                ) {
+                    headers.insert(name, value);
+                }
+            }
+        }
+        headers
+    }
+}
+
+/// Whole seconds to wait, never less than one
+fn retry_after_secs(duration: Duration) -> u64 {
+    (duration.as_secs_f64().ceil() as u64).max(1)
+}
+
+/// Token bucket holding up to a minute worth of a limit, refilled continuously
+#[derive(Debug)]
+struct Bucket {
+    limit: u64,
+    /// May go negative when a request used more tokens than it reserved
+    available: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: u64, now: Instant) -> Self {
+        Self {
+            limit,
+            available: limit as f64,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, limit: u64, now: Instant) {
+        // A changed limit applies right away, without handing out a full new bucket
+        if self.limit != limit {
+            self.available = self.available.min(limit as f64);
+            self.limit = limit;
+        }
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.per_second()).min(limit as f64);
+        self.updated = now;
+    }
+
+    fn per_second(&self) -> f64 {
+        self.limit as f64 / 60.0
+    }
+
+    /// Time until `amount` is available
+    fn wait(&self, amount: f64) -> Duration {
+        if self.available >= amount {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((amount - self.available) / self.per_second())
+    }
+
+    fn status(&self, kind: LimitKind) -> RateLimitStatus {
+        RateLimitStatus {
+            kind,
+            limit: self.limit,
+            remaining: self.available.max(0.0) as u64,
+            reset: self.wait(self.limit as f64),
+        }
+    }
+}
+
+/// Limit applying to a request
+struct Limit {
+    bucket: String,
+    kind: LimitKind,
+    limit: u64,
+}
+
+/// Per-key and per-model request and token rate limits, kept in memory
+///
+/// Token limits are charged an estimate when a request starts, which is replaced
+/// by the real usage once the provider reports it through the
+/// [`RateLimitReservation`].
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    default_rpm: u64,
+    default_tpm: u64,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(&AuthConfig::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: &AuthConfig) -> Self {
+        Self {
+            default_rpm: config.rate_limit_rpm,
+            default_tpm: config.rate_limit_tpm,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count a request of a key, reserving an estimate of the tokens it uses
+    ///
+    /// Only the key limits and the model limits of the primary provider/model are
+    /// checked here; those of a fallback are checked by
+    /// [`RateLimitReservation::admit`] when the fallback is tried.
+    pub fn acquire(
+        &self,
+        key: &ApiKeyRecord,
+        provider: &str,
+        model: &str,
+        tokens: u64,
+    ) -> Result<RateLimitReservation, RateLimitExceeded> {
+        let mut limits = self.key_limits(key);
+        limits.extend(model_limits(&key.id, &key.rate_limits.models, provider, model));
+        if limits.is_empty() && key.rate_limits.models.is_empty() {
+            return Ok(RateLimitReservation::unlimited());
+        }
+
+        let reserved = self.take(&limits, tokens)?;
+        Ok(RateLimitReservation {
+            limiter: Some(self.clone()),
+            key: key.id.clone(),
+            models: key.rate_limits.models.clone(),
+            tokens,
+            reserved: Mutex::new(reserved),
+        })
+    }
+
+    /// Take a request and `tokens` from the buckets of `limits`, or nothing when
+    /// one of them is short
+    fn take(&self, limits: &[Limit], tokens: u64) -> Result<Vec<Reserved>, RateLimitExceeded> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= BUCKET_CAPACITY {
+            buckets.retain(|_, bucket| {
+                bucket.refill(bucket.limit, now);
+                bucket.available < bucket.limit as f64
+            });
+        }
+
+        // A request asking for more tokens than a limit allows drains the bucket rather than never passing
+        let amount = |limit: &Limit| match limit.kind {
+            LimitKind::Requests => 1.0,
+            LimitKind::Tokens => tokens.min(limit.limit) as f64,
+        };
+        let mut refused: Option<(&Limit, Duration)> = None;
+        for limit in limits {
+            let bucket = buckets
+                .entry(limit.bucket.clone())
+                .or_insert_with(|| Bucket::new(limit.limit, now));
+            bucket.refill(limit.limit, now);
+            let wait = bucket.wait(amount(limit));
+            if wait > refused.map_or(Duration::ZERO, |(_, wait)| wait) {
+                refused = Some((limit, wait));
+            }
+        }
+
+        if let Some((limit, retry_after)) = refused {
+            let status = [LimitKind::Requests, LimitKind::Tokens]
+                .into_iter()
+                .filter_map(|kind| {
+                    limits
+                        .iter()
+                        .filter(|limit| limit.kind == kind)
+                        .map(|limit| buckets[&limit.bucket].status(kind))
+                        .min_by_key(|status| status.remaining)
+                })
+                .collect();
+            return Err(RateLimitExceeded {
+                kind: limit.kind,
+                limit: limit.limit,
+                retry_after,
+                status,
+            });
+        }
+
+        let mut reserved = Vec::new();
+        for limit in limits {
+            let amount = amount(limit);
+            if let Some(bucket) = buckets.get_mut(&limit.bucket) {
+                bucket.available -= amount;
+            }
+            reserved.push(Reserved {
+                bucket: limit.bucket.clone(),
+                kind: limit.kind,
+                amount,
+            });
+        }
+        Ok(reserved)
+    }
+
+    /// Limits of the key itself, from the key or the defaults
+    fn key_limits(&self, key: &ApiKeyRecord) -> Vec<Limit> {
+        let rate_limits = &key.rate_limits;
+        [
+            (LimitKind::Requests, rate_limits.rpm.unwrap_or(self.default_rpm)),
+            (LimitKind::Tokens, rate_limits.tpm.unwrap_or(self.default_tpm)),
+        ]
+        .into_iter()
+        .filter(|(_, limit)| *limit > 0)
+        .map(|(kind, limit)| Limit {
+            bucket: format!("{}:{}", key.id, kind),
+            kind,
+            limit,
+        })
+        .collect()
+    }
+}
+
+/// Limits of the first model entry of a key matching a provider/model target
+///
+/// Targets matching the same entry share its buckets.
+fn model_limits(key: &str, models: &[ModelRateLimit], provider: &str, model: &str) -> Vec<Limit> {
+    let Some(model_limit) = models.iter().find(|limit| model_matches(&limit.model, provider, model)) else {
+        return Vec::new();
+    };
+    [
+        (LimitKind::Requests, model_limit.rpm),
+        (LimitKind::Tokens, model_limit.tpm),
+    ]
+    .into_iter()
+    .filter_map(|(kind, limit)| limit.filter(|limit| *limit > 0).map(|limit| (kind, limit)))
+    .map(|(kind, limit)| Limit {
+        bucket: format!("{}:{}:{}", key, model_limit.model, kind),
+        kind,
+        limit,
+    })
+    .collect()
+}
+
+/// Amount taken from a bucket for a request
+#[derive(Debug)]
+struct Reserved {
+    bucket: String,
+    kind: LimitKind,
+    amount: f64,
+}
+
+/// Tokens reserved for a request, to be corrected with its real usage
+///
+/// Dropping the reservation without settling it keeps the estimate charged.
+#[derive(Debug)]
+pub struct RateLimitReservation {
+    limiter: Option<RateLimiter>,
+    /// Id of the key, and its model limits
+    key: String,
+    models: Vec<ModelRateLimit>,
+    /// Token estimate of the request, also reserved for the fallbacks it is sent to
+    tokens: u64,
+    /// Buckets the request was charged to, with the amount taken from each
+    reserved: Mutex<Vec<Reserved>>,
+}
+
+impl RateLimitReservation {
+    fn unlimited() -> Self {
+        Self {
+            limiter: None,
+            key: String::new(),
+            models: Vec::new(),
+            tokens: 0,
+            reserved: Mutex::default(),
+        }
+    }
+
+    /// Check and reserve the model limits of a provider/model target right before
+    /// the request is sent to it
+    ///
+    /// Targets sharing a model entry already reserved, such as the primary target,
+    /// pass as is. A fallback over its limit is refused with
+    /// [`ProviderError::RateLimited`], so the next one is tried.
+    pub fn admit(&self, provider: &str, model: &str) -> Result<(), ProviderError> {
+        let Some(limiter) = &self.limiter else {
+            return Ok(());
+        };
+        let mut reserved = self.reserved.lock().unwrap_or_else(|e| e.into_inner());
+        let limits: Vec<Limit> = model_limits(&self.key, &self.models, provider, model)
+            .into_iter()
+            .filter(|limit| !reserved.iter().any(|reserved| reserved.bucket == limit.bucket))
+            .collect();
+        if limits.is_empty() {
+            return Ok(());
+        }
+
+        match limiter.take(&limits, self.tokens) {
+            Ok(taken) => {
+                reserved.extend(taken);
+                Ok(())
+            }
+            Err(err) => {
+                tracing::debug!(key = %self.key, provider, model, "Skipping rate limited target: {}", err);
+                Err(ProviderError::RateLimited {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Replace the estimate by the tokens the request used
+    pub fn settle(self, used: u64) {
+        self.correct(used);
+    }
+
+    /// Settle with the usage a provider reported, keeping the estimate when it reported none
+    pub fn settle_usage(self, usage: Option<&UsageInfo>) {
+        if let Some(usage) = usage {
+            self.correct(usage.total_tokens.max(0) as u64);
+        }
+    }
+
+    /// Settle with the usage reported on the final chunk of a stream
+    pub fn settle_stream(self, stream: ChatCompletionStream) -> ChatCompletionStream {
+        let mut reservation = Some(self);
+        Box::pin(stream.inspect(move |item| {
+            if let Ok(chunk) = item
+                && let Some(usage) = &chunk.usage
+                && let Some(reservation) = reservation.take()
+            {
+                reservation.correct(usage.total_tokens.max(0) as u64);
+            }
+        }))
+    }
+
+    /// Replace the token estimate by the tokens used
+    fn correct(self, used: u64) {
+        let Some(limiter) = self.limiter else {
+            return;
+        };
+        let reserved = self.reserved.into_inner().unwrap_or_else(|e| e.into_inner());
+        let mut buckets = limiter.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        for reserved in reserved {
+            if reserved.kind == LimitKind::Tokens
+                && let Some(bucket) = buckets.get_mut(&reserved.bucket)
+            {
+                bucket.available = (bucket.available + reserved.amount - used as f64).min(bucket.limit as f64);
+            }
+        }
+    }
+}
+
+/// Rough token count of a request: a token per four bytes of text, plus the output tokens it asks for
+pub fn estimate_tokens(text_len: usize, max_tokens: Option<u32>) -> u64 {
+    text_len.div_ceil(4) as u64 + max_tokens.unwrap_or_default() as u64
+}
+
+/// Bytes of text in a chat message and its tool calls, leaving out images and documents
+pub fn text_len(message: &RequestMessage) -> usize {
+    let text: usize = message
+        .parts()
+        .iter()
+        .map(|part| match part {
+            ContentPart::Text { text } => text.len(),
+            ContentPart::ImageUrl { .. } | ContentPart::File { .. } => 0,
+        })
+        .sum();
+    let calls: usize = message
+        .tool_calls()
+        .iter()
+        .map(|call| call.function.name.len() + call.function.arguments.len())
+        .sum();
+    text + calls
+}
@@ -0,0 +1,19 @@
+//! Virtual API keys clients authenticate with
+
+mod audit;
+mod budgets;
+mod keys;
+mod limits;
+mod scopes;
+
+pub use audit::{AuditEvent, AuditEventId, CLI_ACTOR};
+pub use budgets::{
+    Budget, BudgetCharge, BudgetError, BudgetPeriod, BudgetReport, BudgetScope, BudgetStatus, BudgetTracker, BudgetUnit,
+    QuotaExceeded, SpendTotal,
+};
+pub use keys::{ApiKeyId, ApiKeyRecord, ApiKeyStore, ApiKeyUsage, AuthError, CreateApiKey, NewApiKey, UpdateApiKey};
+pub use limits::{
+    LimitKind, ModelRateLimit, RateLimitExceeded, RateLimitReservation, RateLimitStatus, RateLimiter, RateLimits,
+    estimate_tokens, text_len,
+};
+pub use scopes::{ApiKeyScopes, Endpoint, ScopeList};
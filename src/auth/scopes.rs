@@ -0,0 +1,158 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::AuthError;
+
+/// Families of endpoints a key can be scoped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endpoint {
+    /// Chat completions, including the OpenAI and Anthropic compatible routes
+    Chat,
+    /// Text completions
+    Text,
+    Embeddings,
+    /// Model lists, including the OpenAI compatible route
+    Models,
+    /// API key management, only available to admin keys
+    Admin,
+}
+
+impl Endpoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endpoint::Chat => "chat",
+            Endpoint::Text => "text",
+            Endpoint::Embeddings => "embeddings",
+            Endpoint::Models => "models",
+            Endpoint::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Allow-list and deny-list of a scope
+///
+/// An empty allow-list allows everything; a value on the deny-list is refused even
+/// when it is also allowed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScopeList<T> {
+    pub allow: Vec<T>,
+    pub deny: Vec<T>,
+}
+
+impl<T> Default for ScopeList<T> {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+impl<T> ScopeList<T> {
+    fn permits_by(&self, matches: impl Fn(&T) -> bool) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(&matches)) && !self.deny.iter().any(matches)
+    }
+}
+
+/// Providers, models and endpoint families an API key may use
+///
+/// Provider entries are provider or pool names. Model entries are patterns where
+/// `*` matches any run of characters; a pattern containing `/` is matched against
+/// `provider/model`, any other pattern against the model alone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyScopes {
+    #[serde(default)]
+    pub providers: ScopeList<String>,
+    #[serde(default)]
+    pub models: ScopeList<String>,
+    #[serde(default)]
+    pub endpoints: ScopeList<Endpoint>,
+}
+
+impl ApiKeyScopes {
+    /// Whether the endpoint family is in scope
+    pub fn permits_endpoint(&self, endpoint: Endpoint) -> bool {
+        self.endpoints.permits_by(|allowed| *allowed == endpoint)
+    }
+
+    /// Whether the provider is in scope
+    pub fn permits_provider(&self, provider: &str) -> bool {
+        self.providers.permits_by(|allowed| allowed == provider)
+    }
+
+    /// Whether both the provider and the model are in scope
+    pub fn permits_model(&self, provider: &str, model: &str) -> bool {
+        self.permits_provider(provider)
+            && self
+                .models
+                .permits_by(|pattern| model_matches(pattern, provider, model))
+    }
+
+    /// Check a request to an endpoint family that may reach any of the given targets
+    pub fn check<'a>(
+        &self,
+        endpoint: Endpoint,
+        targets: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<(), AuthError> {
+        if !self.permits_endpoint(endpoint) {
+            return Err(AuthError::Forbidden(format!(
+                "API key is not allowed to use the '{}' endpoints",
+                endpoint
+            )));
+        }
+        for (provider, model) in targets {
+            if !self.permits_provider(provider) {
+                return Err(AuthError::Forbidden(format!(
+                    "API key is not allowed to use provider '{}'",
+                    provider
+                )));
+            }
+            if !self.permits_model(provider, model) {
+                return Err(AuthError::Forbidden(format!(
+                    "API key is not allowed to use model '{}/{}'",
+                    provider, model
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn model_matches(pattern: &str, provider: &str, model: &str) -> bool {
+    if pattern.contains('/') {
+        wildcard_match(pattern, &format!("{}/{}", provider, model))
+    } else {
+        wildcard_match(pattern, model)
+    }
+}
+
+/// Match a pattern where `*` stands for any run of characters, including none
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard, the pattern must match exactly
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
@@ -0,0 +1,69 @@
+use crate::config::ConfigItem;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Seconds a resolved API key is kept in memory before it is read from the database again
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: u64,
+    /// Seconds the outgoing secret of a rotated key keeps working, unless the rotation sets its own
+    #[serde(default = "default_rotation_grace")]
+    pub rotation_grace: u64,
+    /// Requests per minute of keys without their own limit, 0 for no limit
+    #[serde(default)]
+    pub rate_limit_rpm: u64,
+    /// Tokens per minute of keys without their own limit, 0 for no limit
+    #[serde(default)]
+    pub rate_limit_tpm: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl: default_cache_ttl(),
+            rotation_grace: default_rotation_grace(),
+            rate_limit_rpm: 0,
+            rate_limit_tpm: 0,
+        }
+    }
+}
+
+impl AuthConfig {
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        items.push(ConfigItem {
+            section: "Auth".to_string(),
+            key: "API Key Cache TTL".to_string(),
+            value: format!("{}s", self.cache_ttl),
+        });
+        items.push(ConfigItem {
+            section: "Auth".to_string(),
+            key: "API Key Rotation Grace".to_string(),
+            value: format!("{}s", self.rotation_grace),
+        });
+        items.push(ConfigItem {
+            section: "Auth".to_string(),
+            key: "Default Rate Limit".to_string(),
+            value: format!(
+                "{} requests, {} tokens per minute",
+                limit_to_string(self.rate_limit_rpm),
+                limit_to_string(self.rate_limit_tpm)
+            ),
+        });
+    }
+}
+
+fn limit_to_string(limit: u64) -> String {
+    if limit == 0 {
+        "unlimited".to_string()
+    } else {
+        limit.to_string()
+    }
+}
+
+fn default_cache_ttl() -> u64 {
+    60
+}
+
+fn default_rotation_grace() -> u64 {
+    86400
+}
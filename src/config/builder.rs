@@ -1,18 +1,28 @@
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use tabled::{Table, Tabled, settings::Style};
 
+use crate::providers::ModelProvider;
+
 use crate::providers::anthropic::AnthropicConfig;
 use crate::providers::azure_openai::AzureOpenAIConfig;
 use crate::providers::bedrock::BedrockConfig;
 use crate::providers::cohere::CohereConfig;
 use crate::providers::openai::OpenAIConfig;
+use crate::providers::openai_compatible::OpenAICompatibleConfig;
 use crate::providers::vertex::VertexConfig;
 
 use super::app::AppConfig;
+use super::auth::AuthConfig;
+use super::catalog::CatalogConfig;
+use super::circuit_breaker::CircuitBreakerConfig;
 use super::cors::CorsConfig;
 use super::database::DatabaseConfig;
 use super::logging::LoggingConfig;
 use super::mailer::MailerConfig;
+use super::pool::PoolConfig;
+use super::routing::RoutingConfig;
 use super::session::SessionConfig;
 use super::sorai::SoraiConfig;
 use super::storage::StorageConfig;
@@ -32,10 +42,18 @@ pub struct Config {
     #[serde(default)]
     pub database: DatabaseConfig,
     #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
     pub session: SessionConfig,
     #[serde(default)]
     pub storage: StorageConfig,
     #[serde(default)]
+    pub catalog: CatalogConfig,
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
     pub openai: OpenAIConfig,
     #[serde(default)]
     pub anthropic: AnthropicConfig,
@@ -47,6 +65,11 @@ pub struct Config {
     pub azure_openai: AzureOpenAIConfig,
     #[serde(default)]
     pub vertex: VertexConfig,
+    #[serde(default)]
+    pub openai_compatible: Vec<OpenAICompatibleConfig>,
+    /// Pools of members serving a built-in provider
+    #[serde(default)]
+    pub pools: Vec<PoolConfig>,
     #[serde(skip)]
     pub env_file: Option<String>,
 }
@@ -79,6 +102,56 @@ impl Config {
             .ok()
             .and_then(|p| p.parse::<u16>().ok())
             .unwrap_or(config.sorai.port);
+        config.sorai.structured_output_retries = std::env::var("SORAI_STRUCTURED_OUTPUT_RETRIES")
+            .ok()
+            .and_then(|r| r.parse::<u32>().ok())
+            .unwrap_or(config.sorai.structured_output_retries);
+        config.sorai.request_timeout = std::env::var("SORAI_REQUEST_TIMEOUT")
+            .ok()
+            .and_then(|t| t.parse::<u64>().ok())
+            .unwrap_or(config.sorai.request_timeout);
+        config.sorai.upstream_timeout = std::env::var("SORAI_UPSTREAM_TIMEOUT")
+            .ok()
+            .and_then(|t| t.parse::<u64>().ok());
+
+        if let Ok(val) = std::env::var("SORAI_MODEL_CATALOG_FILE")
+            && !val.is_empty()
+        {
+            config.catalog.models = CatalogConfig::read_file(&val)?;
+            config.catalog.file = val;
+        }
+        if let Ok(val) = std::env::var("SORAI_MODEL_CATALOG_STRICT") {
+            config.catalog.strict = val.parse().unwrap_or(config.catalog.strict);
+        }
+        if let Ok(val) = std::env::var("SORAI_MODEL_ALIASES_FILE")
+            && !val.is_empty()
+        {
+            config.routing.aliases = RoutingConfig::read_file(&val)?;
+            config.routing.file = val;
+        }
+
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_ENABLED") {
+            config.circuit_breaker.enabled = val.parse().unwrap_or(config.circuit_breaker.enabled);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_WINDOW") {
+            config.circuit_breaker.window = val.parse().unwrap_or(config.circuit_breaker.window);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_MIN_REQUESTS") {
+            config.circuit_breaker.min_requests = val.parse().unwrap_or(config.circuit_breaker.min_requests);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_FAILURE_RATE") {
+            config.circuit_breaker.failure_rate = val.parse().unwrap_or(config.circuit_breaker.failure_rate);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_SLOW_CALL_MS") {
+            config.circuit_breaker.slow_call_ms = val.parse().unwrap_or(config.circuit_breaker.slow_call_ms);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_OPEN_SECONDS") {
+            config.circuit_breaker.open_seconds = val.parse().unwrap_or(config.circuit_breaker.open_seconds);
+        }
+        if let Ok(val) = std::env::var("SORAI_CIRCUIT_BREAKER_HALF_OPEN_REQUESTS") {
+            config.circuit_breaker.half_open_requests =
+                val.parse().unwrap_or(config.circuit_breaker.half_open_requests);
+        }
 
         if let Ok(val) = std::env::var("PROVIDER_OPENAI_API_KEY") {
             config.openai.api_key = val;
@@ -98,6 +171,15 @@ impl Config {
         if let Ok(val) = std::env::var("PROVIDER_BEDROCK_ACCESS_KEY") {
             config.bedrock.access_key = val;
         }
+        if let Ok(val) = std::env::var("PROVIDER_BEDROCK_SECRET_KEY") {
+            config.bedrock.secret_key = val;
+        }
+        if let Ok(val) = std::env::var("PROVIDER_BEDROCK_SESSION_TOKEN") {
+            config.bedrock.session_token = val;
+        }
+        if let Ok(val) = std::env::var("PROVIDER_BEDROCK_REGION") {
+            config.bedrock.region = val;
+        }
         if let Ok(val) = std::env::var("PROVIDER_BEDROCK_BASE_URL") {
             config.bedrock.base_url = val;
         }
@@ -113,15 +195,58 @@ impl Config {
         if let Ok(val) = std::env::var("PROVIDER_AZURE_OPENAI_ENDPOINT") {
             config.azure_openai.endpoint = val;
         }
+        if let Ok(val) = std::env::var("PROVIDER_AZURE_OPENAI_API_VERSION") {
+            config.azure_openai.api_version = val;
+        }
+        if let Ok(val) = std::env::var("PROVIDER_AZURE_OPENAI_DEPLOYMENTS") {
+            config.azure_openai.deployments = AzureOpenAIConfig::parse_deployments(&val);
+        }
         if let Ok(val) = std::env::var("PROVIDER_VERTEX_PROJECT_ID") {
             config.vertex.project_id = val;
         }
         if let Ok(val) = std::env::var("PROVIDER_VERTEX_CREDENTIALS") {
             config.vertex.credentials = val;
         }
+        if let Ok(val) = std::env::var("PROVIDER_VERTEX_LOCATION") {
+            config.vertex.location = val;
+        }
         if let Ok(val) = std::env::var("PROVIDER_VERTEX_BASE_URL") {
             config.vertex.base_url = val;
         }
+        if let Ok(val) = std::env::var("PROVIDER_VERTEX_TOKEN_URL") {
+            config.vertex.token_url = val;
+        }
+        if let Ok(val) = std::env::var("PROVIDER_OPENAI_COMPATIBLE") {
+            config.openai_compatible = val
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(OpenAICompatibleConfig::from_env)
+                .collect();
+        }
+        for kind in ModelProvider::ALL {
+            if let Some(pool) = PoolConfig::from_env(kind.as_str(), &config.provider_settings(kind)) {
+                config.pools.push(pool);
+            }
+        }
+        let timeout_vars = ModelProvider::ALL
+            .iter()
+            .map(|kind| {
+                (
+                    kind.as_str().to_string(),
+                    format!("PROVIDER_{}_TIMEOUT", kind.as_str().to_uppercase()),
+                )
+            })
+            .chain(config.openai_compatible.iter().map(|named| {
+                let var = format!("{}TIMEOUT", OpenAICompatibleConfig::env_prefix(&named.name));
+                (named.name.clone(), var)
+            }))
+            .collect::<Vec<_>>();
+        for (provider, var) in timeout_vars {
+            if let Some(seconds) = std::env::var(var).ok().and_then(|t| t.parse::<u64>().ok()) {
+                config.sorai.provider_timeouts.insert(provider, seconds);
+            }
+        }
 
         if let Ok(val) = std::env::var("SORAI_LOG_LEVEL") {
             config.logging.level = val;
@@ -217,6 +342,21 @@ impl Config {
         if let Ok(val) = std::env::var("SORAI_DATABASE_AUTO_MIGRATE") {
             config.database.auto_migrate = val.parse().unwrap_or(config.database.auto_migrate);
         }
+        if let Ok(val) = std::env::var("SORAI_API_KEY_CACHE_TTL") {
+            config.auth.cache_ttl = val.parse().unwrap_or(config.auth.cache_ttl);
+        }
+
+        if let Ok(val) = std::env::var("SORAI_API_KEY_ROTATION_GRACE") {
+            config.auth.rotation_grace = val.parse().unwrap_or(config.auth.rotation_grace);
+        }
+
+        if let Ok(val) = std::env::var("SORAI_RATE_LIMIT_RPM") {
+            config.auth.rate_limit_rpm = val.parse().unwrap_or(config.auth.rate_limit_rpm);
+        }
+
+        if let Ok(val) = std::env::var("SORAI_RATE_LIMIT_TPM") {
+            config.auth.rate_limit_tpm = val.parse().unwrap_or(config.auth.rate_limit_tpm);
+        }
 
         if let Ok(val) = std::env::var("SORAI_SESSION_STORAGE") {
             config.session.storage = val;
@@ -252,7 +392,57 @@ impl Config {
         if let Ok(val) = std::env::var("STORAGE_MAX_UPLOAD_SIZE") {
             config.storage.max_upload_size = val.parse().unwrap_or(config.storage.max_upload_size);
         }
+        if let Ok(val) = std::env::var("STORAGE_MEDIA_ALLOWED_HOSTS") {
+            config.storage.media_allowed_hosts = val
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        if let Ok(val) = std::env::var("STORAGE_MEDIA_FETCH_TIMEOUT") {
+            config.storage.media_fetch_timeout = val.parse().unwrap_or(config.storage.media_fetch_timeout);
+        }
+
+        Ok(config)
+    }
+
+    /// Provider wide settings of a built-in provider, as a JSON object
+    pub fn provider_settings(&self, kind: ModelProvider) -> Value {
+        let settings = match kind {
+            ModelProvider::OpenAI => serde_json::to_value(&self.openai),
+            ModelProvider::Anthropic => serde_json::to_value(&self.anthropic),
+            ModelProvider::AzureOpenAI => serde_json::to_value(&self.azure_openai),
+            ModelProvider::Bedrock => serde_json::to_value(&self.bedrock),
+            ModelProvider::Cohere => serde_json::to_value(&self.cohere),
+            ModelProvider::Vertex => serde_json::to_value(&self.vertex),
+            ModelProvider::OpenAICompatible => return Value::Null,
+        };
+        settings.unwrap_or_default()
+    }
+
+    /// Copy of the config with settings of a built-in provider replaced, as for a pool member
+    pub fn with_provider_overrides(
+        &self,
+        kind: ModelProvider,
+        overrides: &BTreeMap<String, String>,
+    ) -> Result<Config, serde_json::Error> {
+        let mut settings = self.provider_settings(kind);
+        if let Some(settings) = settings.as_object_mut() {
+            for (field, value) in overrides {
+                settings.insert(field.clone(), Value::String(value.clone()));
+            }
+        }
 
+        let mut config = self.clone();
+        match kind {
+            ModelProvider::OpenAI => config.openai = serde_json::from_value(settings)?,
+            ModelProvider::Anthropic => config.anthropic = serde_json::from_value(settings)?,
+            ModelProvider::AzureOpenAI => config.azure_openai = serde_json::from_value(settings)?,
+            ModelProvider::Bedrock => config.bedrock = serde_json::from_value(settings)?,
+            ModelProvider::Cohere => config.cohere = serde_json::from_value(settings)?,
+            ModelProvider::Vertex => config.vertex = serde_json::from_value(settings)?,
+            ModelProvider::OpenAICompatible => {}
+        }
         Ok(config)
     }
 
@@ -265,14 +455,24 @@ impl Config {
         self.cors.add_to_debug(&mut items);
         self.mailer.add_to_debug(&mut items);
         self.database.add_to_debug(&mut items);
+        self.auth.add_to_debug(&mut items);
         self.session.add_to_debug(&mut items);
         self.storage.add_to_debug(&mut items);
+        self.catalog.add_to_debug(&mut items);
+        self.routing.add_to_debug(&mut items);
+        self.circuit_breaker.add_to_debug(&mut items);
         self.openai.add_to_debug(&mut items);
         self.anthropic.add_to_debug(&mut items);
         self.bedrock.add_to_debug(&mut items);
         self.cohere.add_to_debug(&mut items);
         self.azure_openai.add_to_debug(&mut items);
         self.vertex.add_to_debug(&mut items);
+        for provider in &self.openai_compatible {
+            provider.add_to_debug(&mut items);
+        }
+        for pool in &self.pools {
+            pool.add_to_debug(&mut items);
+        }
 
         let table = Table::new(items).with(Style::sharp()).to_string();
         println!("{}", table);
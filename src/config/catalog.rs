@@ -0,0 +1,58 @@
+use crate::config::ConfigItem;
+use crate::providers::ModelInfo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogConfig {
+    /// JSON file listing model entries that extend or override the built-in catalog
+    #[serde(default)]
+    pub file: String,
+    /// Reject models missing from the catalog of a provider that has catalog entries
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+    /// Entries read from `file`
+    #[serde(default)]
+    pub models: Vec<ModelInfo>,
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            file: String::new(),
+            strict: default_strict(),
+            models: Vec::new(),
+        }
+    }
+}
+
+impl CatalogConfig {
+    /// Read the model entries of a catalog file
+    pub fn read_file(path: &str) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read model catalog '{}': {}", path, e))?;
+        let models =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse model catalog '{}': {}", path, e))?;
+        Ok(models)
+    }
+
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        items.push(ConfigItem {
+            section: "Model Catalog".to_string(),
+            key: "File".to_string(),
+            value: if self.file.is_empty() {
+                "<not set>".to_string()
+            } else {
+                format!("{} ({} models)", self.file, self.models.len())
+            },
+        });
+        items.push(ConfigItem {
+            section: "Model Catalog".to_string(),
+            key: "Strict".to_string(),
+            value: self.strict.to_string(),
+        });
+    }
+}
+
+fn default_strict() -> bool {
+    true
+}
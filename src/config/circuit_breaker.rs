@@ -0,0 +1,108 @@
+use crate::config::ConfigItem;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Number of recent calls the failure rate is computed over
+    #[serde(default = "default_window")]
+    pub window: u32,
+    /// Calls needed in the window before the circuit may open
+    #[serde(default = "default_min_requests")]
+    pub min_requests: u32,
+    /// Percentage of failed or slow calls in the window that opens the circuit
+    #[serde(default = "default_failure_rate")]
+    pub failure_rate: u32,
+    /// Milliseconds after which a successful call counts as slow, 0 to disable
+    #[serde(default = "default_slow_call_ms")]
+    pub slow_call_ms: u64,
+    /// Seconds an open circuit fails fast before letting trial calls through
+    #[serde(default = "default_open_seconds")]
+    pub open_seconds: u64,
+    /// Trial calls that must succeed in half-open state to close the circuit
+    #[serde(default = "default_half_open_requests")]
+    pub half_open_requests: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            window: default_window(),
+            min_requests: default_min_requests(),
+            failure_rate: default_failure_rate(),
+            slow_call_ms: default_slow_call_ms(),
+            open_seconds: default_open_seconds(),
+            half_open_requests: default_half_open_requests(),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        items.push(ConfigItem {
+            section: "Circuit Breaker".to_string(),
+            key: "Enabled".to_string(),
+            value: self.enabled.to_string(),
+        });
+        if !self.enabled {
+            return;
+        }
+        items.push(ConfigItem {
+            section: "Circuit Breaker".to_string(),
+            key: "Opens At".to_string(),
+            value: format!(
+                "{}% of the last {} calls failed (at least {} calls)",
+                self.failure_rate, self.window, self.min_requests
+            ),
+        });
+        items.push(ConfigItem {
+            section: "Circuit Breaker".to_string(),
+            key: "Slow Call".to_string(),
+            value: if self.slow_call_ms == 0 {
+                "<disabled>".to_string()
+            } else {
+                format!("{}ms", self.slow_call_ms)
+            },
+        });
+        items.push(ConfigItem {
+            section: "Circuit Breaker".to_string(),
+            key: "Open Duration".to_string(),
+            value: format!("{}s", self.open_seconds),
+        });
+        items.push(ConfigItem {
+            section: "Circuit Breaker".to_string(),
+            key: "Half-Open Requests".to_string(),
+            value: self.half_open_requests.to_string(),
+        });
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_window() -> u32 {
+    20
+}
+
+fn default_min_requests() -> u32 {
+    10
+}
+
+fn default_failure_rate() -> u32 {
+    50
+}
+
+fn default_slow_call_ms() -> u64 {
+    20_000
+}
+
+fn default_open_seconds() -> u64 {
+    30
+}
+
+fn default_half_open_requests() -> u32 {
+    1
+}
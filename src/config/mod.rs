@@ -1,11 +1,21 @@
 mod app;
+mod auth;
 mod builder;
+mod catalog;
+mod circuit_breaker;
 mod cors;
 mod database;
 mod logging;
 mod mailer;
+mod pool;
+mod routing;
 mod session;
 mod sorai;
 mod storage;
 
+pub use auth::AuthConfig;
 pub use builder::*;
+pub use circuit_breaker::CircuitBreakerConfig;
+pub use database::DatabaseConfig;
+pub use pool::{PoolConfig, PoolMemberConfig, PoolStrategy};
+pub use storage::StorageConfig;
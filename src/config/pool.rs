@@ -0,0 +1,148 @@
+use crate::config::ConfigItem;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How requests are spread over the members of a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolStrategy {
+    /// Smooth weighted round-robin
+    #[default]
+    RoundRobin,
+    /// Member with the fewest requests in flight relative to its weight
+    LeastInFlight,
+}
+
+impl std::str::FromStr for PoolStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "round_robin" | "weighted_round_robin" => Ok(PoolStrategy::RoundRobin),
+            "least_in_flight" | "least_requests" => Ok(PoolStrategy::LeastInFlight),
+            _ => Err(format!("Unknown pool strategy '{}'", s)),
+        }
+    }
+}
+
+/// Member of a provider pool: one set of credentials, region or deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolMemberConfig {
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Provider settings that differ from the provider wide config, keyed by field name
+    #[serde(default)]
+    pub overrides: BTreeMap<String, String>,
+}
+
+/// Several members serving one provider, read from `PROVIDER_<KIND>_POOL*` environment variables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolConfig {
+    /// Name of the provider the pool serves
+    pub provider: String,
+    #[serde(default)]
+    pub strategy: PoolStrategy,
+    /// Seconds a failing member is taken out of rotation
+    #[serde(default = "default_cooldown")]
+    pub cooldown: u64,
+    pub members: Vec<PoolMemberConfig>,
+}
+
+impl PoolConfig {
+    /// Read the pool of a provider, or None when `PROVIDER_<KIND>_POOL` lists no members
+    ///
+    /// `settings` is the provider wide config; each of its string fields can be
+    /// overridden per member with `PROVIDER_<KIND>_POOL_<MEMBER>_<FIELD>`.
+    pub fn from_env(provider: &str, settings: &Value) -> Option<Self> {
+        let prefix = format!("PROVIDER_{}_POOL", env_name(provider));
+        let var = |key: &str| std::env::var(format!("{}{}", prefix, key)).ok();
+
+        let members: Vec<PoolMemberConfig> = var("")?
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                let member = |field: &str| var(&format!("_{}_{}", env_name(name), env_name(field)));
+                let overrides = settings
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, value)| value.is_string())
+                    .filter_map(|(field, _)| member(field).map(|value| (field.clone(), value)))
+                    .collect();
+                PoolMemberConfig {
+                    name: name.to_string(),
+                    weight: member("weight")
+                        .and_then(|weight| weight.parse().ok())
+                        .unwrap_or_else(default_weight),
+                    overrides,
+                }
+            })
+            .collect();
+        if members.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            provider: provider.to_string(),
+            strategy: var("_STRATEGY")
+                .and_then(|strategy| strategy.parse().ok())
+                .unwrap_or_default(),
+            cooldown: var("_COOLDOWN")
+                .and_then(|cooldown| cooldown.parse().ok())
+                .unwrap_or_else(default_cooldown),
+            members,
+        })
+    }
+
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        let section = format!("Pool ({})", self.provider);
+        items.push(ConfigItem {
+            section: section.clone(),
+            key: "Strategy".to_string(),
+            value: format!("{:?}", self.strategy),
+        });
+        items.push(ConfigItem {
+            section: section.clone(),
+            key: "Cooldown".to_string(),
+            value: format!("{}s", self.cooldown),
+        });
+        // Overrides often carry credentials, so only the overridden field names are shown
+        items.push(ConfigItem {
+            section,
+            key: "Members".to_string(),
+            value: self
+                .members
+                .iter()
+                .map(|member| {
+                    let fields: Vec<&str> = member.overrides.keys().map(String::as_str).collect();
+                    format!("{} (weight {}; {})", member.name, member.weight, fields.join(", "))
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        });
+    }
+}
+
+/// Upper-case a name for use in an environment variable, replacing other characters with `_`
+fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_cooldown() -> u64 {
+    30
+}
@@ -0,0 +1,52 @@
+use crate::config::ConfigItem;
+use crate::providers::ModelAlias;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// JSON file listing the model aliases
+    #[serde(default)]
+    pub file: String,
+    /// Aliases read from `file`
+    #[serde(default)]
+    pub aliases: Vec<ModelAlias>,
+}
+
+impl RoutingConfig {
+    /// Read the aliases of a routing file, rejecting aliases without targets
+    pub fn read_file(path: &str) -> Result<Vec<ModelAlias>, Box<dyn std::error::Error>> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read model aliases '{}': {}", path, e))?;
+        let aliases: Vec<ModelAlias> =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse model aliases '{}': {}", path, e))?;
+        if let Some(alias) = aliases.iter().find(|alias| alias.targets.is_empty()) {
+            return Err(format!("Model alias '{}' in '{}' has no targets", alias.name, path).into());
+        }
+        Ok(aliases)
+    }
+
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        items.push(ConfigItem {
+            section: "Model Routing".to_string(),
+            key: "File".to_string(),
+            value: if self.file.is_empty() {
+                "<not set>".to_string()
+            } else {
+                self.file.clone()
+            },
+        });
+        items.push(ConfigItem {
+            section: "Model Routing".to_string(),
+            key: "Aliases".to_string(),
+            value: if self.aliases.is_empty() {
+                "<none>".to_string()
+            } else {
+                self.aliases
+                    .iter()
+                    .map(|alias| alias.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        });
+    }
+}
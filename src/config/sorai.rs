@@ -1,5 +1,7 @@
 use crate::config::ConfigItem;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoraiConfig {
@@ -7,6 +9,19 @@ pub struct SoraiConfig {
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Extra attempts when a chat response does not match the requested JSON schema
+    #[serde(default = "default_structured_output_retries")]
+    pub structured_output_retries: u32,
+    /// Seconds before a request is answered with 408 Request Timeout
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: u64,
+    /// Seconds a provider may send nothing before its call fails with a timeout,
+    /// half of `request_timeout` when unset so that a fallback still has time to answer
+    #[serde(default)]
+    pub upstream_timeout: Option<u64>,
+    /// Upstream timeouts of single providers, keyed by provider name
+    #[serde(default)]
+    pub provider_timeouts: BTreeMap<String, u64>,
 }
 
 impl Default for SoraiConfig {
@@ -14,11 +29,26 @@ impl Default for SoraiConfig {
         Self {
             host: default_host(),
             port: default_port(),
+            structured_output_retries: default_structured_output_retries(),
+            request_timeout: default_request_timeout(),
+            upstream_timeout: None,
+            provider_timeouts: BTreeMap::new(),
         }
     }
 }
 
 impl SoraiConfig {
+    /// Time a provider may send nothing before its call fails with a timeout
+    pub fn upstream_timeout(&self, provider: &str) -> Duration {
+        let seconds = self
+            .provider_timeouts
+            .get(provider)
+            .copied()
+            .or(self.upstream_timeout)
+            .unwrap_or(self.request_timeout / 2);
+        Duration::from_secs(seconds.max(1))
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "Sorai".to_string(),
@@ -30,6 +60,28 @@ impl SoraiConfig {
             key: "Port".to_string(),
             value: self.port.to_string(),
         });
+        items.push(ConfigItem {
+            section: "Sorai".to_string(),
+            key: "Structured Output Retries".to_string(),
+            value: self.structured_output_retries.to_string(),
+        });
+        items.push(ConfigItem {
+            section: "Sorai".to_string(),
+            key: "Request Timeout".to_string(),
+            value: format!("{}s", self.request_timeout),
+        });
+        items.push(ConfigItem {
+            section: "Sorai".to_string(),
+            key: "Upstream Timeout".to_string(),
+            value: format!("{}s", self.upstream_timeout("").as_secs()),
+        });
+        for (provider, seconds) in &self.provider_timeouts {
+            items.push(ConfigItem {
+                section: "Sorai".to_string(),
+                key: format!("Upstream Timeout ({})", provider),
+                value: format!("{}s", seconds),
+            });
+        }
     }
 }
 
@@ -40,3 +92,11 @@ fn default_host() -> String {
 fn default_port() -> u16 {
     8000
 }
+
+fn default_structured_output_retries() -> u32 {
+    1
+}
+
+fn default_request_timeout() -> u64 {
+    30
+}
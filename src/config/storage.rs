@@ -23,6 +23,10 @@ pub struct StorageConfig {
     pub s3_signed_url_expires: u64,
     #[serde(default = "default_max_upload_size")]
     pub max_upload_size: u64,
+    #[serde(default)]
+    pub media_allowed_hosts: Vec<String>,
+    #[serde(default = "default_media_fetch_timeout")]
+    pub media_fetch_timeout: u64,
 }
 
 impl Default for StorageConfig {
@@ -38,6 +42,8 @@ impl Default for StorageConfig {
             s3_region: default_s3_region(),
             s3_signed_url_expires: default_s3_signed_url_expires(),
             max_upload_size: default_max_upload_size(),
+            media_allowed_hosts: Vec::new(),
+            media_fetch_timeout: default_media_fetch_timeout(),
         }
     }
 }
@@ -94,6 +100,20 @@ impl StorageConfig {
             key: "Max Upload Size".to_string(),
             value: format!("{} bytes", self.max_upload_size),
         });
+        items.push(ConfigItem {
+            section: "Storage".to_string(),
+            key: "Media Allowed Hosts".to_string(),
+            value: if self.media_allowed_hosts.is_empty() {
+                "<not set>".to_string()
+            } else {
+                self.media_allowed_hosts.join(", ")
+            },
+        });
+        items.push(ConfigItem {
+            section: "Storage".to_string(),
+            key: "Media Fetch Timeout".to_string(),
+            value: format!("{}s", self.media_fetch_timeout),
+        });
     }
 }
 
@@ -136,3 +156,7 @@ fn default_s3_signed_url_expires() -> u64 {
 fn default_max_upload_size() -> u64 {
     5242880
 }
+
+fn default_media_fetch_timeout() -> u64 {
+    10
+}
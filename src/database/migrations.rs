@@ -0,0 +1,111 @@
+use super::DatabaseError;
+
+/// Schema migrations, applied in order and recorded in `schema_migrations`
+///
+/// Migrations are never edited once released; schema changes get a new entry.
+const MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "0001_api_keys",
+        "CREATE TABLE api_keys (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            owner TEXT NOT NULL DEFAULT '',
+            prefix TEXT NOT NULL,
+            key_hash TEXT NOT NULL,
+            salt TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            last_used_at INTEGER,
+            disabled INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX idx_api_keys_prefix ON api_keys (prefix);",
+    ),
+    (
+        "0002_api_key_lifecycle",
+        "ALTER TABLE api_keys ADD COLUMN admin INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE api_keys ADD COLUMN revoked_at INTEGER;
+        ALTER TABLE api_keys ADD COLUMN request_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE api_keys ADD COLUMN previous_prefix TEXT;
+        ALTER TABLE api_keys ADD COLUMN previous_hash TEXT;
+        ALTER TABLE api_keys ADD COLUMN previous_salt TEXT;
+        ALTER TABLE api_keys ADD COLUMN previous_expires_at INTEGER;
+        CREATE INDEX idx_api_keys_previous_prefix ON api_keys (previous_prefix);
+        CREATE TABLE audit_log (
+            id TEXT PRIMARY KEY,
+            created_at INTEGER NOT NULL,
+            actor TEXT NOT NULL,
+            action TEXT NOT NULL,
+            key_id TEXT NOT NULL,
+            details TEXT NOT NULL DEFAULT '{}'
+        );
+        CREATE INDEX idx_audit_log_key_id ON audit_log (key_id);",
+    ),
+    (
+        "0003_api_key_scopes",
+        "ALTER TABLE api_keys ADD COLUMN scopes TEXT NOT NULL DEFAULT '{}';",
+    ),
+    (
+        "0004_api_key_rate_limits",
+        "ALTER TABLE api_keys ADD COLUMN rate_limits TEXT NOT NULL DEFAULT '{}';",
+    ),
+    (
+        "0005_api_key_budgets",
+        "ALTER TABLE api_keys ADD COLUMN budgets TEXT NOT NULL DEFAULT '[]';
+        CREATE TABLE owner_budgets (
+            owner TEXT PRIMARY KEY,
+            budgets TEXT NOT NULL DEFAULT '[]',
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE spend (
+            key_id TEXT NOT NULL,
+            owner TEXT NOT NULL,
+            day INTEGER NOT NULL,
+            requests INTEGER NOT NULL DEFAULT 0,
+            tokens INTEGER NOT NULL DEFAULT 0,
+            cost REAL NOT NULL DEFAULT 0,
+            PRIMARY KEY (key_id, owner, day)
+        );
+        CREATE INDEX idx_spend_owner_day ON spend (owner, day);
+        CREATE TABLE budget_events (
+            subject TEXT NOT NULL,
+            budget TEXT NOT NULL,
+            percent INTEGER NOT NULL,
+            window_start INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (subject, budget, percent, window_start)
+        );",
+    ),
+];
+
+/// Apply the migrations missing from the database, returning their names
+pub(super) async fn run(conn: &turso::Connection) -> Result<Vec<&'static str>, DatabaseError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (name TEXT PRIMARY KEY, applied_at INTEGER NOT NULL)",
+        (),
+    )
+    .await?;
+
+    let mut applied = Vec::new();
+    for (name, sql) in MIGRATIONS {
+        let mut rows = conn
+            .query("SELECT 1 FROM schema_migrations WHERE name = ?1", [*name])
+            .await?;
+        if rows.next().await?.is_some() {
+            continue;
+        }
+
+        let result = conn
+            .execute_batch(format!(
+                "BEGIN; {} INSERT INTO schema_migrations (name, applied_at) VALUES ('{}', {}); COMMIT;",
+                sql,
+                name,
+                chrono::Utc::now().timestamp()
+            ))
+            .await;
+        if let Err(e) = result {
+            let _ = conn.execute_batch("ROLLBACK").await;
+            return Err(DatabaseError::Query(format!("Migration '{}' failed: {}", name, e)));
+        }
+        applied.push(*name);
+    }
+    Ok(applied)
+}
@@ -0,0 +1,114 @@
+//! Embedded database backed by turso
+//!
+//! The database is a local SQLite-compatible file, opened on first use and
+//! migrated then when `SORAI_DATABASE_AUTO_MIGRATE` is enabled.
+
+mod migrations;
+
+use std::sync::Arc;
+
+use tokio::sync::OnceCell;
+
+use crate::config::DatabaseConfig;
+
+/// File name of the database inside the data directory when no URL is configured
+const DEFAULT_DATABASE_FILE: &str = "sorai.db";
+
+/// Errors produced while opening or querying the database
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum DatabaseError {
+    #[error("Database URL '{0}' is not supported, only local database files can be used")]
+    Unsupported(String),
+    #[error("Failed to open database '{path}': {message}")]
+    Open { path: String, message: String },
+    #[error("Database query failed: {0}")]
+    Query(String),
+}
+
+impl From<turso::Error> for DatabaseError {
+    fn from(err: turso::Error) -> Self {
+        DatabaseError::Query(err.to_string())
+    }
+}
+
+/// Database is a cheaply cloneable handle to the application database
+#[derive(Clone)]
+pub struct Database {
+    path: String,
+    auto_migrate: bool,
+    inner: Arc<OnceCell<turso::Database>>,
+}
+
+impl Database {
+    /// Handle to the database of the config, opened on first use
+    ///
+    /// Without a URL the database is `sorai.db` in the data directory; a URL is a
+    /// file path, optionally prefixed with `file:`, or `:memory:`.
+    pub fn new(config: &DatabaseConfig, data_dir: &str) -> Self {
+        let path = match config.url.trim() {
+            "" => format!("{}/{}", data_dir.trim_end_matches('/'), DEFAULT_DATABASE_FILE),
+            url => url.strip_prefix("file:").unwrap_or(url).to_string(),
+        };
+        Self {
+            path,
+            auto_migrate: config.auto_migrate,
+            inner: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Private in-memory database, migrated on first use
+    pub fn in_memory() -> Self {
+        Self {
+            path: ":memory:".to_string(),
+            auto_migrate: true,
+            inner: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Path of the database file, or `:memory:`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Open a connection, opening the database first if needed
+    pub async fn connect(&self) -> Result<turso::Connection, DatabaseError> {
+        let database = self.inner.get_or_try_init(|| self.open()).await?;
+        Ok(database.connect()?)
+    }
+
+    /// Apply the migrations that have not run yet, returning their names
+    pub async fn migrate(&self) -> Result<Vec<&'static str>, DatabaseError> {
+        let conn = self.connect().await?;
+        migrations::run(&conn).await
+    }
+
+    async fn open(&self) -> Result<turso::Database, DatabaseError> {
+        if self.path.contains("://") {
+            return Err(DatabaseError::Unsupported(self.path.clone()));
+        }
+        let open_error = |message: String| DatabaseError::Open {
+            path: self.path.clone(),
+            message,
+        };
+
+        if self.path != ":memory:"
+            && let Some(parent) = std::path::Path::new(&self.path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).map_err(|e| open_error(e.to_string()))?;
+        }
+        let database = turso::Builder::new_local(&self.path)
+            .build()
+            .await
+            .map_err(|e| open_error(e.to_string()))?;
+        tracing::debug!("Database '{}' opened", self.path);
+
+        if self.auto_migrate {
+            let applied = migrations::run(&database.connect()?).await?;
+            if !applied.is_empty() {
+                tracing::info!("Applied database migrations: {}", applied.join(", "));
+            }
+        }
+        Ok(database)
+    }
+}
@@ -0,0 +1,257 @@
+use crate::http::middleware::AdminKey;
+use axum::extract::{Json, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+
+use crate::auth::{AuthError, Budget, BudgetError, CreateApiKey, RateLimitExceeded, UpdateApiKey};
+use crate::http::response::{ApiResponse, ErrorCode, ErrorTypeKind, RequestId, create_error};
+use crate::http::state::AppState;
+
+/// Audit events returned when no limit is given
+const DEFAULT_AUDIT_LIMIT: u32 = 100;
+/// Most audit events returned at once
+const MAX_AUDIT_LIMIT: u32 = 1000;
+
+/// Body of a key rotation, all fields optional
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateKeyReq {
+    /// Seconds the outgoing secret keeps working, defaults to `SORAI_API_KEY_ROTATION_GRACE`
+    pub grace_seconds: Option<u64>,
+}
+
+/// Body of an owner budgets update
+#[derive(Debug, Deserialize)]
+pub struct OwnerBudgetsReq {
+    /// Budgets replacing the current ones, an empty list lifting them
+    pub budgets: Vec<Budget>,
+}
+
+/// Query of the audit trail endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub key_id: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Create API key endpoint handler
+/// POST /v1/auth/keys
+/// Requires an admin API key
+///
+/// The response is the only place the plaintext key is returned.
+pub async fn create_key(
+    State(state): State<AppState>,
+    AdminKey(admin): AdminKey,
+    RequestId(request_id): RequestId,
+    Json(request): Json<CreateApiKey>,
+) -> Response {
+    match state.api_keys.create(admin.id(), &request).await {
+        Ok(created) => (StatusCode::CREATED, ApiResponse::success(created, request_id)).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// List API keys endpoint handler
+/// GET /v1/auth/keys
+/// Requires an admin API key
+pub async fn list_keys(State(state): State<AppState>, _admin: AdminKey, RequestId(request_id): RequestId) -> Response {
+    match state.api_keys.list().await {
+        Ok(keys) => ApiResponse::success(keys, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Get API key endpoint handler
+/// GET /v1/auth/keys/{id}
+/// Requires an admin API key
+pub async fn get_key(
+    State(state): State<AppState>,
+    _admin: AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+) -> Response {
+    match state.api_keys.get(&id).await {
+        Ok(key) => ApiResponse::success(key, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Update API key endpoint handler
+/// PATCH /v1/auth/keys/{id}
+/// Requires an admin API key
+///
+/// Changes the name, owner, admin flag, disabled flag, scopes, rate limits or budgets, leaving absent fields untouched.
+pub async fn update_key(
+    State(state): State<AppState>,
+    AdminKey(admin): AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateApiKey>,
+) -> Response {
+    match state.api_keys.update(admin.id(), &id, &request).await {
+        Ok(key) => ApiResponse::success(key, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Revoke API key endpoint handler
+/// DELETE /v1/auth/keys/{id}
+/// Requires an admin API key
+///
+/// Revoked keys stay listed for reference but can no longer be used or rotated.
+pub async fn revoke_key(
+    State(state): State<AppState>,
+    AdminKey(admin): AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+) -> Response {
+    match state.api_keys.revoke(admin.id(), &id).await {
+        Ok(key) => ApiResponse::success(key, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Rotate API key endpoint handler
+/// POST /v1/auth/keys/{id}/rotate
+/// Requires an admin API key
+///
+/// Returns the new plaintext key; the previous one keeps working for the grace period.
+pub async fn rotate_key(
+    State(state): State<AppState>,
+    AdminKey(admin): AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+    request: Option<Json<RotateKeyReq>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    match state.api_keys.rotate(admin.id(), &id, request.grace_seconds).await {
+        Ok(rotated) => ApiResponse::success(rotated, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// API key usage endpoint handler
+/// GET /v1/auth/keys/{id}/usage
+/// Requires an admin API key
+pub async fn key_usage(
+    State(state): State<AppState>,
+    _admin: AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+) -> Response {
+    match state.api_keys.usage(&id).await {
+        Ok(usage) => ApiResponse::success(usage, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// API key spend endpoint handler
+/// GET /v1/auth/keys/{id}/spend
+/// Requires an admin API key
+///
+/// Returns the budgets of the key with the spend of their current window, and the spend of the month.
+pub async fn key_spend(
+    State(state): State<AppState>,
+    _admin: AdminKey,
+    RequestId(request_id): RequestId,
+    Path(id): Path<String>,
+) -> Response {
+    let report = match state.api_keys.get(&id).await {
+        Ok(key) => state.budgets.key_report(&key).await,
+        Err(e) => Err(e),
+    };
+    match report {
+        Ok(report) => ApiResponse::success(report, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Owner budgets endpoint handler
+/// GET /v1/auth/owners/{owner}/budgets
+/// Requires an admin API key
+///
+/// Returns the budgets shared by every key of the owner, with the spend of their current window.
+pub async fn owner_budgets(
+    State(state): State<AppState>,
+    _admin: AdminKey,
+    RequestId(request_id): RequestId,
+    Path(owner): Path<String>,
+) -> Response {
+    match state.budgets.owner_report(&owner).await {
+        Ok(report) => ApiResponse::success(report, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Set owner budgets endpoint handler
+/// PUT /v1/auth/owners/{owner}/budgets
+/// Requires an admin API key
+///
+/// Replaces the budgets of the owner, the hard ceiling of every key it owns taken together.
+pub async fn set_owner_budgets(
+    State(state): State<AppState>,
+    AdminKey(admin): AdminKey,
+    RequestId(request_id): RequestId,
+    Path(owner): Path<String>,
+    Json(request): Json<OwnerBudgetsReq>,
+) -> Response {
+    match state
+        .budgets
+        .set_owner_budgets(admin.id(), &owner, request.budgets)
+        .await
+    {
+        Ok(report) => ApiResponse::success(report, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+/// Audit trail endpoint handler
+/// GET /v1/auth/audit
+/// Requires an admin API key
+///
+/// Lists changes made to API keys and owner budgets along with spend events, most recent first,
+/// optionally for one `key_id`.
+pub async fn audit(
+    State(state): State<AppState>,
+    _admin: AdminKey,
+    RequestId(request_id): RequestId,
+    Query(query): Query<AuditQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, MAX_AUDIT_LIMIT);
+    match state.api_keys.audit(query.key_id.as_deref(), limit).await {
+        Ok(events) => ApiResponse::success(events, request_id).into_response(),
+        Err(e) => auth_error_response(e, request_id),
+    }
+}
+
+pub(crate) fn auth_error_response(err: AuthError, request_id: String) -> Response {
+    if let AuthError::Database(e) = &err {
+        tracing::error!("API key store failed: {}", e);
+    }
+    let response = ApiResponse::<()>::error(
+        create_error(err.error_code(), ErrorTypeKind::Internal, err.to_string()),
+        request_id,
+    );
+    (err.status_code(), response).into_response()
+}
+
+/// Convert a rate limit rejection into a 429 response carrying `Retry-After` and `x-ratelimit-*` headers
+pub(crate) fn rate_limit_response(err: RateLimitExceeded, request_id: String) -> Response {
+    let response = ApiResponse::<()>::error(
+        create_error(ErrorCode::RateLimitError, ErrorTypeKind::Internal, err.to_string()),
+        request_id,
+    );
+    (StatusCode::TOO_MANY_REQUESTS, err.headers(), response).into_response()
+}
+
+/// Convert a budget rejection into a 429 `QUOTA_ERROR` response carrying `Retry-After`
+pub(crate) fn budget_error_response(err: BudgetError, request_id: String) -> Response {
+    if let BudgetError::Database(e) = &err {
+        tracing::error!("Budget check failed: {}", e);
+    }
+    let response = ApiResponse::<()>::error(
+        create_error(err.error_code(), ErrorTypeKind::Internal, err.to_string()),
+        request_id,
+    );
+    (err.status_code(), err.headers(), response).into_response()
+}
@@ -1,142 +1,40 @@
-#![allow(unused_variables, dead_code)]
-
+use crate::auth::{Endpoint, estimate_tokens, text_len};
 use crate::http::middleware::ApiKey;
-use axum::{extract::Json, response::IntoResponse};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use type_safe_id::{StaticType, TypeSafeId};
+use axum::extract::{Json, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::{StreamExt, stream};
+use serde_json::json;
 
+use super::auth::{auth_error_response, budget_error_response, rate_limit_response};
 use crate::http::response::{create_error, ApiResponse, ErrorCode, ErrorTypeKind, RequestId};
-
-/// Chat completion type for TypeID
-#[derive(Default)]
-struct ChatCompletion;
-
-impl StaticType for ChatCompletion {
-    const TYPE: &'static str = "chatcmpl";
-}
-
-/// Text completion type for TypeID
-#[derive(Default)]
-struct TextCompletion;
-
-impl StaticType for TextCompletion {
-    const TYPE: &'static str = "cmpl";
-}
-
-/// Type aliases for completion IDs
-type ChatCompletionId = TypeSafeId<ChatCompletion>;
-type TextCompletionId = TypeSafeId<TextCompletion>;
-
-/// Chat completion request payload
-#[derive(Debug, Deserialize)]
-pub struct ChatCompletionReq {
-    #[serde(default)]
-    pub provider: Option<String>,
-    #[serde(default)]
-    pub model: Option<String>,
-    #[serde(default)]
-    pub messages: Vec<Value>,
-    #[serde(default)]
-    pub params: Option<Value>,
-    #[serde(default)]
-    pub fallbacks: Option<Vec<Value>>,
-}
-
-/// Text completion request payload
-#[derive(Debug, Deserialize)]
-pub struct TextCompletionReq {
-    #[serde(default)]
-    pub provider: Option<String>,
-    #[serde(default)]
-    pub model: Option<String>,
-    #[serde(default)]
-    pub text: Option<String>,
-    #[serde(default)]
-    pub params: Option<Value>,
-    #[serde(default)]
-    pub fallbacks: Option<Vec<Value>>,
-}
-
-/// Chat completion response following OpenAI format
-#[derive(Debug, Serialize)]
-pub struct ChatCompletionResponse {
-    pub id: String,
-    pub object: String,
-    pub choices: Vec<ChatCompletionChoice>,
-    pub model: String,
-    pub created: i64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usage: Option<UsageInfo>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub extra_fields: Option<ExtraFields>,
-}
-
-/// Text completion response following OpenAI format
-#[derive(Debug, Serialize)]
-pub struct TextCompletionResponse {
-    pub id: String,
-    pub object: String,
-    pub choices: Vec<TextCompletionChoice>,
-    pub model: String,
-    pub created: i64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usage: Option<UsageInfo>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub extra_fields: Option<ExtraFields>,
-}
-
-/// Chat completion choice
-#[derive(Debug, Serialize)]
-pub struct ChatCompletionChoice {
-    pub index: i32,
-    pub message: ChatMessage,
-    pub finish_reason: String,
-}
-
-/// Text completion choice
-#[derive(Debug, Serialize)]
-pub struct TextCompletionChoice {
-    pub index: i32,
-    pub text: String,
-    pub finish_reason: String,
-}
-
-/// Chat message structure
-#[derive(Debug, Serialize)]
-pub struct ChatMessage {
-    pub role: String,
-    pub content: String,
-}
-
-/// Usage information
-#[derive(Debug, Serialize)]
-pub struct UsageInfo {
-    pub prompt_tokens: i32,
-    pub completion_tokens: i32,
-    pub total_tokens: i32,
-}
-
-/// Extra fields for Sorai-specific information
-#[derive(Debug, Serialize)]
-pub struct ExtraFields {
-    pub provider: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub model_params: Option<Value>,
-    pub latency: f64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub raw_response: Option<Value>,
-}
+use crate::http::schemas::completions::{ChatCompletionReq, ExtraFields, ResolvedAlias, TextCompletionReq};
+use crate::http::state::AppState;
+use crate::providers::{
+    ChatCompletionStream, ModelRequirements, ProviderError, chat_completion_structured, with_fallbacks,
+};
 
 /// Chat completions endpoint handler
 /// POST /v1/chat/completions
 /// Requires Bearer token authentication
 pub async fn chat_completions(
+    State(state): State<AppState>,
     api_key: ApiKey,
     RequestId(request_id): RequestId,
-    Json(request): Json<ChatCompletionReq>,
+    Json(mut request): Json<ChatCompletionReq>,
 ) -> impl IntoResponse {
-    tracing::debug!("Chat completion request from API key: {}", api_key.key());
+    tracing::debug!("Chat completion request from API key: {}", api_key.prefix());
+
+    let alias = state.providers.routing().resolve(
+        &mut request.provider,
+        &mut request.model,
+        &mut request.params,
+        &mut request.fallbacks,
+    );
+    state.providers.resolve_names(
+        request.provider.as_mut(),
+        request.fallbacks.as_deref_mut().unwrap_or_default(),
+    );
 
     let provider = match &request.provider {
         None => {
@@ -202,45 +100,115 @@ pub async fn chat_completions(
         .into_response();
     }
 
-    let completion_id = ChatCompletionId::new();
-    let response = ChatCompletionResponse {
-        id: completion_id.to_string(),
-        object: "chat.completion".to_string(),
-        choices: vec![ChatCompletionChoice {
-            index: 0,
-            message: ChatMessage {
-                role: "assistant".to_string(),
-                content: "Hello! I'm doing well, thank you for asking. How can I help you today?".to_string(),
-            },
-            finish_reason: "stop".to_string(),
-        }],
-        model: model.clone(),
-        created: chrono::Utc::now().timestamp(),
-        usage: Some(UsageInfo {
-            prompt_tokens: 12,
-            completion_tokens: 19,
-            total_tokens: 31,
-        }),
-        extra_fields: Some(ExtraFields {
-            provider: provider.clone(),
-            model_params: Some(serde_json::json!({})),
-            latency: 1.234,
-            raw_response: Some(serde_json::json!({})),
-        }),
+    let fallbacks = request.fallbacks.clone().unwrap_or_default();
+    if let Err(e) = api_key.authorize(Endpoint::Chat, provider, model, &fallbacks) {
+        return auth_error_response(e, request_id);
+    }
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return budget_error_response(e, request_id),
     };
+    let requirements = ModelRequirements::chat(&request);
+    let estimate = estimate_tokens(request.messages.iter().map(text_len).sum(), requirements.max_tokens);
+    let reservation = match state.rate_limiter.acquire(api_key.record(), provider, model, estimate) {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limit_response(e, request_id),
+    };
+
+    if requirements.streaming {
+        let outcome = with_fallbacks(&state.providers, provider, model, &fallbacks, |provider, model| {
+            let reservation = &reservation;
+            let request = &request;
+            let requirements = &requirements;
+            let catalog = state.providers.catalog();
+            let media = state.providers.media();
+            async move {
+                catalog.check(provider.name(), &model, requirements)?;
+                reservation.admit(provider.name(), &model)?;
+                let request = media.prepare(provider.as_ref(), &model, request).await?;
+                provider.chat_completion_stream(&model, &request).await
+            }
+        })
+        .await;
+
+        return match outcome.result {
+            Ok(stream) => {
+                let stream = reservation.settle_stream(stream);
+                let stream = charge.settle_stream(state.providers.catalog(), &outcome.attempts, stream);
+                let answered = outcome.attempts.last();
+                let extra_fields = ExtraFields {
+                    provider: answered.map(|a| a.provider.clone()).unwrap_or_default(),
+                    model_params: request.params.clone(),
+                    latency: answered.map(|a| a.latency).unwrap_or_default(),
+                    alias: ResolvedAlias::new(alias, &outcome.attempts),
+                    attempts: Some(outcome.attempts),
+                    ..Default::default()
+                };
+                stream_response(stream, extra_fields)
+            }
+            Err(e) => {
+                reservation.settle(0);
+                provider_error_response(e, request_id)
+            }
+        };
+    }
 
-    ApiResponse::success(response, request_id).into_response()
+    let outcome = with_fallbacks(&state.providers, provider, model, &fallbacks, |provider, model| {
+        let reservation = &reservation;
+        let request = &request;
+        let requirements = &requirements;
+        let catalog = state.providers.catalog();
+        let media = state.providers.media();
+        let retries = state.providers.structured_output_retries();
+        async move {
+            catalog.check(provider.name(), &model, requirements)?;
+            reservation.admit(provider.name(), &model)?;
+            let request = media.prepare(provider.as_ref(), &model, request).await?;
+            chat_completion_structured(provider.as_ref(), &model, &request, retries).await
+        }
+    })
+    .await;
+
+    match outcome.result {
+        Ok(mut response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            if let Some(extra) = response.extra_fields.as_mut() {
+                extra.alias = ResolvedAlias::new(alias, &outcome.attempts);
+                extra.attempts = Some(outcome.attempts);
+            }
+            ApiResponse::success(response, request_id).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error_response(e, request_id)
+        }
+    }
 }
 
 /// Text completions endpoint handler
 /// POST /v1/text/completions
 /// Requires Bearer token authentication
 pub async fn text_completions(
+    State(state): State<AppState>,
     api_key: ApiKey,
     RequestId(request_id): RequestId,
-    Json(request): Json<TextCompletionReq>,
+    Json(mut request): Json<TextCompletionReq>,
 ) -> impl IntoResponse {
-    tracing::debug!("Text completion request from API key: {}", api_key.key());
+    tracing::debug!("Text completion request from API key: {}", api_key.prefix());
+
+    let alias = state.providers.routing().resolve(
+        &mut request.provider,
+        &mut request.model,
+        &mut request.params,
+        &mut request.fallbacks,
+    );
+    state.providers.resolve_names(
+        request.provider.as_mut(),
+        request.fallbacks.as_deref_mut().unwrap_or_default(),
+    );
 
     let provider = match &request.provider {
         None => {
@@ -294,7 +262,7 @@ pub async fn text_completions(
         Some(model) => model,
     };
 
-    let text = match &request.text {
+    match &request.text {
         None => {
             return ApiResponse::<()>::error(
                 create_error(
@@ -317,32 +285,98 @@ pub async fn text_completions(
             )
             .into_response();
         }
-        Some(text) => text,
-    };
+        Some(_) => {}
+    }
 
-    let completion_id = TextCompletionId::new();
-    let response = TextCompletionResponse {
-        id: completion_id.to_string(),
-        object: "text.completion".to_string(),
-        choices: vec![TextCompletionChoice {
-            index: 0,
-            text: format!("This is a placeholder completion for: {}", text),
-            finish_reason: "stop".to_string(),
-        }],
-        model: model.clone(),
-        created: chrono::Utc::now().timestamp(),
-        usage: Some(UsageInfo {
-            prompt_tokens: 5,
-            completion_tokens: 12,
-            total_tokens: 17,
-        }),
-        extra_fields: Some(ExtraFields {
-            provider: provider.clone(),
-            model_params: Some(serde_json::json!({})),
-            latency: 0.3,
-            raw_response: Some(serde_json::json!({})),
-        }),
+    let fallbacks = request.fallbacks.clone().unwrap_or_default();
+    if let Err(e) = api_key.authorize(Endpoint::Text, provider, model, &fallbacks) {
+        return auth_error_response(e, request_id);
+    }
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return budget_error_response(e, request_id),
+    };
+    let requirements = ModelRequirements::text(&request);
+    let estimate = estimate_tokens(request.text.as_deref().map_or(0, str::len), requirements.max_tokens);
+    let reservation = match state.rate_limiter.acquire(api_key.record(), provider, model, estimate) {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limit_response(e, request_id),
     };
+    let outcome = with_fallbacks(&state.providers, provider, model, &fallbacks, |provider, model| {
+        let reservation = &reservation;
+        let request = &request;
+        let requirements = &requirements;
+        let catalog = state.providers.catalog();
+        async move {
+            catalog.check(provider.name(), &model, requirements)?;
+            reservation.admit(provider.name(), &model)?;
+            provider.text_completion(&model, request).await
+        }
+    })
+    .await;
+
+    match outcome.result {
+        Ok(mut response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            if let Some(extra) = response.extra_fields.as_mut() {
+                extra.alias = ResolvedAlias::new(alias, &outcome.attempts);
+                extra.attempts = Some(outcome.attempts);
+            }
+            ApiResponse::success(response, request_id).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error_response(e, request_id)
+        }
+    }
+}
+
+/// Convert a chunk stream into an OpenAI-compatible SSE response terminated by `[DONE]`
+///
+/// Sorai specific fields are attached to the first chunk. An error after the
+/// stream started is sent as a final `{"error": ...}` event, since the status
+/// code has already been sent.
+fn stream_response(stream: ChatCompletionStream, extra_fields: ExtraFields) -> Response {
+    let mut extra_fields = Some(extra_fields);
 
-    ApiResponse::success(response, request_id).into_response()
+    let events = stream
+        .map(move |item| match item {
+            Ok(mut chunk) => {
+                chunk.extra_fields = extra_fields.take();
+                Event::default().json_data(chunk)
+            }
+            Err(e) => {
+                tracing::warn!("Provider stream failed: {}", e);
+                let error = create_error(e.error_code(), error_kind(&e), e.to_string());
+                Event::default().json_data(json!({ "error": error }))
+            }
+        })
+        .chain(stream::once(async { Ok(Event::default().data("[DONE]")) }));
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Convert a provider error into an API error response with a matching status code
+pub(crate) fn provider_error_response(err: ProviderError, request_id: String) -> Response {
+    tracing::warn!("Provider request failed: {}", err);
+    let response = ApiResponse::<()>::error(
+        create_error(err.error_code(), error_kind(&err), err.to_string()),
+        request_id,
+    );
+    (err.status_code(), response).into_response()
+}
+
+/// Errors caused by the upstream provider are external, everything else is internal
+fn error_kind(err: &ProviderError) -> ErrorTypeKind {
+    match err {
+        ProviderError::Upstream { .. }
+        | ProviderError::Timeout { .. }
+        | ProviderError::Transport { .. }
+        | ProviderError::Authentication { .. }
+        | ProviderError::SchemaMismatch { .. } => ErrorTypeKind::External,
+        _ => ErrorTypeKind::Internal,
+    }
 }
@@ -0,0 +1,132 @@
+use crate::auth::{Endpoint, estimate_tokens};
+use crate::http::middleware::ApiKey;
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+
+use super::auth::{auth_error_response, budget_error_response, rate_limit_response};
+use super::completions::provider_error_response;
+use crate::http::response::{ApiResponse, ErrorCode, ErrorTypeKind, RequestId, create_error};
+use crate::http::schemas::completions::ResolvedAlias;
+use crate::http::schemas::embeddings::EmbeddingReq;
+use crate::http::state::AppState;
+use crate::metrics::record_token_usage;
+use crate::providers::{ModelRequirements, with_fallbacks};
+
+/// Embeddings endpoint handler
+/// POST /v1/embeddings
+/// Requires Bearer token authentication
+pub async fn embeddings(
+    State(state): State<AppState>,
+    api_key: ApiKey,
+    RequestId(request_id): RequestId,
+    Json(mut request): Json<EmbeddingReq>,
+) -> impl IntoResponse {
+    tracing::debug!("Embeddings request from API key: {}", api_key.prefix());
+
+    let alias = state.providers.routing().resolve(
+        &mut request.provider,
+        &mut request.model,
+        &mut request.params,
+        &mut request.fallbacks,
+    );
+    state.providers.resolve_names(
+        request.provider.as_mut(),
+        request.fallbacks.as_deref_mut().unwrap_or_default(),
+    );
+
+    let provider = match &request.provider {
+        Some(provider) if !provider.is_empty() => provider,
+        _ => {
+            return ApiResponse::<()>::error(
+                create_error(
+                    ErrorCode::MissingRequiredParameter,
+                    ErrorTypeKind::Internal,
+                    "Provider is required",
+                ),
+                request_id.clone(),
+            )
+            .into_response();
+        }
+    };
+
+    let model = match &request.model {
+        Some(model) if !model.is_empty() => model,
+        _ => {
+            return ApiResponse::<()>::error(
+                create_error(
+                    ErrorCode::MissingRequiredParameter,
+                    ErrorTypeKind::Internal,
+                    "Model is required",
+                ),
+                request_id.clone(),
+            )
+            .into_response();
+        }
+    };
+
+    if request.inputs().is_empty() {
+        return ApiResponse::<()>::error(
+            create_error(
+                ErrorCode::MissingRequiredParameter,
+                ErrorTypeKind::Internal,
+                "Input is required",
+            ),
+            request_id.clone(),
+        )
+        .into_response();
+    }
+
+    let fallbacks = request.fallbacks.clone().unwrap_or_default();
+    if let Err(e) = api_key.authorize(Endpoint::Embeddings, provider, model, &fallbacks) {
+        return auth_error_response(e, request_id);
+    }
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return budget_error_response(e, request_id),
+    };
+    let requirements = ModelRequirements::embeddings();
+    let estimate = estimate_tokens(request.inputs().iter().map(String::len).sum(), None);
+    let reservation = match state.rate_limiter.acquire(api_key.record(), provider, model, estimate) {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limit_response(e, request_id),
+    };
+    let outcome = with_fallbacks(&state.providers, provider, model, &fallbacks, |provider, model| {
+        let reservation = &reservation;
+        let request = &request;
+        let requirements = &requirements;
+        let catalog = state.providers.catalog();
+        async move {
+            catalog.check(provider.name(), &model, requirements)?;
+            reservation.admit(provider.name(), &model)?;
+            provider.embeddings(&model, request).await
+        }
+    })
+    .await;
+
+    match outcome.result {
+        Ok(mut response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            if let (Some(answered), Some(usage)) = (outcome.attempts.last(), &response.usage) {
+                record_token_usage(
+                    &answered.provider,
+                    &answered.model,
+                    "prompt",
+                    usage.prompt_tokens.max(0) as u64,
+                );
+            }
+            if let Some(extra) = response.extra_fields.as_mut() {
+                extra.alias = ResolvedAlias::new(alias, &outcome.attempts);
+                extra.attempts = Some(outcome.attempts);
+            }
+            response.encode(request.encoding_format.unwrap_or_default());
+            ApiResponse::success(response, request_id).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error_response(e, request_id)
+        }
+    }
+}
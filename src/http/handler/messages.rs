@@ -0,0 +1,359 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::{StreamExt, stream};
+use serde_json::json;
+
+use crate::auth::{BudgetError, Endpoint, RateLimitExceeded, estimate_tokens, text_len};
+use crate::http::middleware::{ApiKey, AuthRejection};
+use crate::http::response::ErrorCode;
+use crate::http::schemas::completions::ChatCompletionChunk;
+use crate::http::schemas::messages::{
+    ContentBlock, ContentBlockDelta, MessageDelta, MessageDeltaUsage, MessageId, MessagesError, MessagesErrorResponse,
+    MessagesReq, MessagesResponse, MessagesStreamEvent, parse_fallbacks, stop_reason,
+};
+use crate::http::schemas::openai::split_model;
+use crate::http::state::AppState;
+use crate::providers::{
+    ChatCompletionStream, ModelAlias, ModelRequirements, ProviderError, chat_completion_structured, with_fallbacks,
+};
+
+/// Anthropic-compatible messages endpoint handler
+/// POST /v1/messages
+/// Requires Bearer token or `x-api-key` authentication
+///
+/// The request is translated to a chat completion, so it can be served by any
+/// provider, and the answer is translated back to the Anthropic format.
+pub async fn messages(
+    State(state): State<AppState>,
+    api_key: Result<ApiKey, AuthRejection>,
+    body: Result<Json<MessagesReq>, JsonRejection>,
+) -> Response {
+    let api_key = match api_key {
+        Ok(api_key) => api_key,
+        Err(rejection) => {
+            let status = rejection.status();
+            let error = rejection.into_error();
+            return error_response(
+                status,
+                MessagesError::new(error.code, error.reason.to_string()),
+            );
+        }
+    };
+    tracing::debug!("Anthropic-compatible messages request from API key: {}", api_key.prefix());
+
+    let request = match body {
+        Ok(Json(request)) => request,
+        Err(rejection) => {
+            return error_response(
+                rejection.status(),
+                MessagesError::new(ErrorCode::InvalidRequest, rejection.body_text()),
+            );
+        }
+    };
+
+    let alias = state.providers.routing().get(&request.model);
+    let (mut provider, model) = match alias.and_then(ModelAlias::primary) {
+        Some(target) => (target.provider.clone(), target.model.clone()),
+        None => match split_model(&request.model) {
+            Some((provider, model)) => (provider.to_string(), model.to_string()),
+            None => {
+                return invalid_request(format!(
+                    "model: '{}' must be in the 'provider/model' format or an alias",
+                    request.model
+                ));
+            }
+        },
+    };
+
+    if request.messages.is_empty() {
+        return invalid_request("messages: at least one message is required");
+    }
+    let mut fallbacks = match parse_fallbacks(request.fallbacks.as_deref().unwrap_or_default()) {
+        Ok(fallbacks) => fallbacks,
+        Err(message) => return invalid_request(format!("fallbacks: {}", message)),
+    };
+    let streaming = request.stream == Some(true);
+    let mut chat_request = match request.into_chat_request(&provider, &model) {
+        Ok(chat_request) => chat_request,
+        Err(message) => return invalid_request(format!("messages: {}", message)),
+    };
+    if let Some(alias) = alias {
+        fallbacks = alias.fallbacks().chain(fallbacks).collect();
+        alias.apply_params(&mut chat_request.params);
+    }
+    state.providers.resolve_names(Some(&mut provider), &mut fallbacks);
+    if let Err(e) = api_key.authorize(Endpoint::Chat, &provider, &model, &fallbacks) {
+        return error_response(e.status_code(), MessagesError::new(e.error_code(), e.to_string()));
+    }
+    let requirements = ModelRequirements {
+        streaming,
+        ..ModelRequirements::chat(&chat_request)
+    };
+    let estimate = estimate_tokens(
+        chat_request.messages.iter().map(text_len).sum(),
+        requirements.max_tokens,
+    );
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return over_budget(e),
+    };
+    let reservation = match state
+        .rate_limiter
+        .acquire(api_key.record(), &provider, &model, estimate)
+    {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limited(e),
+    };
+
+    if streaming {
+        let outcome = with_fallbacks(&state.providers, &provider, &model, &fallbacks, |provider, model| {
+            let reservation = &reservation;
+            let request = &chat_request;
+            let requirements = &requirements;
+            let catalog = state.providers.catalog();
+            let media = state.providers.media();
+            async move {
+                catalog.check(provider.name(), &model, requirements)?;
+                reservation.admit(provider.name(), &model)?;
+                let request = media.prepare(provider.as_ref(), &model, request).await?;
+                provider.chat_completion_stream(&model, &request).await
+            }
+        })
+        .await;
+
+        return match outcome.result {
+            Ok(stream) => {
+                let stream = reservation.settle_stream(stream);
+                stream_response(charge.settle_stream(state.providers.catalog(), &outcome.attempts, stream))
+            }
+            Err(e) => {
+                reservation.settle(0);
+                provider_error(e)
+            }
+        };
+    }
+
+    let outcome = with_fallbacks(&state.providers, &provider, &model, &fallbacks, |provider, model| {
+        let reservation = &reservation;
+        let request = &chat_request;
+        let requirements = &requirements;
+        let catalog = state.providers.catalog();
+        let media = state.providers.media();
+        let retries = state.providers.structured_output_retries();
+        async move {
+            catalog.check(provider.name(), &model, requirements)?;
+            reservation.admit(provider.name(), &model)?;
+            let request = media.prepare(provider.as_ref(), &model, request).await?;
+            chat_completion_structured(provider.as_ref(), &model, &request, retries).await
+        }
+    })
+    .await;
+
+    match outcome.result {
+        Ok(response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            Json(MessagesResponse::from_chat(response)).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error(e)
+        }
+    }
+}
+
+/// Kind of the content block currently open in a stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OpenBlock {
+    Text,
+    /// Tool use block, keyed by the index of the OpenAI tool call
+    ToolUse(i32),
+}
+
+/// Re-encodes OpenAI-style chunks as Anthropic stream events
+///
+/// Text and tool calls are emitted as consecutive content blocks; a block is
+/// closed as soon as a delta for another block arrives.
+struct MessagesStreamEncoder {
+    id: String,
+    started: bool,
+    block: Option<OpenBlock>,
+    index: usize,
+    stop_reason: Option<String>,
+    output_tokens: i32,
+}
+
+impl MessagesStreamEncoder {
+    fn new() -> Self {
+        Self {
+            id: MessageId::new().to_string(),
+            started: false,
+            block: None,
+            index: 0,
+            stop_reason: None,
+            output_tokens: 0,
+        }
+    }
+
+    fn encode(&mut self, chunk: ChatCompletionChunk) -> Vec<MessagesStreamEvent> {
+        let mut events = self.start(&chunk.model);
+
+        if let Some(usage) = chunk.usage {
+            self.output_tokens = usage.completion_tokens;
+        }
+
+        for choice in chunk.choices.into_iter().filter(|choice| choice.index == 0) {
+            if let Some(text) = choice.delta.content.filter(|text| !text.is_empty()) {
+                self.open(OpenBlock::Text, ContentBlock::Text { text: String::new() }, &mut events);
+                events.push(MessagesStreamEvent::ContentBlockDelta {
+                    index: self.index,
+                    delta: ContentBlockDelta::TextDelta { text },
+                });
+            }
+
+            for call in choice.delta.tool_calls.unwrap_or_default() {
+                let function = call.function.unwrap_or_default();
+                let block = ContentBlock::ToolUse {
+                    id: call.id.unwrap_or_default(),
+                    name: function.name.unwrap_or_default(),
+                    input: json!({}),
+                };
+                self.open(OpenBlock::ToolUse(call.index), block, &mut events);
+
+                if let Some(arguments) = function.arguments.filter(|arguments| !arguments.is_empty()) {
+                    events.push(MessagesStreamEvent::ContentBlockDelta {
+                        index: self.index,
+                        delta: ContentBlockDelta::InputJsonDelta {
+                            partial_json: arguments,
+                        },
+                    });
+                }
+            }
+
+            if let Some(reason) = choice.finish_reason {
+                self.stop_reason = Some(stop_reason(&reason));
+            }
+        }
+
+        events
+    }
+
+    /// Events closing the message once the upstream stream has ended
+    fn finish(&mut self) -> Vec<MessagesStreamEvent> {
+        let mut events = self.start("");
+        self.close(&mut events);
+        events.push(MessagesStreamEvent::MessageDelta {
+            delta: MessageDelta {
+                stop_reason: Some(self.stop_reason.take().unwrap_or_else(|| "end_turn".to_string())),
+                stop_sequence: None,
+            },
+            usage: MessageDeltaUsage {
+                output_tokens: self.output_tokens,
+            },
+        });
+        events.push(MessagesStreamEvent::MessageStop);
+        events
+    }
+
+    /// `message_start` event, only for the first call
+    fn start(&mut self, model: &str) -> Vec<MessagesStreamEvent> {
+        if self.started {
+            return Vec::new();
+        }
+        self.started = true;
+        vec![MessagesStreamEvent::MessageStart {
+            message: MessagesResponse::empty(self.id.clone(), model.to_string()),
+        }]
+    }
+
+    /// Open a content block unless it is already the current one
+    fn open(&mut self, block: OpenBlock, content_block: ContentBlock, events: &mut Vec<MessagesStreamEvent>) {
+        if self.block == Some(block) {
+            return;
+        }
+        if self.block.is_some() {
+            self.close(events);
+            self.index += 1;
+        }
+        self.block = Some(block);
+        events.push(MessagesStreamEvent::ContentBlockStart {
+            index: self.index,
+            content_block,
+        });
+    }
+
+    fn close(&mut self, events: &mut Vec<MessagesStreamEvent>) {
+        if self.block.take().is_some() {
+            events.push(MessagesStreamEvent::ContentBlockStop { index: self.index });
+        }
+    }
+}
+
+/// Convert a chunk stream into Anthropic stream events
+///
+/// An error after the stream started is sent as a final `error` event.
+fn stream_response(stream: ChatCompletionStream) -> Response {
+    let events = stream::unfold(Some((stream, MessagesStreamEncoder::new())), |state| async move {
+        let (mut stream, mut encoder) = state?;
+        match stream.next().await {
+            Some(Ok(chunk)) => Some((encoder.encode(chunk), Some((stream, encoder)))),
+            Some(Err(e)) => {
+                tracing::warn!("Provider stream failed: {}", e);
+                let error = MessagesStreamEvent::Error {
+                    error: MessagesError::new(e.error_code(), e.to_string()),
+                };
+                Some((vec![error], None))
+            }
+            None => Some((encoder.finish(), None)),
+        }
+    })
+    .flat_map(|events| {
+        stream::iter(
+            events
+                .into_iter()
+                .map(|event| Event::default().event(event.name()).json_data(event)),
+        )
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Convert a provider error into an Anthropic error response with a matching status code
+fn provider_error(err: ProviderError) -> Response {
+    tracing::warn!("Provider request failed: {}", err);
+    error_response(err.status_code(), MessagesError::new(err.error_code(), err.to_string()))
+}
+
+fn rate_limited(err: RateLimitExceeded) -> Response {
+    let mut response = error_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        MessagesError::new(ErrorCode::RateLimitError, err.to_string()),
+    );
+    response.headers_mut().extend(err.headers());
+    response
+}
+
+fn over_budget(err: BudgetError) -> Response {
+    if let BudgetError::Database(e) = &err {
+        tracing::error!("Budget check failed: {}", e);
+    }
+    let mut response = error_response(err.status_code(), MessagesError::new(err.error_code(), err.to_string()));
+    response.headers_mut().extend(err.headers());
+    response
+}
+
+fn invalid_request(message: impl Into<String>) -> Response {
+    error_response(
+        StatusCode::BAD_REQUEST,
+        MessagesError::new(ErrorCode::InvalidRequest, message),
+    )
+}
+
+fn error_response(status: StatusCode, error: MessagesError) -> Response {
+    (status, Json(MessagesErrorResponse::new(error))).into_response()
+}
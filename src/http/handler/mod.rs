@@ -1,10 +1,14 @@
+pub mod auth;
 pub mod completions;
+pub mod embeddings;
+pub mod messages;
+pub mod models;
+pub mod openai;
 #[cfg(not(debug_assertions))]
 pub mod spa;
 pub mod system;
 
 // TODO: Add additional handler modules:
-// - auth: API key management endpoints (create, list, revoke, rotate)
 // - admin: Administrative endpoints for system management
 // - users: User management endpoints (if multi-tenant support is added)
 // - analytics: Usage analytics and reporting endpoints
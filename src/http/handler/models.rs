@@ -0,0 +1,33 @@
+use crate::auth::Endpoint;
+use crate::http::middleware::ApiKey;
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+
+use super::auth::auth_error_response;
+use crate::http::response::{ApiResponse, RequestId};
+use crate::http::state::AppState;
+use crate::providers::ModelInfo;
+
+/// Model catalog endpoint handler
+/// GET /v1/models
+/// Requires Bearer token authentication
+///
+/// Lists the catalog entries of every registered provider, with their capabilities and pricing.
+/// Keys without the `models` endpoint family in scope are refused, otherwise only models
+/// in the scopes of the API key are listed.
+pub async fn models(State(state): State<AppState>, api_key: ApiKey, RequestId(request_id): RequestId) -> Response {
+    tracing::debug!("Model list request from API key: {}", api_key.prefix());
+
+    let scopes = api_key.scopes();
+    if let Err(e) = scopes.check(Endpoint::Models, []) {
+        return auth_error_response(e, request_id);
+    }
+    let models: Vec<ModelInfo> = state
+        .providers
+        .available_models()
+        .into_iter()
+        .filter(|model| scopes.permits_model(&model.provider, &model.id))
+        .cloned()
+        .collect();
+    ApiResponse::success(models, request_id).into_response()
+}
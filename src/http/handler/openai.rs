@@ -0,0 +1,501 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Json, State};
+use axum::http::{Method, StatusCode, Uri};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::{StreamExt, stream};
+use serde_json::{Map, Value};
+
+use crate::auth::{AuthError, BudgetError, Endpoint, RateLimitExceeded, estimate_tokens, text_len};
+use crate::http::middleware::{ApiKey, AuthRejection};
+use crate::http::response::ErrorCode;
+use crate::http::schemas::completions::{ChatCompletionReq, Fallback, RequestMessage, TextCompletionReq};
+use crate::http::schemas::openai::{ModelList, ModelObject, OpenAIError, OpenAIErrorResponse, split_model};
+use crate::http::state::AppState;
+use crate::providers::{
+    ChatCompletionStream, ModelRequirements, ProviderError, ProviderRegistry, chat_completion_structured,
+    with_fallbacks,
+};
+
+/// Provider, model and fallbacks taken out of an OpenAI request body
+struct Route {
+    provider: String,
+    model: String,
+    fallbacks: Vec<Fallback>,
+}
+
+/// OpenAI-compatible chat completions endpoint handler
+/// POST /v1/chat/completions
+/// Requires Bearer token authentication
+///
+/// The body is an OpenAI request whose `model` is `provider/model`; fields other
+/// than `model`, `messages` and `fallbacks` are passed to the provider as params.
+pub async fn chat_completions(
+    State(state): State<AppState>,
+    api_key: Result<ApiKey, AuthRejection>,
+    body: Result<Json<Map<String, Value>>, JsonRejection>,
+) -> Response {
+    let api_key = match api_key {
+        Ok(api_key) => api_key,
+        Err(rejection) => return auth_error(rejection),
+    };
+    tracing::debug!(
+        "OpenAI-compatible chat completion request from API key: {}",
+        api_key.prefix()
+    );
+
+    let mut body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) => return invalid_body(rejection),
+    };
+    let route = match take_route(&mut body, &state.providers) {
+        Ok(route) => route,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    if let Err(e) = api_key.authorize(Endpoint::Chat, &route.provider, &route.model, &route.fallbacks) {
+        return forbidden(e);
+    }
+
+    let messages = match body.remove("messages") {
+        Some(Value::Array(messages)) if !messages.is_empty() => {
+            match serde_json::from_value::<Vec<RequestMessage>>(Value::Array(messages)) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        OpenAIError::new(ErrorCode::InvalidRequest, format!("Invalid 'messages': {}", e))
+                            .with_param("messages"),
+                    );
+                }
+            }
+        }
+        _ => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                OpenAIError::new(
+                    ErrorCode::MissingRequiredParameter,
+                    "'messages' must be a non-empty array",
+                )
+                .with_param("messages"),
+            );
+        }
+    };
+
+    let request = ChatCompletionReq {
+        provider: Some(route.provider.clone()),
+        model: Some(route.model.clone()),
+        messages,
+        params: (!body.is_empty()).then_some(Value::Object(body)),
+        fallbacks: None,
+    };
+
+    let requirements = ModelRequirements::chat(&request);
+    let estimate = estimate_tokens(request.messages.iter().map(text_len).sum(), requirements.max_tokens);
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return over_budget(e),
+    };
+    let reservation = match state
+        .rate_limiter
+        .acquire(api_key.record(), &route.provider, &route.model, estimate)
+    {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limited(e),
+    };
+
+    if requirements.streaming {
+        let outcome = with_fallbacks(
+            &state.providers,
+            &route.provider,
+            &route.model,
+            &route.fallbacks,
+            |provider, model| {
+                let reservation = &reservation;
+                let request = &request;
+                let requirements = &requirements;
+                let catalog = state.providers.catalog();
+                let media = state.providers.media();
+                async move {
+                    catalog.check(provider.name(), &model, requirements)?;
+                    reservation.admit(provider.name(), &model)?;
+                    let request = media.prepare(provider.as_ref(), &model, request).await?;
+                    provider.chat_completion_stream(&model, &request).await
+                }
+            },
+        )
+        .await;
+
+        return match outcome.result {
+            Ok(stream) => {
+                let stream = reservation.settle_stream(stream);
+                stream_response(charge.settle_stream(state.providers.catalog(), &outcome.attempts, stream))
+            }
+            Err(e) => {
+                reservation.settle(0);
+                provider_error(e)
+            }
+        };
+    }
+
+    let outcome = with_fallbacks(
+        &state.providers,
+        &route.provider,
+        &route.model,
+        &route.fallbacks,
+        |provider, model| {
+            let reservation = &reservation;
+            let request = &request;
+            let requirements = &requirements;
+            let catalog = state.providers.catalog();
+            let media = state.providers.media();
+            let retries = state.providers.structured_output_retries();
+            async move {
+                catalog.check(provider.name(), &model, requirements)?;
+                reservation.admit(provider.name(), &model)?;
+                let request = media.prepare(provider.as_ref(), &model, request).await?;
+                chat_completion_structured(provider.as_ref(), &model, &request, retries).await
+            }
+        },
+    )
+    .await;
+
+    match outcome.result {
+        Ok(mut response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            response.extra_fields = None;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error(e)
+        }
+    }
+}
+
+/// OpenAI-compatible (legacy) completions endpoint handler
+/// POST /v1/completions
+/// Requires Bearer token authentication
+pub async fn completions(
+    State(state): State<AppState>,
+    api_key: Result<ApiKey, AuthRejection>,
+    body: Result<Json<Map<String, Value>>, JsonRejection>,
+) -> Response {
+    let api_key = match api_key {
+        Ok(api_key) => api_key,
+        Err(rejection) => return auth_error(rejection),
+    };
+    tracing::debug!("OpenAI-compatible completion request from API key: {}", api_key.prefix());
+
+    let mut body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) => return invalid_body(rejection),
+    };
+    let route = match take_route(&mut body, &state.providers) {
+        Ok(route) => route,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    if let Err(e) = api_key.authorize(Endpoint::Text, &route.provider, &route.model, &route.fallbacks) {
+        return forbidden(e);
+    }
+
+    // A single prompt may also be sent as a one element list
+    let prompt = match body.remove("prompt") {
+        Some(Value::Array(prompts)) if prompts.len() > 1 => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                OpenAIError::new(ErrorCode::InvalidRequest, "Batched prompts are not supported").with_param("prompt"),
+            );
+        }
+        Some(Value::Array(prompts)) => prompts.into_iter().next(),
+        prompt => prompt,
+    };
+    let prompt = match prompt {
+        Some(Value::String(prompt)) if !prompt.is_empty() => prompt,
+        _ => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                OpenAIError::new(
+                    ErrorCode::MissingRequiredParameter,
+                    "'prompt' must be a non-empty string",
+                )
+                .with_param("prompt"),
+            );
+        }
+    };
+
+    if body.get("stream").and_then(Value::as_bool) == Some(true) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            OpenAIError::new(
+                ErrorCode::InvalidRequest,
+                "Streaming is not supported for text completions",
+            )
+            .with_param("stream"),
+        );
+    }
+
+    let request = TextCompletionReq {
+        provider: Some(route.provider.clone()),
+        model: Some(route.model.clone()),
+        text: Some(prompt),
+        params: (!body.is_empty()).then_some(Value::Object(body)),
+        fallbacks: None,
+    };
+
+    let requirements = ModelRequirements::text(&request);
+    let estimate = estimate_tokens(request.text.as_deref().map_or(0, str::len), requirements.max_tokens);
+    let charge = match state.budgets.check(api_key.record()).await {
+        Ok(charge) => charge,
+        Err(e) => return over_budget(e),
+    };
+    let reservation = match state
+        .rate_limiter
+        .acquire(api_key.record(), &route.provider, &route.model, estimate)
+    {
+        Ok(reservation) => reservation,
+        Err(e) => return rate_limited(e),
+    };
+    let outcome = with_fallbacks(
+        &state.providers,
+        &route.provider,
+        &route.model,
+        &route.fallbacks,
+        |provider, model| {
+            let reservation = &reservation;
+            let request = &request;
+            let requirements = &requirements;
+            let catalog = state.providers.catalog();
+            async move {
+                catalog.check(provider.name(), &model, requirements)?;
+                reservation.admit(provider.name(), &model)?;
+                provider.text_completion(&model, request).await
+            }
+        },
+    )
+    .await;
+
+    match outcome.result {
+        Ok(mut response) => {
+            reservation.settle_usage(response.usage.as_ref());
+            charge
+                .settle(state.providers.catalog(), &outcome.attempts, response.usage.as_ref())
+                .await;
+            response.object = "text_completion".to_string();
+            response.extra_fields = None;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            reservation.settle(0);
+            provider_error(e)
+        }
+    }
+}
+
+/// OpenAI-compatible model list endpoint handler
+/// GET /v1/models
+/// Requires Bearer token authentication
+///
+/// Lists the catalog entries of every registered provider, as `provider/model` ids,
+/// followed by the model aliases served by a registered provider. Keys without the
+/// `models` endpoint family in scope are refused, otherwise only models in the scopes
+/// of the API key are listed.
+pub async fn models(State(state): State<AppState>, api_key: Result<ApiKey, AuthRejection>) -> Response {
+    let api_key = match api_key {
+        Ok(api_key) => api_key,
+        Err(rejection) => return auth_error(rejection),
+    };
+    let scopes = api_key.scopes();
+    if let Err(e) = scopes.check(Endpoint::Models, []) {
+        return forbidden(e);
+    }
+
+    let models = state
+        .providers
+        .available_models()
+        .into_iter()
+        .filter(|model| scopes.permits_model(&model.provider, &model.id))
+        .map(|model| ModelObject {
+            id: format!("{}/{}", model.provider, model.id),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: model.provider.clone(),
+        });
+    let aliases = state
+        .providers
+        .routing()
+        .aliases()
+        .into_iter()
+        .filter(|alias| {
+            alias
+                .targets
+                .iter()
+                .any(|target| state.providers.get(&target.provider).is_ok())
+                && alias
+                    .targets
+                    .iter()
+                    .all(|target| scopes.permits_model(&target.provider, &target.model))
+        })
+        .map(|alias| ModelObject {
+            id: alias.name.clone(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: "sorai".to_string(),
+        });
+    let data = models.chain(aliases).collect();
+
+    Json(ModelList {
+        object: "list".to_string(),
+        data,
+    })
+    .into_response()
+}
+
+/// Handler for unknown OpenAI-compatible routes
+pub async fn not_found(method: Method, uri: Uri) -> Response {
+    error_response(
+        StatusCode::NOT_FOUND,
+        OpenAIError::new(
+            ErrorCode::InvalidRequest,
+            format!("Invalid URL ({} {})", method, uri.path()),
+        ),
+    )
+}
+
+/// Take the `provider/model` id and the optional `fallbacks` list out of a request body
+///
+/// A model naming an alias is routed to the alias targets, with the alias default
+/// params added to the body. Providers are returned under their registered names.
+fn take_route(body: &mut Map<String, Value>, registry: &ProviderRegistry) -> Result<Route, OpenAIError> {
+    let id = match body.remove("model") {
+        Some(Value::String(id)) if !id.is_empty() => id,
+        _ => {
+            return Err(OpenAIError::new(
+                ErrorCode::MissingRequiredParameter,
+                "You must provide a model parameter",
+            )
+            .with_param("model"));
+        }
+    };
+
+    let fallbacks = match body.remove("fallbacks") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(ids)) => ids
+            .iter()
+            .map(|id| {
+                id.as_str()
+                    .and_then(split_model)
+                    .map(|(provider, model)| Fallback {
+                        provider: provider.to_string(),
+                        model: model.to_string(),
+                    })
+                    .ok_or_else(|| {
+                        OpenAIError::new(
+                            ErrorCode::InvalidRequest,
+                            format!("Fallback {} must be a 'provider/model' string", id),
+                        )
+                        .with_param("fallbacks")
+                    })
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(
+                OpenAIError::new(ErrorCode::InvalidRequest, "'fallbacks' must be an array").with_param("fallbacks"),
+            );
+        }
+    };
+
+    let mut route = if let Some(alias) = registry.routing().get(&id)
+        && let Some(target) = alias.primary()
+    {
+        alias.fill_params(body);
+        Route {
+            provider: target.provider.clone(),
+            model: target.model.clone(),
+            fallbacks: alias.fallbacks().chain(fallbacks).collect(),
+        }
+    } else {
+        let (provider, model) = split_model(&id).ok_or_else(|| {
+            OpenAIError::new(
+                ErrorCode::InvalidRequest,
+                format!("Model '{}' must be in the 'provider/model' format or an alias", id),
+            )
+            .with_param("model")
+        })?;
+        Route {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            fallbacks,
+        }
+    };
+    registry.resolve_names(Some(&mut route.provider), &mut route.fallbacks);
+
+    Ok(route)
+}
+
+/// Convert a chunk stream into an OpenAI SSE response terminated by `[DONE]`
+fn stream_response(stream: ChatCompletionStream) -> Response {
+    let events = stream
+        .map(|item| match item {
+            Ok(chunk) => Event::default().json_data(chunk),
+            Err(e) => {
+                tracing::warn!("Provider stream failed: {}", e);
+                Event::default().json_data(OpenAIErrorResponse {
+                    error: OpenAIError::new(e.error_code(), e.to_string()),
+                })
+            }
+        })
+        .chain(stream::once(async { Ok(Event::default().data("[DONE]")) }));
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Convert a provider error into an OpenAI error response with a matching status code
+fn provider_error(err: ProviderError) -> Response {
+    tracing::warn!("Provider request failed: {}", err);
+    error_response(err.status_code(), OpenAIError::new(err.error_code(), err.to_string()))
+}
+
+fn auth_error(rejection: AuthRejection) -> Response {
+    let status = rejection.status();
+    let error = rejection.into_error();
+    error_response(
+        status,
+        OpenAIError::new(error.code, error.reason.to_string()),
+    )
+}
+
+fn forbidden(err: AuthError) -> Response {
+    error_response(err.status_code(), OpenAIError::new(err.error_code(), err.to_string()))
+}
+
+fn rate_limited(err: RateLimitExceeded) -> Response {
+    let mut response = error_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        OpenAIError::new(ErrorCode::RateLimitError, err.to_string()),
+    );
+    response.headers_mut().extend(err.headers());
+    response
+}
+
+fn over_budget(err: BudgetError) -> Response {
+    if let BudgetError::Database(e) = &err {
+        tracing::error!("Budget check failed: {}", e);
+    }
+    let mut response = error_response(err.status_code(), OpenAIError::new(err.error_code(), err.to_string()));
+    response.headers_mut().extend(err.headers());
+    response
+}
+
+fn invalid_body(rejection: JsonRejection) -> Response {
+    error_response(
+        rejection.status(),
+        OpenAIError::new(ErrorCode::InvalidRequest, rejection.body_text()),
+    )
+}
+
+fn error_response(status: StatusCode, error: OpenAIError) -> Response {
+    (status, Json(OpenAIErrorResponse { error })).into_response()
+}
@@ -1,120 +1,194 @@
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRef, FromRequestParts};
 use axum::http::{request::Parts, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::RequestPartsExt;
 use axum_extra::headers::{authorization::Bearer, Authorization};
 use axum_extra::TypedHeader;
 
+use crate::auth::{ApiKeyRecord, ApiKeyScopes, ApiKeyStore, AuthError, Endpoint};
 use crate::http::response::{create_error, ApiResponse, ErrorCode, ErrorTypeKind};
+use crate::http::schemas::completions::Fallback;
 
-/// Valid API keys for authentication
-/// TODO: Move this to configuration file or database
-const VALID_API_KEYS: &[&str] = &["sk-1234", "sk-4321"];
-
-/// API Key claims extracted from Bearer token
+/// API key resolved from the Bearer token through the [`ApiKeyStore`]
 #[derive(Debug, Clone)]
 pub struct ApiKey {
-    pub key: String,
-    pub is_valid: bool,
+    record: ApiKeyRecord,
 }
 
 impl ApiKey {
-    /// Create new API key instance
-    pub fn new(key: String) -> Self {
-        let is_valid = VALID_API_KEYS.contains(&key.as_str());
-        Self { key, is_valid }
+    /// Id of the stored key
+    pub fn id(&self) -> &str {
+        &self.record.id
+    }
+
+    /// Leading characters of the key, safe to log
+    pub fn prefix(&self) -> &str {
+        &self.record.prefix
+    }
+
+    pub fn name(&self) -> &str {
+        &self.record.name
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.record.owner
     }
 
-    /// Check if API key is valid
-    pub fn is_valid(&self) -> bool {
-        self.is_valid
+    /// Stored key metadata
+    pub fn record(&self) -> &ApiKeyRecord {
+        &self.record
     }
 
-    /// Get the API key value
-    pub fn key(&self) -> &str {
-        &self.key
+    /// Providers, models and endpoint families the key may use
+    pub fn scopes(&self) -> &ApiKeyScopes {
+        &self.record.scopes
     }
 
-    // TODO: Add methods for:
-    // - Rate limiting per API key
-    // - Usage tracking and analytics
-    // - API key permissions/scopes
-    // - API key metadata (name, created_at, last_used, etc.)
+    /// Check that the key may call an endpoint family with a provider and model,
+    /// along with every fallback the request may be retried with
+    pub fn authorize(
+        &self,
+        endpoint: Endpoint,
+        provider: &str,
+        model: &str,
+        fallbacks: &[Fallback],
+    ) -> Result<(), AuthError> {
+        let targets = std::iter::once((provider, model)).chain(
+            fallbacks
+                .iter()
+                .map(|fallback| (fallback.provider.as_str(), fallback.model.as_str())),
+        );
+        self.record.scopes.check(endpoint, targets)
+    }
 }
 
 /// Auth rejection type
 pub struct AuthRejection {
+    status: StatusCode,
     request_id: Option<String>,
     error: crate::http::response::ErrorType,
 }
 
+impl AuthRejection {
+    /// HTTP status the rejection is reported with
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Error describing why authentication failed, for routes rendering their own error format
+    pub fn into_error(self) -> crate::http::response::ErrorType {
+        self.error
+    }
+}
+
 impl IntoResponse for AuthRejection {
     fn into_response(self) -> Response {
         let request_id = self.request_id.unwrap_or_else(|| {
             let id = crate::http::response::HttpRequestId::new();
             id.to_string()
         });
-        (
-            StatusCode::UNAUTHORIZED,
-            ApiResponse::<()>::error(self.error, request_id),
-        )
-            .into_response()
+        (self.status, ApiResponse::<()>::error(self.error, request_id)).into_response()
     }
 }
 
 impl<S> FromRequestParts<S> for ApiKey
 where
+    ApiKeyStore: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = AuthRejection;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        const REQUEST_ID_HEADER_NAME: &str = "x-request-id";
-
-        // Extract the token from the authorization header
-        let TypedHeader(Authorization(bearer)) =
-            parts
-                .extract::<TypedHeader<Authorization<Bearer>>>()
-                .await
-                .map_err(|_| AuthRejection {
-                    request_id: parts
-                        .headers
-                        .get(REQUEST_ID_HEADER_NAME)
-                        .and_then(|h| h.to_str().ok())
-                        .map(|s| s.to_string()),
-                    error: create_error(
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        const API_KEY_HEADER_NAME: &str = "x-api-key";
+
+        let request_id = request_id(parts);
+        let reject = |status: StatusCode, code: ErrorCode, message: &str| AuthRejection {
+            status,
+            request_id: request_id.clone(),
+            error: create_error(code, ErrorTypeKind::Internal, message),
+        };
+
+        // Extract the token from the authorization header, falling back to the
+        // `x-api-key` header sent by Anthropic SDKs
+        let token = match parts.extract::<TypedHeader<Authorization<Bearer>>>().await {
+            Ok(TypedHeader(Authorization(bearer))) => bearer.token().to_string(),
+            Err(_) => parts
+                .headers
+                .get(API_KEY_HEADER_NAME)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string())
+                .ok_or_else(|| {
+                    reject(
+                        StatusCode::UNAUTHORIZED,
                         ErrorCode::AuthenticationError,
-                        ErrorTypeKind::Internal,
                         "Missing or invalid Authorization header. Please provide a valid Bearer token.",
-                    ),
-                })?;
+                    )
+                })?,
+        };
+
+        match ApiKeyStore::from_ref(state).authenticate(&token).await {
+            Ok(record) => Ok(ApiKey { record }),
+            Err(AuthError::Disabled) => Err(reject(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::AuthenticationError,
+                "API key is disabled.",
+            )),
+            Err(AuthError::Revoked) => Err(reject(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::AuthenticationError,
+                "API key has been revoked.",
+            )),
+            Err(e @ AuthError::Database(_)) => {
+                tracing::error!("Failed to resolve API key: {}", e);
+                Err(reject(
+                    e.status_code(),
+                    e.error_code(),
+                    "Unable to verify the API key, please retry later.",
+                ))
+            }
+            Err(_) => Err(reject(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::AuthenticationError,
+                "Invalid API key. Please check your Bearer token.",
+            )),
+        }
+    }
+}
 
-        let api_key = ApiKey::new(bearer.token().to_string());
+/// API key allowed to use the admin API
+#[derive(Debug, Clone)]
+pub struct AdminKey(pub ApiKey);
+
+impl<S> FromRequestParts<S> for AdminKey
+where
+    ApiKeyStore: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthRejection;
 
-        if !api_key.is_valid() {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let api_key = ApiKey::from_request_parts(parts, state).await?;
+        if !api_key.record.admin || !api_key.scopes().permits_endpoint(Endpoint::Admin) {
             return Err(AuthRejection {
-                request_id: parts
-                    .headers
-                    .get(REQUEST_ID_HEADER_NAME)
-                    .and_then(|h| h.to_str().ok())
-                    .map(|s| s.to_string()),
+                status: StatusCode::FORBIDDEN,
+                request_id: request_id(parts),
                 error: create_error(
-                    ErrorCode::AuthenticationError,
+                    ErrorCode::AuthorizationError,
                     ErrorTypeKind::Internal,
-                    "Invalid API key. Please check your Bearer token.",
+                    "API key is not allowed to use the admin API.",
                 ),
             });
         }
-
-        Ok(api_key)
+        Ok(AdminKey(api_key))
     }
 }
 
-// TODO: Implement additional auth features:
-// - API key management endpoints (CRUD operations)
-// - API key rotation and expiration
-// - Different permission levels for API keys
-// - Integration with external auth providers
-// - Audit logging for authentication events
-// - Rate limiting based on API key
-// - API key usage analytics and reporting
+fn request_id(parts: &Parts) -> Option<String> {
+    const REQUEST_ID_HEADER_NAME: &str = "x-request-id";
+
+    parts
+        .headers
+        .get(REQUEST_ID_HEADER_NAME)
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string())
+}
@@ -1,13 +1,15 @@
 mod handler;
 mod router;
-mod schemas;
 mod server;
+mod state;
 
 pub mod middleware;
 pub mod response;
+pub mod schemas;
 
 pub use router::create_router;
 pub use server::*;
+pub use state::AppState;
 
 // TODO: Export additional modules when implemented:
 // - Rate limiting utilities
@@ -1,10 +1,10 @@
-use super::handler::{completions, system};
+use super::handler::{auth, completions, embeddings, messages, models, openai, system};
+use super::state::AppState;
 use axum::routing::{get, post};
 use axum::Router;
-use metrics_exporter_prometheus::PrometheusHandle;
 
 /// Create application router with all routes
-pub fn create_router(prometheus_handle: PrometheusHandle) -> Router {
+pub fn create_router(state: AppState) -> Router {
     let mut router = Router::new()
         // Public routes - no authentication required
         .route("/", get(system::index))
@@ -17,9 +17,36 @@ pub fn create_router(prometheus_handle: PrometheusHandle) -> Router {
                 // API v1 routes - require Bearer token authentication
                 .route("/v1/chat/completions", post(completions::chat_completions))
                 .route("/v1/text/completions", post(completions::text_completions))
+                .route("/v1/embeddings", post(embeddings::embeddings))
+                .route("/v1/models", get(models::models))
+                // API key management - require an admin API key
+                .route("/v1/auth/keys", get(auth::list_keys).post(auth::create_key))
+                .route(
+                    "/v1/auth/keys/{id}",
+                    get(auth::get_key).patch(auth::update_key).delete(auth::revoke_key),
+                )
+                .route("/v1/auth/keys/{id}/usage", get(auth::key_usage))
+                .route("/v1/auth/keys/{id}/rotate", post(auth::rotate_key))
+                .route("/v1/auth/keys/{id}/spend", get(auth::key_spend))
+                .route(
+                    "/v1/auth/owners/{owner}/budgets",
+                    get(auth::owner_budgets).put(auth::set_owner_budgets),
+                )
+                .route("/v1/auth/audit", get(auth::audit))
                 // Fallback for API routes - return JSON error
                 .fallback(system::api_not_found_handler)
-                .with_state(prometheus_handle.clone()),
+                .with_state(state.clone()),
+        )
+        // OpenAI and Anthropic compatible routes - raw provider JSON, for use with the official SDKs
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/chat/completions", post(openai::chat_completions))
+                .route("/completions", post(openai::completions))
+                .route("/models", get(openai::models))
+                .route("/messages", post(messages::messages))
+                .fallback(openai::not_found)
+                .with_state(state.clone()),
         );
 
     #[cfg(not(debug_assertions))]
@@ -56,10 +83,9 @@ pub fn create_router(prometheus_handle: PrometheusHandle) -> Router {
         );
     }
 
-    router.with_state(prometheus_handle)
+    router.with_state(state)
 
     // TODO: Add additional route groups:
-    // - /api/v1/auth/* - API key management endpoints (protected with admin auth)
     // - /api/v1/admin/* - Administrative endpoints (protected with admin auth)
     // - /api/v1/users/* - User management endpoints (protected with admin auth)
     // - /api/v1/analytics/* - Usage analytics endpoints (protected)
@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use type_safe_id::{StaticType, TypeSafeId};
+
+/// Chat completion type for TypeID
+#[derive(Default)]
+pub struct ChatCompletion;
+
+impl StaticType for ChatCompletion {
+    const TYPE: &'static str = "chatcmpl";
+}
+
+/// Text completion type for TypeID
+#[derive(Default)]
+pub struct TextCompletion;
+
+impl StaticType for TextCompletion {
+    const TYPE: &'static str = "cmpl";
+}
+
+/// Tool call type for TypeID, used for providers that do not assign call ids
+#[derive(Default)]
+pub struct ToolCallType;
+
+impl StaticType for ToolCallType {
+    const TYPE: &'static str = "call";
+}
+
+/// Type aliases for completion IDs
+pub type ChatCompletionId = TypeSafeId<ChatCompletion>;
+pub type TextCompletionId = TypeSafeId<TextCompletion>;
+pub type ToolCallId = TypeSafeId<ToolCallType>;
+
+/// Chat completion request payload
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChatCompletionReq {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<RequestMessage>,
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub fallbacks: Option<Vec<Fallback>>,
+}
+
+/// Message of a chat completion request, in OpenAI format
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestMessage {
+    #[serde(default)]
+    pub role: MessageRole,
+    /// Missing or null for assistant messages that only call tools
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<ChatContent>,
+    /// Function name a `tool` message answers, for clients that do not send call ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Tool calls of an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Id of the tool call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Remaining message fields, passed through as-is to OpenAI-compatible providers
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl RequestMessage {
+    /// Message with plain text content
+    pub fn new(role: MessageRole, text: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: Some(ChatContent::Text(text.into())),
+            ..Default::default()
+        }
+    }
+
+    /// Text content, with the text parts of a content array joined by newlines
+    pub fn text(&self) -> String {
+        match &self.content {
+            Some(ChatContent::Text(text)) => text.clone(),
+            Some(ChatContent::Parts(parts)) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => String::new(),
+        }
+    }
+
+    /// Content as typed parts, plain text content being a single text part
+    pub fn parts(&self) -> Cow<'_, [ContentPart]> {
+        match &self.content {
+            Some(ChatContent::Text(text)) => Cow::Owned(vec![ContentPart::Text { text: text.clone() }]),
+            Some(ChatContent::Parts(parts)) => Cow::Borrowed(parts),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+
+    /// Whether the message carries system instructions
+    pub fn is_system(&self) -> bool {
+        matches!(self.role, MessageRole::System | MessageRole::Developer)
+    }
+
+    /// Tool calls of an assistant message, empty for other messages
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.tool_calls.as_deref().unwrap_or_default()
+    }
+}
+
+/// Author of a request message, `user` when missing
+///
+/// `model`, Gemini's name for the assistant role, is accepted as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    System,
+    Developer,
+    #[default]
+    User,
+    #[serde(alias = "model")]
+    Assistant,
+    Tool,
+}
+
+/// Content of a request message, either plain text or an array of typed parts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+/// Text completion request payload
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TextCompletionReq {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub fallbacks: Option<Vec<Fallback>>,
+}
+
+/// Provider and model tried when the primary provider fails with a retryable error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fallback {
+    pub provider: String,
+    pub model: String,
+}
+
+/// Chat completion response following OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub choices: Vec<ChatCompletionChoice>,
+    pub model: String,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_fields: Option<ExtraFields>,
+}
+
+/// Text completion response following OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextCompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub choices: Vec<TextCompletionChoice>,
+    pub model: String,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_fields: Option<ExtraFields>,
+}
+
+/// Chat completion choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChoice {
+    pub index: i32,
+    pub message: ChatMessage,
+    pub finish_reason: String,
+}
+
+/// Text completion choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextCompletionChoice {
+    pub index: i32,
+    pub text: String,
+    pub finish_reason: String,
+}
+
+/// Chat message structure
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+    /// Tool calls requested by the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Id of the tool call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Tool call requested by the model, in OpenAI format
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+impl ToolCall {
+    /// Function call with JSON-encoded arguments
+    pub fn function(id: impl Into<String>, name: impl Into<String>, arguments: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: function_type(),
+            function: FunctionCall {
+                name: name.into(),
+                arguments: arguments.into(),
+            },
+        }
+    }
+}
+
+/// Function name and JSON-encoded arguments of a tool call
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+impl FunctionCall {
+    /// Arguments as a JSON value, an empty object when they are missing or not valid JSON
+    pub fn parsed_arguments(&self) -> Value {
+        serde_json::from_str(&self.arguments)
+            .ok()
+            .filter(Value::is_object)
+            .unwrap_or_else(|| Value::Object(Default::default()))
+    }
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// Typed part of a message's `content` array, in OpenAI format
+///
+/// Base64 images are sent as `image_url` parts holding a `data:` URL, and
+/// documents such as PDFs as `file` parts whose `file_data` is a `data:` URL,
+/// raw base64 data named by `filename`, or an http(s) URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+    File { file: FileData },
+}
+
+/// Image referenced by a URL or inlined as a `data:` URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Document attached to a message
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_data: Option<String>,
+}
+
+/// Streamed chat completion chunk following OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub choices: Vec<ChatCompletionChunkChoice>,
+    pub model: String,
+    pub created: i64,
+    /// Only set on the final chunk, which carries no choices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_fields: Option<ExtraFields>,
+}
+
+/// Streamed chat completion choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunkChoice {
+    pub index: i32,
+    pub delta: ChatMessageDelta,
+    pub finish_reason: Option<String>,
+}
+
+/// Incremental part of a chat message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatMessageDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// Incremental part of a tool call
+///
+/// The first delta of a call carries its id and function name; later deltas
+/// with the same index append to its arguments.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    #[serde(default)]
+    pub index: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionCallDelta>,
+}
+
+impl ToolCallDelta {
+    /// First delta of a call, announcing its id and function name
+    pub fn start(index: i32, id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            index,
+            id: Some(id.into()),
+            kind: Some(function_type()),
+            function: Some(FunctionCallDelta {
+                name: Some(name.into()),
+                arguments: Some(String::new()),
+            }),
+        }
+    }
+
+    /// Delta appending a piece of JSON-encoded arguments to a call
+    pub fn arguments(index: i32, arguments: impl Into<String>) -> Self {
+        Self {
+            index,
+            function: Some(FunctionCallDelta {
+                name: None,
+                arguments: Some(arguments.into()),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Incremental part of a function call
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
+/// Usage information
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageInfo {
+    pub prompt_tokens: i32,
+    pub completion_tokens: i32,
+    pub total_tokens: i32,
+}
+
+/// Extra fields for Sorai-specific information
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExtraFields {
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_params: Option<Value>,
+    pub latency: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_response: Option<Value>,
+    /// Safety ratings or content filter results reported by the provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_filter: Option<Value>,
+    /// Providers tried for this request, in order, ending with the one that answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<Vec<ProviderAttempt>>,
+    /// Model alias the request was made with and the target that answered it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<ResolvedAlias>,
+}
+
+/// Model alias of a request, resolved to a concrete provider and model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedAlias {
+    pub name: String,
+    pub provider: String,
+    pub model: String,
+}
+
+impl ResolvedAlias {
+    /// Resolution of `alias` to the target of the last attempt, the one that answered
+    pub fn new(alias: Option<String>, attempts: &[ProviderAttempt]) -> Option<Self> {
+        let answered = attempts.last()?;
+        Some(Self {
+            name: alias?,
+            provider: answered.provider.clone(),
+            model: answered.model.clone(),
+        })
+    }
+}
+
+/// Outcome of a single provider call within a fallback chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderAttempt {
+    pub provider: String,
+    pub model: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub latency: f64,
+}
@@ -0,0 +1,133 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::completions::{ExtraFields, Fallback, UsageInfo};
+
+/// Embeddings request payload
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EmbeddingReq {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub input: Option<EmbeddingInput>,
+    /// Number of dimensions of the returned vectors, for models that support shortening them
+    #[serde(default)]
+    pub dimensions: Option<u32>,
+    #[serde(default)]
+    pub encoding_format: Option<EncodingFormat>,
+    /// Provider specific parameters, such as Cohere's `input_type`
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub fallbacks: Option<Vec<Fallback>>,
+}
+
+impl EmbeddingReq {
+    /// Inputs as a list
+    pub fn inputs(&self) -> Vec<String> {
+        self.input.as_ref().map(EmbeddingInput::to_vec).unwrap_or_default()
+    }
+
+    /// String parameter from `params`, such as `input_type`
+    pub fn param_str(&self, name: &str) -> Option<&str> {
+        self.params.as_ref().and_then(|params| params[name].as_str())
+    }
+}
+
+/// Input may be sent as a single string or a list of strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl EmbeddingInput {
+    /// Inputs as a list
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            EmbeddingInput::Single(s) => vec![s.clone()],
+            EmbeddingInput::Multiple(v) => v.clone(),
+        }
+    }
+}
+
+/// Encoding of the returned vectors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingFormat {
+    #[default]
+    Float,
+    /// Little-endian `f32` values encoded as base64, as returned by OpenAI
+    Base64,
+}
+
+/// Embeddings response following OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    pub object: String,
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_fields: Option<ExtraFields>,
+}
+
+impl EmbeddingResponse {
+    /// Build a response from vectors ordered like the inputs
+    pub fn new(model: String, vectors: Vec<Vec<f32>>, usage: Option<UsageInfo>, extra_fields: ExtraFields) -> Self {
+        Self {
+            object: "list".to_string(),
+            data: vectors
+                .into_iter()
+                .enumerate()
+                .map(|(index, vector)| EmbeddingData {
+                    object: "embedding".to_string(),
+                    index: index as i32,
+                    embedding: Embedding::Float(vector),
+                })
+                .collect(),
+            model,
+            usage,
+            extra_fields: Some(extra_fields),
+        }
+    }
+
+    /// Re-encode every vector in the requested format
+    pub fn encode(&mut self, format: EncodingFormat) {
+        if format == EncodingFormat::Base64 {
+            for data in &mut self.data {
+                if let Embedding::Float(vector) = &data.embedding {
+                    data.embedding = Embedding::Base64(encode_base64(vector));
+                }
+            }
+        }
+    }
+}
+
+/// A single embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingData {
+    pub object: String,
+    pub index: i32,
+    pub embedding: Embedding,
+}
+
+/// Embedding vector, as floats or base64 depending on the requested encoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Embedding {
+    Float(Vec<f32>),
+    Base64(String),
+}
+
+/// Encode a vector as base64 of its little-endian `f32` bytes
+pub fn encode_base64(vector: &[f32]) -> String {
+    let bytes: Vec<u8> = vector.iter().flat_map(|value| value.to_le_bytes()).collect();
+    STANDARD.encode(bytes)
+}
@@ -0,0 +1,505 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use type_safe_id::{StaticType, TypeSafeId};
+
+use super::completions::{
+    ChatCompletionReq, ChatCompletionResponse, ChatContent, ContentPart, Fallback, FileData, ImageUrl, MessageRole,
+    RequestMessage, ToolCall,
+};
+use crate::http::response::ErrorCode;
+
+/// Message type for TypeID
+#[derive(Default)]
+pub struct Message;
+
+impl StaticType for Message {
+    const TYPE: &'static str = "msg";
+}
+
+/// Type alias for message IDs
+pub type MessageId = TypeSafeId<Message>;
+
+/// Anthropic Messages API request accepted by `POST /v1/messages`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessagesReq {
+    /// Model id in the `provider/model` format
+    pub model: String,
+    pub messages: Vec<MessageParam>,
+    #[serde(default)]
+    pub system: Option<MessageContent>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub top_p: Option<f64>,
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    #[serde(default)]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(default)]
+    pub stream: Option<bool>,
+    #[serde(default)]
+    pub tools: Option<Vec<ToolDefinition>>,
+    #[serde(default)]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+    /// Sorai extension: fallback `provider/model` ids tried on retryable errors
+    #[serde(default)]
+    pub fallbacks: Option<Vec<String>>,
+}
+
+/// A single conversation turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageParam {
+    pub role: String,
+    pub content: MessageContent,
+}
+
+/// Message content, either plain text or a list of content blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+impl MessageContent {
+    /// Text of the content, joining text blocks with newlines
+    pub fn text(&self) -> String {
+        match self {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Content block of a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+    },
+    Document {
+        source: ImageSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<MessageContent>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+/// Source of an image or document block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+impl ImageSource {
+    /// Image as a URL, inlining base64 data as a data URL
+    pub fn url(&self) -> String {
+        match self {
+            ImageSource::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+            ImageSource::Url { url } => url.clone(),
+        }
+    }
+}
+
+/// Tool the model may call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
+
+/// How the model should use the provided tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoice {
+    Auto,
+    Any,
+    Tool { name: String },
+    None,
+}
+
+impl MessagesReq {
+    /// Translate into a chat completion request with OpenAI-style messages and params
+    ///
+    /// Tool results become `tool` messages placed before the rest of the user
+    /// turn, and tool uses become `tool_calls` on the assistant message.
+    pub fn into_chat_request(self, provider: &str, model: &str) -> Result<ChatCompletionReq, String> {
+        let mut messages = Vec::new();
+
+        if let Some(system) = &self.system {
+            let text = system.text();
+            if !text.is_empty() {
+                messages.push(RequestMessage::new(MessageRole::System, text));
+            }
+        }
+
+        for message in &self.messages {
+            let role = if message.role == "assistant" {
+                MessageRole::Assistant
+            } else {
+                MessageRole::User
+            };
+            match &message.content {
+                MessageContent::Text(text) => messages.push(RequestMessage::new(role, text.clone())),
+                MessageContent::Blocks(blocks) if role == MessageRole::Assistant => {
+                    messages.push(assistant_message(blocks)?)
+                }
+                MessageContent::Blocks(blocks) => messages.extend(user_messages(role, blocks)?),
+            }
+        }
+
+        let mut params = Map::new();
+        if let Some(max_tokens) = self.max_tokens {
+            params.insert("max_tokens".to_string(), json!(max_tokens));
+        }
+        if let Some(temperature) = self.temperature {
+            params.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(top_p) = self.top_p {
+            params.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(top_k) = self.top_k {
+            params.insert("top_k".to_string(), json!(top_k));
+        }
+        if let Some(stop) = self.stop_sequences.filter(|stop| !stop.is_empty()) {
+            params.insert("stop".to_string(), json!(stop));
+        }
+        if let Some(stream) = self.stream {
+            params.insert("stream".to_string(), json!(stream));
+        }
+        if let Some(tools) = self.tools.filter(|tools| !tools.is_empty()) {
+            let tools: Vec<Value> = tools
+                .into_iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description.unwrap_or_default(),
+                            "parameters": tool.input_schema,
+                        }
+                    })
+                })
+                .collect();
+            params.insert("tools".to_string(), Value::Array(tools));
+        }
+        if let Some(choice) = self.tool_choice {
+            let choice = match choice {
+                ToolChoice::Auto => json!("auto"),
+                ToolChoice::Any => json!("required"),
+                ToolChoice::None => json!("none"),
+                ToolChoice::Tool { name } => json!({ "type": "function", "function": { "name": name } }),
+            };
+            params.insert("tool_choice".to_string(), choice);
+        }
+        if let Some(user) = self.metadata.as_ref().and_then(|m| m["user_id"].as_str()) {
+            params.insert("user".to_string(), json!(user));
+        }
+
+        Ok(ChatCompletionReq {
+            provider: Some(provider.to_string()),
+            model: Some(model.to_string()),
+            messages,
+            params: (!params.is_empty()).then_some(Value::Object(params)),
+            fallbacks: None,
+        })
+    }
+}
+
+/// Assistant turn: text blocks become the content, tool uses become tool calls
+fn assistant_message(blocks: &[ContentBlock]) -> Result<RequestMessage, String> {
+    let mut text = Vec::new();
+    let mut tool_calls = Vec::new();
+
+    for block in blocks {
+        match block {
+            ContentBlock::Text { text: part } => text.push(part.as_str()),
+            ContentBlock::ToolUse { id, name, input } => {
+                tool_calls.push(ToolCall::function(id.as_str(), name.as_str(), input.to_string()))
+            }
+            _ => return Err("Assistant messages may only contain text and tool_use blocks".to_string()),
+        }
+    }
+
+    let mut message = RequestMessage::new(MessageRole::Assistant, text.join("\n"));
+    if !tool_calls.is_empty() {
+        message.tool_calls = Some(tool_calls);
+    }
+    Ok(message)
+}
+
+/// User turn: tool results become `tool` messages, other blocks become content parts
+fn user_messages(role: MessageRole, blocks: &[ContentBlock]) -> Result<Vec<RequestMessage>, String> {
+    let mut messages = Vec::new();
+    let mut parts = Vec::new();
+
+    for block in blocks {
+        match block {
+            ContentBlock::Text { text } => parts.push(ContentPart::Text { text: text.clone() }),
+            ContentBlock::Image { source } => parts.push(ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: source.url(),
+                    detail: None,
+                },
+            }),
+            ContentBlock::Document { source } => parts.push(ContentPart::File {
+                file: FileData {
+                    filename: None,
+                    file_data: Some(source.url()),
+                },
+            }),
+            ContentBlock::ToolResult {
+                tool_use_id, content, ..
+            } => messages.push(RequestMessage {
+                tool_call_id: Some(tool_use_id.clone()),
+                ..RequestMessage::new(
+                    MessageRole::Tool,
+                    content.as_ref().map(MessageContent::text).unwrap_or_default(),
+                )
+            }),
+            ContentBlock::ToolUse { .. } => {
+                return Err("tool_use blocks are only allowed in assistant messages".to_string());
+            }
+            ContentBlock::Unsupported => return Err("Unsupported content block type".to_string()),
+        }
+    }
+
+    // A single text part is sent as plain content, which every provider understands
+    match parts.as_mut_slice() {
+        [] => {}
+        [ContentPart::Text { text }] => messages.push(RequestMessage::new(role, std::mem::take(text))),
+        _ => messages.push(RequestMessage {
+            content: Some(ChatContent::Parts(parts)),
+            ..RequestMessage::new(role, String::new())
+        }),
+    }
+    Ok(messages)
+}
+
+/// Anthropic Messages API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagesResponse {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub role: String,
+    pub model: String,
+    pub content: Vec<ContentBlock>,
+    pub stop_reason: Option<String>,
+    pub stop_sequence: Option<String>,
+    pub usage: MessagesUsage,
+}
+
+/// Token usage in the Anthropic format
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MessagesUsage {
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+}
+
+impl MessagesResponse {
+    /// Empty assistant message, as announced at the start of a stream
+    pub fn empty(id: String, model: String) -> Self {
+        Self {
+            id,
+            kind: "message".to_string(),
+            role: "assistant".to_string(),
+            model,
+            content: Vec::new(),
+            stop_reason: None,
+            stop_sequence: None,
+            usage: MessagesUsage::default(),
+        }
+    }
+
+    /// Translate the first choice of a chat completion
+    pub fn from_chat(response: ChatCompletionResponse) -> Self {
+        let mut message = Self::empty(MessageId::new().to_string(), response.model);
+
+        if let Some(choice) = response.choices.into_iter().next() {
+            if !choice.message.content.is_empty() {
+                message.content.push(ContentBlock::Text {
+                    text: choice.message.content,
+                });
+            }
+            for call in choice.message.tool_calls.unwrap_or_default() {
+                message.content.push(ContentBlock::ToolUse {
+                    input: call.function.parsed_arguments(),
+                    id: call.id,
+                    name: call.function.name,
+                });
+            }
+            message.stop_reason = Some(stop_reason(&choice.finish_reason));
+        }
+
+        if let Some(usage) = response.usage {
+            message.usage = MessagesUsage {
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+            };
+        }
+        message
+    }
+}
+
+/// Map an OpenAI finish reason to an Anthropic stop reason
+pub fn stop_reason(finish_reason: &str) -> String {
+    match finish_reason {
+        "length" => "max_tokens",
+        "tool_calls" | "function_call" => "tool_use",
+        _ => "end_turn",
+    }
+    .to_string()
+}
+
+/// Parse the Sorai `fallbacks` extension, given as `provider/model` ids
+pub fn parse_fallbacks(ids: &[String]) -> Result<Vec<Fallback>, String> {
+    ids.iter()
+        .map(|id| {
+            super::openai::split_model(id)
+                .map(|(provider, model)| Fallback {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                })
+                .ok_or_else(|| format!("Fallback '{}' must be a 'provider/model' id", id))
+        })
+        .collect()
+}
+
+/// Server-sent event of a streamed Messages API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessagesStreamEvent {
+    MessageStart {
+        message: MessagesResponse,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        delta: MessageDelta,
+        usage: MessageDeltaUsage,
+    },
+    MessageStop,
+    Error {
+        error: MessagesError,
+    },
+}
+
+impl MessagesStreamEvent {
+    /// Event name, sent as the SSE `event` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessagesStreamEvent::MessageStart { .. } => "message_start",
+            MessagesStreamEvent::ContentBlockStart { .. } => "content_block_start",
+            MessagesStreamEvent::ContentBlockDelta { .. } => "content_block_delta",
+            MessagesStreamEvent::ContentBlockStop { .. } => "content_block_stop",
+            MessagesStreamEvent::MessageDelta { .. } => "message_delta",
+            MessagesStreamEvent::MessageStop => "message_stop",
+            MessagesStreamEvent::Error { .. } => "error",
+        }
+    }
+}
+
+/// Incremental part of a content block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlockDelta {
+    TextDelta { text: String },
+    InputJsonDelta { partial_json: String },
+}
+
+/// Top-level changes to the message reported at the end of a stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDelta {
+    pub stop_reason: Option<String>,
+    pub stop_sequence: Option<String>,
+}
+
+/// Cumulative output token count reported at the end of a stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDeltaUsage {
+    pub output_tokens: i32,
+}
+
+/// Error body in the Anthropic format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagesErrorResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub error: MessagesError,
+}
+
+impl MessagesErrorResponse {
+    pub fn new(error: MessagesError) -> Self {
+        Self {
+            kind: "error".to_string(),
+            error,
+        }
+    }
+}
+
+/// Error details in the Anthropic format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagesError {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub message: String,
+}
+
+impl MessagesError {
+    /// Build an error from a Sorai error code, mapping it to the closest Anthropic error type
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        let kind = match code {
+            ErrorCode::MissingRequiredParameter | ErrorCode::InvalidRequest => "invalid_request_error",
+            ErrorCode::AuthenticationError => "authentication_error",
+            ErrorCode::AuthorizationError => "permission_error",
+            ErrorCode::RateLimitError => "rate_limit_error",
+            ErrorCode::QuotaError => "billing_error",
+            ErrorCode::ApiError | ErrorCode::ProviderError | ErrorCode::ServiceError => "api_error",
+        };
+
+        Self {
+            kind: kind.to_string(),
+            message: message.into(),
+        }
+    }
+}
@@ -1,3 +1,7 @@
 #![allow(unused_variables, unused_imports, dead_code)]
 
+pub mod completions;
+pub mod embeddings;
+pub mod messages;
+pub mod openai;
 pub mod sorai;
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::http::response::ErrorCode;
+
+/// Error body returned by the OpenAI-compatible routes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIErrorResponse {
+    pub error: OpenAIError,
+}
+
+/// Error details in the OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIError {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub param: Option<String>,
+    pub code: Option<String>,
+}
+
+impl OpenAIError {
+    /// Build an error from a Sorai error code, mapping it to the closest OpenAI error type
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        let kind = match code {
+            ErrorCode::MissingRequiredParameter | ErrorCode::InvalidRequest => "invalid_request_error",
+            ErrorCode::AuthenticationError => "authentication_error",
+            ErrorCode::AuthorizationError => "permission_error",
+            ErrorCode::RateLimitError => "rate_limit_error",
+            ErrorCode::QuotaError => "insufficient_quota",
+            ErrorCode::ApiError | ErrorCode::ProviderError | ErrorCode::ServiceError => "api_error",
+        };
+
+        Self {
+            message: message.into(),
+            kind: kind.to_string(),
+            param: None,
+            code: serde_json::to_value(code)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_lowercase)),
+        }
+    }
+
+    /// Name the request parameter the error is about
+    pub fn with_param(mut self, param: &str) -> Self {
+        self.param = Some(param.to_string());
+        self
+    }
+}
+
+/// Response of `GET /v1/models`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelList {
+    pub object: String,
+    pub data: Vec<ModelObject>,
+}
+
+/// A model addressable through the OpenAI-compatible routes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelObject {
+    /// Model id in the `provider/model` format
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub owned_by: String,
+}
+
+/// Split a `provider/model` id on its first slash, so that model names may contain slashes
+pub fn split_model(id: &str) -> Option<(&str, &str)> {
+    id.split_once('/')
+        .filter(|(provider, model)| !provider.is_empty() && !model.is_empty())
+}
@@ -1,4 +1,5 @@
 use super::router::create_router;
+use super::state::AppState;
 use crate::config::Config;
 use crate::http::middleware::MakeTypeSafeRequestId;
 use crate::http::middleware::{analytics_middleware, connection_info_middleware, create_cors_layer, track_metrics};
@@ -287,8 +288,17 @@ impl HttpServer {
 
         let x_request_id = HeaderName::from_static(REQUEST_ID_HEADER);
 
-        // Create base router with Prometheus handle
-        let mut app = create_router(prometheus_handle);
+        // Build shared state with the provider registry
+        let state = AppState::from_config(prometheus_handle, &self.config);
+        tracing::info!("Providers available: [{}]", state.providers.names().join(", "));
+
+        // Open the database up front so a bad path or schema fails at startup
+        // rather than on the first authenticated request
+        state.api_keys.database().connect().await?;
+        tracing::info!("Database: {}", state.api_keys.database().path());
+
+        // Create base router with application state
+        let mut app = create_router(state);
 
         // Add CORS layer if enabled
         let cors_enabled = if let Some(cors_layer) = create_cors_layer(&self.config) {
@@ -299,7 +309,7 @@ impl HttpServer {
         };
 
         // Get timeout request from config
-        let timeout_requests = 30u64;
+        let timeout_requests = self.config.sorai.request_timeout;
 
         // Add middleware layers in correct order
         let middleware = ServiceBuilder::new()
@@ -0,0 +1,58 @@
+use axum::extract::FromRef;
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::sync::Arc;
+
+use crate::auth::{ApiKeyStore, BudgetTracker, RateLimiter};
+use crate::config::AuthConfig;
+use crate::config::Config;
+use crate::database::Database;
+use crate::providers::ProviderRegistry;
+
+/// Shared application state available to all handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub prometheus_handle: PrometheusHandle,
+    pub providers: Arc<ProviderRegistry>,
+    pub api_keys: ApiKeyStore,
+    pub rate_limiter: RateLimiter,
+    pub budgets: BudgetTracker,
+}
+
+impl AppState {
+    /// Create application state from an existing provider registry and key store
+    pub fn new(prometheus_handle: PrometheusHandle, providers: ProviderRegistry, api_keys: ApiKeyStore) -> Self {
+        Self {
+            prometheus_handle,
+            providers: Arc::new(providers),
+            budgets: BudgetTracker::new(api_keys.database().clone(), &AuthConfig::default()),
+            api_keys,
+            rate_limiter: RateLimiter::default(),
+        }
+    }
+
+    /// Create application state with providers and the database built from configuration
+    pub fn from_config(prometheus_handle: PrometheusHandle, config: &Config) -> Self {
+        let database = Database::new(&config.database, &config.app.data_dir);
+        Self {
+            rate_limiter: RateLimiter::new(&config.auth),
+            budgets: BudgetTracker::new(database.clone(), &config.auth),
+            ..Self::new(
+                prometheus_handle,
+                ProviderRegistry::from_config(config),
+                ApiKeyStore::new(database, &config.auth),
+            )
+        }
+    }
+}
+
+impl FromRef<AppState> for PrometheusHandle {
+    fn from_ref(state: &AppState) -> Self {
+        state.prometheus_handle.clone()
+    }
+}
+
+impl FromRef<AppState> for ApiKeyStore {
+    fn from_ref(state: &AppState) -> Self {
+        state.api_keys.clone()
+    }
+}
@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod config;
+pub mod database;
 pub mod http;
 pub mod metrics;
 pub mod providers;
@@ -2,6 +2,8 @@ use clap::{CommandFactory, Parser};
 use clap_derive::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use sorai::auth::{ApiKeyScopes, ApiKeyStore, Budget, CLI_ACTOR, CreateApiKey, RateLimits};
+use sorai::database::Database;
 use sorai::{Config, http::HttpServer};
 
 /// Sorai Server
@@ -35,6 +37,145 @@ enum Commands {
     /// System health check (alias: hc)
     #[command(alias = "hc")]
     Healthcheck,
+    /// Apply pending database migrations
+    Migrate,
+    /// Manage the API keys clients authenticate with
+    Keys {
+        #[command(subcommand)]
+        command: KeyCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommands {
+    /// Create a key and print it, it cannot be displayed again
+    Create {
+        /// Name describing what the key is used for
+        #[arg(long)]
+        name: String,
+        /// Team or person the key belongs to
+        #[arg(long, default_value = "")]
+        owner: String,
+        /// Allow the key to manage other keys through the admin API
+        #[arg(long)]
+        admin: bool,
+        /// Providers, models and endpoints the key may use, as JSON
+        #[arg(long, value_name = "JSON", value_parser = parse_json::<ApiKeyScopes>)]
+        scopes: Option<Box<ApiKeyScopes>>,
+        /// Requests and tokens per minute the key may use, as JSON
+        #[arg(long, value_name = "JSON", value_parser = parse_json::<RateLimits>)]
+        rate_limits: Option<Box<RateLimits>>,
+        /// Spend budget of the key, as JSON; repeat for several budgets
+        #[arg(long = "budget", value_name = "JSON", value_parser = parse_budget)]
+        budgets: Vec<Budget>,
+    },
+    /// List the stored keys
+    List,
+    /// Disable a key by id
+    Disable { id: String },
+    /// Re-enable a disabled key by id
+    Enable { id: String },
+    /// Revoke a key by id for good
+    Revoke { id: String },
+    /// Replace the secret of a key by id and print the new key
+    Rotate {
+        id: String,
+        /// Seconds the previous key keeps working, defaults to SORAI_API_KEY_ROTATION_GRACE
+        #[arg(long, value_name = "SECONDS")]
+        grace: Option<u64>,
+    },
+}
+
+/// Load the config for commands working on the database, exiting on failure
+fn load_config(env_file: Option<&PathBuf>, data_dir: Option<&PathBuf>) -> Config {
+    let env_file = env_file.map(|p| p.to_string_lossy().to_string());
+    let mut config = match Config::load(env_file) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load config: {e}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(data_dir) = data_dir {
+        config.app.data_dir = data_dir.to_string_lossy().to_string();
+    }
+    config
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<Box<T>, String> {
+    serde_json::from_str(value).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+fn parse_budget(value: &str) -> Result<Budget, String> {
+    parse_json(value).map(|budget| *budget)
+}
+
+async fn run_key_command(store: &ApiKeyStore, command: KeyCommands) -> Result<(), sorai::auth::AuthError> {
+    match command {
+        KeyCommands::Create {
+            name,
+            owner,
+            admin,
+            scopes,
+            rate_limits,
+            budgets,
+        } => {
+            let params = CreateApiKey {
+                name,
+                owner,
+                admin,
+                scopes: scopes.map(|scopes| *scopes).unwrap_or_default(),
+                rate_limits: rate_limits.map(|limits| *limits).unwrap_or_default(),
+                budgets,
+            };
+            let created = store.create(CLI_ACTOR, &params).await?;
+            println!("Created API key {} ({})", created.record.id, created.record.name);
+            println!("{}", created.key);
+            println!("Store it now, the key cannot be displayed again.");
+        }
+        KeyCommands::List => {
+            for key in store.list().await? {
+                let last_used = key
+                    .last_used_at
+                    .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                    .map_or_else(|| "never".to_string(), |t| t.to_rfc3339());
+                let status = match (key.revoked_at, key.disabled) {
+                    (Some(_), _) => "  (revoked)",
+                    (None, true) => "  (disabled)",
+                    (None, false) => "",
+                };
+                println!(
+                    "{}  {}...  {}  owner={}  last_used={}{}{}",
+                    key.id,
+                    key.prefix,
+                    key.name,
+                    key.owner,
+                    last_used,
+                    if key.admin { "  (admin)" } else { "" },
+                    status
+                );
+            }
+        }
+        KeyCommands::Disable { id } => {
+            store.set_disabled(CLI_ACTOR, &id, true).await?;
+            println!("Disabled API key {}", id);
+        }
+        KeyCommands::Enable { id } => {
+            store.set_disabled(CLI_ACTOR, &id, false).await?;
+            println!("Enabled API key {}", id);
+        }
+        KeyCommands::Revoke { id } => {
+            store.revoke(CLI_ACTOR, &id).await?;
+            println!("Revoked API key {}", id);
+        }
+        KeyCommands::Rotate { id, grace } => {
+            let rotated = store.rotate(CLI_ACTOR, &id, grace).await?;
+            println!("Rotated API key {} ({})", rotated.record.id, rotated.record.name);
+            println!("{}", rotated.key);
+            println!("Store it now, the key cannot be displayed again.");
+        }
+    }
+    Ok(())
 }
 
 #[tokio::main]
@@ -111,5 +252,29 @@ async fn main() {
                 }
             }
         }
+        Commands::Migrate => {
+            let mut config = load_config(cli.env_file.as_ref(), cli.data_dir.as_ref());
+            // Migrate explicitly so the applied migrations can be reported
+            config.database.auto_migrate = false;
+            let database = Database::new(&config.database, &config.app.data_dir);
+
+            match database.migrate().await {
+                Ok(applied) if applied.is_empty() => println!("Database {} is up to date", database.path()),
+                Ok(applied) => println!("Applied migrations to {}: {}", database.path(), applied.join(", ")),
+                Err(e) => {
+                    eprintln!("Migration failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Keys { command } => {
+            let config = load_config(cli.env_file.as_ref(), cli.data_dir.as_ref());
+            let store = ApiKeyStore::new(Database::new(&config.database, &config.app.data_dir), &config.auth);
+
+            if let Err(e) = run_key_command(&store, command).await {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
     }
 }
@@ -86,6 +86,20 @@ pub fn record_error(provider: &str, error_type: &str) {
     metrics::counter!("sorai_errors_total", &labels).increment(1);
 }
 
+/// Record the circuit breaker state of a provider: 0 closed, 1 half-open, 2 open
+pub fn record_circuit_breaker_state(provider: &str, state: f64) {
+    let labels = [("provider", provider.to_string())];
+
+    metrics::gauge!("sorai_circuit_breaker_state", &labels).set(state);
+}
+
+/// Record a provider pool member taken out of rotation after a failure
+pub fn record_pool_ejection(provider: &str, member: &str) {
+    let labels = [("provider", provider.to_string()), ("member", member.to_string())];
+
+    metrics::counter!("sorai_pool_ejections_total", &labels).increment(1);
+}
+
 /// Record fallback usage
 pub fn record_fallback_usage(primary_provider: &str, fallback_provider: &str) {
     let labels = [
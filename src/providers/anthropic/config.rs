@@ -9,7 +9,27 @@ pub struct AnthropicConfig {
     pub base_url: String,
 }
 
+/// Default Anthropic API base URL
+pub const ANTHROPIC_DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Anthropic API version sent with every request
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
 impl AnthropicConfig {
+    /// Anthropic requires an API key
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    /// Base URL to send requests to
+    pub fn base_url(&self) -> &str {
+        if self.base_url.is_empty() {
+            ANTHROPIC_DEFAULT_BASE_URL
+        } else {
+            &self.base_url
+        }
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "Anthropic".to_string(),
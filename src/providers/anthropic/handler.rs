@@ -0,0 +1,367 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Instant;
+
+use super::{
+    ANTHROPIC_VERSION, AnthropicConfig, AnthropicContentBlock, AnthropicMessage, AnthropicMessagesReq,
+    AnthropicMessagesRes, AnthropicSource, AnthropicStreamTranslator, AnthropicTool, AnthropicToolChoice,
+    anthropic_finish_reason,
+};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ContentPart,
+    ExtraFields, MessageRole, RequestMessage, TextCompletionChoice, TextCompletionId, TextCompletionReq,
+    TextCompletionResponse, ToolCall,
+};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::params::{ModelParams, ToolChoice};
+use crate::providers::{
+    ChatCompletionStream, Media, MediaKind, MediaSource, ModelProvider, Provider, ProviderCapabilities, ProviderError,
+    SseDecoder, translate_stream,
+};
+
+/// Anthropic requires `max_tokens`; used when the request does not set one
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Parameters Anthropic accepts besides the ones mapped explicitly
+const PASSTHROUGH_PARAMS: &[&str] = &["top_k", "metadata"];
+
+/// Anthropic Messages API adapter
+pub struct AnthropicProvider {
+    config: AnthropicConfig,
+    client: reqwest::Client,
+}
+
+impl AnthropicProvider {
+    /// Create a new Anthropic adapter using the shared HTTP client
+    pub fn new(config: AnthropicConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+
+    /// Translate OpenAI-style messages and params into a Messages API request
+    ///
+    /// System messages are lifted into the top-level `system` field and
+    /// consecutive turns with the same role are merged, as Anthropic requires
+    /// strictly alternating user/assistant turns. Assistant tool calls become
+    /// `tool_use` blocks and `tool` messages become `tool_result` blocks of a user turn.
+    pub fn build_request(model: &str, messages: &[RequestMessage], params: Option<&Value>) -> AnthropicMessagesReq {
+        let params = ModelParams::from_value(params);
+
+        let mut system = Vec::new();
+        let mut turns: Vec<AnthropicMessage> = Vec::new();
+
+        for message in messages {
+            let text = message.text();
+
+            if message.is_system() {
+                if !text.is_empty() {
+                    system.push(text);
+                }
+                continue;
+            }
+
+            let mut blocks = Vec::new();
+            let role = match message.role {
+                MessageRole::Assistant => {
+                    if !text.is_empty() {
+                        blocks.push(AnthropicContentBlock::Text { text });
+                    }
+                    for call in message.tool_calls() {
+                        blocks.push(AnthropicContentBlock::ToolUse {
+                            input: call.function.parsed_arguments(),
+                            id: call.id.clone(),
+                            name: call.function.name.clone(),
+                        });
+                    }
+                    "assistant"
+                }
+                MessageRole::Tool => {
+                    let tool_use_id = message.tool_call_id.clone().unwrap_or_default();
+                    blocks.push(AnthropicContentBlock::ToolResult {
+                        tool_use_id,
+                        content: text,
+                    });
+                    "user"
+                }
+                _ => {
+                    blocks.extend(content_blocks(message));
+                    "user"
+                }
+            };
+
+            if blocks.is_empty() {
+                continue;
+            }
+
+            match turns.last_mut() {
+                Some(last) if last.role == role => last.content.extend(blocks),
+                _ => turns.push(AnthropicMessage {
+                    role: role.to_string(),
+                    content: blocks,
+                }),
+            }
+        }
+
+        // Anthropic has no JSON mode, so a requested schema becomes a forced tool
+        let response_tool = params.response_format().and_then(|format| format.tool());
+        let mut tool_choice = params.tool_choice().map(|choice| match choice {
+            ToolChoice::Auto => AnthropicToolChoice::Auto,
+            ToolChoice::None => AnthropicToolChoice::None,
+            ToolChoice::Required => AnthropicToolChoice::Any,
+            ToolChoice::Function(name) => AnthropicToolChoice::Tool { name },
+        });
+        if let Some(tool) = &response_tool {
+            tool_choice = Some(AnthropicToolChoice::Tool {
+                name: tool.name.clone(),
+            });
+        }
+        let tools: Vec<AnthropicTool> = params
+            .tools()
+            .into_iter()
+            .map(|tool| tool.function)
+            .chain(response_tool)
+            .map(|function| AnthropicTool {
+                input_schema: function.schema(),
+                name: function.name,
+                description: function.description,
+            })
+            .collect();
+
+        let stop_sequences = params.stop_sequences();
+
+        AnthropicMessagesReq {
+            model: model.to_string(),
+            max_tokens: params.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system: if system.is_empty() {
+                None
+            } else {
+                Some(system.join("\n\n"))
+            },
+            messages: turns,
+            stop_sequences: if stop_sequences.is_empty() {
+                None
+            } else {
+                Some(stop_sequences)
+            },
+            temperature: params.temperature,
+            top_p: params.top_p,
+            stream: None,
+            tools: if tools.is_empty() { None } else { Some(tools) },
+            tool_choice,
+            extra: params
+                .extra
+                .into_iter()
+                .filter(|(key, _)| PASSTHROUGH_PARAMS.contains(&key.as_str()))
+                .collect(),
+        }
+    }
+
+    /// Build an authenticated Messages API request
+    fn post(&self, body: &AnthropicMessagesReq) -> reqwest::RequestBuilder {
+        self.client
+            .post(join_url(self.config.base_url(), "v1/messages"))
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(body)
+    }
+
+    /// Send a Messages API request and return the raw and decoded response with latency
+    async fn send(&self, body: &AnthropicMessagesReq) -> Result<(Value, AnthropicMessagesRes, f64), ProviderError> {
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post(body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response = decode(self.name(), &raw)?;
+        Ok((raw, response, latency))
+    }
+}
+
+/// Text, image and document blocks of a user message, in order
+fn content_blocks(message: &RequestMessage) -> Vec<AnthropicContentBlock> {
+    message
+        .parts()
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } if text.is_empty() => None,
+            ContentPart::Text { text } => Some(AnthropicContentBlock::Text { text: text.clone() }),
+            part => Media::from_part(part).map(|media| {
+                let source = match media.source {
+                    MediaSource::Url(url) => AnthropicSource::Url { url },
+                    MediaSource::Base64 { media_type, data } => AnthropicSource::Base64 { media_type, data },
+                };
+                match media.kind {
+                    MediaKind::Image => AnthropicContentBlock::Image { source },
+                    MediaKind::Document => AnthropicContentBlock::Document { source },
+                }
+            }),
+        })
+        .collect()
+}
+
+/// Name of the forced tool carrying structured output, if the request asks for a schema
+fn response_tool_name(params: Option<&Value>) -> Option<String> {
+    ModelParams::from_value(params)
+        .response_format()
+        .and_then(|format| format.tool())
+        .map(|tool| tool.name)
+}
+
+/// Tool calls requested by the `tool_use` blocks of an Anthropic response
+///
+/// A call to the structured output tool is not a tool call; its input is the content.
+fn response_tool_calls(response: &AnthropicMessagesRes, response_tool: Option<&str>) -> Option<Vec<ToolCall>> {
+    let calls: Vec<ToolCall> = response
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            AnthropicContentBlock::ToolUse { name, .. } if Some(name.as_str()) == response_tool => None,
+            AnthropicContentBlock::ToolUse { id, name, input } => {
+                Some(ToolCall::function(id.clone(), name.clone(), input.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    (!calls.is_empty()).then_some(calls)
+}
+
+/// Concatenate the text blocks of an Anthropic response, or the input of the structured output tool call
+fn response_text(response: &AnthropicMessagesRes, response_tool: Option<&str>) -> String {
+    let output = response.content.iter().find_map(|block| match block {
+        AnthropicContentBlock::ToolUse { name, input, .. } if Some(name.as_str()) == response_tool => {
+            Some(input.to_string())
+        }
+        _ => None,
+    });
+    if let Some(output) = output {
+        return output;
+    }
+
+    response
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            AnthropicContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::Anthropic
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: true,
+            image_urls: true,
+            embeddings: false,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = Self::build_request(model, &request.messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(&body).await?;
+
+        let response_tool = response_tool_name(request.params.as_ref());
+        let tool_calls = response_tool_calls(&response, response_tool.as_deref());
+        let finish_reason = match response.stop_reason.as_deref() {
+            Some("tool_use") if tool_calls.is_none() => "stop".to_string(),
+            stop_reason => anthropic_finish_reason(stop_reason),
+        };
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: response_text(&response, response_tool.as_deref()),
+                    tool_calls,
+                    tool_call_id: None,
+                },
+                finish_reason,
+            }],
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model.clone()
+            },
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        let mut body = Self::build_request(model, &request.messages, request.params.as_ref());
+        body.stream = Some(true);
+        let response = send_stream(self.name(), self.post(&body)).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            SseDecoder::new(),
+            AnthropicStreamTranslator::new(self.name(), model)
+                .with_response_tool(response_tool_name(request.params.as_ref())),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        // Anthropic has no legacy completions API, so the prompt is sent as a single user turn
+        let messages = [RequestMessage::new(
+            MessageRole::User,
+            request.text.clone().unwrap_or_default(),
+        )];
+        let body = Self::build_request(model, &messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(&body).await?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: vec![TextCompletionChoice {
+                index: 0,
+                text: response_text(&response, response_tool_name(request.params.as_ref()).as_deref()),
+                finish_reason: anthropic_finish_reason(response.stop_reason.as_deref()),
+            }],
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model.clone()
+            },
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+}
@@ -2,8 +2,10 @@
 
 mod config;
 mod handler;
+mod stream;
 mod types;
 
 pub use config::*;
 pub use handler::*;
+pub use stream::*;
 pub use types::*;
@@ -0,0 +1,119 @@
+use super::{
+    AnthropicContentBlock, AnthropicStreamDelta, AnthropicStreamEvent, AnthropicUsage, anthropic_finish_reason,
+};
+use crate::http::schemas::completions::{ChatCompletionChunk, ToolCallDelta};
+use crate::providers::client::decode_str;
+use crate::providers::{ChunkBuilder, ChunkTranslator, ProviderError, SseEvent};
+
+/// Translates Messages API streaming events into chat completion chunks
+///
+/// Input tokens are reported by `message_start` and output tokens by
+/// `message_delta`; both are combined into the usage chunk sent on `message_stop`.
+/// Each `tool_use` block becomes a tool call, numbered in order of appearance,
+/// except calls to the structured output tool, whose input is streamed as content.
+pub struct AnthropicStreamTranslator {
+    provider: String,
+    chunks: ChunkBuilder,
+    usage: AnthropicUsage,
+    tool_calls: i32,
+    /// Tool call index of the `tool_use` block currently streamed, if any
+    tool_call: Option<i32>,
+    /// Name of the tool forced to carry structured output
+    response_tool: Option<String>,
+    /// Whether the block currently streamed is the structured output
+    response_block: bool,
+}
+
+impl AnthropicStreamTranslator {
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            chunks: ChunkBuilder::new(model),
+            usage: AnthropicUsage::default(),
+            tool_calls: 0,
+            tool_call: None,
+            response_tool: None,
+            response_block: false,
+        }
+    }
+
+    /// Stream the input of calls to the named tool as message content
+    pub fn with_response_tool(mut self, name: Option<String>) -> Self {
+        self.response_tool = name;
+        self
+    }
+}
+
+impl ChunkTranslator for AnthropicStreamTranslator {
+    type Frame = SseEvent;
+
+    fn translate(&mut self, event: SseEvent) -> Result<Vec<ChatCompletionChunk>, ProviderError> {
+        let event: AnthropicStreamEvent = decode_str(&self.provider, &event.data)?;
+
+        let chunks = match event {
+            AnthropicStreamEvent::MessageStart { message } => {
+                self.chunks.set_model(&message.model);
+                self.usage = message.usage;
+                vec![self.chunks.role()]
+            }
+            AnthropicStreamEvent::ContentBlockStart {
+                content_block: AnthropicContentBlock::ToolUse { name, .. },
+                ..
+            } if self.response_tool.as_ref() == Some(&name) => {
+                self.tool_call = None;
+                self.response_block = true;
+                Vec::new()
+            }
+            AnthropicStreamEvent::ContentBlockStart {
+                content_block: AnthropicContentBlock::ToolUse { id, name, .. },
+                ..
+            } => {
+                self.response_block = false;
+                let index = self.tool_calls;
+                self.tool_calls += 1;
+                self.tool_call = Some(index);
+                vec![self.chunks.tool_call(ToolCallDelta::start(index, id, name))]
+            }
+            AnthropicStreamEvent::ContentBlockStart { .. } => {
+                self.tool_call = None;
+                self.response_block = false;
+                Vec::new()
+            }
+            AnthropicStreamEvent::ContentBlockDelta {
+                delta: AnthropicStreamDelta::TextDelta { text },
+                ..
+            } => vec![self.chunks.content(text)],
+            AnthropicStreamEvent::ContentBlockDelta {
+                delta: AnthropicStreamDelta::InputJsonDelta { partial_json },
+                ..
+            } => match self.tool_call {
+                _ if partial_json.is_empty() => Vec::new(),
+                _ if self.response_block => vec![self.chunks.content(partial_json)],
+                Some(index) => {
+                    vec![self.chunks.tool_call(ToolCallDelta::arguments(index, partial_json))]
+                }
+                _ => Vec::new(),
+            },
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                if let Some(usage) = usage {
+                    self.usage.output_tokens = usage.output_tokens;
+                }
+                let finish_reason = match delta.stop_reason.as_deref() {
+                    Some("tool_use") if self.tool_calls == 0 => "stop".to_string(),
+                    stop_reason => anthropic_finish_reason(stop_reason),
+                };
+                vec![self.chunks.finish(finish_reason)]
+            }
+            AnthropicStreamEvent::MessageStop => vec![self.chunks.usage(self.usage.into())],
+            AnthropicStreamEvent::Error { error } => {
+                return Err(ProviderError::Upstream {
+                    provider: self.provider.clone(),
+                    status: error.status(),
+                    message: error.message,
+                });
+            }
+            _ => Vec::new(),
+        };
+        Ok(chunks)
+    }
+}
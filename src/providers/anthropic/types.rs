@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::http::schemas::completions::UsageInfo;
+
+// AnthropicMessagesReq represents an Anthropic Messages API request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AnthropicMessagesReq {
+    pub model: String,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<AnthropicTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<AnthropicToolChoice>,
+    /// Anthropic specific parameters such as `top_k` or `metadata`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// AnthropicMessage represents a single conversation turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicMessage {
+    pub role: String,
+    pub content: Vec<AnthropicContentBlock>,
+}
+
+// AnthropicContentBlock represents a content block inside a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: AnthropicSource,
+    },
+    Document {
+        source: AnthropicSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+// AnthropicSource represents the data of an image or document block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+// AnthropicTool represents a tool definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicTool {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
+
+// AnthropicToolChoice represents how the model should use the tools
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicToolChoice {
+    Auto,
+    Any,
+    Tool { name: String },
+    None,
+}
+
+// AnthropicMessagesRes represents an Anthropic Messages API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicMessagesRes {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub role: String,
+    #[serde(default)]
+    pub content: Vec<AnthropicContentBlock>,
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+    #[serde(default)]
+    pub usage: AnthropicUsage,
+}
+
+// AnthropicUsage represents token usage reported by Anthropic
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct AnthropicUsage {
+    #[serde(default)]
+    pub input_tokens: i32,
+    #[serde(default)]
+    pub output_tokens: i32,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<i32>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<i32>,
+}
+
+// AnthropicStreamEvent represents a Messages API streaming event
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamEvent {
+    MessageStart {
+        message: AnthropicStreamMessage,
+    },
+    ContentBlockStart {
+        #[serde(default)]
+        index: i32,
+        content_block: AnthropicContentBlock,
+    },
+    ContentBlockDelta {
+        #[serde(default)]
+        index: i32,
+        delta: AnthropicStreamDelta,
+    },
+    MessageDelta {
+        delta: AnthropicMessageDelta,
+        #[serde(default)]
+        usage: Option<AnthropicUsage>,
+    },
+    MessageStop,
+    Error {
+        error: AnthropicStreamError,
+    },
+    /// `ping`, `content_block_stop` and event types added in the future
+    #[serde(other)]
+    Other,
+}
+
+// AnthropicStreamMessage represents the message announced by `message_start`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnthropicStreamMessage {
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub usage: AnthropicUsage,
+}
+
+// AnthropicStreamDelta represents the delta of a `content_block_delta` event
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+// AnthropicMessageDelta represents the delta of a `message_delta` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnthropicMessageDelta {
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+}
+
+// AnthropicStreamError represents an error reported in the middle of a stream
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnthropicStreamError {
+    #[serde(default, rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl AnthropicStreamError {
+    /// HTTP status Anthropic uses for the same error type outside of a stream
+    pub fn status(&self) -> u16 {
+        match self.kind.as_str() {
+            "invalid_request_error" => 400,
+            "rate_limit_error" => 429,
+            "overloaded_error" => 529,
+            _ => 500,
+        }
+    }
+}
+
+impl From<AnthropicUsage> for UsageInfo {
+    fn from(usage: AnthropicUsage) -> Self {
+        // Cached prompt tokens are billed as input as well
+        let prompt_tokens = usage.input_tokens
+            + usage.cache_creation_input_tokens.unwrap_or(0)
+            + usage.cache_read_input_tokens.unwrap_or(0);
+        UsageInfo {
+            prompt_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: prompt_tokens + usage.output_tokens,
+        }
+    }
+}
+
+/// Map an Anthropic `stop_reason` to an OpenAI-style `finish_reason`
+pub fn anthropic_finish_reason(stop_reason: Option<&str>) -> String {
+    match stop_reason {
+        Some("max_tokens") => "length",
+        Some("tool_use") => "tool_calls",
+        Some("refusal") => "content_filter",
+        _ => "stop",
+    }
+    .to_string()
+}
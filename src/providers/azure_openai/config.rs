@@ -1,5 +1,9 @@
 use crate::config::{ConfigItem, redact_sensitive};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Azure OpenAI data-plane API version used when none is configured
+pub const AZURE_OPENAI_DEFAULT_API_VERSION: &str = "2024-10-21";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AzureOpenAIConfig {
@@ -7,9 +11,76 @@ pub struct AzureOpenAIConfig {
     pub api_key: String,
     #[serde(default)]
     pub endpoint: String,
+    #[serde(default)]
+    pub api_version: String,
+    /// Public model name to Azure deployment mapping
+    #[serde(default)]
+    pub deployments: BTreeMap<String, AzureDeployment>,
+}
+
+/// Azure deployment serving a public model name
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct AzureDeployment {
+    pub deployment: String,
+    /// Overrides the provider wide `api_version` for this deployment
+    #[serde(default)]
+    pub api_version: Option<String>,
 }
 
 impl AzureOpenAIConfig {
+    /// Azure needs both an API key and the resource endpoint
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty() && !self.endpoint.is_empty()
+    }
+
+    /// Provider wide API version
+    pub fn api_version(&self) -> &str {
+        if self.api_version.is_empty() {
+            AZURE_OPENAI_DEFAULT_API_VERSION
+        } else {
+            &self.api_version
+        }
+    }
+
+    /// Resolve a public model name to its deployment name and API version
+    ///
+    /// Models without a mapping are assumed to be deployed under their own name.
+    pub fn resolve(&self, model: &str) -> (String, String) {
+        match self.deployments.get(model) {
+            Some(entry) => (
+                entry.deployment.clone(),
+                entry
+                    .api_version
+                    .clone()
+                    .unwrap_or_else(|| self.api_version().to_string()),
+            ),
+            None => (model.to_string(), self.api_version().to_string()),
+        }
+    }
+
+    /// Parse a deployment list in the `model=deployment[@api-version],...` format
+    pub fn parse_deployments(value: &str) -> BTreeMap<String, AzureDeployment> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (model, target) = entry.split_once('=').unwrap_or((entry, entry));
+                let (deployment, api_version) = match target.split_once('@') {
+                    Some((deployment, version)) => (deployment, Some(version.trim().to_string())),
+                    None => (target, None),
+                };
+                (
+                    model.trim().to_string(),
+                    AzureDeployment {
+                        deployment: deployment.trim().to_string(),
+                        api_version,
+                    },
+                )
+            })
+            .collect()
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "Azure OpenAI".to_string(),
@@ -25,5 +96,26 @@ impl AzureOpenAIConfig {
                 self.endpoint.clone()
             },
         });
+        items.push(ConfigItem {
+            section: "Azure OpenAI".to_string(),
+            key: "API Version".to_string(),
+            value: self.api_version().to_string(),
+        });
+        items.push(ConfigItem {
+            section: "Azure OpenAI".to_string(),
+            key: "Deployments".to_string(),
+            value: if self.deployments.is_empty() {
+                "<not set>".to_string()
+            } else {
+                self.deployments
+                    .iter()
+                    .map(|(model, entry)| match &entry.api_version {
+                        Some(version) => format!("{}={}@{}", model, entry.deployment, version),
+                        None => format!("{}={}", model, entry.deployment),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        });
     }
 }
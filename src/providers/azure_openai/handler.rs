@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use std::time::Instant;
+
+use super::{AzureOpenAIConfig, azure_content_filter};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ExtraFields,
+    TextCompletionChoice, TextCompletionId, TextCompletionReq, TextCompletionResponse,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::openai::{
+    OpenAIChatRes, OpenAIEmbeddingRes, OpenAIProvider, OpenAIStreamTranslator, OpenAITextRes,
+};
+use crate::providers::{
+    ChatCompletionStream, ModelProvider, Provider, ProviderCapabilities, ProviderError, SseDecoder, translate_stream,
+};
+
+/// Azure OpenAI adapter
+///
+/// Azure speaks the OpenAI wire format, but addresses models by deployment and
+/// authenticates with an `api-key` header.
+pub struct AzureOpenAIProvider {
+    config: AzureOpenAIConfig,
+    client: reqwest::Client,
+}
+
+impl AzureOpenAIProvider {
+    /// Create a new Azure OpenAI adapter using the shared HTTP client
+    pub fn new(config: AzureOpenAIConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+
+    /// URL of a deployment operation (e.g. `chat/completions`) for a public model name
+    pub fn deployment_url(&self, model: &str, operation: &str) -> String {
+        let (deployment, api_version) = self.config.resolve(model);
+        format!(
+            "{}?api-version={}",
+            join_url(
+                &self.config.endpoint,
+                &format!("openai/deployments/{}/{}", deployment, operation)
+            ),
+            api_version
+        )
+    }
+
+    /// Build an authenticated POST request for a deployment operation
+    fn post(&self, model: &str, operation: &str) -> reqwest::RequestBuilder {
+        self.client
+            .post(self.deployment_url(model, operation))
+            .header("api-key", &self.config.api_key)
+    }
+}
+
+#[async_trait]
+impl Provider for AzureOpenAIProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::AzureOpenAI
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: true,
+            image_urls: true,
+            embeddings: true,
+        }
+    }
+
+    fn models(&self) -> Vec<String> {
+        self.config.deployments.keys().cloned().collect()
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = OpenAIProvider::build_chat_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post(model, "chat/completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIChatRes = decode(self.name(), &raw)?;
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| ChatCompletionChoice {
+                    index: choice.index,
+                    message: ChatMessage {
+                        role: choice.message.role,
+                        content: choice.message.content.unwrap_or_default(),
+                        tool_calls: choice.message.tool_calls,
+                        tool_call_id: None,
+                    },
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                content_filter: azure_content_filter(&raw),
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        let body = OpenAIProvider::build_chat_stream_request(model, request);
+        let response = send_stream(self.name(), self.post(model, "chat/completions").json(&body)).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            SseDecoder::new(),
+            OpenAIStreamTranslator::new(self.name(), model),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        let body = OpenAIProvider::build_text_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post(model, "completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAITextRes = decode(self.name(), &raw)?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| TextCompletionChoice {
+                    index: choice.index,
+                    text: choice.text,
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                content_filter: azure_content_filter(&raw),
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        let body = OpenAIProvider::build_embedding_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post(model, "embeddings").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIEmbeddingRes = decode(self.name(), &raw)?;
+
+        Ok(EmbeddingResponse::new(
+            if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model.clone()
+            },
+            response.vectors(),
+            response.usage.map(Into::into),
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
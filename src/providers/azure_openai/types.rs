@@ -0,0 +1,34 @@
+use serde_json::{Value, json};
+
+/// Collect Azure content filter annotations from a raw chat or text completion response
+///
+/// Azure reports `prompt_filter_results` at the top level and `content_filter_results`
+/// on every choice. Returns `None` when the response carries neither.
+pub fn azure_content_filter(raw: &Value) -> Option<Value> {
+    let prompt = raw.get("prompt_filter_results").cloned();
+    let choices: Vec<Value> = raw["choices"]
+        .as_array()
+        .map(|choices| {
+            choices
+                .iter()
+                .filter_map(|choice| {
+                    choice.get("content_filter_results").map(|results| {
+                        json!({
+                            "index": choice["index"],
+                            "content_filter_results": results,
+                        })
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if prompt.is_none() && choices.is_empty() {
+        return None;
+    }
+
+    Some(json!({
+        "prompt_filter_results": prompt.unwrap_or(Value::Null),
+        "choices": choices,
+    }))
+}
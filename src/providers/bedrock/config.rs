@@ -1,17 +1,55 @@
 use crate::config::{ConfigItem, redact_sensitive};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BedrockConfig {
     #[serde(default)]
     pub api_key: String,
     #[serde(default)]
     pub access_key: String,
     #[serde(default)]
+    pub secret_key: String,
+    #[serde(default)]
+    pub session_token: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    #[serde(default)]
     pub base_url: String,
 }
 
+impl Default for BedrockConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            session_token: String::new(),
+            region: default_region(),
+            base_url: String::new(),
+        }
+    }
+}
+
 impl BedrockConfig {
+    /// Bedrock is usable with a Bedrock API key or with an AWS access/secret key pair
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty() || self.uses_sigv4()
+    }
+
+    /// Whether requests are signed with SigV4 instead of using the Bedrock API key
+    pub fn uses_sigv4(&self) -> bool {
+        self.api_key.is_empty() && !self.access_key.is_empty() && !self.secret_key.is_empty()
+    }
+
+    /// Base URL of the Bedrock runtime endpoint for the configured region
+    pub fn base_url(&self) -> String {
+        if self.base_url.is_empty() {
+            format!("https://bedrock-runtime.{}.amazonaws.com", self.region)
+        } else {
+            self.base_url.clone()
+        }
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "AWS Bedrock".to_string(),
@@ -23,6 +61,21 @@ impl BedrockConfig {
             key: "Access Key".to_string(),
             value: redact_sensitive(&self.access_key),
         });
+        items.push(ConfigItem {
+            section: "AWS Bedrock".to_string(),
+            key: "Secret Key".to_string(),
+            value: redact_sensitive(&self.secret_key),
+        });
+        items.push(ConfigItem {
+            section: "AWS Bedrock".to_string(),
+            key: "Session Token".to_string(),
+            value: redact_sensitive(&self.session_token),
+        });
+        items.push(ConfigItem {
+            section: "AWS Bedrock".to_string(),
+            key: "Region".to_string(),
+            value: self.region.clone(),
+        });
         items.push(ConfigItem {
             section: "AWS Bedrock".to_string(),
             key: "Base URL".to_string(),
@@ -34,3 +87,7 @@ impl BedrockConfig {
         });
     }
 }
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
@@ -0,0 +1,218 @@
+use crate::providers::FrameDecoder;
+
+/// Length of the prelude: total length, headers length and prelude CRC
+const PRELUDE_LEN: usize = 12;
+
+/// Length of the CRC trailing every message
+const MESSAGE_CRC_LEN: usize = 4;
+
+/// Upper bound of a single message, as documented by AWS
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+/// A message of the `application/vnd.amazon.eventstream` encoding
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventStreamMessage {
+    /// String headers such as `:event-type`; headers of other types are skipped
+    pub headers: Vec<(String, String)>,
+    pub payload: Vec<u8>,
+}
+
+impl EventStreamMessage {
+    /// Value of a string header
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Incremental decoder of the AWS event stream binary encoding
+///
+/// Each message is laid out as a 12 byte prelude (total length, headers
+/// length, prelude CRC32), the headers, the payload and a CRC32 of the
+/// whole message. Both checksums are verified.
+#[derive(Debug, Default)]
+pub struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the message at the start of the buffer, if it is complete
+    fn next_message(&mut self) -> Result<Option<EventStreamMessage>, String> {
+        if self.buffer.len() < PRELUDE_LEN {
+            return Ok(None);
+        }
+
+        let total_len = read_u32(&self.buffer[0..4]) as usize;
+        let headers_len = read_u32(&self.buffer[4..8]) as usize;
+        let prelude_crc = read_u32(&self.buffer[8..12]);
+
+        if crc32(&self.buffer[0..8]) != prelude_crc {
+            return Err("event stream prelude checksum mismatch".to_string());
+        }
+        if total_len > MAX_MESSAGE_LEN || total_len < PRELUDE_LEN + headers_len + MESSAGE_CRC_LEN {
+            return Err(format!("invalid event stream message length {}", total_len));
+        }
+        if self.buffer.len() < total_len {
+            return Ok(None);
+        }
+
+        let message: Vec<u8> = self.buffer.drain(..total_len).collect();
+        let message_crc = read_u32(&message[total_len - MESSAGE_CRC_LEN..]);
+        if crc32(&message[..total_len - MESSAGE_CRC_LEN]) != message_crc {
+            return Err("event stream message checksum mismatch".to_string());
+        }
+
+        let headers = parse_headers(&message[PRELUDE_LEN..PRELUDE_LEN + headers_len])?;
+        let payload = message[PRELUDE_LEN + headers_len..total_len - MESSAGE_CRC_LEN].to_vec();
+        Ok(Some(EventStreamMessage { headers, payload }))
+    }
+}
+
+impl FrameDecoder for EventStreamDecoder {
+    type Frame = EventStreamMessage;
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<EventStreamMessage>, String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut messages = Vec::new();
+        while let Some(message) = self.next_message()? {
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+}
+
+/// Parse the header section of a message, keeping string headers only
+fn parse_headers(mut bytes: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let mut headers = Vec::new();
+
+    while !bytes.is_empty() {
+        let name_len = bytes[0] as usize;
+        let name = take(&mut bytes, 1 + name_len)?[1..].to_vec();
+        let name = String::from_utf8(name).map_err(|e| e.to_string())?;
+        let value_type = take(&mut bytes, 1)?[0];
+
+        let value_len = match value_type {
+            // boolean true / false carry no value
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            // byte array and string are prefixed with a 2 byte length
+            6 | 7 => {
+                let len = take(&mut bytes, 2)?;
+                u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            other => return Err(format!("unknown event stream header type {}", other)),
+        };
+        let value = take(&mut bytes, value_len)?;
+
+        if value_type == 7 {
+            let value = String::from_utf8(value.to_vec()).map_err(|e| e.to_string())?;
+            headers.push((name, value));
+        }
+    }
+
+    Ok(headers)
+}
+
+/// Split `len` bytes off the front of a slice
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if bytes.len() < len {
+        return Err("truncated event stream headers".to_string());
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// CRC-32 (IEEE 802.3), as used by the event stream checksums
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(event_type: &str, payload: &[u8]) -> Vec<u8> {
+        let mut headers = Vec::new();
+        for (name, value) in [(":message-type", "event"), (":event-type", event_type)] {
+            headers.push(name.len() as u8);
+            headers.extend_from_slice(name.as_bytes());
+            headers.push(7);
+            headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            headers.extend_from_slice(value.as_bytes());
+        }
+        // a non-string header, which the decoder skips
+        headers.push(2);
+        headers.extend_from_slice(b"id");
+        headers.push(4);
+        headers.extend_from_slice(&7u32.to_be_bytes());
+
+        let total_len = (PRELUDE_LEN + headers.len() + payload.len() + MESSAGE_CRC_LEN) as u32;
+        let mut message = Vec::new();
+        message.extend_from_slice(&total_len.to_be_bytes());
+        message.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+        message.extend_from_slice(&crc32(&message).to_be_bytes());
+        message.extend_from_slice(&headers);
+        message.extend_from_slice(payload);
+        message.extend_from_slice(&crc32(&message).to_be_bytes());
+        message
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_decode_messages_split_across_reads() {
+        let mut bytes = encode("messageStart", br#"{"role":"assistant"}"#);
+        bytes.extend(encode("contentBlockDelta", br#"{"delta":{"text":"Hi"}}"#));
+
+        let mut decoder = EventStreamDecoder::new();
+        let mut messages = decoder.decode(&bytes[..5]).unwrap();
+        assert!(messages.is_empty());
+        messages.extend(decoder.decode(&bytes[5..40]).unwrap());
+        messages.extend(decoder.decode(&bytes[40..]).unwrap());
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].header(":event-type"), Some("messageStart"));
+        assert_eq!(messages[0].header(":message-type"), Some("event"));
+        assert_eq!(messages[0].header("id"), None);
+        assert_eq!(messages[0].payload, br#"{"role":"assistant"}"#);
+        assert_eq!(messages[1].header(":event-type"), Some("contentBlockDelta"));
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupted_message() {
+        let mut bytes = encode("messageStop", br#"{"stopReason":"end_turn"}"#);
+        let last = bytes.len() - 6;
+        bytes[last] ^= 0xFF;
+
+        let mut decoder = EventStreamDecoder::new();
+        assert!(decoder.decode(&bytes).unwrap_err().contains("checksum"));
+    }
+}
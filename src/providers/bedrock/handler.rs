@@ -0,0 +1,535 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::time::Instant;
+
+use super::{
+    BedrockBytes, BedrockCohereEmbedReq, BedrockCohereEmbedRes, BedrockConfig, BedrockContentBlock, BedrockConverseReq,
+    BedrockConverseRes, BedrockDocument, BedrockImage, BedrockInferenceConfig, BedrockMessage, BedrockStreamTranslator,
+    BedrockSystemBlock, BedrockTitanEmbedReq, BedrockTitanEmbedRes, BedrockTool, BedrockToolConfig, BedrockToolResult,
+    BedrockToolSpec, BedrockToolUse, EventStreamDecoder, SigV4Signer, bedrock_finish_reason, uri_encode,
+};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ContentPart,
+    ExtraFields, MessageRole, RequestMessage, TextCompletionChoice, TextCompletionId, TextCompletionReq,
+    TextCompletionResponse, ToolCall, UsageInfo,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::params::{ModelParams, ToolChoice};
+use crate::providers::{
+    ChatCompletionStream, Media, MediaKind, MediaSource, ModelProvider, Provider, ProviderCapabilities, ProviderError,
+    translate_stream,
+};
+
+/// Service name used in the SigV4 credential scope
+const SIGNING_SERVICE: &str = "bedrock";
+
+/// Parameters forwarded as `additionalModelRequestFields` besides the ones mapped explicitly
+const PASSTHROUGH_PARAMS: &[&str] = &["top_k"];
+
+/// `input_type` sent to Cohere embedding models when the request does not set one
+const DEFAULT_COHERE_INPUT_TYPE: &str = "search_document";
+
+/// AWS Bedrock Converse API adapter
+pub struct BedrockProvider {
+    config: BedrockConfig,
+    client: reqwest::Client,
+}
+
+impl BedrockProvider {
+    /// Create a new Bedrock adapter using the shared HTTP client
+    pub fn new(config: BedrockConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+
+    /// Translate OpenAI-style messages and params into a Converse request
+    ///
+    /// System messages are lifted into the top-level `system` blocks and
+    /// consecutive turns with the same role are merged, as Converse requires
+    /// alternating user/assistant turns. Assistant tool calls become `toolUse`
+    /// blocks and `tool` messages become `toolResult` blocks of a user turn.
+    pub fn build_request(messages: &[RequestMessage], params: Option<&Value>) -> BedrockConverseReq {
+        let params = ModelParams::from_value(params);
+
+        let mut system = Vec::new();
+        let mut turns: Vec<BedrockMessage> = Vec::new();
+
+        for message in messages {
+            let text = message.text();
+
+            if message.is_system() {
+                if !text.is_empty() {
+                    system.push(BedrockSystemBlock { text });
+                }
+                continue;
+            }
+
+            let mut blocks = Vec::new();
+            let role = match message.role {
+                MessageRole::Assistant => {
+                    if !text.is_empty() {
+                        blocks.push(BedrockContentBlock::Text { text });
+                    }
+                    for call in message.tool_calls() {
+                        blocks.push(BedrockContentBlock::ToolUse {
+                            tool_use: BedrockToolUse {
+                                input: call.function.parsed_arguments(),
+                                tool_use_id: call.id.clone(),
+                                name: call.function.name.clone(),
+                            },
+                        });
+                    }
+                    "assistant"
+                }
+                MessageRole::Tool => {
+                    let tool_use_id = message.tool_call_id.clone().unwrap_or_default();
+                    blocks.push(BedrockContentBlock::ToolResult {
+                        tool_result: BedrockToolResult {
+                            tool_use_id,
+                            content: vec![BedrockContentBlock::Text { text }],
+                        },
+                    });
+                    "user"
+                }
+                _ => {
+                    blocks.extend(content_blocks(message));
+                    "user"
+                }
+            };
+
+            if blocks.is_empty() {
+                continue;
+            }
+
+            match turns.last_mut() {
+                Some(last) if last.role == role => last.content.extend(blocks),
+                _ => turns.push(BedrockMessage {
+                    role: role.to_string(),
+                    content: blocks,
+                }),
+            }
+        }
+
+        // Converse has no JSON mode, so a requested schema becomes a forced tool
+        let response_tool = params.response_format().and_then(|format| format.tool());
+        // Converse has no way to forbid tool use, so `none` falls back to the default `auto`
+        let mut tool_choice = params.tool_choice().and_then(|choice| match choice {
+            ToolChoice::Auto | ToolChoice::None => None,
+            ToolChoice::Required => Some(json!({ "any": {} })),
+            ToolChoice::Function(name) => Some(json!({ "tool": { "name": name } })),
+        });
+        if let Some(tool) = &response_tool {
+            tool_choice = Some(json!({ "tool": { "name": tool.name } }));
+        }
+        let tools: Vec<BedrockTool> = params
+            .tools()
+            .into_iter()
+            .map(|tool| tool.function)
+            .chain(response_tool)
+            .map(|function| BedrockTool {
+                tool_spec: BedrockToolSpec {
+                    input_schema: json!({ "json": function.schema() }),
+                    name: function.name,
+                    description: function.description,
+                },
+            })
+            .collect();
+
+        let inference_config = BedrockInferenceConfig {
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            top_p: params.top_p,
+            stop_sequences: params.stop_sequences(),
+        };
+        let has_inference_config = inference_config.max_tokens.is_some()
+            || inference_config.temperature.is_some()
+            || inference_config.top_p.is_some()
+            || !inference_config.stop_sequences.is_empty();
+
+        let additional: Map<String, Value> = params
+            .extra
+            .into_iter()
+            .filter(|(key, _)| PASSTHROUGH_PARAMS.contains(&key.as_str()))
+            .collect();
+
+        BedrockConverseReq {
+            messages: turns,
+            system,
+            inference_config: has_inference_config.then_some(inference_config),
+            additional_model_request_fields: if additional.is_empty() {
+                None
+            } else {
+                Some(Value::Object(additional))
+            },
+            tool_config: if tools.is_empty() {
+                None
+            } else {
+                Some(BedrockToolConfig { tools, tool_choice })
+            },
+        }
+    }
+
+    /// Build an authenticated request for a model action such as `converse` or `invoke`
+    fn post<T: Serialize>(
+        &self,
+        model: &str,
+        action: &str,
+        body: &T,
+    ) -> Result<reqwest::RequestBuilder, ProviderError> {
+        let url = join_url(
+            &self.config.base_url(),
+            &format!("model/{}/{}", uri_encode(model), action),
+        );
+        let payload = serde_json::to_vec(body).map_err(|e| ProviderError::InvalidRequest(e.to_string()))?;
+
+        let mut request = self.client.post(&url).header("content-type", "application/json");
+
+        if self.config.uses_sigv4() {
+            let parsed = reqwest::Url::parse(&url).map_err(|e| ProviderError::InvalidRequest(e.to_string()))?;
+            let signer = self.signer();
+            for (name, value) in signer.sign("POST", &parsed, &[], &payload, chrono::Utc::now()) {
+                request = request.header(name, value);
+            }
+        } else {
+            request = request.bearer_auth(&self.config.api_key);
+        }
+
+        Ok(request.body(payload))
+    }
+
+    /// Send a Converse request and return the raw and decoded response with latency
+    async fn send(
+        &self,
+        model: &str,
+        body: &BedrockConverseReq,
+    ) -> Result<(Value, BedrockConverseRes, f64), ProviderError> {
+        let request = self.post(model, "converse", body)?.header("accept", "application/json");
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), request).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response = decode(self.name(), &raw)?;
+        Ok((raw, response, latency))
+    }
+
+    /// Call InvokeModel and decode the model specific response
+    async fn invoke<T: Serialize, R: DeserializeOwned>(&self, model: &str, body: &T) -> Result<R, ProviderError> {
+        let request = self.post(model, "invoke", body)?.header("accept", "application/json");
+        let raw = send_json(self.name(), request).await?;
+        decode(self.name(), &raw)
+    }
+
+    /// Embed inputs with a Titan model, which accepts a single text per call
+    async fn embed_titan(&self, model: &str, request: &EmbeddingReq) -> Result<(Vec<Vec<f32>>, i32), ProviderError> {
+        let mut vectors = Vec::new();
+        let mut tokens = 0;
+        for input in request.inputs() {
+            let body = BedrockTitanEmbedReq {
+                input_text: input,
+                dimensions: request.dimensions,
+            };
+            let response: BedrockTitanEmbedRes = self.invoke(model, &body).await?;
+            tokens += response.input_text_token_count;
+            vectors.push(response.embedding);
+        }
+        Ok((vectors, tokens))
+    }
+
+    /// Embed inputs with a Cohere model in a single call; Bedrock does not report its token count in the body
+    async fn embed_cohere(&self, model: &str, request: &EmbeddingReq) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let body = BedrockCohereEmbedReq {
+            texts: request.inputs(),
+            input_type: request
+                .param_str("input_type")
+                .unwrap_or(DEFAULT_COHERE_INPUT_TYPE)
+                .to_string(),
+        };
+        let response: BedrockCohereEmbedRes = self.invoke(model, &body).await?;
+        Ok(response.embeddings)
+    }
+
+    /// SigV4 signer for the configured AWS credentials and region
+    fn signer(&self) -> SigV4Signer {
+        SigV4Signer {
+            access_key: self.config.access_key.clone(),
+            secret_key: self.config.secret_key.clone(),
+            session_token: if self.config.session_token.is_empty() {
+                None
+            } else {
+                Some(self.config.session_token.clone())
+            },
+            region: self.config.region.clone(),
+            service: SIGNING_SERVICE.to_string(),
+        }
+    }
+}
+
+/// Text, image and document blocks of a user message, in order
+///
+/// Converse only takes inline bytes, so media still referenced by URL is skipped;
+/// HTTP URLs are inlined by the gateway before the request reaches this adapter.
+fn content_blocks(message: &RequestMessage) -> Vec<BedrockContentBlock> {
+    message
+        .parts()
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } if text.is_empty() => None,
+            ContentPart::Text { text } => Some(BedrockContentBlock::Text { text: text.clone() }),
+            part => {
+                let media = Media::from_part(part)?;
+                let MediaSource::Base64 { media_type, data } = media.source else {
+                    return None;
+                };
+                let source = BedrockBytes { bytes: data };
+                Some(match media.kind {
+                    MediaKind::Image => BedrockContentBlock::Image {
+                        image: BedrockImage {
+                            format: media_type.trim_start_matches("image/").to_string(),
+                            source,
+                        },
+                    },
+                    MediaKind::Document => BedrockContentBlock::Document {
+                        document: BedrockDocument {
+                            format: document_format(&media_type).to_string(),
+                            name: document_name(media.filename.as_deref()),
+                            source,
+                        },
+                    },
+                })
+            }
+        })
+        .collect()
+}
+
+/// Converse document format for a MIME type
+fn document_format(media_type: &str) -> &str {
+    match media_type {
+        "text/plain" => "txt",
+        "text/markdown" => "md",
+        "text/csv" => "csv",
+        "text/html" => "html",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        _ => "pdf",
+    }
+}
+
+/// Document name Converse accepts: the file stem with characters other than
+/// alphanumerics, single spaces, hyphens, parentheses and brackets replaced
+fn document_name(filename: Option<&str>) -> String {
+    let stem = filename
+        .map(|name| name.rsplit_once('.').map_or(name, |(stem, _)| stem))
+        .unwrap_or_default();
+    let name: String = stem
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || "-()[] ".contains(c) => c,
+            _ => '-',
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if name.is_empty() { "document".to_string() } else { name }
+}
+
+/// Name of the forced tool carrying structured output, if the request asks for a schema
+fn response_tool_name(params: Option<&Value>) -> Option<String> {
+    ModelParams::from_value(params)
+        .response_format()
+        .and_then(|format| format.tool())
+        .map(|tool| tool.name)
+}
+
+/// Tool calls requested by the `toolUse` blocks of a Converse response
+///
+/// A call to the structured output tool is not a tool call; its input is the content.
+fn response_tool_calls(response: &BedrockConverseRes, response_tool: Option<&str>) -> Option<Vec<ToolCall>> {
+    let calls: Vec<ToolCall> = response
+        .output
+        .message
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            BedrockContentBlock::ToolUse { tool_use } if Some(tool_use.name.as_str()) == response_tool => None,
+            BedrockContentBlock::ToolUse { tool_use } => Some(ToolCall::function(
+                tool_use.tool_use_id.clone(),
+                tool_use.name.clone(),
+                tool_use.input.to_string(),
+            )),
+            _ => None,
+        })
+        .collect();
+    (!calls.is_empty()).then_some(calls)
+}
+
+/// Concatenate the text blocks of a Converse response, or the input of the structured output tool call
+fn response_text(response: &BedrockConverseRes, response_tool: Option<&str>) -> String {
+    let output = response.output.message.content.iter().find_map(|block| match block {
+        BedrockContentBlock::ToolUse { tool_use } if Some(tool_use.name.as_str()) == response_tool => {
+            Some(tool_use.input.to_string())
+        }
+        _ => None,
+    });
+    if let Some(output) = output {
+        return output;
+    }
+
+    response
+        .output
+        .message
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            BedrockContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Provider for BedrockProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::Bedrock
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: true,
+            image_urls: false,
+            embeddings: true,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = Self::build_request(&request.messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(model, &body).await?;
+
+        let response_tool = response_tool_name(request.params.as_ref());
+        let tool_calls = response_tool_calls(&response, response_tool.as_deref());
+        let finish_reason = match response.stop_reason.as_deref() {
+            Some("tool_use") if tool_calls.is_none() => "stop".to_string(),
+            stop_reason => bedrock_finish_reason(stop_reason),
+        };
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: response_text(&response, response_tool.as_deref()),
+                    tool_calls,
+                    tool_call_id: None,
+                },
+                finish_reason,
+            }],
+            model: model.to_string(),
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        let body = Self::build_request(&request.messages, request.params.as_ref());
+        let response_tool = response_tool_name(request.params.as_ref());
+        let request = self
+            .post(model, "converse-stream", &body)?
+            .header("accept", "application/vnd.amazon.eventstream");
+        let response = send_stream(self.name(), request).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            EventStreamDecoder::new(),
+            BedrockStreamTranslator::new(self.name(), model).with_response_tool(response_tool),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        // Converse is chat only, so the prompt is sent as a single user turn
+        let messages = [RequestMessage::new(
+            MessageRole::User,
+            request.text.clone().unwrap_or_default(),
+        )];
+        let body = Self::build_request(&messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(model, &body).await?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: vec![TextCompletionChoice {
+                index: 0,
+                text: response_text(&response, response_tool_name(request.params.as_ref()).as_deref()),
+                finish_reason: bedrock_finish_reason(response.stop_reason.as_deref()),
+            }],
+            model: model.to_string(),
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        let start = Instant::now();
+        let (vectors, usage) = if model.contains("cohere.") {
+            (self.embed_cohere(model, request).await?, None)
+        } else {
+            let (vectors, tokens) = self.embed_titan(model, request).await?;
+            let usage = UsageInfo {
+                prompt_tokens: tokens,
+                completion_tokens: 0,
+                total_tokens: tokens,
+            };
+            (vectors, Some(usage))
+        };
+        let latency = start.elapsed().as_secs_f64();
+
+        Ok(EmbeddingResponse::new(
+            model.to_string(),
+            vectors,
+            usage,
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
@@ -1,9 +1,15 @@
 #![allow(unused_variables, unused_imports, dead_code)]
 
 mod config;
+mod event_stream;
 mod handler;
+mod sigv4;
+mod stream;
 mod types;
 
 pub use config::*;
+pub use event_stream::*;
 pub use handler::*;
+pub use sigv4::*;
+pub use stream::*;
 pub use types::*;
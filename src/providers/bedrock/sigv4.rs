@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use ring::{digest, hmac};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// AWS Signature Version 4 request signer
+#[derive(Debug, Clone)]
+pub struct SigV4Signer {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+    pub region: String,
+    pub service: String,
+}
+
+impl SigV4Signer {
+    /// Sign a request and return the headers that must be added to it
+    ///
+    /// `headers` are additional headers (besides `host` and `x-amz-date`)
+    /// that will be sent with the request and should be covered by the signature.
+    pub fn sign(
+        &self,
+        method: &str,
+        url: &Url,
+        headers: &[(&str, &str)],
+        payload: &[u8],
+        time: DateTime<Utc>,
+    ) -> Vec<(String, String)> {
+        let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = time.format("%Y%m%d").to_string();
+
+        let mut signed: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+            .collect();
+        signed.push(("host".to_string(), host_header(url)));
+        signed.push(("x-amz-date".to_string(), amz_date.clone()));
+        if let Some(token) = &self.session_token {
+            signed.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        signed.sort();
+
+        let canonical_headers: String = signed.iter().map(|(k, v)| format!("{}:{}\n", k, v)).collect();
+        let signed_headers = signed.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>().join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method.to_uppercase(),
+            canonical_uri(url),
+            canonical_query(url),
+            canonical_headers,
+            signed_headers,
+            sha256_hex(payload)
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            ALGORITHM,
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let signing_key = [
+            date.as_str(),
+            self.region.as_str(),
+            self.service.as_str(),
+            "aws4_request",
+        ]
+        .iter()
+        .fold(format!("AWS4{}", self.secret_key).into_bytes(), |key, part| {
+            hmac_sha256(&key, part.as_bytes())
+        });
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let mut result = vec![
+            (
+                "authorization".to_string(),
+                format!(
+                    "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+                    ALGORITHM, self.access_key, scope, signed_headers, signature
+                ),
+            ),
+            ("x-amz-date".to_string(), amz_date),
+        ];
+        if let Some(token) = &self.session_token {
+            result.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        result
+    }
+}
+
+/// Host header value, including the port when it is not the scheme default
+fn host_header(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// Canonical URI: every path segment URI-encoded again (non-S3 services double-encode)
+fn canonical_uri(url: &Url) -> String {
+    let path = url.path();
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+}
+
+/// Canonical query string: parameters sorted by name and value, both URI-encoded
+fn canonical_query(url: &Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (uri_encode(&k), uri_encode(&v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters
+pub fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(digest::digest(&digest::SHA256, data))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    hmac::sign(&key, data).as_ref().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn example_signer() -> SigV4Signer {
+        SigV4Signer {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+            region: "us-east-1".to_string(),
+            service: "service".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_get_vanilla_signature() {
+        // "get-vanilla" case from the AWS SigV4 test suite
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        let headers = example_signer().sign("GET", &url, &[], b"", time);
+
+        assert_eq!(
+            headers[0].1,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        assert_eq!(headers[1], ("x-amz-date".to_string(), "20150830T123600Z".to_string()));
+    }
+
+    #[test]
+    fn test_get_vanilla_query_order_signature() {
+        // "get-vanilla-query-order-key-case" case from the AWS SigV4 test suite
+        let url = Url::parse("https://example.amazonaws.com/?Param2=value2&Param1=value1").unwrap();
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        let headers = example_signer().sign("GET", &url, &[], b"", time);
+
+        assert!(
+            headers[0]
+                .1
+                .ends_with("Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500")
+        );
+    }
+
+    #[test]
+    fn test_session_token_is_signed() {
+        let signer = SigV4Signer {
+            session_token: Some("session".to_string()),
+            ..example_signer()
+        };
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let headers = signer.sign("GET", &url, &[], b"", Utc::now());
+
+        assert!(
+            headers[0]
+                .1
+                .contains("SignedHeaders=host;x-amz-date;x-amz-security-token")
+        );
+        assert_eq!(headers[2], ("x-amz-security-token".to_string(), "session".to_string()));
+    }
+
+    #[test]
+    fn test_canonical_uri_double_encodes() {
+        let url = Url::parse("https://bedrock-runtime.us-east-1.amazonaws.com/model/a.b-v1%3A0/converse").unwrap();
+        assert_eq!(canonical_uri(&url), "/model/a.b-v1%253A0/converse");
+    }
+}
@@ -0,0 +1,114 @@
+use serde::de::DeserializeOwned;
+
+use std::collections::HashMap;
+
+use super::{
+    BedrockContentBlockDeltaEvent, BedrockContentBlockStartEvent, BedrockMessageStopEvent, BedrockMetadataEvent,
+    EventStreamMessage, bedrock_exception_status, bedrock_finish_reason,
+};
+use crate::http::schemas::completions::{ChatCompletionChunk, ToolCallDelta};
+use crate::providers::client::{decode_str, upstream_error_message};
+use crate::providers::{ChunkBuilder, ChunkTranslator, ProviderError};
+
+/// Translates ConverseStream events into chat completion chunks
+///
+/// Tool use blocks are numbered in order of appearance; their input deltas are
+/// matched to the call by content block index. The input of the structured
+/// output tool is streamed as content instead.
+pub struct BedrockStreamTranslator {
+    provider: String,
+    chunks: ChunkBuilder,
+    /// Tool call index for each content block index carrying a tool use
+    tool_calls: HashMap<i32, i32>,
+    /// Name of the tool forced to carry structured output
+    response_tool: Option<String>,
+    /// Content block index of the structured output, once started
+    response_block: Option<i32>,
+}
+
+impl BedrockStreamTranslator {
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            chunks: ChunkBuilder::new(model),
+            tool_calls: HashMap::new(),
+            response_tool: None,
+            response_block: None,
+        }
+    }
+
+    /// Stream the input of calls to the named tool as message content
+    pub fn with_response_tool(mut self, name: Option<String>) -> Self {
+        self.response_tool = name;
+        self
+    }
+
+    fn payload<T: DeserializeOwned>(&self, message: &EventStreamMessage) -> Result<T, ProviderError> {
+        decode_str(&self.provider, &String::from_utf8_lossy(&message.payload))
+    }
+}
+
+impl ChunkTranslator for BedrockStreamTranslator {
+    type Frame = EventStreamMessage;
+
+    fn translate(&mut self, message: EventStreamMessage) -> Result<Vec<ChatCompletionChunk>, ProviderError> {
+        if message.header(":message-type") != Some("event") {
+            return Err(ProviderError::Upstream {
+                provider: self.provider.clone(),
+                status: bedrock_exception_status(message.header(":exception-type")),
+                message: upstream_error_message(&String::from_utf8_lossy(&message.payload)),
+            });
+        }
+
+        let chunks = match message.header(":event-type") {
+            Some("messageStart") => vec![self.chunks.role()],
+            Some("contentBlockStart") => {
+                let event: BedrockContentBlockStartEvent = self.payload(&message)?;
+                match event.start.tool_use {
+                    Some(tool_use) if self.response_tool.as_ref() == Some(&tool_use.name) => {
+                        self.response_block = Some(event.content_block_index);
+                        Vec::new()
+                    }
+                    Some(tool_use) => {
+                        let index = self.tool_calls.len() as i32;
+                        self.tool_calls.insert(event.content_block_index, index);
+                        vec![
+                            self.chunks
+                                .tool_call(ToolCallDelta::start(index, tool_use.tool_use_id, tool_use.name)),
+                        ]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            Some("contentBlockDelta") => {
+                let event: BedrockContentBlockDeltaEvent = self.payload(&message)?;
+                let tool_call = self.tool_calls.get(&event.content_block_index).copied();
+                let response = self.response_block == Some(event.content_block_index);
+                match (event.delta.text, event.delta.tool_use, tool_call) {
+                    (Some(text), _, _) => vec![self.chunks.content(text)],
+                    (None, Some(tool_use), _) if response && !tool_use.input.is_empty() => {
+                        vec![self.chunks.content(tool_use.input)]
+                    }
+                    (None, Some(tool_use), Some(index)) if !tool_use.input.is_empty() => {
+                        vec![self.chunks.tool_call(ToolCallDelta::arguments(index, tool_use.input))]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            Some("messageStop") => {
+                let event: BedrockMessageStopEvent = self.payload(&message)?;
+                let finish_reason = match event.stop_reason.as_deref() {
+                    Some("tool_use") if self.tool_calls.is_empty() => "stop".to_string(),
+                    stop_reason => bedrock_finish_reason(stop_reason),
+                };
+                vec![self.chunks.finish(finish_reason)]
+            }
+            Some("metadata") => {
+                let event: BedrockMetadataEvent = self.payload(&message)?;
+                vec![self.chunks.usage(event.usage.into())]
+            }
+            _ => Vec::new(),
+        };
+        Ok(chunks)
+    }
+}
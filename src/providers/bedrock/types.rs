@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::schemas::completions::UsageInfo;
+
+// BedrockConverseReq represents a Bedrock Converse API request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockConverseReq {
+    pub messages: Vec<BedrockMessage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub system: Vec<BedrockSystemBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inference_config: Option<BedrockInferenceConfig>,
+    /// Model specific parameters (e.g. `top_k`) forwarded untouched to the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_model_request_fields: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<BedrockToolConfig>,
+}
+
+// BedrockMessage represents a single conversation turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockMessage {
+    pub role: String,
+    pub content: Vec<BedrockContentBlock>,
+}
+
+// BedrockContentBlock represents a content block inside a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BedrockContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        #[serde(rename = "toolUse")]
+        tool_use: BedrockToolUse,
+    },
+    ToolResult {
+        #[serde(rename = "toolResult")]
+        tool_result: BedrockToolResult,
+    },
+    Image {
+        image: BedrockImage,
+    },
+    Document {
+        document: BedrockDocument,
+    },
+    Other(Value),
+}
+
+// BedrockImage represents an image passed inline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockImage {
+    pub format: String,
+    pub source: BedrockBytes,
+}
+
+// BedrockDocument represents a document passed inline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockDocument {
+    pub format: String,
+    pub name: String,
+    pub source: BedrockBytes,
+}
+
+// BedrockBytes represents base64 encoded media data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockBytes {
+    pub bytes: String,
+}
+
+// BedrockToolUse represents a tool call requested by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolUse {
+    pub tool_use_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub input: Value,
+}
+
+// BedrockToolResult represents the result of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolResult {
+    pub tool_use_id: String,
+    pub content: Vec<BedrockContentBlock>,
+}
+
+// BedrockToolConfig represents the tools offered to the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolConfig {
+    pub tools: Vec<BedrockTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<Value>,
+}
+
+// BedrockTool represents a single tool definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockTool {
+    pub tool_spec: BedrockToolSpec,
+}
+
+// BedrockToolSpec represents the name, description and input schema of a tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
+
+// BedrockSystemBlock represents a system prompt block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockSystemBlock {
+    pub text: String,
+}
+
+// BedrockInferenceConfig represents the common inference parameters
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockInferenceConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+}
+
+// BedrockConverseRes represents a Bedrock Converse API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockConverseRes {
+    pub output: BedrockOutput,
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: BedrockUsage,
+    #[serde(default)]
+    pub metrics: Option<BedrockMetrics>,
+}
+
+// BedrockOutput represents the output union of a Converse response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockOutput {
+    pub message: BedrockMessage,
+}
+
+// BedrockUsage represents token usage reported by Bedrock
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockUsage {
+    #[serde(default)]
+    pub input_tokens: i32,
+    #[serde(default)]
+    pub output_tokens: i32,
+    #[serde(default)]
+    pub total_tokens: i32,
+}
+
+// BedrockMetrics represents server side metrics of a Converse call
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockMetrics {
+    #[serde(default)]
+    pub latency_ms: i64,
+}
+
+// BedrockContentBlockStartEvent represents a `contentBlockStart` stream event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockContentBlockStartEvent {
+    #[serde(default)]
+    pub content_block_index: i32,
+    pub start: BedrockBlockStart,
+}
+
+// BedrockBlockStart represents the start of a content block, only set for tool use
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockBlockStart {
+    #[serde(default)]
+    pub tool_use: Option<BedrockToolUseStart>,
+}
+
+// BedrockToolUseStart represents the id and name of a streamed tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolUseStart {
+    pub tool_use_id: String,
+    pub name: String,
+}
+
+// BedrockContentBlockDeltaEvent represents a `contentBlockDelta` stream event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockContentBlockDeltaEvent {
+    #[serde(default)]
+    pub content_block_index: i32,
+    pub delta: BedrockDelta,
+}
+
+// BedrockDelta represents the delta of a content block
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockDelta {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_use: Option<BedrockToolUseDelta>,
+}
+
+// BedrockToolUseDelta represents a piece of the JSON-encoded input of a streamed tool call
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BedrockToolUseDelta {
+    #[serde(default)]
+    pub input: String,
+}
+
+// BedrockMessageStopEvent represents a `messageStop` stream event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockMessageStopEvent {
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+}
+
+// BedrockMetadataEvent represents the `metadata` stream event sent after `messageStop`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockMetadataEvent {
+    #[serde(default)]
+    pub usage: BedrockUsage,
+}
+
+// BedrockTitanEmbedReq represents an Amazon Titan embeddings InvokeModel request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockTitanEmbedReq {
+    pub input_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+// BedrockTitanEmbedRes represents an Amazon Titan embeddings InvokeModel response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockTitanEmbedRes {
+    pub embedding: Vec<f32>,
+    #[serde(default)]
+    pub input_text_token_count: i32,
+}
+
+// BedrockCohereEmbedReq represents a Cohere embeddings InvokeModel request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BedrockCohereEmbedReq {
+    pub texts: Vec<String>,
+    pub input_type: String,
+}
+
+// BedrockCohereEmbedRes represents a Cohere embeddings InvokeModel response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockCohereEmbedRes {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+impl From<BedrockUsage> for UsageInfo {
+    fn from(usage: BedrockUsage) -> Self {
+        UsageInfo {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: if usage.total_tokens > 0 {
+                usage.total_tokens
+            } else {
+                usage.input_tokens + usage.output_tokens
+            },
+        }
+    }
+}
+
+/// Map a Bedrock `stopReason` to an OpenAI-style `finish_reason`
+pub fn bedrock_finish_reason(stop_reason: Option<&str>) -> String {
+    match stop_reason {
+        Some("max_tokens") => "length",
+        Some("tool_use") => "tool_calls",
+        Some("guardrail_intervened") | Some("content_filtered") => "content_filter",
+        _ => "stop",
+    }
+    .to_string()
+}
+
+/// HTTP status Bedrock uses for an exception type reported inside a stream
+pub fn bedrock_exception_status(exception_type: Option<&str>) -> u16 {
+    match exception_type {
+        Some("validationException") => 400,
+        Some("throttlingException") => 429,
+        Some("serviceUnavailableException") => 503,
+        Some("modelStreamErrorException") => 424,
+        _ => 500,
+    }
+}
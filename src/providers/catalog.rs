@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+
+use super::{MediaKind, ModelParams, ProviderError, message_media};
+use crate::http::schemas::completions::{ChatCompletionReq, TextCompletionReq};
+
+/// What a model is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelKind {
+    /// Chat and text completions
+    #[default]
+    Chat,
+    Embedding,
+}
+
+/// Kind of input a model accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Text,
+    Image,
+    Document,
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModelPricing {
+    #[serde(default)]
+    pub input: f64,
+    #[serde(default)]
+    pub output: f64,
+}
+
+/// Catalog entry describing a model served by a provider
+///
+/// Fields left unset are unknown and not checked; models advertised by a
+/// provider without catalog metadata are listed with only their id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub provider: String,
+    pub id: String,
+    #[serde(default)]
+    pub kind: ModelKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// Accepted input modalities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<Modality>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+impl ModelInfo {
+    /// Entry for a model known by id only
+    pub fn new(provider: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            id: id.into(),
+            kind: ModelKind::Chat,
+            context_window: None,
+            max_output_tokens: None,
+            modalities: None,
+            tools: None,
+            streaming: None,
+            pricing: None,
+        }
+    }
+}
+
+/// Features a request needs from the model it is sent to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelRequirements {
+    pub kind: ModelKind,
+    pub streaming: bool,
+    pub tools: bool,
+    pub modalities: Vec<Modality>,
+    pub max_tokens: Option<u32>,
+}
+
+impl ModelRequirements {
+    /// Requirements of a chat completion request
+    pub fn chat(request: &ChatCompletionReq) -> Self {
+        let params = ModelParams::from_value(request.params.as_ref());
+        let mut modalities = vec![Modality::Text];
+        for media in request.messages.iter().flat_map(message_media) {
+            let modality = match media.kind {
+                MediaKind::Image => Modality::Image,
+                MediaKind::Document => Modality::Document,
+            };
+            if !modalities.contains(&modality) {
+                modalities.push(modality);
+            }
+        }
+
+        Self {
+            kind: ModelKind::Chat,
+            streaming: params.stream == Some(true),
+            tools: !params.tools().is_empty(),
+            modalities,
+            max_tokens: params.max_tokens,
+        }
+    }
+
+    /// Requirements of a text completion request
+    pub fn text(request: &TextCompletionReq) -> Self {
+        let params = ModelParams::from_value(request.params.as_ref());
+        Self {
+            kind: ModelKind::Chat,
+            streaming: params.stream == Some(true),
+            modalities: vec![Modality::Text],
+            max_tokens: params.max_tokens,
+            ..Default::default()
+        }
+    }
+
+    /// Requirements of an embeddings request
+    pub fn embeddings() -> Self {
+        Self {
+            kind: ModelKind::Embedding,
+            ..Default::default()
+        }
+    }
+}
+
+/// Built-in catalog entries: provider, model, kind, context window, max output tokens,
+/// modalities, tool support and input/output price per million tokens
+type BuiltinModel = (
+    &'static str,
+    &'static str,
+    ModelKind,
+    u32,
+    u32,
+    &'static [Modality],
+    bool,
+    f64,
+    f64,
+);
+
+const TEXT: &[Modality] = &[Modality::Text];
+const MULTIMODAL: &[Modality] = &[Modality::Text, Modality::Image, Modality::Document];
+
+#[rustfmt::skip]
+const BUILTIN_MODELS: &[BuiltinModel] = &[
+    ("openai", "gpt-4.1", ModelKind::Chat, 1_047_576, 32_768, MULTIMODAL, true, 2.0, 8.0),
+    ("openai", "gpt-4.1-mini", ModelKind::Chat, 1_047_576, 32_768, MULTIMODAL, true, 0.4, 1.6),
+    ("openai", "gpt-4o", ModelKind::Chat, 128_000, 16_384, MULTIMODAL, true, 2.5, 10.0),
+    ("openai", "gpt-4o-mini", ModelKind::Chat, 128_000, 16_384, MULTIMODAL, true, 0.15, 0.6),
+    ("openai", "o3-mini", ModelKind::Chat, 200_000, 100_000, TEXT, true, 1.1, 4.4),
+    ("openai", "gpt-3.5-turbo", ModelKind::Chat, 16_385, 4_096, TEXT, true, 0.5, 1.5),
+    ("openai", "gpt-3.5-turbo-instruct", ModelKind::Chat, 4_096, 4_096, TEXT, false, 1.5, 2.0),
+    ("openai", "text-embedding-3-small", ModelKind::Embedding, 8_191, 0, TEXT, false, 0.02, 0.0),
+    ("openai", "text-embedding-3-large", ModelKind::Embedding, 8_191, 0, TEXT, false, 0.13, 0.0),
+    ("anthropic", "claude-opus-4-20250514", ModelKind::Chat, 200_000, 32_000, MULTIMODAL, true, 15.0, 75.0),
+    ("anthropic", "claude-sonnet-4-20250514", ModelKind::Chat, 200_000, 64_000, MULTIMODAL, true, 3.0, 15.0),
+    ("anthropic", "claude-3-7-sonnet-latest", ModelKind::Chat, 200_000, 64_000, MULTIMODAL, true, 3.0, 15.0),
+    ("anthropic", "claude-3-5-sonnet-latest", ModelKind::Chat, 200_000, 8_192, MULTIMODAL, true, 3.0, 15.0),
+    ("anthropic", "claude-3-5-haiku-latest", ModelKind::Chat, 200_000, 8_192, MULTIMODAL, true, 0.8, 4.0),
+    ("anthropic", "claude-3-haiku-20240307", ModelKind::Chat, 200_000, 4_096, MULTIMODAL, true, 0.25, 1.25),
+    ("anthropic", "claude-3-sonnet-20240229", ModelKind::Chat, 200_000, 4_096, MULTIMODAL, true, 3.0, 15.0),
+    ("bedrock", "anthropic.claude-3-5-sonnet-20241022-v2:0", ModelKind::Chat, 200_000, 8_192, MULTIMODAL, true, 3.0, 15.0),
+    ("bedrock", "anthropic.claude-3-haiku-20240307-v1:0", ModelKind::Chat, 200_000, 4_096, MULTIMODAL, true, 0.25, 1.25),
+    ("bedrock", "amazon.nova-pro-v1:0", ModelKind::Chat, 300_000, 5_120, MULTIMODAL, true, 0.8, 3.2),
+    ("bedrock", "amazon.nova-lite-v1:0", ModelKind::Chat, 300_000, 5_120, MULTIMODAL, true, 0.06, 0.24),
+    ("bedrock", "meta.llama3-70b-instruct-v1:0", ModelKind::Chat, 8_192, 2_048, TEXT, false, 2.65, 3.5),
+    ("bedrock", "amazon.titan-embed-text-v2:0", ModelKind::Embedding, 8_192, 0, TEXT, false, 0.02, 0.0),
+    ("bedrock", "cohere.embed-english-v3", ModelKind::Embedding, 512, 0, TEXT, false, 0.1, 0.0),
+    ("cohere", "command-a-03-2025", ModelKind::Chat, 256_000, 8_000, TEXT, true, 2.5, 10.0),
+    ("cohere", "command-r-plus-08-2024", ModelKind::Chat, 128_000, 4_000, TEXT, true, 2.5, 10.0),
+    ("cohere", "command-r-08-2024", ModelKind::Chat, 128_000, 4_000, TEXT, true, 0.15, 0.6),
+    ("cohere", "command-r-plus", ModelKind::Chat, 128_000, 4_000, TEXT, true, 2.5, 10.0),
+    ("cohere", "command-r", ModelKind::Chat, 128_000, 4_000, TEXT, true, 0.15, 0.6),
+    ("cohere", "command", ModelKind::Chat, 4_096, 4_000, TEXT, false, 1.0, 2.0),
+    ("cohere", "embed-v4.0", ModelKind::Embedding, 128_000, 0, TEXT, false, 0.12, 0.0),
+    ("cohere", "embed-english-v3.0", ModelKind::Embedding, 512, 0, TEXT, false, 0.1, 0.0),
+    ("cohere", "embed-multilingual-v3.0", ModelKind::Embedding, 512, 0, TEXT, false, 0.1, 0.0),
+    ("vertex", "gemini-2.5-pro", ModelKind::Chat, 1_048_576, 65_536, MULTIMODAL, true, 1.25, 10.0),
+    ("vertex", "gemini-2.5-flash", ModelKind::Chat, 1_048_576, 65_536, MULTIMODAL, true, 0.3, 2.5),
+    ("vertex", "gemini-2.0-flash", ModelKind::Chat, 1_048_576, 8_192, MULTIMODAL, true, 0.1, 0.4),
+    ("vertex", "gemini-1.5-pro", ModelKind::Chat, 2_097_152, 8_192, MULTIMODAL, true, 1.25, 5.0),
+    ("vertex", "text-embedding-004", ModelKind::Embedding, 2_048, 0, TEXT, false, 0.025, 0.0),
+];
+
+/// ModelCatalog lists the models each provider serves along with their capabilities
+///
+/// Requests are checked against the catalog before reaching a provider. A model
+/// missing from the catalog is rejected when the catalog is strict and lists
+/// other models of the same provider; providers without any entry accept any model.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCatalog {
+    models: Vec<ModelInfo>,
+    strict: bool,
+}
+
+impl ModelCatalog {
+    /// Create an empty catalog
+    pub fn new(strict: bool) -> Self {
+        Self {
+            models: Vec::new(),
+            strict,
+        }
+    }
+
+    /// Create a catalog holding the built-in entries of well-known models
+    pub fn builtin(strict: bool) -> Self {
+        let mut catalog = Self::new(strict);
+        for &(provider, id, kind, context_window, max_output_tokens, modalities, tools, input, output) in BUILTIN_MODELS
+        {
+            catalog.insert(ModelInfo {
+                kind,
+                context_window: Some(context_window),
+                max_output_tokens: (kind == ModelKind::Chat).then_some(max_output_tokens),
+                modalities: Some(modalities.to_vec()),
+                tools: Some(tools),
+                streaming: Some(kind == ModelKind::Chat),
+                pricing: Some(ModelPricing { input, output }),
+                ..ModelInfo::new(provider, id)
+            });
+        }
+        catalog
+    }
+
+    /// Add an entry, replacing the entry of the same provider and model if there is one
+    pub fn insert(&mut self, model: ModelInfo) {
+        match self
+            .models
+            .iter_mut()
+            .find(|entry| entry.provider == model.provider && entry.id == model.id)
+        {
+            Some(entry) => *entry = model,
+            None => self.models.push(model),
+        }
+    }
+
+    /// Add id-only entries for models a provider advertises, keeping existing entries
+    pub fn advertise(&mut self, provider: &str, models: Vec<String>) {
+        for id in models {
+            if self.get(provider, &id).is_none() {
+                self.models.push(ModelInfo::new(provider, id));
+            }
+        }
+    }
+
+    /// All entries, in insertion order
+    pub fn models(&self) -> &[ModelInfo] {
+        &self.models
+    }
+
+    /// Entries of a provider, in insertion order
+    pub fn provider_models<'a>(&'a self, provider: &'a str) -> impl Iterator<Item = &'a ModelInfo> + 'a {
+        self.models.iter().filter(move |model| model.provider == provider)
+    }
+
+    /// Look up a model of a provider
+    pub fn get(&self, provider: &str, model: &str) -> Option<&ModelInfo> {
+        self.models
+            .iter()
+            .find(|entry| entry.provider == provider && entry.id == model)
+    }
+
+    /// Whether models missing from the catalog are rejected
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Check that a provider serves the model and that the model supports what the request needs
+    pub fn check(&self, provider: &str, model: &str, requirements: &ModelRequirements) -> Result<(), ProviderError> {
+        let Some(info) = self.get(provider, model) else {
+            if self.strict && self.provider_models(provider).next().is_some() {
+                return Err(ProviderError::UnknownModel {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                });
+            }
+            return Ok(());
+        };
+
+        let unsupported = |feature: String| {
+            Err(ProviderError::Unsupported {
+                provider: provider.to_string(),
+                feature,
+            })
+        };
+
+        if info.kind != requirements.kind {
+            let endpoint = match requirements.kind {
+                ModelKind::Chat => "completions",
+                ModelKind::Embedding => "embeddings",
+            };
+            return unsupported(format!("{} with model '{}'", endpoint, model));
+        }
+        if requirements.streaming && info.streaming == Some(false) {
+            return unsupported(format!("streaming with model '{}'", model));
+        }
+        if requirements.tools && info.tools == Some(false) {
+            return unsupported(format!("tool calling with model '{}'", model));
+        }
+        if let Some(modalities) = &info.modalities
+            && let Some(modality) = requirements
+                .modalities
+                .iter()
+                .find(|modality| !modalities.contains(modality))
+        {
+            let input = match modality {
+                Modality::Text => "text",
+                Modality::Image => "image",
+                Modality::Document => "document",
+            };
+            return unsupported(format!("{} input with model '{}'", input, model));
+        }
+        if let (Some(requested), Some(maximum)) = (requirements.max_tokens, info.max_output_tokens)
+            && requested > maximum
+        {
+            return Err(ProviderError::InvalidRequest(format!(
+                "max_tokens of {} exceeds the maximum of {} output tokens for model '{}'",
+                requested, maximum, model
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::ProviderError;
+use crate::config::CircuitBreakerConfig;
+use crate::metrics::record_circuit_breaker_state;
+
+/// State of a provider circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests flow normally while failures are counted
+    Closed,
+    /// A limited number of trial requests decide whether the circuit closes again
+    HalfOpen,
+    /// Requests fail fast until the open duration has passed
+    Open,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::HalfOpen => "half_open",
+            CircuitState::Open => "open",
+        }
+    }
+
+    /// Value reported by the `sorai_circuit_breaker_state` gauge
+    fn gauge(self) -> f64 {
+        match self {
+            CircuitState::Closed => 0.0,
+            CircuitState::HalfOpen => 1.0,
+            CircuitState::Open => 2.0,
+        }
+    }
+}
+
+/// What a finished call says about the health of a provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Success,
+    Failure,
+    /// Errors raised before the provider was reached, such as invalid requests
+    Ignored,
+}
+
+impl Outcome {
+    fn of(error: Option<&ProviderError>, latency: Duration, slow_call: Option<Duration>) -> Self {
+        match error {
+            None if slow_call.is_some_and(|slow_call| latency >= slow_call) => Outcome::Failure,
+            None => Outcome::Success,
+            Some(ProviderError::Upstream { status, .. }) if *status == 429 || *status >= 500 => Outcome::Failure,
+            Some(
+                ProviderError::Timeout { .. }
+                | ProviderError::Transport { .. }
+                | ProviderError::Authentication { .. }
+                | ProviderError::InvalidResponse { .. },
+            ) => Outcome::Failure,
+            // The provider answered, the request or its output was at fault
+            Some(ProviderError::Upstream { .. } | ProviderError::SchemaMismatch { .. }) => Outcome::Success,
+            Some(_) => Outcome::Ignored,
+        }
+    }
+}
+
+struct Circuit {
+    state: CircuitState,
+    /// Bumped on every state change, so calls started in an earlier state are not counted
+    generation: u64,
+    /// Recent calls while closed, `true` for failed or slow calls
+    calls: VecDeque<bool>,
+    opened_at: Instant,
+    /// Trial calls in flight and trial calls that succeeded while half-open
+    trials: u32,
+    successes: u32,
+}
+
+/// CircuitBreaker stops sending requests to a provider that keeps failing
+///
+/// While closed, the last `window` calls are tracked, and the circuit opens once
+/// at least `min_requests` of them were made and `failure_rate` percent failed or
+/// were slower than `slow_call_ms`. An open circuit rejects calls with
+/// [`ProviderError::CircuitOpen`] for `open_seconds`, then turns half-open and
+/// lets `half_open_requests` trial calls through: the circuit closes when they
+/// all succeed and opens again on the first failure.
+pub struct CircuitBreaker {
+    provider: String,
+    config: CircuitBreakerConfig,
+    circuit: Mutex<Circuit>,
+}
+
+impl CircuitBreaker {
+    pub fn new(provider: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        let provider = provider.into();
+        record_circuit_breaker_state(&provider, CircuitState::Closed.gauge());
+        Self {
+            provider,
+            config,
+            circuit: Mutex::new(Circuit {
+                state: CircuitState::Closed,
+                generation: 0,
+                calls: VecDeque::new(),
+                opened_at: Instant::now(),
+                trials: 0,
+                successes: 0,
+            }),
+        }
+    }
+
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    /// Ask to make a call, failing fast while the circuit is open
+    ///
+    /// The returned permit reports the outcome of the call; dropping it without
+    /// recording leaves the circuit as it was.
+    pub fn acquire(&self) -> Result<CircuitPermit<'_>, ProviderError> {
+        let mut circuit = self.lock();
+
+        if circuit.state == CircuitState::Open {
+            if circuit.opened_at.elapsed() < Duration::from_secs(self.config.open_seconds) {
+                return Err(self.open_error());
+            }
+            self.transition(&mut circuit, CircuitState::HalfOpen);
+        }
+        if circuit.state == CircuitState::HalfOpen {
+            if circuit.trials >= self.config.half_open_requests.max(1) {
+                return Err(self.open_error());
+            }
+            circuit.trials += 1;
+        }
+
+        Ok(CircuitPermit {
+            breaker: self,
+            generation: circuit.generation,
+            recorded: false,
+        })
+    }
+
+    fn finish(&self, generation: u64, outcome: Outcome) {
+        let mut circuit = self.lock();
+        if circuit.generation != generation {
+            return;
+        }
+
+        match circuit.state {
+            CircuitState::Closed => {
+                if outcome == Outcome::Ignored {
+                    return;
+                }
+                circuit.calls.push_back(outcome == Outcome::Failure);
+                while circuit.calls.len() > self.config.window.max(1) as usize {
+                    circuit.calls.pop_front();
+                }
+
+                let calls = circuit.calls.len() as u64;
+                let failures = circuit.calls.iter().filter(|&&failed| failed).count() as u64;
+                let min_requests = self.config.min_requests.clamp(1, self.config.window.max(1)) as u64;
+                if calls >= min_requests && failures > 0 && failures * 100 >= self.config.failure_rate as u64 * calls {
+                    tracing::warn!(
+                        "Circuit breaker of provider '{}' opened after {} of {} calls failed",
+                        self.provider,
+                        failures,
+                        calls
+                    );
+                    self.transition(&mut circuit, CircuitState::Open);
+                }
+            }
+            CircuitState::HalfOpen => {
+                circuit.trials = circuit.trials.saturating_sub(1);
+                match outcome {
+                    Outcome::Failure => {
+                        tracing::warn!(
+                            "Circuit breaker of provider '{}' reopened after a failed trial call",
+                            self.provider
+                        );
+                        self.transition(&mut circuit, CircuitState::Open);
+                    }
+                    Outcome::Success => {
+                        circuit.successes += 1;
+                        if circuit.successes >= self.config.half_open_requests.max(1) {
+                            tracing::info!("Circuit breaker of provider '{}' closed", self.provider);
+                            self.transition(&mut circuit, CircuitState::Closed);
+                        }
+                    }
+                    Outcome::Ignored => {}
+                }
+            }
+            CircuitState::Open => {}
+        }
+    }
+
+    fn transition(&self, circuit: &mut Circuit, state: CircuitState) {
+        circuit.state = state;
+        circuit.generation += 1;
+        circuit.calls.clear();
+        circuit.trials = 0;
+        circuit.successes = 0;
+        if state == CircuitState::Open {
+            circuit.opened_at = Instant::now();
+        }
+        record_circuit_breaker_state(&self.provider, state.gauge());
+    }
+
+    fn open_error(&self) -> ProviderError {
+        ProviderError::CircuitOpen {
+            provider: self.provider.clone(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Circuit> {
+        self.circuit.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Permission to make one call through a [`CircuitBreaker`]
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    generation: u64,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    /// Report how the call went: its error, if any, and how long it took
+    pub fn record(mut self, error: Option<&ProviderError>, latency: Duration) {
+        let slow_call =
+            (self.breaker.config.slow_call_ms > 0).then(|| Duration::from_millis(self.breaker.config.slow_call_ms));
+        self.recorded = true;
+        self.breaker
+            .finish(self.generation, Outcome::of(error, latency, slow_call));
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.finish(self.generation, Outcome::Ignored);
+        }
+    }
+}
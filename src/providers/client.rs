@@ -0,0 +1,136 @@
+use reqwest::RequestBuilder;
+use reqwest::dns::Resolve;
+use reqwest::redirect::Policy;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::ProviderError;
+
+/// Connect timeout applied to every upstream request
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Build the shared HTTP client used by all provider adapters
+pub fn http_client() -> reqwest::Client {
+    client_builder().build().expect("Failed to build HTTP client")
+}
+
+/// Build the HTTP client of a provider, failing calls with [`ProviderError::Timeout`]
+/// once the provider sends nothing for `timeout`, while waiting for the response as
+/// well as between the chunks of a stream
+pub fn upstream_client(timeout: Duration) -> reqwest::Client {
+    client_builder()
+        .read_timeout(timeout)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+/// Build the HTTP client downloading message attachments, which resolves host names
+/// with `resolver`, follows no redirects and gives up on a download after `timeout`
+pub(super) fn media_client(timeout: Duration, resolver: Arc<dyn Resolve>) -> reqwest::Client {
+    client_builder()
+        .timeout(timeout)
+        .redirect(Policy::none())
+        .dns_resolver(resolver)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .user_agent(concat!("sorai/", env!("CARGO_PKG_VERSION")))
+}
+
+/// Send a request and decode the JSON body, mapping non-success statuses to provider errors
+pub async fn send_json(provider: &str, request: RequestBuilder) -> Result<Value, ProviderError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+
+    if !status.is_success() {
+        return Err(ProviderError::Upstream {
+            provider: provider.to_string(),
+            status: status.as_u16(),
+            message: upstream_error_message(&body),
+        });
+    }
+
+    serde_json::from_str(&body).map_err(|e| ProviderError::InvalidResponse {
+        provider: provider.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Send a streaming request, mapping non-success statuses to provider errors
+///
+/// The body of a successful response is left unread for the caller to consume.
+pub async fn send_stream(provider: &str, request: RequestBuilder) -> Result<reqwest::Response, ProviderError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+        return Err(ProviderError::Upstream {
+            provider: provider.to_string(),
+            status: status.as_u16(),
+            message: upstream_error_message(&body),
+        });
+    }
+
+    Ok(response)
+}
+
+/// Decode a JSON value into a typed provider response
+pub fn decode<T: serde::de::DeserializeOwned>(provider: &str, value: &Value) -> Result<T, ProviderError> {
+    serde_json::from_value(value.clone()).map_err(|e| ProviderError::InvalidResponse {
+        provider: provider.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Decode a JSON string, such as a streamed event payload, into a typed provider response
+pub fn decode_str<T: serde::de::DeserializeOwned>(provider: &str, body: &str) -> Result<T, ProviderError> {
+    serde_json::from_str(body).map_err(|e| ProviderError::InvalidResponse {
+        provider: provider.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Extract a human readable message from an upstream error body
+pub fn upstream_error_message(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return if body.is_empty() {
+            "empty response body".to_string()
+        } else {
+            body.to_string()
+        };
+    };
+
+    // Most providers use `{"error": {"message": ...}}`, some use `{"message": ...}`
+    value["error"]["message"]
+        .as_str()
+        .or_else(|| value["error"].as_str())
+        .or_else(|| value["message"].as_str())
+        .or_else(|| value["Message"].as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Join a base URL and a path without doubling slashes
+pub fn join_url(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
@@ -9,7 +9,24 @@ pub struct CohereConfig {
     pub base_url: String,
 }
 
+/// Default Cohere API base URL
+pub const COHERE_DEFAULT_BASE_URL: &str = "https://api.cohere.com";
+
 impl CohereConfig {
+    /// Cohere is usable once an API key is set
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    /// Base URL to send requests to
+    pub fn base_url(&self) -> &str {
+        if self.base_url.is_empty() {
+            COHERE_DEFAULT_BASE_URL
+        } else {
+            &self.base_url
+        }
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "Cohere".to_string(),
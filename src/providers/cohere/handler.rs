@@ -0,0 +1,322 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+use super::{
+    CohereChatReq, CohereChatRes, CohereConfig, CohereContent, CohereContentBlock, CohereEmbedReq, CohereEmbedRes,
+    CohereImageUrl, CohereMessage, cohere_finish_reason,
+};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ContentPart,
+    ExtraFields, MessageRole, RequestMessage, TextCompletionChoice, TextCompletionId, TextCompletionReq,
+    TextCompletionResponse, UsageInfo,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json};
+use crate::providers::params::{ModelParams, ResponseFormat};
+use crate::providers::{ModelProvider, Provider, ProviderCapabilities, ProviderError};
+
+/// Parameters Cohere accepts under the same name besides the ones mapped explicitly
+const PASSTHROUGH_PARAMS: &[&str] = &["seed", "frequency_penalty", "presence_penalty"];
+
+/// Embed v2 `input_type` used when the request does not set one
+const DEFAULT_INPUT_TYPE: &str = "search_document";
+
+/// Cohere Chat v2 adapter
+pub struct CohereProvider {
+    config: CohereConfig,
+    client: reqwest::Client,
+}
+
+impl CohereProvider {
+    /// Create a new Cohere adapter using the shared HTTP client
+    pub fn new(config: CohereConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+
+    /// Translate OpenAI-style messages and params into a Chat v2 request
+    ///
+    /// Chat v2 accepts OpenAI-shaped tools and tool calls, so those are forwarded
+    /// as-is; `tool_choice` is reduced to the `REQUIRED`/`NONE` values Cohere supports.
+    /// A JSON schema `response_format` becomes JSON mode with that schema.
+    pub fn build_request(model: &str, messages: &[RequestMessage], params: Option<&Value>) -> CohereChatReq {
+        let params = ModelParams::from_value(params);
+
+        let messages = messages.iter().filter_map(cohere_message).collect();
+        let stop_sequences = params.stop_sequences();
+
+        CohereChatReq {
+            model: model.to_string(),
+            messages,
+            tools: params.extra.get("tools").and_then(Value::as_array).cloned(),
+            tool_choice: params.extra.get("tool_choice").and_then(cohere_tool_choice),
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            p: params.top_p,
+            k: params.extra.get("top_k").and_then(Value::as_u64).map(|k| k as u32),
+            stop_sequences: if stop_sequences.is_empty() {
+                None
+            } else {
+                Some(stop_sequences)
+            },
+            response_format: params.response_format().map(cohere_response_format),
+            extra: params
+                .extra
+                .into_iter()
+                .filter(|(key, _)| PASSTHROUGH_PARAMS.contains(&key.as_str()))
+                .collect(),
+        }
+    }
+
+    /// Translate a Sorai embeddings request into an Embed v2 request
+    ///
+    /// `input_type` is read from `params` and defaults to `search_document`.
+    pub fn build_embed_request(model: &str, request: &EmbeddingReq) -> CohereEmbedReq {
+        CohereEmbedReq {
+            model: model.to_string(),
+            texts: request.inputs(),
+            input_type: request
+                .param_str("input_type")
+                .unwrap_or(DEFAULT_INPUT_TYPE)
+                .to_string(),
+            embedding_types: vec!["float".to_string()],
+            output_dimension: request.dimensions,
+        }
+    }
+
+    /// Send a Chat v2 request and return the raw and decoded response with latency
+    async fn send(&self, body: &CohereChatReq) -> Result<(Value, CohereChatRes, f64), ProviderError> {
+        let request = self
+            .client
+            .post(join_url(self.config.base_url(), "v2/chat"))
+            .bearer_auth(&self.config.api_key)
+            .json(body);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), request).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response = decode(self.name(), &raw)?;
+        Ok((raw, response, latency))
+    }
+}
+
+/// Translate a single OpenAI-style message, skipping messages with nothing to send
+fn cohere_message(message: &RequestMessage) -> Option<CohereMessage> {
+    let text = message.text();
+    let content = (!text.is_empty()).then_some(CohereContent::Text(text));
+
+    if message.is_system() {
+        return content.map(|content| CohereMessage {
+            role: "system".to_string(),
+            content: Some(content),
+            ..Default::default()
+        });
+    }
+
+    match message.role {
+        MessageRole::Tool => Some(CohereMessage {
+            role: "tool".to_string(),
+            content: Some(content.unwrap_or(CohereContent::Text(String::new()))),
+            tool_call_id: message.tool_call_id.clone(),
+            ..Default::default()
+        }),
+        MessageRole::Assistant => {
+            let tool_calls = Some(message.tool_calls().to_vec()).filter(|calls| !calls.is_empty());
+            if content.is_none() && tool_calls.is_none() {
+                return None;
+            }
+            Some(CohereMessage {
+                role: "assistant".to_string(),
+                content,
+                tool_calls,
+                ..Default::default()
+            })
+        }
+        _ => user_content(message).map(|content| CohereMessage {
+            role: "user".to_string(),
+            content: Some(content),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Content of a user message, as typed blocks when it carries images
+fn user_content(message: &RequestMessage) -> Option<CohereContent> {
+    let parts = message.parts();
+    if !parts.iter().any(|part| matches!(part, ContentPart::ImageUrl { .. })) {
+        let text = message.text();
+        return (!text.is_empty()).then_some(CohereContent::Text(text));
+    }
+
+    let blocks = parts
+        .into_owned()
+        .into_iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } => Some(CohereContentBlock::Text { text }),
+            ContentPart::ImageUrl { image_url } => Some(CohereContentBlock::ImageUrl {
+                image_url: CohereImageUrl { url: image_url.url },
+            }),
+            ContentPart::File { .. } => None,
+        })
+        .collect();
+    Some(CohereContent::Blocks(blocks))
+}
+
+/// Map a `response_format` to Cohere's, which nests the schema directly under `json_schema`
+fn cohere_response_format(format: ResponseFormat) -> Value {
+    match format {
+        ResponseFormat::Text => json!({ "type": "text" }),
+        ResponseFormat::JsonObject => json!({ "type": "json_object" }),
+        ResponseFormat::JsonSchema { schema, .. } => json!({ "type": "json_object", "json_schema": schema }),
+    }
+}
+
+/// Map an OpenAI `tool_choice` to Cohere's; `auto` is Cohere's default and maps to `None`
+fn cohere_tool_choice(choice: &Value) -> Option<String> {
+    match choice {
+        Value::String(choice) if choice == "required" => Some("REQUIRED".to_string()),
+        Value::String(choice) if choice == "none" => Some("NONE".to_string()),
+        // Cohere cannot force a specific function, requiring a tool call is the closest match
+        Value::Object(_) => Some("REQUIRED".to_string()),
+        _ => None,
+    }
+}
+
+/// Concatenate the text blocks of a Chat v2 response
+fn response_text(response: &CohereChatRes) -> String {
+    match &response.message.content {
+        Some(CohereContent::Text(text)) => text.clone(),
+        Some(CohereContent::Blocks(blocks)) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                CohereContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[async_trait]
+impl Provider for CohereProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::Cohere
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: false,
+            tools: true,
+            vision: true,
+            documents: false,
+            image_urls: true,
+            embeddings: true,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = Self::build_request(model, &request.messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(&body).await?;
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: response_text(&response),
+                    tool_calls: response.message.tool_calls.clone(),
+                    tool_call_id: None,
+                },
+                finish_reason: cohere_finish_reason(response.finish_reason.as_deref()),
+            }],
+            model: model.to_string(),
+            created: chrono::Utc::now().timestamp(),
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        // Cohere's generate API is deprecated, so the prompt is sent as a single user turn
+        let messages = [RequestMessage::new(
+            MessageRole::User,
+            request.text.clone().unwrap_or_default(),
+        )];
+        let body = Self::build_request(model, &messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(&body).await?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: vec![TextCompletionChoice {
+                index: 0,
+                text: response_text(&response),
+                finish_reason: cohere_finish_reason(response.finish_reason.as_deref()),
+            }],
+            model: model.to_string(),
+            created: chrono::Utc::now().timestamp(),
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        let body = Self::build_embed_request(model, request);
+        let request_builder = self
+            .client
+            .post(join_url(self.config.base_url(), "v2/embed"))
+            .bearer_auth(&self.config.api_key)
+            .json(&body);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), request_builder).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: CohereEmbedRes = decode(self.name(), &raw)?;
+        let usage = response
+            .meta
+            .and_then(|meta| meta.billed_units)
+            .map(|tokens| UsageInfo {
+                prompt_tokens: tokens.input_tokens as i32,
+                completion_tokens: 0,
+                total_tokens: tokens.input_tokens as i32,
+            });
+
+        Ok(EmbeddingResponse::new(
+            model.to_string(),
+            response.embeddings.float,
+            usage,
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
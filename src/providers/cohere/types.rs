@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::http::schemas::completions::{ToolCall, UsageInfo};
+
+// CohereChatReq represents a Cohere Chat v2 request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CohereChatReq {
+    pub model: String,
+    pub messages: Vec<CohereMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<Value>,
+    /// Cohere specific parameters such as `seed` or `frequency_penalty`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// CohereMessage represents a Chat v2 message
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CohereMessage {
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<CohereContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_plan: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+// CohereContent represents message content, either plain text or typed blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CohereContent {
+    Text(String),
+    Blocks(Vec<CohereContentBlock>),
+}
+
+// CohereContentBlock represents a typed content block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CohereContentBlock {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: CohereImageUrl,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+// CohereImageUrl represents an image given by URL or as a base64 data URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohereImageUrl {
+    pub url: String,
+}
+
+// CohereChatRes represents a Cohere Chat v2 response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohereChatRes {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    pub message: CohereMessage,
+    #[serde(default)]
+    pub usage: Option<CohereUsage>,
+}
+
+// CohereUsage represents usage reported by Cohere
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct CohereUsage {
+    #[serde(default)]
+    pub billed_units: Option<CohereTokens>,
+    #[serde(default)]
+    pub tokens: Option<CohereTokens>,
+}
+
+// CohereTokens represents a pair of input/output token counts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct CohereTokens {
+    #[serde(default)]
+    pub input_tokens: f64,
+    #[serde(default)]
+    pub output_tokens: f64,
+}
+
+impl From<CohereUsage> for UsageInfo {
+    fn from(usage: CohereUsage) -> Self {
+        // Billed units are what the account is charged for; raw token counts are the fallback
+        let tokens = usage.billed_units.or(usage.tokens).unwrap_or_default();
+        let prompt_tokens = tokens.input_tokens as i32;
+        let completion_tokens = tokens.output_tokens as i32;
+        UsageInfo {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+}
+
+// CohereEmbedReq represents a Cohere Embed v2 request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CohereEmbedReq {
+    pub model: String,
+    pub texts: Vec<String>,
+    pub input_type: String,
+    pub embedding_types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dimension: Option<u32>,
+}
+
+// CohereEmbedRes represents a Cohere Embed v2 response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohereEmbedRes {
+    pub embeddings: CohereEmbeddings,
+    #[serde(default)]
+    pub meta: Option<CohereMeta>,
+}
+
+// CohereEmbeddings represents embeddings grouped by requested type
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CohereEmbeddings {
+    #[serde(default)]
+    pub float: Vec<Vec<f32>>,
+}
+
+// CohereMeta represents response metadata, including billed units
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct CohereMeta {
+    #[serde(default)]
+    pub billed_units: Option<CohereTokens>,
+}
+
+/// Map a Cohere `finish_reason` to an OpenAI-style `finish_reason`
+pub fn cohere_finish_reason(finish_reason: Option<&str>) -> String {
+    match finish_reason {
+        Some("MAX_TOKENS") => "length",
+        Some("TOOL_CALL") => "tool_calls",
+        Some("ERROR") => "error",
+        _ => "stop",
+    }
+    .to_string()
+}
@@ -0,0 +1,108 @@
+use axum::http::StatusCode;
+
+use crate::http::response::ErrorCode;
+
+/// Errors produced while dispatching a request to an upstream provider
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProviderError {
+    #[error("Unknown provider '{0}'")]
+    UnknownProvider(String),
+    #[error("Provider '{0}' is not configured")]
+    NotConfigured(String),
+    #[error("Model '{model}' is not served by provider '{provider}'")]
+    UnknownModel { provider: String, model: String },
+    #[error("Provider '{provider}' does not support {feature}")]
+    Unsupported { provider: String, feature: String },
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("Request to provider '{provider}' timed out")]
+    Timeout { provider: String },
+    #[error("Failed to reach provider '{provider}': {message}")]
+    Transport { provider: String, message: String },
+    #[error("Provider '{provider}' returned status {status}: {message}")]
+    Upstream {
+        provider: String,
+        status: u16,
+        message: String,
+    },
+    #[error("Invalid response from provider '{provider}': {message}")]
+    InvalidResponse { provider: String, message: String },
+    #[error("Failed to authenticate with provider '{provider}': {message}")]
+    Authentication { provider: String, message: String },
+    #[error("Response from provider '{provider}' does not match the requested schema: {message}")]
+    SchemaMismatch { provider: String, message: String },
+    #[error("Provider '{provider}' is unavailable, its circuit breaker is open")]
+    CircuitOpen { provider: String },
+    #[error("Rate limit of the API key for model '{model}' on provider '{provider}' exceeded")]
+    RateLimited { provider: String, model: String },
+}
+
+impl ProviderError {
+    /// Build a provider error from a reqwest transport error
+    pub fn from_reqwest(provider: &str, err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ProviderError::Timeout {
+                provider: provider.to_string(),
+            }
+        } else if err.is_decode() {
+            ProviderError::InvalidResponse {
+                provider: provider.to_string(),
+                message: err.to_string(),
+            }
+        } else {
+            ProviderError::Transport {
+                provider: provider.to_string(),
+                message: err.to_string(),
+            }
+        }
+    }
+
+    /// Whether the request may succeed on another provider
+    ///
+    /// Timeouts, transport failures, rate limits, upstream 5xx responses,
+    /// unconfigured providers, providers with an open circuit breaker, targets over
+    /// a model rate limit of the key and output that kept failing schema validation
+    /// are retryable; request errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::NotConfigured(_) | ProviderError::Timeout { .. } | ProviderError::Transport { .. } => true,
+            ProviderError::SchemaMismatch { .. } | ProviderError::CircuitOpen { .. } => true,
+            ProviderError::RateLimited { .. } => true,
+            ProviderError::Upstream { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// HTTP status code returned to the client for this error
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ProviderError::UnknownProvider(_) | ProviderError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ProviderError::Unsupported { .. } => StatusCode::BAD_REQUEST,
+            ProviderError::UnknownModel { .. } => StatusCode::NOT_FOUND,
+            ProviderError::NotConfigured(_) | ProviderError::CircuitOpen { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            ProviderError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            ProviderError::Upstream { status: 429, .. } | ProviderError::RateLimited { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            ProviderError::Upstream {
+                status: 400 | 404 | 413 | 422,
+                ..
+            } => StatusCode::BAD_REQUEST,
+            ProviderError::Transport { .. } | ProviderError::Upstream { .. } => StatusCode::BAD_GATEWAY,
+            ProviderError::InvalidResponse { .. } | ProviderError::Authentication { .. } => StatusCode::BAD_GATEWAY,
+            ProviderError::SchemaMismatch { .. } => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    /// Error code reported in the response envelope
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            ProviderError::UnknownProvider(_) | ProviderError::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            ProviderError::Unsupported { .. } | ProviderError::UnknownModel { .. } => ErrorCode::InvalidRequest,
+            ProviderError::Upstream { status: 429, .. } | ProviderError::RateLimited { .. } => {
+                ErrorCode::RateLimitError
+            }
+            _ => ErrorCode::ProviderError,
+        }
+    }
+}
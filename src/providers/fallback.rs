@@ -0,0 +1,115 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::{Provider, ProviderError, ProviderRegistry};
+use crate::http::schemas::completions::{Fallback, ProviderAttempt};
+use crate::metrics::record_fallback_usage;
+
+/// Result of running a request through a fallback chain
+pub struct FallbackOutcome<T> {
+    pub result: Result<T, ProviderError>,
+    pub attempts: Vec<ProviderAttempt>,
+}
+
+/// Run `call` against the primary provider/model, then against each fallback in order
+///
+/// The chain advances only on retryable errors (see [`ProviderError::is_retryable`]);
+/// any other error, or the error of the last target, is returned as-is. Every switch
+/// to a fallback is recorded in `sorai_fallback_usage_total`.
+///
+/// Targets whose circuit breaker is open are skipped without being called, and the
+/// outcome of every call is reported to the breaker of its provider.
+pub async fn with_fallbacks<T, F, Fut>(
+    registry: &ProviderRegistry,
+    provider: &str,
+    model: &str,
+    fallbacks: &[Fallback],
+    call: F,
+) -> FallbackOutcome<T>
+where
+    F: Fn(Arc<dyn Provider>, String) -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>>,
+{
+    let targets = std::iter::once((provider, model))
+        .chain(fallbacks.iter().map(|f| (f.provider.as_str(), f.model.as_str())))
+        .collect::<Vec<_>>();
+
+    let mut attempts: Vec<ProviderAttempt> = Vec::with_capacity(targets.len());
+    let mut last_error = None;
+
+    for (index, (target_provider, target_model)) in targets.iter().enumerate() {
+        // Attempts and metrics use the registered provider name, whatever the spelling
+        // of the request, so that they are priced and matched against limits as such
+        let resolved = registry.get(target_provider);
+        let attempted = resolved
+            .as_ref()
+            .map_or_else(|_| target_provider.to_string(), |resolved| resolved.name().to_string());
+        if index > 0 {
+            tracing::info!(
+                "Falling back from '{}' to '{}/{}'",
+                provider,
+                target_provider,
+                target_model
+            );
+            record_fallback_usage(&attempts[0].provider, &attempted);
+        }
+
+        let start = Instant::now();
+        let result = match resolved {
+            Ok(resolved) => match registry
+                .circuit_breaker(resolved.name())
+                .map(|b| b.acquire())
+                .transpose()
+            {
+                Ok(permit) => {
+                    let result = call(resolved, target_model.to_string()).await;
+                    if let Some(permit) = permit {
+                        permit.record(result.as_ref().err(), start.elapsed());
+                    }
+                    result
+                }
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        let latency = start.elapsed().as_secs_f64();
+
+        match result {
+            Ok(response) => {
+                attempts.push(ProviderAttempt {
+                    provider: attempted,
+                    model: target_model.to_string(),
+                    success: true,
+                    error: None,
+                    latency,
+                });
+                return FallbackOutcome {
+                    result: Ok(response),
+                    attempts,
+                };
+            }
+            Err(e) => {
+                tracing::warn!("Provider '{}' failed: {}", target_provider, e);
+                attempts.push(ProviderAttempt {
+                    provider: attempted,
+                    model: target_model.to_string(),
+                    success: false,
+                    error: Some(e.to_string()),
+                    latency,
+                });
+
+                let retryable = e.is_retryable();
+                last_error = Some(e);
+                if !retryable {
+                    break;
+                }
+            }
+        }
+    }
+
+    FallbackOutcome {
+        result: Err(last_error.unwrap_or_else(|| ProviderError::UnknownProvider(provider.to_string()))),
+        attempts,
+    }
+}
@@ -0,0 +1,467 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+
+use super::client::media_client;
+use super::models::supports_vision;
+use super::{Provider, ProviderCapabilities, ProviderError};
+use crate::config::StorageConfig;
+use crate::http::schemas::completions::{ChatCompletionReq, ChatContent, ContentPart, RequestMessage};
+
+/// Redirects followed when downloading a single attachment
+const MAX_REDIRECTS: usize = 5;
+
+/// Image types accepted by every vision-capable provider
+const IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Document types accepted as message attachments
+const DOCUMENT_TYPES: [&str; 9] = [
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+    "text/markdown",
+    "text/html",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+];
+
+/// Kind of media attached to a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Document,
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaKind::Image => write!(f, "image"),
+            MediaKind::Document => write!(f, "document"),
+        }
+    }
+}
+
+/// Location of media data, either remote or inlined as base64
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaSource {
+    Url(String),
+    Base64 { media_type: String, data: String },
+}
+
+impl MediaSource {
+    /// Parse a URL, a base64 `data:` URL, or raw base64 data typed after `filename`
+    pub fn parse(value: &str, filename: Option<&str>) -> Self {
+        if let Some((media_type, data)) = value
+            .strip_prefix("data:")
+            .and_then(|rest| rest.split_once(','))
+            .and_then(|(meta, data)| meta.strip_suffix(";base64").map(|media_type| (media_type, data)))
+        {
+            return MediaSource::Base64 {
+                media_type: media_type.to_string(),
+                data: data.to_string(),
+            };
+        }
+
+        if value.contains("://") || value.starts_with("data:") {
+            MediaSource::Url(value.to_string())
+        } else {
+            MediaSource::Base64 {
+                media_type: guess_media_type(filename.unwrap_or_default()),
+                data: value.to_string(),
+            }
+        }
+    }
+
+    /// Media as a URL, inlining base64 data as a `data:` URL
+    pub fn url(&self) -> String {
+        match self {
+            MediaSource::Url(url) => url.clone(),
+            MediaSource::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+        }
+    }
+
+    /// MIME type of the media, guessed from the URL path for remote media
+    pub fn media_type(&self) -> String {
+        match self {
+            MediaSource::Url(url) => guess_media_type(url_path(url)),
+            MediaSource::Base64 { media_type, .. } => media_type.clone(),
+        }
+    }
+
+    /// Whether the media is remote and can be downloaded over HTTP
+    fn is_fetchable(&self) -> bool {
+        matches!(self, MediaSource::Url(url) if url.starts_with("http://") || url.starts_with("https://"))
+    }
+}
+
+/// Image or document attached to a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Media {
+    pub kind: MediaKind,
+    pub source: MediaSource,
+    pub filename: Option<String>,
+}
+
+impl Media {
+    /// Media carried by a content part, None for text parts and files without data
+    pub fn from_part(part: &ContentPart) -> Option<Self> {
+        match part {
+            ContentPart::Text { .. } => None,
+            ContentPart::ImageUrl { image_url } => Some(Media {
+                kind: MediaKind::Image,
+                source: MediaSource::parse(&image_url.url, None),
+                filename: None,
+            }),
+            ContentPart::File { file } => file.file_data.as_deref().map(|data| Media {
+                kind: MediaKind::Document,
+                source: MediaSource::parse(data, file.filename.as_deref()),
+                filename: file.filename.clone(),
+            }),
+        }
+    }
+}
+
+/// Images and documents attached to a request message
+pub fn message_media(message: &RequestMessage) -> Vec<Media> {
+    message.parts().iter().filter_map(Media::from_part).collect()
+}
+
+/// MediaResolver validates message attachments and inlines remote ones
+///
+/// Remote media is only downloaded from hosts on public networks, unless the host
+/// is explicitly allowed, so that requests cannot reach the gateway's own network.
+#[derive(Debug, Clone)]
+pub struct MediaResolver {
+    client: reqwest::Client,
+    max_size: u64,
+    hosts: Arc<AllowedHosts>,
+}
+
+impl Default for MediaResolver {
+    fn default() -> Self {
+        Self::new(&StorageConfig::default())
+    }
+}
+
+impl MediaResolver {
+    /// Create a resolver with the attachment size, download timeout and allowed hosts of `config`
+    pub fn new(config: &StorageConfig) -> Self {
+        let hosts = Arc::new(AllowedHosts(
+            config
+                .media_allowed_hosts
+                .iter()
+                .map(|host| host.to_lowercase())
+                .collect(),
+        ));
+        let timeout = Duration::from_secs(config.media_fetch_timeout.max(1));
+        Self {
+            client: media_client(timeout, hosts.clone()),
+            max_size: config.max_upload_size,
+            hosts,
+        }
+    }
+
+    /// Maximum size of a single attachment, in bytes
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Prepare the attachments of a chat request for the provider and model it is sent to
+    ///
+    /// Images are rejected for providers or models without vision support, and
+    /// documents for providers that cannot read them. Inlined media must have an
+    /// accepted MIME type and fit in the maximum upload size. Remote documents, and
+    /// remote images for providers that do not fetch URLs themselves, are downloaded
+    /// and inlined as base64 under the same checks.
+    pub async fn prepare<'a>(
+        &self,
+        provider: &dyn Provider,
+        model: &str,
+        request: &'a ChatCompletionReq,
+    ) -> Result<Cow<'a, ChatCompletionReq>, ProviderError> {
+        let capabilities = provider.capabilities();
+        let mut fetch = false;
+
+        for media in request.messages.iter().flat_map(message_media) {
+            match media.kind {
+                MediaKind::Image if !capabilities.vision || !supports_vision(model) => {
+                    return Err(ProviderError::Unsupported {
+                        provider: provider.name().to_string(),
+                        feature: format!("image input with model '{}'", model),
+                    });
+                }
+                MediaKind::Document if !capabilities.documents => {
+                    return Err(ProviderError::Unsupported {
+                        provider: provider.name().to_string(),
+                        feature: "document input".to_string(),
+                    });
+                }
+                _ => {}
+            }
+
+            match &media.source {
+                MediaSource::Base64 { media_type, data } => self.check(media.kind, media_type, decoded_size(data))?,
+                source => fetch |= source.is_fetchable() && needs_inlining(media.kind, &capabilities),
+            }
+        }
+
+        if !fetch {
+            return Ok(Cow::Borrowed(request));
+        }
+
+        let mut request = request.clone();
+        for message in &mut request.messages {
+            self.inline_message(message, &capabilities).await?;
+        }
+        Ok(Cow::Owned(request))
+    }
+
+    /// Replace remote media of a message with base64 `data:` URLs
+    async fn inline_message(
+        &self,
+        message: &mut RequestMessage,
+        capabilities: &ProviderCapabilities,
+    ) -> Result<(), ProviderError> {
+        let Some(ChatContent::Parts(parts)) = &mut message.content else {
+            return Ok(());
+        };
+
+        for part in parts {
+            let Some(media) = Media::from_part(part) else {
+                continue;
+            };
+            if !media.source.is_fetchable() || !needs_inlining(media.kind, capabilities) {
+                continue;
+            }
+
+            let url = media.source.url();
+            let inlined = self.fetch(media.kind, &url).await?.url();
+            match part {
+                ContentPart::ImageUrl { image_url } => image_url.url = inlined,
+                ContentPart::File { file } => {
+                    file.file_data = Some(inlined);
+                    file.filename.get_or_insert_with(|| file_name(&url));
+                }
+                ContentPart::Text { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Download remote media, enforcing the MIME and size checks
+    ///
+    /// Every hop of a redirect must pass the same host checks as the URL itself.
+    async fn fetch(&self, kind: MediaKind, url: &str) -> Result<MediaSource, ProviderError> {
+        let fetch_error =
+            |message: String| ProviderError::InvalidRequest(format!("Failed to fetch '{}': {}", url, message));
+
+        let mut target = Url::parse(url).map_err(|e| fetch_error(e.to_string()))?;
+        let mut redirects = 0;
+        let mut response = loop {
+            self.hosts.check(&target).await.map_err(&fetch_error)?;
+            let response = self
+                .client
+                .get(target.clone())
+                .send()
+                .await
+                .map_err(|e| fetch_error(e.to_string()))?;
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let Some(location) = response.headers().get(LOCATION).and_then(|value| value.to_str().ok()) else {
+                break response;
+            };
+            if redirects == MAX_REDIRECTS {
+                return Err(fetch_error("too many redirects".to_string()));
+            }
+            redirects += 1;
+            target = target.join(location).map_err(|e| fetch_error(e.to_string()))?;
+        };
+        if !response.status().is_success() {
+            return Err(fetch_error(format!("status {}", response.status().as_u16())));
+        }
+        if let Some(length) = response.content_length() {
+            self.check_size(length)?;
+        }
+
+        let media_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty() && value != "application/octet-stream")
+            .unwrap_or_else(|| guess_media_type(url_path(url)));
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| fetch_error(e.to_string()))? {
+            bytes.extend_from_slice(&chunk);
+            self.check_size(bytes.len() as u64)?;
+        }
+        self.check(kind, &media_type, bytes.len() as u64)?;
+
+        Ok(MediaSource::Base64 {
+            media_type,
+            data: STANDARD.encode(&bytes),
+        })
+    }
+
+    /// Check the MIME type and size of an attachment
+    fn check(&self, kind: MediaKind, media_type: &str, size: u64) -> Result<(), ProviderError> {
+        let allowed = match kind {
+            MediaKind::Image => IMAGE_TYPES.contains(&media_type),
+            MediaKind::Document => DOCUMENT_TYPES.contains(&media_type),
+        };
+        if !allowed {
+            return Err(ProviderError::InvalidRequest(format!(
+                "Unsupported {} type '{}'",
+                kind, media_type
+            )));
+        }
+        self.check_size(size)
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), ProviderError> {
+        if size > self.max_size {
+            return Err(ProviderError::InvalidRequest(format!(
+                "Attachment exceeds the maximum upload size of {} bytes",
+                self.max_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Hosts remote media may be downloaded from even though they are not on a public network
+///
+/// Also resolves host names for the download client, so that the addresses checked
+/// are the ones connected to.
+#[derive(Debug)]
+struct AllowedHosts(Vec<String>);
+
+impl AllowedHosts {
+    fn allows(&self, host: &str) -> bool {
+        self.0.iter().any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    /// Check that a URL may be downloaded, resolving its host
+    async fn check(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("unsupported scheme '{}'", url.scheme()));
+        }
+        let Some(host) = url.host_str() else {
+            return Err("missing host".to_string());
+        };
+        if self.allows(host) {
+            return Ok(());
+        }
+        match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) if !is_public(ip) => Err(format!("address {} is not public", ip)),
+            Ok(_) => Ok(()),
+            Err(_) => self.lookup(host).await.map(|_| ()),
+        }
+    }
+
+    /// Addresses of a host name, refused unless they are all public or the host is allowed
+    async fn lookup(&self, host: &str) -> Result<Vec<SocketAddr>, String> {
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|e| format!("failed to resolve host '{}': {}", host, e))?
+            .collect();
+        if !self.allows(host)
+            && let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip()))
+        {
+            return Err(format!("host '{}' resolves to non-public address {}", host, addr.ip()));
+        }
+        Ok(addrs)
+    }
+}
+
+impl Resolve for AllowedHosts {
+    fn resolve(&self, name: Name) -> Resolving {
+        let hosts = AllowedHosts(self.0.clone());
+        Box::pin(async move {
+            let addrs = hosts.lookup(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether an address is reachable on the public internet
+///
+/// Loopback, private, link-local (including cloud metadata endpoints), shared,
+/// documentation, multicast and reserved ranges are not.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(mapped));
+            }
+            let [first, second, ..] = ip.segments();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && second == 0x0db8))
+        }
+    }
+}
+
+/// Whether remote media of this kind must be inlined before reaching the provider
+///
+/// No provider fetches remote documents, while most fetch remote images themselves.
+fn needs_inlining(kind: MediaKind, capabilities: &ProviderCapabilities) -> bool {
+    kind == MediaKind::Document || !capabilities.image_urls
+}
+
+/// Size in bytes of base64 encoded data
+fn decoded_size(data: &str) -> u64 {
+    (data.trim_end_matches('=').len() as u64 * 3) / 4
+}
+
+/// MIME type guessed from a file name or path, `application/octet-stream` when unknown
+fn guess_media_type(path: &str) -> String {
+    mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .essence_str()
+        .to_string()
+}
+
+/// Path of a URL, without query string or fragment
+fn url_path(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Last path segment of a URL, used to name downloaded documents
+fn file_name(url: &str) -> String {
+    url_path(url)
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or("document")
+        .to_string()
+}
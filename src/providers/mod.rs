@@ -5,4 +5,38 @@ pub mod azure_openai;
 pub mod bedrock;
 pub mod cohere;
 pub mod openai;
+pub mod openai_compatible;
 pub mod vertex;
+
+mod catalog;
+mod circuit;
+mod client;
+mod error;
+mod fallback;
+mod media;
+mod params;
+mod pool;
+mod provider;
+mod registry;
+mod routing;
+mod schema;
+mod stream;
+mod structured;
+
+pub use catalog::{ModelCatalog, ModelInfo, ModelKind, ModelPricing, ModelRequirements, Modality};
+pub use circuit::{CircuitBreaker, CircuitPermit, CircuitState};
+pub use client::{http_client, upstream_client};
+pub use error::ProviderError;
+pub use fallback::{FallbackOutcome, with_fallbacks};
+pub use media::{Media, MediaKind, MediaResolver, MediaSource, message_media};
+pub use models::{ModelProvider, supports_vision};
+pub use params::{FunctionDefinition, FunctionTool, ModelParams, ResponseFormat, StopSequences, ToolChoice};
+pub use pool::{PoolMember, ProviderPool};
+pub use provider::{Provider, ProviderCapabilities};
+pub use registry::ProviderRegistry;
+pub use routing::{AliasTarget, ModelAlias, RoutingTable};
+pub use schema::validate as validate_schema;
+pub use stream::{
+    ChatCompletionStream, ChunkBuilder, ChunkTranslator, FrameDecoder, SseDecoder, SseEvent, translate_stream,
+};
+pub use structured::chat_completion_structured;
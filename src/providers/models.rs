@@ -1,3 +1,35 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::ProviderError;
+
+/// Model families known to accept text input only, matched anywhere in the model name
+const TEXT_ONLY_MODELS: [&str; 20] = [
+    "gpt-3.5",
+    "gpt-4-0314",
+    "gpt-4-0613",
+    "gpt-4-32k",
+    "o1-mini",
+    "o3-mini",
+    "claude-instant",
+    "claude-2",
+    "command-r",
+    "command-light",
+    "titan-text",
+    "llama2",
+    "llama3-8b",
+    "llama3-70b",
+    "llama3-1-",
+    "mistral-7b",
+    "mixtral",
+    "text-bison",
+    "chat-bison",
+    "gemini-1.0-pro",
+];
+
+/// Text-only models matched by their full name, as newer families share the prefix
+const TEXT_ONLY_EXACT: [&str; 3] = ["gpt-4", "command", "command-nightly"];
+
 /// ModelProvider represents the different AI model providers supported by Sorai.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ModelProvider {
@@ -7,4 +39,65 @@ pub enum ModelProvider {
     Bedrock,
     Cohere,
     Vertex,
+    /// A named, config-defined provider speaking the OpenAI wire format
+    OpenAICompatible,
+}
+
+impl ModelProvider {
+    /// All built-in providers, in registration order
+    ///
+    /// OpenAI-compatible providers are registered by name from the config instead.
+    pub const ALL: [ModelProvider; 6] = [
+        ModelProvider::OpenAI,
+        ModelProvider::Anthropic,
+        ModelProvider::AzureOpenAI,
+        ModelProvider::Bedrock,
+        ModelProvider::Cohere,
+        ModelProvider::Vertex,
+    ];
+
+    /// Provider identifier as used in the `provider` field of requests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelProvider::OpenAI => "openai",
+            ModelProvider::Anthropic => "anthropic",
+            ModelProvider::AzureOpenAI => "azure_openai",
+            ModelProvider::Bedrock => "bedrock",
+            ModelProvider::Cohere => "cohere",
+            ModelProvider::Vertex => "vertex",
+            ModelProvider::OpenAICompatible => "openai_compatible",
+        }
+    }
+}
+
+impl fmt::Display for ModelProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ModelProvider {
+    type Err = ProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "openai" => Ok(ModelProvider::OpenAI),
+            "anthropic" => Ok(ModelProvider::Anthropic),
+            "azure_openai" | "azure-openai" | "azure" => Ok(ModelProvider::AzureOpenAI),
+            "bedrock" | "aws_bedrock" => Ok(ModelProvider::Bedrock),
+            "cohere" => Ok(ModelProvider::Cohere),
+            "vertex" | "vertex_ai" | "google_vertex" => Ok(ModelProvider::Vertex),
+            "openai_compatible" | "openai-compatible" => Ok(ModelProvider::OpenAICompatible),
+            _ => Err(ProviderError::UnknownProvider(s.to_string())),
+        }
+    }
+}
+
+/// Whether a model accepts image input
+///
+/// Only known text-only model families are rejected; unknown models are
+/// assumed to accept images and left for the provider to refuse.
+pub fn supports_vision(model: &str) -> bool {
+    let model = model.to_lowercase();
+    !TEXT_ONLY_EXACT.contains(&model.as_str()) && !TEXT_ONLY_MODELS.iter().any(|family| model.contains(family))
 }
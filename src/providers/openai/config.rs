@@ -9,7 +9,24 @@ pub struct OpenAIConfig {
     pub base_url: String,
 }
 
+/// Default OpenAI API base URL
+pub const OPENAI_DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
 impl OpenAIConfig {
+    /// OpenAI is usable with an API key, or with a custom base URL for keyless local servers
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty() || !self.base_url.is_empty()
+    }
+
+    /// Base URL to send requests to
+    pub fn base_url(&self) -> &str {
+        if self.base_url.is_empty() {
+            OPENAI_DEFAULT_BASE_URL
+        } else {
+            &self.base_url
+        }
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "OpenAI".to_string(),
@@ -2,8 +2,10 @@
 
 mod config;
 mod route;
+mod stream;
 mod types;
 
 pub use config::*;
 pub use route::*;
+pub use stream::*;
 pub use types::*;
@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use std::time::Instant;
+
+use super::{
+    OpenAIChatReq, OpenAIChatRes, OpenAIConfig, OpenAIEmbeddingReq, OpenAIEmbeddingRes, OpenAIStreamOptions,
+    OpenAIStreamTranslator, OpenAITextReq, OpenAITextRes,
+};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ExtraFields,
+    TextCompletionChoice, TextCompletionId, TextCompletionReq, TextCompletionResponse,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::params::ModelParams;
+use crate::providers::{
+    ChatCompletionStream, ModelProvider, Provider, ProviderCapabilities, ProviderError, SseDecoder, translate_stream,
+};
+
+/// OpenAI chat and text completion adapter
+pub struct OpenAIProvider {
+    config: OpenAIConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAIProvider {
+    /// Create a new OpenAI adapter using the shared HTTP client
+    pub fn new(config: OpenAIConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+
+    /// Build an authenticated POST request for the given API path
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.post(join_url(self.config.base_url(), path));
+        if self.config.api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.config.api_key)
+        }
+    }
+
+    /// Translate a Sorai chat request into the OpenAI wire format
+    pub fn build_chat_request(model: &str, request: &ChatCompletionReq) -> OpenAIChatReq {
+        let params = ModelParams::from_value(request.params.as_ref());
+        let response_format = params.response_format();
+        let mut extra = params.extra;
+        if let Some(format) = response_format {
+            extra.insert("response_format".to_string(), format.to_openai());
+        }
+
+        OpenAIChatReq {
+            model: model.to_string(),
+            messages: request.messages.clone(),
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            top_p: params.top_p,
+            stop: params.stop.as_ref().map(|s| s.to_vec()),
+            extra,
+            ..Default::default()
+        }
+    }
+
+    /// Translate a Sorai chat request into a streamed OpenAI request that reports usage
+    pub fn build_chat_stream_request(model: &str, request: &ChatCompletionReq) -> OpenAIChatReq {
+        let mut body = Self::build_chat_request(model, request);
+        body.extra.remove("stream_options");
+        body.stream = Some(true);
+        body.stream_options = Some(OpenAIStreamOptions { include_usage: true });
+        body
+    }
+
+    /// Translate a Sorai text request into the OpenAI wire format
+    pub fn build_text_request(model: &str, request: &TextCompletionReq) -> OpenAITextReq {
+        let params = ModelParams::from_value(request.params.as_ref());
+
+        OpenAITextReq {
+            model: model.to_string(),
+            prompt: request.text.clone().unwrap_or_default(),
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            top_p: params.top_p,
+            stop: params.stop.as_ref().map(|s| s.to_vec()),
+            extra: params.extra,
+        }
+    }
+
+    /// Translate a Sorai embeddings request into the OpenAI wire format
+    ///
+    /// Vectors are always requested as floats; base64 encoding is applied by the gateway.
+    pub fn build_embedding_request(model: &str, request: &EmbeddingReq) -> OpenAIEmbeddingReq {
+        OpenAIEmbeddingReq {
+            model: model.to_string(),
+            input: request.inputs(),
+            dimensions: request.dimensions,
+            encoding_format: "float".to_string(),
+            extra: request
+                .params
+                .as_ref()
+                .and_then(|params| params.as_object().cloned())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for OpenAIProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::OpenAI
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: true,
+            image_urls: true,
+            embeddings: true,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = Self::build_chat_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("chat/completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIChatRes = decode(self.name(), &raw)?;
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| ChatCompletionChoice {
+                    index: choice.index,
+                    message: ChatMessage {
+                        role: choice.message.role,
+                        content: choice.message.content.unwrap_or_default(),
+                        tool_calls: choice.message.tool_calls,
+                        tool_call_id: None,
+                    },
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        let body = Self::build_chat_stream_request(model, request);
+        let response = send_stream(self.name(), self.post("chat/completions").json(&body)).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            SseDecoder::new(),
+            OpenAIStreamTranslator::new(self.name(), model),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        let body = Self::build_text_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAITextRes = decode(self.name(), &raw)?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| TextCompletionChoice {
+                    index: choice.index,
+                    text: choice.text,
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        let body = OpenAIProvider::build_embedding_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("embeddings").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIEmbeddingRes = decode(self.name(), &raw)?;
+
+        Ok(EmbeddingResponse::new(
+            if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model.clone()
+            },
+            response.vectors(),
+            response.usage.map(Into::into),
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
@@ -0,0 +1,51 @@
+use super::OpenAIChatChunk;
+use crate::http::schemas::completions::{ChatCompletionChunk, ChatMessageDelta};
+use crate::providers::client::decode_str;
+use crate::providers::{ChunkBuilder, ChunkTranslator, ProviderError, SseEvent};
+
+/// Translates OpenAI chat completion chunks, also used by OpenAI-compatible providers
+pub struct OpenAIStreamTranslator {
+    provider: String,
+    chunks: ChunkBuilder,
+}
+
+impl OpenAIStreamTranslator {
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            chunks: ChunkBuilder::new(model),
+        }
+    }
+}
+
+impl ChunkTranslator for OpenAIStreamTranslator {
+    type Frame = SseEvent;
+
+    fn translate(&mut self, event: SseEvent) -> Result<Vec<ChatCompletionChunk>, ProviderError> {
+        if event.data == "[DONE]" {
+            return Ok(Vec::new());
+        }
+
+        let chunk: OpenAIChatChunk = decode_str(&self.provider, &event.data)?;
+        self.chunks.set_model(&chunk.model);
+
+        let mut chunks: Vec<_> = chunk
+            .choices
+            .into_iter()
+            .map(|choice| {
+                let delta = ChatMessageDelta {
+                    role: choice.delta.role,
+                    content: choice.delta.content,
+                    tool_calls: choice.delta.tool_calls,
+                };
+                self.chunks.delta(choice.index, delta, choice.finish_reason)
+            })
+            .collect();
+
+        // Usage arrives on a last chunk without choices when `include_usage` is set
+        if let Some(usage) = chunk.usage {
+            chunks.push(self.chunks.usage(usage.into()));
+        }
+        Ok(chunks)
+    }
+}
@@ -1,3 +1,209 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::http::schemas::completions::{RequestMessage, ToolCall, ToolCallDelta, UsageInfo};
+
 // OpenAIChatReq represents an OpenAI chat completion request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAIChatReq {
+    pub model: String,
+    pub messages: Vec<RequestMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAIStreamOptions>,
+    /// Remaining OpenAI parameters (tools, response_format, seed, ...) passed through as-is
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// OpenAIStreamOptions represents the options of a streamed chat completion
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct OpenAIStreamOptions {
+    pub include_usage: bool,
+}
 
 // OpenAIChatRes represents an OpenAI chat completion response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatRes {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub object: String,
+    #[serde(default)]
+    pub created: i64,
+    #[serde(default)]
+    pub model: String,
+    pub choices: Vec<OpenAIChatChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
+
+// OpenAIChatChoice represents a single choice in an OpenAI chat completion response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatChoice {
+    #[serde(default)]
+    pub index: i32,
+    pub message: OpenAIMessage,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+// OpenAIMessage represents a message returned by OpenAI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+// OpenAIChatChunk represents a streamed OpenAI chat completion chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatChunk {
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub choices: Vec<OpenAIChunkChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
+
+// OpenAIChunkChoice represents a single choice in a streamed chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChunkChoice {
+    #[serde(default)]
+    pub index: i32,
+    #[serde(default)]
+    pub delta: OpenAIDelta,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+// OpenAIDelta represents the message delta of a streamed choice
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAIDelta {
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+// OpenAITextReq represents an OpenAI (legacy) text completion request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAITextReq {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// OpenAITextRes represents an OpenAI text completion response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAITextRes {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub object: String,
+    #[serde(default)]
+    pub created: i64,
+    #[serde(default)]
+    pub model: String,
+    pub choices: Vec<OpenAITextChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
+
+// OpenAITextChoice represents a single choice in an OpenAI text completion response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAITextChoice {
+    #[serde(default)]
+    pub index: i32,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+// OpenAIEmbeddingReq represents an OpenAI embeddings request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAIEmbeddingReq {
+    pub model: String,
+    pub input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+    pub encoding_format: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// OpenAIEmbeddingRes represents an OpenAI embeddings response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIEmbeddingRes {
+    #[serde(default)]
+    pub model: String,
+    pub data: Vec<OpenAIEmbeddingData>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
+
+impl OpenAIEmbeddingRes {
+    /// Vectors ordered by input index
+    pub fn vectors(&self) -> Vec<Vec<f32>> {
+        let mut data: Vec<&OpenAIEmbeddingData> = self.data.iter().collect();
+        data.sort_by_key(|d| d.index);
+        data.into_iter().map(|d| d.embedding.clone()).collect()
+    }
+}
+
+// OpenAIEmbeddingData represents a single embedding in an OpenAI embeddings response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIEmbeddingData {
+    #[serde(default)]
+    pub index: i32,
+    pub embedding: Vec<f32>,
+}
+
+// OpenAIUsage represents token usage reported by OpenAI
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct OpenAIUsage {
+    #[serde(default)]
+    pub prompt_tokens: i32,
+    #[serde(default)]
+    pub completion_tokens: i32,
+    #[serde(default)]
+    pub total_tokens: i32,
+}
+
+impl From<OpenAIUsage> for UsageInfo {
+    fn from(usage: OpenAIUsage) -> Self {
+        UsageInfo {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: if usage.total_tokens > 0 {
+                usage.total_tokens
+            } else {
+                usage.prompt_tokens + usage.completion_tokens
+            },
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::config::{ConfigItem, redact_sensitive};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Prefix of the per-provider environment variables, followed by the upper-cased name
+pub const OPENAI_COMPATIBLE_ENV_PREFIX: &str = "PROVIDER_OPENAI_COMPATIBLE_";
+
+/// A named provider speaking the OpenAI wire format, such as llama.cpp, Jan, vLLM or Ollama
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAICompatibleConfig {
+    /// Name used in the `provider` field of requests
+    pub name: String,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Models served by this provider; empty means any model is accepted
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl OpenAICompatibleConfig {
+    /// Read a named provider from `PROVIDER_OPENAI_COMPATIBLE_<NAME>_*` environment variables
+    pub fn from_env(name: &str) -> Self {
+        let prefix = Self::env_prefix(name);
+        let var = |key: &str| std::env::var(format!("{}{}", prefix, key)).unwrap_or_default();
+
+        Self {
+            name: name.to_string(),
+            base_url: var("BASE_URL"),
+            api_key: var("API_KEY"),
+            headers: Self::parse_headers(&var("HEADERS")),
+            models: var("MODELS")
+                .split(',')
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Environment variable prefix of a named provider, e.g. `PROVIDER_OPENAI_COMPATIBLE_LLAMA_CPP_`
+    pub fn env_prefix(name: &str) -> String {
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}{}_", OPENAI_COMPATIBLE_ENV_PREFIX, name)
+    }
+
+    /// Parse a header list in the `Name=value,...` format
+    pub fn parse_headers(value: &str) -> BTreeMap<String, String> {
+        value
+            .split(',')
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, _)| !name.is_empty())
+            .collect()
+    }
+
+    /// A named provider is usable once it has a base URL; the API key is optional
+    pub fn is_configured(&self) -> bool {
+        !self.name.is_empty() && !self.base_url.is_empty()
+    }
+
+    /// Check whether the provider serves a model
+    pub fn serves(&self, model: &str) -> bool {
+        self.models.is_empty() || self.models.iter().any(|m| m == model)
+    }
+
+    pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
+        let section = format!("OpenAI-compatible ({})", self.name);
+        items.push(ConfigItem {
+            section: section.clone(),
+            key: "Base URL".to_string(),
+            value: if self.base_url.is_empty() {
+                "<not set>".to_string()
+            } else {
+                self.base_url.clone()
+            },
+        });
+        items.push(ConfigItem {
+            section: section.clone(),
+            key: "API Key".to_string(),
+            value: redact_sensitive(&self.api_key),
+        });
+        items.push(ConfigItem {
+            section: section.clone(),
+            key: "Headers".to_string(),
+            value: if self.headers.is_empty() {
+                "<not set>".to_string()
+            } else {
+                // Header values often carry credentials, so only names are shown
+                self.headers.keys().cloned().collect::<Vec<_>>().join(", ")
+            },
+        });
+        items.push(ConfigItem {
+            section,
+            key: "Models".to_string(),
+            value: if self.models.is_empty() {
+                "<any>".to_string()
+            } else {
+                self.models.join(", ")
+            },
+        });
+    }
+}
@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::time::Instant;
+
+use super::OpenAICompatibleConfig;
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ExtraFields,
+    TextCompletionChoice, TextCompletionId, TextCompletionReq, TextCompletionResponse,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::openai::{
+    OpenAIChatRes, OpenAIEmbeddingRes, OpenAIProvider, OpenAIStreamTranslator, OpenAITextRes,
+};
+use crate::providers::{
+    ChatCompletionStream, ModelProvider, Provider, ProviderCapabilities, ProviderError, SseDecoder, translate_stream,
+};
+
+/// Adapter for a named OpenAI-compatible server
+///
+/// The request and response formats are OpenAI's; only the base URL, the
+/// optional bearer token, the extra headers and the served models differ.
+pub struct OpenAICompatibleProvider {
+    config: OpenAICompatibleConfig,
+    client: reqwest::Client,
+    headers: HeaderMap,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create a new adapter using the shared HTTP client
+    ///
+    /// Extra headers with an invalid name or value are skipped with a warning.
+    pub fn new(config: OpenAICompatibleConfig, client: reqwest::Client) -> Self {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => tracing::warn!("Skipping invalid header '{}' for provider '{}'", name, config.name),
+            }
+        }
+
+        Self {
+            config,
+            client,
+            headers,
+        }
+    }
+
+    /// Reject models outside of the configured model list
+    fn check_model(&self, model: &str) -> Result<(), ProviderError> {
+        if self.config.serves(model) {
+            Ok(())
+        } else {
+            Err(ProviderError::InvalidRequest(format!(
+                "Model '{}' is not served by provider '{}'",
+                model, self.config.name
+            )))
+        }
+    }
+
+    /// Build a POST request with the extra headers and, if configured, the bearer token
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .post(join_url(&self.config.base_url, path))
+            .headers(self.headers.clone());
+
+        if self.config.api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.config.api_key)
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for OpenAICompatibleProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::OpenAICompatible
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: false,
+            image_urls: true,
+            embeddings: true,
+        }
+    }
+
+    fn models(&self) -> Vec<String> {
+        self.config.models.clone()
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        self.check_model(model)?;
+        let body = OpenAIProvider::build_chat_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("chat/completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIChatRes = decode(self.name(), &raw)?;
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| ChatCompletionChoice {
+                    index: choice.index,
+                    message: ChatMessage {
+                        role: choice.message.role,
+                        content: choice.message.content.unwrap_or_default(),
+                        tool_calls: choice.message.tool_calls,
+                        tool_call_id: None,
+                    },
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        self.check_model(model)?;
+        let body = OpenAIProvider::build_chat_stream_request(model, request);
+        let response = send_stream(self.name(), self.post("chat/completions").json(&body)).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            SseDecoder::new(),
+            OpenAIStreamTranslator::new(self.name(), model),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        self.check_model(model)?;
+        let body = OpenAIProvider::build_text_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("completions").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAITextRes = decode(self.name(), &raw)?;
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| TextCompletionChoice {
+                    index: choice.index,
+                    text: choice.text,
+                    finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
+                })
+                .collect(),
+            model: if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model
+            },
+            created: if response.created > 0 {
+                response.created
+            } else {
+                chrono::Utc::now().timestamp()
+            },
+            usage: response.usage.map(Into::into),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        self.check_model(model)?;
+        let body = OpenAIProvider::build_embedding_request(model, request);
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), self.post("embeddings").json(&body)).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: OpenAIEmbeddingRes = decode(self.name(), &raw)?;
+
+        Ok(EmbeddingResponse::new(
+            if response.model.is_empty() {
+                model.to_string()
+            } else {
+                response.model.clone()
+            },
+            response.vectors(),
+            response.usage.map(Into::into),
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
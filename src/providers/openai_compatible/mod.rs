@@ -0,0 +1,7 @@
+#![allow(unused_variables, unused_imports, dead_code)]
+
+mod config;
+mod handler;
+
+pub use config::*;
+pub use handler::*;
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Generation parameters shared by all providers, parsed from the request `params` object
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, alias = "stop_sequences", skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopSequences>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    /// Provider specific parameters passed through untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Stop sequences may be sent as a single string or a list of strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StopSequences {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl StopSequences {
+    /// Stop sequences as a list
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            StopSequences::Single(s) => vec![s.clone()],
+            StopSequences::Multiple(v) => v.clone(),
+        }
+    }
+}
+
+/// Function tool offered to the model, in OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionTool {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+/// Name, description and JSON schema of a function tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Value>,
+}
+
+impl FunctionDefinition {
+    /// JSON schema of the arguments, an empty object schema when none was given
+    pub fn schema(&self) -> Value {
+        self.parameters
+            .clone()
+            .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} }))
+    }
+}
+
+/// How the model should use the offered tools
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolChoice {
+    Auto,
+    None,
+    /// The model must call at least one tool
+    Required,
+    /// The model must call the named function
+    Function(String),
+}
+
+impl ToolChoice {
+    /// Parse an OpenAI `tool_choice`, either a mode string or a named function object
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(mode) => match mode.as_str() {
+                "auto" => Some(ToolChoice::Auto),
+                "none" => Some(ToolChoice::None),
+                "required" | "any" => Some(ToolChoice::Required),
+                _ => None,
+            },
+            Value::Object(_) => value["function"]["name"]
+                .as_str()
+                .map(|name| ToolChoice::Function(name.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Output format requested with `response_format`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    Text,
+    /// Any JSON object
+    JsonObject,
+    /// JSON matching the given schema
+    JsonSchema {
+        name: String,
+        schema: Value,
+        strict: Option<bool>,
+    },
+}
+
+impl ResponseFormat {
+    /// Name used for a schema that was given without one
+    pub const DEFAULT_NAME: &'static str = "response";
+
+    /// Parse a `response_format`, accepting the schema flat or nested under `json_schema` as OpenAI sends it
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value["type"].as_str()? {
+            "text" => Some(ResponseFormat::Text),
+            "json_object" => Some(ResponseFormat::JsonObject),
+            "json_schema" => {
+                let format = value.get("json_schema").unwrap_or(value);
+                Some(ResponseFormat::JsonSchema {
+                    name: format["name"].as_str().unwrap_or(Self::DEFAULT_NAME).to_string(),
+                    schema: format.get("schema").cloned()?,
+                    strict: format["strict"].as_bool(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Schema the output must match, None for plain text
+    ///
+    /// JSON mode has no schema of its own but still requires a JSON object.
+    pub fn schema(&self) -> Option<Value> {
+        match self {
+            ResponseFormat::Text => None,
+            ResponseFormat::JsonObject => Some(serde_json::json!({ "type": "object" })),
+            ResponseFormat::JsonSchema { schema, .. } => Some(schema.clone()),
+        }
+    }
+
+    /// Name of the schema
+    pub fn name(&self) -> &str {
+        match self {
+            ResponseFormat::JsonSchema { name, .. } => name,
+            _ => Self::DEFAULT_NAME,
+        }
+    }
+
+    /// Tool whose arguments carry the output, for providers that enforce a schema only on tool input
+    ///
+    /// Such providers are offered this tool and forced to call it; the arguments
+    /// of the call are then returned as the message content.
+    pub fn tool(&self) -> Option<FunctionDefinition> {
+        self.schema().map(|schema| FunctionDefinition {
+            name: self.name().to_string(),
+            description: Some("Respond with output matching this schema".to_string()),
+            parameters: Some(schema),
+        })
+    }
+
+    /// The format in OpenAI's wire shape
+    pub fn to_openai(&self) -> Value {
+        match self {
+            ResponseFormat::Text => serde_json::json!({ "type": "text" }),
+            ResponseFormat::JsonObject => serde_json::json!({ "type": "json_object" }),
+            ResponseFormat::JsonSchema { name, schema, strict } => {
+                let mut json_schema = serde_json::json!({ "name": name, "schema": schema });
+                if let Some(strict) = strict {
+                    json_schema["strict"] = Value::Bool(*strict);
+                }
+                serde_json::json!({ "type": "json_schema", "json_schema": json_schema })
+            }
+        }
+    }
+}
+
+impl ModelParams {
+    /// Parse params from the optional request value, ignoring fields with unexpected types
+    pub fn from_value(params: Option<&Value>) -> Self {
+        match params {
+            Some(value @ Value::Object(_)) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                tracing::debug!("Failed to parse model params, passing them through: {}", e);
+                ModelParams {
+                    extra: value.as_object().cloned().unwrap_or_default(),
+                    ..Default::default()
+                }
+            }),
+            _ => ModelParams::default(),
+        }
+    }
+
+    /// Stop sequences as a list, empty when none were given
+    pub fn stop_sequences(&self) -> Vec<String> {
+        self.stop.as_ref().map(StopSequences::to_vec).unwrap_or_default()
+    }
+
+    /// Function tools from `tools`, skipping entries that are not function tools
+    pub fn tools(&self) -> Vec<FunctionTool> {
+        self.extra
+            .get("tools")
+            .and_then(Value::as_array)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(|tool| serde_json::from_value::<FunctionTool>(tool.clone()).ok())
+                    .filter(|tool| tool.kind.is_empty() || tool.kind == "function")
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parsed `tool_choice`, if one was given
+    pub fn tool_choice(&self) -> Option<ToolChoice> {
+        self.extra.get("tool_choice").and_then(ToolChoice::from_value)
+    }
+
+    /// Parsed `response_format`, if one was given
+    pub fn response_format(&self) -> Option<ResponseFormat> {
+        self.extra.get("response_format").and_then(ResponseFormat::from_value)
+    }
+}
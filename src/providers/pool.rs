@@ -0,0 +1,265 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+
+use super::{ChatCompletionStream, ModelProvider, Provider, ProviderCapabilities, ProviderError};
+use crate::config::PoolStrategy;
+use crate::http::schemas::completions::{
+    ChatCompletionReq, ChatCompletionResponse, TextCompletionReq, TextCompletionResponse,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::metrics::record_pool_ejection;
+
+/// One set of credentials, region or deployment within a [`ProviderPool`]
+pub struct PoolMember {
+    name: String,
+    weight: u32,
+    provider: Arc<dyn Provider>,
+    in_flight: AtomicUsize,
+    ejected_until: Mutex<Option<Instant>>,
+}
+
+impl PoolMember {
+    /// Create a member; a weight of zero counts as one
+    pub fn new(name: impl Into<String>, weight: u32, provider: Arc<dyn Provider>) -> Self {
+        Self {
+            name: name.into(),
+            weight: weight.max(1),
+            provider,
+            in_flight: AtomicUsize::new(0),
+            ejected_until: Mutex::new(None),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    /// Requests currently being served by this member, streams included
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Whether the member is out of rotation after a failure
+    pub fn is_ejected(&self) -> bool {
+        let until = *self.ejected_until.lock().unwrap_or_else(|e| e.into_inner());
+        until.is_some_and(|until| Instant::now() < until)
+    }
+
+    fn eject(&self, cooldown: Duration) {
+        *self.ejected_until.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + cooldown);
+    }
+
+    /// Count a request as in flight until the returned guard is dropped
+    fn start(self: &Arc<Self>) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlight(self.clone())
+    }
+}
+
+/// Guard counting a request in flight on a pool member
+struct InFlight(Arc<PoolMember>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// ProviderPool spreads the requests for one provider over several members
+///
+/// A member answering with an error that points at the member itself (rejected
+/// credentials, rate limiting, a server error, an unreachable endpoint or a timeout)
+/// is taken out of rotation for the cooldown, and the request is retried on another member.
+/// When every member is out of rotation they are all tried again rather than
+/// failing outright.
+pub struct ProviderPool {
+    members: Vec<Arc<PoolMember>>,
+    strategy: PoolStrategy,
+    cooldown: Duration,
+    /// Current weights of the smooth weighted round-robin, one per member
+    current: Mutex<Vec<i64>>,
+}
+
+impl ProviderPool {
+    /// Create a pool, or None without members
+    ///
+    /// Every member must be an implementation of the same provider.
+    pub fn new(members: Vec<PoolMember>, strategy: PoolStrategy, cooldown: Duration) -> Option<Self> {
+        if members.is_empty() {
+            return None;
+        }
+        Some(Self {
+            current: Mutex::new(vec![0; members.len()]),
+            members: members.into_iter().map(Arc::new).collect(),
+            strategy,
+            cooldown,
+        })
+    }
+
+    pub fn members(&self) -> &[Arc<PoolMember>] {
+        &self.members
+    }
+
+    fn first(&self) -> &dyn Provider {
+        self.members[0].provider.as_ref()
+    }
+
+    /// Pick the next member among those not tried yet, preferring members in rotation
+    fn select(&self, tried: &[usize]) -> Option<usize> {
+        let untried: Vec<usize> = (0..self.members.len()).filter(|index| !tried.contains(index)).collect();
+        let healthy: Vec<usize> = untried
+            .iter()
+            .copied()
+            .filter(|&index| !self.members[index].is_ejected())
+            .collect();
+        let candidates = if healthy.is_empty() { untried } else { healthy };
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match self.strategy {
+            PoolStrategy::RoundRobin => {
+                let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+                let total: i64 = candidates.iter().map(|&index| self.members[index].weight as i64).sum();
+                for &index in &candidates {
+                    current[index] += self.members[index].weight as i64;
+                }
+                let selected = candidates
+                    .iter()
+                    .copied()
+                    .max_by_key(|&index| (current[index], std::cmp::Reverse(index)))?;
+                current[selected] -= total;
+                Some(selected)
+            }
+            PoolStrategy::LeastInFlight => candidates.iter().copied().min_by(|&a, &b| {
+                let (a, b) = (&self.members[a], &self.members[b]);
+                (a.in_flight() * b.weight as usize).cmp(&(b.in_flight() * a.weight as usize))
+            }),
+        }
+    }
+
+    /// Run `call` on the selected member, moving on to the next one while members fail
+    async fn dispatch<T, F, Fut>(&self, call: F) -> Result<T, ProviderError>
+    where
+        F: Fn(Arc<PoolMember>) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut tried = Vec::with_capacity(self.members.len());
+        let mut last_error = None;
+
+        while let Some(index) = self.select(&tried) {
+            tried.push(index);
+            let member = self.members[index].clone();
+            tracing::debug!(
+                "Provider '{}' dispatching to pool member '{}'",
+                self.name(),
+                member.name
+            );
+
+            match call(member.clone()).await {
+                Err(e) if is_member_failure(&e) => {
+                    tracing::warn!(
+                        "Pool member '{}' of provider '{}' taken out of rotation for {}s: {}",
+                        member.name,
+                        self.name(),
+                        self.cooldown.as_secs(),
+                        e
+                    );
+                    member.eject(self.cooldown);
+                    record_pool_ejection(self.name(), &member.name);
+                    last_error = Some(e);
+                }
+                result => return result,
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProviderError::NotConfigured(self.name().to_string())))
+    }
+}
+
+/// Errors caused by the member rather than the request, worth another member
+fn is_member_failure(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::Upstream { status, .. } => matches!(status, 401 | 403 | 429) || *status >= 500,
+        ProviderError::Authentication { .. } | ProviderError::Transport { .. } | ProviderError::Timeout { .. } => true,
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl Provider for ProviderPool {
+    fn kind(&self) -> ModelProvider {
+        self.first().kind()
+    }
+
+    fn name(&self) -> &str {
+        self.first().name()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.first().capabilities()
+    }
+
+    fn models(&self) -> Vec<String> {
+        self.first().models()
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        self.dispatch(|member| async move {
+            let _in_flight = member.start();
+            member.provider.chat_completion(model, request).await
+        })
+        .await
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        self.dispatch(|member| async move {
+            let in_flight = member.start();
+            let stream = member.provider.chat_completion_stream(model, request).await?;
+            // The request stays in flight until the client is done with the stream
+            let stream: ChatCompletionStream = Box::pin(stream.map(move |item| {
+                let _ = &in_flight;
+                item
+            }));
+            Ok(stream)
+        })
+        .await
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        self.dispatch(|member| async move {
+            let _in_flight = member.start();
+            member.provider.text_completion(model, request).await
+        })
+        .await
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        self.dispatch(|member| async move {
+            let _in_flight = member.start();
+            member.provider.embeddings(model, request).await
+        })
+        .await
+    }
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::{ChatCompletionStream, ModelProvider, ProviderError};
+use crate::http::schemas::completions::{
+    ChatCompletionReq, ChatCompletionResponse, TextCompletionReq, TextCompletionResponse,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+
+/// Features an upstream provider implementation supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct ProviderCapabilities {
+    pub chat_completion: bool,
+    pub text_completion: bool,
+    pub streaming: bool,
+    pub tools: bool,
+    pub vision: bool,
+    /// Accepts documents such as PDFs attached to messages
+    pub documents: bool,
+    /// Fetches remote image URLs itself, so they need not be inlined
+    pub image_urls: bool,
+    pub embeddings: bool,
+}
+
+/// Provider is implemented by every upstream adapter the gateway can dispatch to
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Provider family this implementation belongs to
+    fn kind(&self) -> ModelProvider;
+
+    /// Name used to address this provider in requests
+    fn name(&self) -> &str {
+        self.kind().as_str()
+    }
+
+    /// Features supported by this provider
+    fn capabilities(&self) -> ProviderCapabilities;
+
+    /// Models this provider is configured to serve, empty when any model may be requested
+    fn models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Run a chat completion against the given model
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError>;
+
+    /// Stream a chat completion against the given model as OpenAI-style chunks
+    ///
+    /// Errors returned here happen before any chunk was produced, so the
+    /// request can still be retried on a fallback provider.
+    async fn chat_completion_stream(
+        &self,
+        _model: &str,
+        _request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        Err(ProviderError::Unsupported {
+            provider: self.name().to_string(),
+            feature: "streaming".to_string(),
+        })
+    }
+
+    /// Run a text completion against the given model
+    async fn text_completion(
+        &self,
+        _model: &str,
+        _request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        Err(ProviderError::Unsupported {
+            provider: self.name().to_string(),
+            feature: "text completions".to_string(),
+        })
+    }
+
+    /// Embed the request inputs with the given model, returning float vectors in input order
+    ///
+    /// The raw upstream response is not attached, as it would repeat every vector.
+    async fn embeddings(&self, _model: &str, _request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        Err(ProviderError::Unsupported {
+            provider: self.name().to_string(),
+            feature: "embeddings".to_string(),
+        })
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::anthropic::AnthropicProvider;
+use super::azure_openai::AzureOpenAIProvider;
+use super::bedrock::BedrockProvider;
+use super::catalog::{ModelCatalog, ModelInfo};
+use super::circuit::CircuitBreaker;
+use super::client::upstream_client;
+use super::cohere::CohereProvider;
+use super::media::MediaResolver;
+use super::openai::OpenAIProvider;
+use super::openai_compatible::OpenAICompatibleProvider;
+use super::pool::{PoolMember, ProviderPool};
+use super::routing::RoutingTable;
+use super::vertex::VertexProvider;
+use super::{ModelProvider, Provider, ProviderError};
+use crate::config::{CircuitBreakerConfig, Config, PoolConfig};
+use crate::http::schemas::completions::Fallback;
+
+/// ProviderRegistry maps provider names to live provider implementations
+#[derive(Clone)]
+pub struct ProviderRegistry {
+    providers: HashMap<String, Arc<dyn Provider>>,
+    media: MediaResolver,
+    catalog: ModelCatalog,
+    routing: RoutingTable,
+    structured_output_retries: u32,
+    circuit_breaker: CircuitBreakerConfig,
+    /// Circuit breaker of every registered provider, empty when breakers are disabled
+    breakers: HashMap<String, Arc<CircuitBreaker>>,
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        Self {
+            providers: HashMap::new(),
+            media: MediaResolver::default(),
+            catalog: ModelCatalog::new(Config::default().catalog.strict),
+            routing: RoutingTable::default(),
+            structured_output_retries: Config::default().sorai.structured_output_retries,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            breakers: HashMap::new(),
+        }
+    }
+}
+
+impl ProviderRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a registry containing every provider that is configured
+    pub fn from_config(config: &Config) -> Self {
+        let mut catalog = ModelCatalog::builtin(config.catalog.strict);
+        for model in &config.catalog.models {
+            catalog.insert(model.clone());
+        }
+        let mut registry = Self {
+            media: MediaResolver::new(&config.storage),
+            catalog,
+            routing: RoutingTable::new(config.routing.aliases.clone()),
+            structured_output_retries: config.sorai.structured_output_retries,
+            circuit_breaker: config.circuit_breaker.clone(),
+            ..Self::new()
+        };
+
+        for kind in ModelProvider::ALL {
+            let client = upstream_client(config.sorai.upstream_timeout(kind.as_str()));
+            let provider = match config.pools.iter().find(|pool| pool.provider == kind.as_str()) {
+                Some(pool) => build_pool(kind, pool, config, &client),
+                None => build_provider(kind, config, &client),
+            };
+            match provider {
+                Some(provider) => {
+                    tracing::debug!("Provider '{}' registered", kind);
+                    registry.register(provider);
+                }
+                None => tracing::debug!("Provider '{}' is not configured, skipping", kind),
+            }
+        }
+
+        for named in &config.openai_compatible {
+            if !named.is_configured() {
+                tracing::warn!("OpenAI-compatible provider '{}' has no base URL, skipping", named.name);
+            } else if named.name.parse::<ModelProvider>().is_ok() || registry.providers.contains_key(&named.name) {
+                tracing::warn!(
+                    "OpenAI-compatible provider name '{}' is already in use, skipping",
+                    named.name
+                );
+            } else {
+                tracing::debug!("OpenAI-compatible provider '{}' registered", named.name);
+                let client = upstream_client(config.sorai.upstream_timeout(&named.name));
+                registry.register(Arc::new(OpenAICompatibleProvider::new(named.clone(), client)));
+            }
+        }
+
+        registry
+    }
+
+    /// Register a provider under its own name, replacing any previous entry
+    ///
+    /// Models the provider advertises are added to the catalog if missing.
+    pub fn register(&mut self, provider: Arc<dyn Provider>) {
+        self.catalog.advertise(provider.name(), provider.models());
+        if self.circuit_breaker.enabled {
+            let breaker = CircuitBreaker::new(provider.name(), self.circuit_breaker.clone());
+            self.breakers.insert(provider.name().to_string(), Arc::new(breaker));
+        }
+        self.providers.insert(provider.name().to_string(), provider);
+    }
+
+    /// Catalog of the models each provider serves
+    pub fn catalog(&self) -> &ModelCatalog {
+        &self.catalog
+    }
+
+    /// Replace the model catalog, keeping the models advertised by registered providers
+    pub fn set_catalog(&mut self, catalog: ModelCatalog) {
+        self.catalog = catalog;
+        for provider in self.providers.values() {
+            self.catalog.advertise(provider.name(), provider.models());
+        }
+    }
+
+    /// Catalog entries of the registered providers, ordered by provider name
+    pub fn available_models(&self) -> Vec<&ModelInfo> {
+        let mut models: Vec<&ModelInfo> = self
+            .catalog
+            .models()
+            .iter()
+            .filter(|model| self.providers.contains_key(&model.provider))
+            .collect();
+        models.sort_by(|a, b| a.provider.cmp(&b.provider));
+        models
+    }
+
+    /// Model aliases clients may use instead of a provider and model
+    pub fn routing(&self) -> &RoutingTable {
+        &self.routing
+    }
+
+    /// Replace the model aliases
+    pub fn set_routing(&mut self, routing: RoutingTable) {
+        self.routing = routing;
+    }
+
+    /// Resolver preparing message attachments before they are sent to a provider
+    pub fn media(&self) -> &MediaResolver {
+        &self.media
+    }
+
+    /// Replace the resolver preparing message attachments
+    pub fn set_media(&mut self, media: MediaResolver) {
+        self.media = media;
+    }
+
+    /// Extra attempts when a response does not match the requested JSON schema
+    pub fn structured_output_retries(&self) -> u32 {
+        self.structured_output_retries
+    }
+
+    /// Set the number of extra attempts for responses that do not match the requested JSON schema
+    pub fn set_structured_output_retries(&mut self, retries: u32) {
+        self.structured_output_retries = retries;
+    }
+
+    /// Circuit breaker guarding a provider by its registered name, None when breakers are disabled
+    pub fn circuit_breaker(&self, name: &str) -> Option<&CircuitBreaker> {
+        self.breakers.get(name).map(Arc::as_ref)
+    }
+
+    /// Replace the circuit breaker settings, resetting the breaker of every registered provider
+    pub fn set_circuit_breaker(&mut self, config: CircuitBreakerConfig) {
+        self.breakers = if config.enabled {
+            self.providers
+                .keys()
+                .map(|name| {
+                    (
+                        name.clone(),
+                        Arc::new(CircuitBreaker::new(name.as_str(), config.clone())),
+                    )
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        self.circuit_breaker = config;
+    }
+
+    /// Resolve a provider by the name given in a request
+    pub fn get(&self, name: &str) -> Result<Arc<dyn Provider>, ProviderError> {
+        if let Some(provider) = self.providers.get(name) {
+            return Ok(provider.clone());
+        }
+
+        let kind: ModelProvider = name.parse()?;
+        self.get_kind(kind)
+    }
+
+    /// Registered name of the provider given in a request
+    ///
+    /// Built-in providers may be named in any case and by their aliases, so `OpenAI`
+    /// becomes `openai` and `azure` becomes `azure_openai`. Names that do not resolve
+    /// are returned unchanged.
+    pub fn provider_name(&self, name: &str) -> String {
+        if self.providers.contains_key(name) {
+            return name.to_string();
+        }
+
+        match name.parse::<ModelProvider>() {
+            Ok(kind) => kind.as_str().to_string(),
+            Err(_) => name.to_string(),
+        }
+    }
+
+    /// Rewrite the provider and fallback targets of a request to their registered names,
+    /// so scopes, rate limits, circuit breakers and pricing all see one spelling
+    pub fn resolve_names(&self, provider: Option<&mut String>, fallbacks: &mut [Fallback]) {
+        if let Some(provider) = provider {
+            *provider = self.provider_name(provider);
+        }
+        for fallback in fallbacks {
+            fallback.provider = self.provider_name(&fallback.provider);
+        }
+    }
+
+    /// Resolve a provider by its kind
+    pub fn get_kind(&self, kind: ModelProvider) -> Result<Arc<dyn Provider>, ProviderError> {
+        self.providers
+            .get(kind.as_str())
+            .cloned()
+            .ok_or_else(|| ProviderError::NotConfigured(kind.to_string()))
+    }
+
+    /// Names of all registered providers, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.providers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Check whether any provider is registered
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Build a pool of adapters for a provider kind, one per configured member
+///
+/// Members are built from the provider config with their overrides applied;
+/// members that end up unconfigured are skipped.
+fn build_pool(
+    kind: ModelProvider,
+    pool: &PoolConfig,
+    config: &Config,
+    client: &reqwest::Client,
+) -> Option<Arc<dyn Provider>> {
+    let members = pool
+        .members
+        .iter()
+        .filter_map(|member| {
+            let provider = match config.with_provider_overrides(kind, &member.overrides) {
+                Ok(member_config) => build_provider(kind, &member_config, client),
+                Err(e) => {
+                    tracing::warn!("Pool member '{}' of provider '{}' is invalid: {}", member.name, kind, e);
+                    None
+                }
+            };
+            match provider {
+                Some(provider) => Some(PoolMember::new(member.name.clone(), member.weight, provider)),
+                None => {
+                    tracing::warn!(
+                        "Pool member '{}' of provider '{}' is not configured, skipping",
+                        member.name,
+                        kind
+                    );
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let pool = ProviderPool::new(members, pool.strategy, Duration::from_secs(pool.cooldown))?;
+    Some(Arc::new(pool))
+}
+
+/// Build the adapter for a provider kind, or None when it is not configured
+fn build_provider(kind: ModelProvider, config: &Config, client: &reqwest::Client) -> Option<Arc<dyn Provider>> {
+    match kind {
+        ModelProvider::OpenAI if config.openai.is_configured() => {
+            Some(Arc::new(OpenAIProvider::new(config.openai.clone(), client.clone())))
+        }
+        ModelProvider::Anthropic if config.anthropic.is_configured() => Some(Arc::new(AnthropicProvider::new(
+            config.anthropic.clone(),
+            client.clone(),
+        ))),
+        ModelProvider::Bedrock if config.bedrock.is_configured() => {
+            Some(Arc::new(BedrockProvider::new(config.bedrock.clone(), client.clone())))
+        }
+        ModelProvider::Cohere if config.cohere.is_configured() => {
+            Some(Arc::new(CohereProvider::new(config.cohere.clone(), client.clone())))
+        }
+        ModelProvider::AzureOpenAI if config.azure_openai.is_configured() => Some(Arc::new(AzureOpenAIProvider::new(
+            config.azure_openai.clone(),
+            client.clone(),
+        ))),
+        ModelProvider::Vertex if config.vertex.is_configured() => {
+            Some(Arc::new(VertexProvider::new(config.vertex.clone(), client.clone())))
+        }
+        _ => None,
+    }
+}
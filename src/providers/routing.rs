@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::http::schemas::completions::Fallback;
+
+/// Provider and model an alias resolves to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasTarget {
+    pub provider: String,
+    pub model: String,
+}
+
+/// Model name that resolves to one or more concrete targets
+///
+/// The first target serves the request and the others are tried as fallbacks,
+/// in order, ahead of any fallbacks the request lists itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelAlias {
+    pub name: String,
+    pub targets: Vec<AliasTarget>,
+    /// Default params, used for keys the request does not set
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub params: Map<String, Value>,
+}
+
+impl ModelAlias {
+    /// Target serving requests made with the alias
+    pub fn primary(&self) -> Option<&AliasTarget> {
+        self.targets.first()
+    }
+
+    /// Targets after the first, as fallbacks
+    pub fn fallbacks(&self) -> impl Iterator<Item = Fallback> + '_ {
+        self.targets.iter().skip(1).map(|target| Fallback {
+            provider: target.provider.clone(),
+            model: target.model.clone(),
+        })
+    }
+
+    /// Add the default params for keys missing from `params`
+    pub fn fill_params(&self, params: &mut Map<String, Value>) {
+        for (key, value) in &self.params {
+            params.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    /// Add the default params to optional request params, leaving params that are not an object alone
+    pub fn apply_params(&self, params: &mut Option<Value>) {
+        match params {
+            Some(Value::Object(params)) => self.fill_params(params),
+            None if !self.params.is_empty() => *params = Some(Value::Object(self.params.clone())),
+            _ => {}
+        }
+    }
+}
+
+/// RoutingTable holds the model aliases clients may use instead of a provider and model
+#[derive(Debug, Clone, Default)]
+pub struct RoutingTable {
+    aliases: HashMap<String, ModelAlias>,
+}
+
+impl RoutingTable {
+    /// Create a table from a list of aliases, a later alias replacing an earlier one of the same name
+    pub fn new(aliases: Vec<ModelAlias>) -> Self {
+        let mut table = Self::default();
+        for alias in aliases {
+            table.insert(alias);
+        }
+        table
+    }
+
+    /// Add an alias, replacing the alias of the same name if there is one
+    ///
+    /// Aliases without targets are ignored, as they cannot serve a request.
+    pub fn insert(&mut self, alias: ModelAlias) {
+        if alias.targets.is_empty() {
+            tracing::warn!("Ignoring model alias '{}' without targets", alias.name);
+            return;
+        }
+        self.aliases.insert(alias.name.clone(), alias);
+    }
+
+    /// Look up an alias by name
+    pub fn get(&self, name: &str) -> Option<&ModelAlias> {
+        self.aliases.get(name)
+    }
+
+    /// All aliases, ordered by name
+    pub fn aliases(&self) -> Vec<&ModelAlias> {
+        let mut aliases: Vec<&ModelAlias> = self.aliases.values().collect();
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        aliases
+    }
+
+    /// Rewrite a request made with an alias to target the alias' providers and models
+    ///
+    /// A request is aliased when it names no provider and its model is an alias.
+    /// The provider and model are replaced by the first target, the other targets
+    /// are put ahead of the request fallbacks and missing params are filled with
+    /// the alias defaults. Returns the name of the alias the request was made with.
+    pub fn resolve(
+        &self,
+        provider: &mut Option<String>,
+        model: &mut Option<String>,
+        params: &mut Option<Value>,
+        fallbacks: &mut Option<Vec<Fallback>>,
+    ) -> Option<String> {
+        if provider.as_deref().is_some_and(|provider| !provider.is_empty()) {
+            return None;
+        }
+        let alias = self.get(model.as_deref()?)?;
+        let target = alias.primary()?;
+
+        *provider = Some(target.provider.clone());
+        *model = Some(target.model.clone());
+        *fallbacks = Some(alias.fallbacks().chain(fallbacks.take().unwrap_or_default()).collect());
+        alias.apply_params(params);
+
+        tracing::debug!(
+            "Resolved model alias '{}' to '{}/{}'",
+            alias.name,
+            target.provider,
+            target.model
+        );
+        Some(alias.name.clone())
+    }
+}
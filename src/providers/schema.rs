@@ -0,0 +1,235 @@
+use serde_json::Value;
+
+/// Validate a JSON value against a JSON schema, returning the first violation found
+///
+/// Covers the keywords structured output schemas rely on: `type`, `enum`, `const`,
+/// `properties`, `required`, `additionalProperties`, `items`, `prefixItems`, length,
+/// size and range bounds, `anyOf`, `oneOf`, `allOf` and local `$ref`s into `$defs`
+/// or `definitions`. Other keywords, such as `pattern` and `format`, are ignored.
+pub fn validate(schema: &Value, instance: &Value) -> Result<(), String> {
+    Validator { root: schema }.check(schema, instance, "")
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&self, schema: &'a Value, instance: &Value, path: &str) -> Result<(), String> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => return Err(violation(path, "no value is allowed here")),
+            Value::Object(_) => schema,
+            _ => return Ok(()),
+        };
+
+        if let Some(reference) = schema["$ref"].as_str() {
+            let target = self
+                .resolve(reference)
+                .ok_or_else(|| violation(path, &format!("unresolvable reference '{}'", reference)))?;
+            if !std::ptr::eq(target, schema) {
+                self.check(target, instance, path)?;
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(kind) => vec![kind.as_str()],
+                Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let nullable = schema["nullable"].as_bool() == Some(true) && instance.is_null();
+            if !allowed.is_empty() && !nullable && !allowed.iter().any(|kind| has_type(instance, kind)) {
+                return Err(violation(
+                    path,
+                    &format!("expected {}, got {}", allowed.join(" or "), type_name(instance)),
+                ));
+            }
+        }
+
+        if let Some(options) = schema["enum"].as_array()
+            && !options.contains(instance)
+        {
+            return Err(violation(
+                path,
+                &format!("{} is not one of {}", instance, Value::from(options.clone())),
+            ));
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != instance
+        {
+            return Err(violation(path, &format!("expected {}", expected)));
+        }
+
+        self.check_combinators(schema, instance, path)?;
+
+        match instance {
+            Value::Object(object) => {
+                for key in schema["required"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !object.contains_key(key) {
+                        return Err(violation(path, &format!("missing required property '{}'", key)));
+                    }
+                }
+
+                let properties = schema["properties"].as_object();
+                for (key, value) in object {
+                    let child = format!("{}/{}", path, key);
+                    match properties.and_then(|properties| properties.get(key)) {
+                        Some(property) => self.check(property, value, &child)?,
+                        None => match schema.get("additionalProperties") {
+                            Some(Value::Bool(false)) => {
+                                return Err(violation(path, &format!("unexpected property '{}'", key)));
+                            }
+                            Some(additional) => self.check(additional, value, &child)?,
+                            None => {}
+                        },
+                    }
+                }
+
+                check_bounds(
+                    schema,
+                    "minProperties",
+                    "maxProperties",
+                    object.len(),
+                    "properties",
+                    path,
+                )?;
+            }
+            Value::Array(items) => {
+                let prefix = schema["prefixItems"].as_array().map(Vec::as_slice).unwrap_or_default();
+                for (index, item) in items.iter().enumerate() {
+                    let child = format!("{}/{}", path, index);
+                    match prefix.get(index) {
+                        Some(item_schema) => self.check(item_schema, item, &child)?,
+                        None => {
+                            if let Some(item_schema) = schema.get("items") {
+                                self.check(item_schema, item, &child)?;
+                            }
+                        }
+                    }
+                }
+
+                check_bounds(schema, "minItems", "maxItems", items.len(), "items", path)?;
+            }
+            Value::String(text) => {
+                check_bounds(
+                    schema,
+                    "minLength",
+                    "maxLength",
+                    text.chars().count(),
+                    "characters",
+                    path,
+                )?;
+            }
+            Value::Number(number) => {
+                let value = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = schema["minimum"].as_f64()
+                    && value < minimum
+                {
+                    return Err(violation(path, &format!("{} is less than {}", number, minimum)));
+                }
+                if let Some(maximum) = schema["maximum"].as_f64()
+                    && value > maximum
+                {
+                    return Err(violation(path, &format!("{} is greater than {}", number, maximum)));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn check_combinators(&self, schema: &'a Value, instance: &Value, path: &str) -> Result<(), String> {
+        if let Some(all) = schema["allOf"].as_array() {
+            for option in all {
+                self.check(option, instance, path)?;
+            }
+        }
+        if let Some(any) = schema["anyOf"].as_array()
+            && !any.iter().any(|option| self.check(option, instance, path).is_ok())
+        {
+            return Err(violation(path, "does not match any of the allowed schemas"));
+        }
+        if let Some(one) = schema["oneOf"].as_array() {
+            let matches = one
+                .iter()
+                .filter(|option| self.check(option, instance, path).is_ok())
+                .count();
+            if matches != 1 {
+                return Err(violation(
+                    path,
+                    &format!("matches {} of the oneOf schemas instead of exactly one", matches),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve a local reference such as `#/$defs/item`
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        match reference.strip_prefix('#')? {
+            "" => Some(self.root),
+            pointer => self.root.pointer(pointer),
+        }
+    }
+}
+
+/// Check the `min`/`max` keywords of a schema against a count
+fn check_bounds(schema: &Value, min: &str, max: &str, count: usize, unit: &str, path: &str) -> Result<(), String> {
+    if let Some(min) = schema[min].as_u64()
+        && (count as u64) < min
+    {
+        return Err(violation(
+            path,
+            &format!("expected at least {} {}, got {}", min, unit, count),
+        ));
+    }
+    if let Some(max) = schema[max].as_u64()
+        && (count as u64) > max
+    {
+        return Err(violation(
+            path,
+            &format!("expected at most {} {}, got {}", max, unit, count),
+        ));
+    }
+    Ok(())
+}
+
+fn has_type(instance: &Value, kind: &str) -> bool {
+    match kind {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64() || instance.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+/// Violation message prefixed with the JSON pointer of the offending value
+fn violation(path: &str, message: &str) -> String {
+    if path.is_empty() {
+        message.to_string()
+    } else {
+        format!("{}: {}", path, message)
+    }
+}
@@ -0,0 +1,276 @@
+use axum::body::Bytes;
+use futures_util::{Stream, StreamExt, stream};
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use super::ProviderError;
+use crate::http::schemas::completions::{
+    ChatCompletionChunk, ChatCompletionChunkChoice, ChatCompletionId, ChatMessageDelta, ToolCallDelta, UsageInfo,
+};
+
+/// Stream of OpenAI-style chat completion chunks produced by a provider
+pub type ChatCompletionStream = Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, ProviderError>> + Send>>;
+
+/// Splits a raw response body into provider frames, buffering partial frames across reads
+pub trait FrameDecoder: Send + 'static {
+    type Frame;
+
+    /// Feed the next piece of the body and return every frame it completed
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Self::Frame>, String>;
+}
+
+/// Translates provider frames into OpenAI-style chunks
+pub trait ChunkTranslator: Send + 'static {
+    type Frame;
+
+    /// Translate a single frame; frames without anything to report yield no chunks
+    fn translate(&mut self, frame: Self::Frame) -> Result<Vec<ChatCompletionChunk>, ProviderError>;
+
+    /// Chunks emitted once the upstream body has ended
+    fn finish(&mut self) -> Vec<ChatCompletionChunk> {
+        Vec::new()
+    }
+}
+
+/// Builds the chunks of a single streamed completion, sharing one id and timestamp
+#[derive(Debug, Clone)]
+pub struct ChunkBuilder {
+    id: String,
+    model: String,
+    created: i64,
+}
+
+impl ChunkBuilder {
+    pub fn new(model: &str) -> Self {
+        Self {
+            id: ChatCompletionId::new().to_string(),
+            model: model.to_string(),
+            created: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// Replace the model with the one reported by the provider, if any
+    pub fn set_model(&mut self, model: &str) {
+        if !model.is_empty() {
+            self.model = model.to_string();
+        }
+    }
+
+    /// Chunk carrying a message delta and, on the last one, the finish reason
+    pub fn delta(&self, index: i32, delta: ChatMessageDelta, finish_reason: Option<String>) -> ChatCompletionChunk {
+        self.chunk(
+            vec![ChatCompletionChunkChoice {
+                index,
+                delta,
+                finish_reason,
+            }],
+            None,
+        )
+    }
+
+    /// First chunk of a message, announcing the assistant role
+    pub fn role(&self) -> ChatCompletionChunk {
+        self.delta(
+            0,
+            ChatMessageDelta {
+                role: Some("assistant".to_string()),
+                content: Some(String::new()),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    /// Chunk carrying a piece of generated text
+    pub fn content(&self, text: impl Into<String>) -> ChatCompletionChunk {
+        self.delta(
+            0,
+            ChatMessageDelta {
+                content: Some(text.into()),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    /// Chunk carrying a piece of a tool call
+    pub fn tool_call(&self, delta: ToolCallDelta) -> ChatCompletionChunk {
+        self.delta(
+            0,
+            ChatMessageDelta {
+                tool_calls: Some(vec![delta]),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    /// Chunk closing the message with the given finish reason
+    pub fn finish(&self, finish_reason: String) -> ChatCompletionChunk {
+        self.delta(0, ChatMessageDelta::default(), Some(finish_reason))
+    }
+
+    /// Final chunk reporting token usage, without choices
+    pub fn usage(&self, usage: UsageInfo) -> ChatCompletionChunk {
+        self.chunk(Vec::new(), Some(usage))
+    }
+
+    fn chunk(&self, choices: Vec<ChatCompletionChunkChoice>, usage: Option<UsageInfo>) -> ChatCompletionChunk {
+        ChatCompletionChunk {
+            id: self.id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            choices,
+            model: self.model.clone(),
+            created: self.created,
+            usage,
+            extra_fields: None,
+        }
+    }
+}
+
+/// A single Server-Sent Event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Incremental Server-Sent Events parser
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a single line, returning the event it completed if it was blank
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            if self.data.is_empty() {
+                self.event = None;
+                return None;
+            }
+            return Some(SseEvent {
+                event: self.event.take(),
+                data: std::mem::take(&mut self.data).join("\n"),
+            });
+        }
+
+        // Lines starting with a colon are comments, often used as keep-alives
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl FrameDecoder for SseDecoder {
+    type Frame = SseEvent;
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<SseEvent>, String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line = self.buffer.drain(..=end).collect::<Vec<_>>();
+            let line = String::from_utf8(line).map_err(|e| e.to_string())?;
+            if let Some(event) = self.line(line.trim_end_matches(['\r', '\n'])) {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Drive an upstream streaming response through a decoder and translator
+///
+/// The returned stream ends after the first error, whether it comes from the
+/// transport, the decoder or the translator.
+pub fn translate_stream<D, T>(
+    provider: &str,
+    response: reqwest::Response,
+    decoder: D,
+    translator: T,
+) -> ChatCompletionStream
+where
+    D: FrameDecoder,
+    T: ChunkTranslator<Frame = D::Frame>,
+{
+    struct State<D, T> {
+        provider: String,
+        body: Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>,
+        decoder: D,
+        translator: T,
+        pending: VecDeque<Result<ChatCompletionChunk, ProviderError>>,
+        done: bool,
+    }
+
+    let state = State {
+        provider: provider.to_string(),
+        body: Box::pin(response.bytes_stream()),
+        decoder,
+        translator,
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    Box::pin(stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((item, state));
+            }
+            if state.done {
+                return None;
+            }
+
+            match state.body.next().await {
+                Some(Ok(bytes)) => {
+                    let frames = match state.decoder.decode(&bytes) {
+                        Ok(frames) => frames,
+                        Err(message) => {
+                            state.pending.push_back(Err(ProviderError::InvalidResponse {
+                                provider: state.provider.clone(),
+                                message,
+                            }));
+                            state.done = true;
+                            continue;
+                        }
+                    };
+                    for frame in frames {
+                        match state.translator.translate(frame) {
+                            Ok(chunks) => state.pending.extend(chunks.into_iter().map(Ok)),
+                            Err(e) => {
+                                state.pending.push_back(Err(e));
+                                state.done = true;
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    state
+                        .pending
+                        .push_back(Err(ProviderError::from_reqwest(&state.provider, e)));
+                    state.done = true;
+                }
+                None => {
+                    state.pending.extend(state.translator.finish().into_iter().map(Ok));
+                    state.done = true;
+                }
+            }
+        }
+    }))
+}
@@ -0,0 +1,66 @@
+use super::schema::validate;
+use super::{ModelParams, Provider, ProviderError};
+use crate::http::schemas::completions::{ChatCompletionReq, ChatCompletionResponse};
+
+/// Run a chat completion, validating the output against the requested `response_format`
+///
+/// Each choice that answers with content must parse as JSON matching the schema;
+/// choices that call tools are left alone. A mismatching response is requested
+/// again up to `retries` times before failing with [`ProviderError::SchemaMismatch`].
+/// Requests without a JSON response format are sent once and returned as-is.
+pub async fn chat_completion_structured(
+    provider: &dyn Provider,
+    model: &str,
+    request: &ChatCompletionReq,
+    retries: u32,
+) -> Result<ChatCompletionResponse, ProviderError> {
+    let schema = ModelParams::from_value(request.params.as_ref())
+        .response_format()
+        .and_then(|format| format.schema());
+    let Some(schema) = schema else {
+        return provider.chat_completion(model, request).await;
+    };
+
+    let mut attempt = 0;
+    loop {
+        let response = provider.chat_completion(model, request).await?;
+        let message = match check_response(&schema, &response) {
+            Ok(()) => return Ok(response),
+            Err(message) => message,
+        };
+
+        if attempt == retries {
+            return Err(ProviderError::SchemaMismatch {
+                provider: provider.name().to_string(),
+                message,
+            });
+        }
+        attempt += 1;
+        tracing::warn!(
+            "Response from provider '{}' does not match the requested schema ({}), retrying ({}/{})",
+            provider.name(),
+            message,
+            attempt,
+            retries
+        );
+    }
+}
+
+/// Check the content of every choice that did not call tools against the schema
+fn check_response(schema: &serde_json::Value, response: &ChatCompletionResponse) -> Result<(), String> {
+    for choice in &response.choices {
+        if choice
+            .message
+            .tool_calls
+            .as_ref()
+            .is_some_and(|calls| !calls.is_empty())
+        {
+            continue;
+        }
+
+        let output: serde_json::Value = serde_json::from_str(choice.message.content.trim())
+            .map_err(|e| format!("output is not valid JSON: {}", e))?;
+        validate(schema, &output)?;
+    }
+    Ok(())
+}
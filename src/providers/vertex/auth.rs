@@ -0,0 +1,208 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use ring::rand::SystemRandom;
+use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OnceCell};
+
+use super::GOOGLE_TOKEN_URL;
+use crate::providers::ProviderError;
+use crate::providers::client::upstream_error_message;
+
+/// OAuth scope granting access to Vertex AI
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// Lifetime requested for the signed JWT assertion
+const ASSERTION_LIFETIME_SECS: i64 = 3600;
+
+/// Tokens are refreshed this long before they actually expire
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Fields of a Google service account JSON key used for token minting
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: String,
+    #[serde(default)]
+    pub project_id: String,
+    #[serde(default)]
+    pub token_uri: String,
+}
+
+impl ServiceAccountKey {
+    /// Read a service account key from a JSON file
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("failed to read '{}': {}", path, e))?;
+        serde_json::from_str(&content).map_err(|e| format!("invalid service account key '{}': {}", path, e))
+    }
+
+    /// Build a signed RS256 JWT assertion for the JWT-bearer grant
+    pub fn assertion(&self, audience: &str, issued_at: i64) -> Result<String, String> {
+        let header = json!({ "alg": "RS256", "typ": "JWT", "kid": self.private_key_id });
+        let claims = json!({
+            "iss": self.client_email,
+            "scope": CLOUD_PLATFORM_SCOPE,
+            "aud": audience,
+            "iat": issued_at,
+            "exp": issued_at + ASSERTION_LIFETIME_SECS,
+        });
+
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+
+        let key_pair = RsaKeyPair::from_pkcs8(&pem_to_der(&self.private_key)?)
+            .map_err(|e| format!("invalid private key: {}", e))?;
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                signing_input.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|_| "failed to sign JWT assertion".to_string())?;
+
+        Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+/// Decode the base64 body of a PEM encoded key
+fn pem_to_der(pem: &str) -> Result<Vec<u8>, String> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| format!("invalid PEM private key: {}", e))
+}
+
+// TokenRes represents an OAuth token endpoint response
+#[derive(Debug, Deserialize)]
+struct TokenRes {
+    access_token: String,
+    #[serde(default)]
+    expires_in: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// Mints and caches OAuth access tokens for a service account
+pub struct ServiceAccountAuth {
+    credentials_path: String,
+    token_url: String,
+    key: OnceCell<ServiceAccountKey>,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl ServiceAccountAuth {
+    /// Create a token source for the key at `credentials_path`
+    ///
+    /// An empty `token_url` falls back to the key's `token_uri`, then to Google's endpoint.
+    pub fn new(credentials_path: &str, token_url: &str) -> Self {
+        Self {
+            credentials_path: credentials_path.to_string(),
+            token_url: token_url.to_string(),
+            key: OnceCell::new(),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Service account key, loaded from disk on first use
+    pub async fn key(&self, provider: &str) -> Result<&ServiceAccountKey, ProviderError> {
+        self.key
+            .get_or_try_init(|| async {
+                ServiceAccountKey::from_file(&self.credentials_path).map_err(|message| ProviderError::Authentication {
+                    provider: provider.to_string(),
+                    message,
+                })
+            })
+            .await
+    }
+
+    /// Return a valid access token, minting a new one when the cached token is about to expire
+    pub async fn access_token(&self, provider: &str, client: &reqwest::Client) -> Result<String, ProviderError> {
+        // Holding the lock while minting keeps concurrent requests from minting in parallel
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref()
+            && token.expires_at > Instant::now() + REFRESH_MARGIN
+        {
+            return Ok(token.token.clone());
+        }
+
+        let token = self.mint(provider, client).await?;
+        *cached = Some(token.clone());
+        Ok(token.token)
+    }
+
+    /// Exchange a signed JWT assertion for an access token
+    async fn mint(&self, provider: &str, client: &reqwest::Client) -> Result<CachedToken, ProviderError> {
+        let auth_error = |message: String| ProviderError::Authentication {
+            provider: provider.to_string(),
+            message,
+        };
+
+        let key = self.key(provider).await?;
+        let token_url = if !self.token_url.is_empty() {
+            self.token_url.as_str()
+        } else if !key.token_uri.is_empty() {
+            key.token_uri.as_str()
+        } else {
+            GOOGLE_TOKEN_URL
+        };
+
+        let assertion = key
+            .assertion(token_url, chrono::Utc::now().timestamp())
+            .map_err(auth_error)?;
+        let body = format!(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion={}",
+            assertion
+        );
+
+        let response = client
+            .post(token_url)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ProviderError::from_reqwest(provider, e))?;
+        if !status.is_success() {
+            return Err(auth_error(format!(
+                "token endpoint returned status {}: {}",
+                status.as_u16(),
+                upstream_error_message(&text)
+            )));
+        }
+
+        let token: TokenRes =
+            serde_json::from_str(&text).map_err(|e| auth_error(format!("invalid token response: {}", e)))?;
+        let lifetime = if token.expires_in > 0 {
+            token.expires_in
+        } else {
+            ASSERTION_LIFETIME_SECS as u64
+        };
+
+        Ok(CachedToken {
+            token: token.access_token,
+            expires_at: Instant::now() + Duration::from_secs(lifetime),
+        })
+    }
+}
@@ -8,10 +8,43 @@ pub struct VertexConfig {
     #[serde(default)]
     pub credentials: String,
     #[serde(default)]
+    pub location: String,
+    #[serde(default)]
     pub base_url: String,
+    #[serde(default)]
+    pub token_url: String,
 }
 
+/// Region used when no location is configured
+pub const VERTEX_DEFAULT_LOCATION: &str = "us-central1";
+
+/// Google OAuth token endpoint used when neither config nor credentials override it
+pub const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
 impl VertexConfig {
+    /// Vertex needs a service account credentials file to mint access tokens
+    pub fn is_configured(&self) -> bool {
+        !self.credentials.is_empty()
+    }
+
+    /// Region of the Vertex AI endpoint
+    pub fn location(&self) -> &str {
+        if self.location.is_empty() {
+            VERTEX_DEFAULT_LOCATION
+        } else {
+            &self.location
+        }
+    }
+
+    /// Base URL of the regional Vertex AI endpoint
+    pub fn base_url(&self) -> String {
+        if self.base_url.is_empty() {
+            format!("https://{}-aiplatform.googleapis.com/v1", self.location())
+        } else {
+            self.base_url.clone()
+        }
+    }
+
     pub fn add_to_debug(&self, items: &mut Vec<ConfigItem>) {
         items.push(ConfigItem {
             section: "Google Vertex AI".to_string(),
@@ -31,6 +64,11 @@ impl VertexConfig {
                 self.credentials.clone()
             },
         });
+        items.push(ConfigItem {
+            section: "Google Vertex AI".to_string(),
+            key: "Location".to_string(),
+            value: self.location().to_string(),
+        });
         items.push(ConfigItem {
             section: "Google Vertex AI".to_string(),
             key: "Base URL".to_string(),
@@ -40,5 +78,14 @@ impl VertexConfig {
                 self.base_url.clone()
             },
         });
+        items.push(ConfigItem {
+            section: "Google Vertex AI".to_string(),
+            key: "Token URL".to_string(),
+            value: if self.token_url.is_empty() {
+                "<default>".to_string()
+            } else {
+                self.token_url.clone()
+            },
+        });
     }
 }
@@ -0,0 +1,598 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::time::Instant;
+
+use super::{
+    GeminiBlob, GeminiContent, GeminiFileData, GeminiFunctionCall, GeminiFunctionCallingConfig,
+    GeminiFunctionDeclaration, GeminiFunctionResponse, GeminiGenerateReq, GeminiGenerateRes, GeminiGenerationConfig,
+    GeminiPart, GeminiTool, GeminiToolConfig, ServiceAccountAuth, VertexConfig, VertexEmbedInstance,
+    VertexEmbedParameters, VertexEmbedReq, VertexEmbedRes, VertexStreamTranslator, gemini_finish_reason,
+};
+use crate::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionId, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ContentPart,
+    ExtraFields, MessageRole, RequestMessage, TextCompletionChoice, TextCompletionId, TextCompletionReq,
+    TextCompletionResponse, ToolCall, ToolCallId, UsageInfo,
+};
+use crate::http::schemas::embeddings::{EmbeddingReq, EmbeddingResponse};
+use crate::providers::client::{decode, join_url, send_json, send_stream};
+use crate::providers::params::{ModelParams, ResponseFormat, ToolChoice};
+use crate::providers::{
+    ChatCompletionStream, Media, MediaSource, ModelProvider, Provider, ProviderCapabilities, ProviderError, SseDecoder,
+    translate_stream,
+};
+
+/// Google Vertex AI Gemini adapter
+pub struct VertexProvider {
+    config: VertexConfig,
+    client: reqwest::Client,
+    auth: ServiceAccountAuth,
+}
+
+impl VertexProvider {
+    /// Create a new Vertex AI adapter using the shared HTTP client
+    pub fn new(config: VertexConfig, client: reqwest::Client) -> Self {
+        let auth = ServiceAccountAuth::new(&config.credentials, &config.token_url);
+        Self { config, client, auth }
+    }
+
+    /// Translate OpenAI-style messages and params into a `generateContent` request
+    ///
+    /// System messages become the `systemInstruction`, assistant turns use the
+    /// `model` role and consecutive turns with the same role are merged.
+    /// Assistant tool calls become `functionCall` parts and `tool` messages
+    /// become `functionResponse` parts, matched to their call by function name.
+    pub fn build_request(messages: &[RequestMessage], params: Option<&Value>) -> GeminiGenerateReq {
+        let params = ModelParams::from_value(params);
+
+        let mut system = Vec::new();
+        let mut contents: Vec<GeminiContent> = Vec::new();
+
+        for message in messages {
+            let text = message.text();
+
+            if message.is_system() {
+                if !text.is_empty() {
+                    system.push(GeminiPart::Text { text });
+                }
+                continue;
+            }
+
+            let mut parts = Vec::new();
+            let role = match message.role {
+                MessageRole::Assistant => {
+                    if !text.is_empty() {
+                        parts.push(GeminiPart::Text { text });
+                    }
+                    for call in message.tool_calls() {
+                        parts.push(GeminiPart::FunctionCall {
+                            function_call: GeminiFunctionCall {
+                                args: call.function.parsed_arguments(),
+                                name: call.function.name.clone(),
+                            },
+                        });
+                    }
+                    "model"
+                }
+                MessageRole::Tool => {
+                    let name = message
+                        .tool_call_id
+                        .as_deref()
+                        .and_then(|id| tool_call_name(messages, id))
+                        .or_else(|| message.name.clone())
+                        .unwrap_or_default();
+                    parts.push(GeminiPart::FunctionResponse {
+                        function_response: GeminiFunctionResponse {
+                            name,
+                            response: function_response(text),
+                        },
+                    });
+                    "user"
+                }
+                _ => {
+                    parts.extend(content_parts(message));
+                    "user"
+                }
+            };
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            match contents.last_mut() {
+                Some(last) if last.role == role => last.parts.extend(parts),
+                _ => contents.push(GeminiContent {
+                    role: role.to_string(),
+                    parts,
+                }),
+            }
+        }
+
+        let declarations: Vec<GeminiFunctionDeclaration> = params
+            .tools()
+            .into_iter()
+            .map(|tool| GeminiFunctionDeclaration {
+                name: tool.function.name,
+                description: tool.function.description,
+                parameters: tool.function.parameters,
+            })
+            .collect();
+        let tool_config = params.tool_choice().map(|choice| {
+            let (mode, allowed_function_names) = match choice {
+                ToolChoice::Auto => ("AUTO", Vec::new()),
+                ToolChoice::None => ("NONE", Vec::new()),
+                ToolChoice::Required => ("ANY", Vec::new()),
+                ToolChoice::Function(name) => ("ANY", vec![name]),
+            };
+            GeminiToolConfig {
+                function_calling_config: GeminiFunctionCallingConfig {
+                    mode: mode.to_string(),
+                    allowed_function_names,
+                },
+            }
+        });
+
+        let response_format = params
+            .response_format()
+            .filter(|format| *format != ResponseFormat::Text);
+        let generation_config = GeminiGenerationConfig {
+            max_output_tokens: params.max_tokens,
+            temperature: params.temperature,
+            top_p: params.top_p,
+            top_k: params.extra.get("top_k").and_then(Value::as_u64).map(|k| k as u32),
+            stop_sequences: params.stop_sequences(),
+            response_mime_type: response_format.is_some().then(|| "application/json".to_string()),
+            response_schema: match &response_format {
+                Some(ResponseFormat::JsonSchema { schema, .. }) => Some(response_schema(schema)),
+                _ => None,
+            },
+        };
+        let has_generation_config = generation_config.max_output_tokens.is_some()
+            || generation_config.temperature.is_some()
+            || generation_config.top_p.is_some()
+            || generation_config.top_k.is_some()
+            || !generation_config.stop_sequences.is_empty()
+            || generation_config.response_mime_type.is_some();
+
+        GeminiGenerateReq {
+            contents,
+            system_instruction: if system.is_empty() {
+                None
+            } else {
+                Some(GeminiContent {
+                    role: String::new(),
+                    parts: system,
+                })
+            },
+            generation_config: has_generation_config.then_some(generation_config),
+            safety_settings: params.extra.get("safety_settings").cloned(),
+            tools: if declarations.is_empty() {
+                None
+            } else {
+                Some(vec![GeminiTool {
+                    function_declarations: declarations,
+                }])
+            },
+            tool_config,
+        }
+    }
+
+    /// Translate a Sorai embeddings request into a text embedding `predict` request
+    ///
+    /// `task_type` is read from `params` and applied to every input.
+    pub fn build_embed_request(request: &EmbeddingReq) -> VertexEmbedReq {
+        let task_type = request.param_str("task_type").map(str::to_string);
+        VertexEmbedReq {
+            instances: request
+                .inputs()
+                .into_iter()
+                .map(|content| VertexEmbedInstance {
+                    content,
+                    task_type: task_type.clone(),
+                })
+                .collect(),
+            parameters: request.dimensions.map(|dimensions| VertexEmbedParameters {
+                output_dimensionality: Some(dimensions),
+            }),
+        }
+    }
+
+    /// Build an authenticated request for a model method such as `generateContent` or `predict`
+    async fn post<T: Serialize>(
+        &self,
+        model: &str,
+        method: &str,
+        body: &T,
+    ) -> Result<reqwest::RequestBuilder, ProviderError> {
+        let token = self.auth.access_token(self.name(), &self.client).await?;
+
+        let project_id = if self.config.project_id.is_empty() {
+            self.auth.key(self.name()).await?.project_id.clone()
+        } else {
+            self.config.project_id.clone()
+        };
+        if project_id.is_empty() {
+            return Err(ProviderError::NotConfigured(self.name().to_string()));
+        }
+
+        let path = format!(
+            "projects/{}/locations/{}/publishers/google/models/{}:{}",
+            project_id,
+            self.config.location(),
+            model,
+            method
+        );
+        Ok(self
+            .client
+            .post(join_url(&self.config.base_url(), &path))
+            .bearer_auth(token)
+            .json(body))
+    }
+
+    /// Send a `generateContent` request and return the raw and decoded response with latency
+    async fn send(
+        &self,
+        model: &str,
+        body: &GeminiGenerateReq,
+    ) -> Result<(Value, GeminiGenerateRes, f64), ProviderError> {
+        let request = self.post(model, "generateContent", body).await?;
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), request).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response = decode(self.name(), &raw)?;
+        Ok((raw, response, latency))
+    }
+}
+
+/// Name of the function called with the given id by an earlier assistant message
+///
+/// Gemini matches tool results by function name rather than call id.
+fn tool_call_name(messages: &[RequestMessage], id: &str) -> Option<String> {
+    messages
+        .iter()
+        .flat_map(RequestMessage::tool_calls)
+        .find(|call| call.id == id)
+        .map(|call| call.function.name.clone())
+}
+
+/// Text, image and document parts of a user message, in order
+///
+/// Inlined media becomes `inlineData`; media left as a URL, such as a `gs://`
+/// Cloud Storage URI, is referenced as `fileData`.
+fn content_parts(message: &RequestMessage) -> Vec<GeminiPart> {
+    message
+        .parts()
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } if text.is_empty() => None,
+            ContentPart::Text { text } => Some(GeminiPart::Text { text: text.clone() }),
+            part => Media::from_part(part).map(|media| {
+                let mime_type = media.source.media_type();
+                match media.source {
+                    MediaSource::Base64 { data, .. } => GeminiPart::InlineData {
+                        inline_data: GeminiBlob { mime_type, data },
+                    },
+                    MediaSource::Url(file_uri) => GeminiPart::FileData {
+                        file_data: GeminiFileData { mime_type, file_uri },
+                    },
+                }
+            }),
+        })
+        .collect()
+}
+
+/// Function result as the object Gemini expects, wrapping results that are not JSON objects
+/// Keywords of the OpenAPI schema subset accepted as a `responseSchema`
+const RESPONSE_SCHEMA_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "minItems",
+    "maxItems",
+    "properties",
+    "required",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "propertyOrdering",
+];
+
+/// Deepest `$ref` nesting inlined into a `responseSchema`; recursive schemas are cut off there
+const MAX_SCHEMA_DEPTH: usize = 16;
+
+/// Translate a JSON schema into the OpenAPI subset Gemini accepts as a `responseSchema`
+///
+/// Local `$ref`s are inlined, `["T", "null"]` types become nullable `T` and `const`
+/// becomes a single-value `enum`; keywords outside the subset are dropped.
+fn response_schema(schema: &Value) -> Value {
+    convert_schema(schema, schema, 0)
+}
+
+fn convert_schema(root: &Value, schema: &Value, depth: usize) -> Value {
+    let Some(object) = schema.as_object() else {
+        return json!({});
+    };
+    if let Some(target) = object
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        && depth < MAX_SCHEMA_DEPTH
+    {
+        return convert_schema(root, target, depth + 1);
+    }
+
+    let mut converted = Map::new();
+    for (key, value) in object {
+        let value = match key.as_str() {
+            "type" => match value {
+                Value::Array(kinds) => {
+                    if kinds.iter().any(|kind| kind == "null") {
+                        converted.insert("nullable".to_string(), Value::Bool(true));
+                    }
+                    match kinds.iter().find(|kind| *kind != "null") {
+                        Some(kind) => kind.clone(),
+                        None => continue,
+                    }
+                }
+                kind => kind.clone(),
+            },
+            "const" => {
+                converted.insert("enum".to_string(), json!([value]));
+                continue;
+            }
+            "items" => convert_schema(root, value, depth),
+            "anyOf" => Value::Array(
+                value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|option| convert_schema(root, option, depth))
+                    .collect(),
+            ),
+            "properties" => Value::Object(
+                value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, property)| (name.clone(), convert_schema(root, property, depth)))
+                    .collect(),
+            ),
+            key if RESPONSE_SCHEMA_KEYWORDS.contains(&key) => value.clone(),
+            _ => continue,
+        };
+        converted.insert(key.clone(), value);
+    }
+    Value::Object(converted)
+}
+
+fn function_response(text: String) -> Value {
+    match serde_json::from_str(&text) {
+        Ok(Value::Object(object)) => Value::Object(object),
+        _ => json!({ "content": text }),
+    }
+}
+
+/// Tool calls requested by the first candidate
+///
+/// Gemini does not assign ids to function calls, so one is generated for each call.
+fn response_tool_calls(response: &GeminiGenerateRes) -> Option<Vec<ToolCall>> {
+    let calls: Vec<ToolCall> = response
+        .candidates
+        .first()?
+        .content
+        .function_calls()
+        .map(|call| ToolCall::function(ToolCallId::new().to_string(), call.name.clone(), call.args.to_string()))
+        .collect();
+    (!calls.is_empty()).then_some(calls)
+}
+
+/// Text and finish reason of the first candidate
+///
+/// Gemini reports a regular stop after function calls, which is reported as `tool_calls`.
+/// A prompt blocked before generation has no candidates and is reported as a content filter stop.
+fn response_output(response: &GeminiGenerateRes) -> (String, String) {
+    match response.candidates.first() {
+        Some(candidate) => {
+            let text = candidate
+                .content
+                .parts
+                .iter()
+                .filter_map(|part| match part {
+                    GeminiPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            let finish_reason = match gemini_finish_reason(candidate.finish_reason.as_deref()) {
+                reason if reason == "stop" && candidate.content.function_calls().next().is_some() => {
+                    "tool_calls".to_string()
+                }
+                reason => reason,
+            };
+            (text, finish_reason)
+        }
+        None if response
+            .prompt_feedback
+            .as_ref()
+            .is_some_and(|f| f.block_reason.is_some()) =>
+        {
+            (String::new(), "content_filter".to_string())
+        }
+        None => (String::new(), "stop".to_string()),
+    }
+}
+
+/// Safety ratings of the first candidate and the prompt feedback, if any were reported
+fn safety_ratings(response: &GeminiGenerateRes) -> Option<Value> {
+    let candidate_ratings = response
+        .candidates
+        .first()
+        .map(|c| c.safety_ratings.clone())
+        .unwrap_or_default();
+
+    if candidate_ratings.is_empty() && response.prompt_feedback.is_none() {
+        return None;
+    }
+
+    Some(json!({
+        "safety_ratings": candidate_ratings,
+        "prompt_feedback": response.prompt_feedback,
+    }))
+}
+
+#[async_trait]
+impl Provider for VertexProvider {
+    fn kind(&self) -> ModelProvider {
+        ModelProvider::Vertex
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            vision: true,
+            documents: true,
+            image_urls: false,
+            embeddings: true,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        let body = Self::build_request(&request.messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(model, &body).await?;
+        let (content, finish_reason) = response_output(&response);
+
+        Ok(ChatCompletionResponse {
+            id: ChatCompletionId::new().to_string(),
+            object: "chat.completion".to_string(),
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content,
+                    tool_calls: response_tool_calls(&response),
+                    tool_call_id: None,
+                },
+                finish_reason,
+            }],
+            model: response.model_version.clone().unwrap_or_else(|| model.to_string()),
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage_metadata.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                content_filter: safety_ratings(&response),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        let body = Self::build_request(&request.messages, request.params.as_ref());
+        let request = self.post(model, "streamGenerateContent?alt=sse", &body).await?;
+        let response = send_stream(self.name(), request).await?;
+
+        Ok(translate_stream(
+            self.name(),
+            response,
+            SseDecoder::new(),
+            VertexStreamTranslator::new(self.name(), model),
+        ))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        // Gemini is chat only, so the prompt is sent as a single user turn
+        let messages = [RequestMessage::new(
+            MessageRole::User,
+            request.text.clone().unwrap_or_default(),
+        )];
+        let body = Self::build_request(&messages, request.params.as_ref());
+        let (raw, response, latency) = self.send(model, &body).await?;
+        let (text, finish_reason) = response_output(&response);
+
+        Ok(TextCompletionResponse {
+            id: TextCompletionId::new().to_string(),
+            object: "text.completion".to_string(),
+            choices: vec![TextCompletionChoice {
+                index: 0,
+                text,
+                finish_reason,
+            }],
+            model: response.model_version.clone().unwrap_or_else(|| model.to_string()),
+            created: chrono::Utc::now().timestamp(),
+            usage: Some(response.usage_metadata.into()),
+            extra_fields: Some(ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                raw_response: Some(raw),
+                content_filter: safety_ratings(&response),
+                ..Default::default()
+            }),
+        })
+    }
+
+    async fn embeddings(&self, model: &str, request: &EmbeddingReq) -> Result<EmbeddingResponse, ProviderError> {
+        let body = Self::build_embed_request(request);
+        let request_builder = self.post(model, "predict", &body).await?;
+
+        let start = Instant::now();
+        let raw = send_json(self.name(), request_builder).await?;
+        let latency = start.elapsed().as_secs_f64();
+
+        let response: VertexEmbedRes = decode(self.name(), &raw)?;
+        let tokens: f64 = response
+            .predictions
+            .iter()
+            .filter_map(|prediction| prediction.embeddings.statistics)
+            .map(|statistics| statistics.token_count)
+            .sum();
+        let vectors = response
+            .predictions
+            .into_iter()
+            .map(|prediction| prediction.embeddings.values)
+            .collect();
+
+        Ok(EmbeddingResponse::new(
+            model.to_string(),
+            vectors,
+            Some(UsageInfo {
+                prompt_tokens: tokens as i32,
+                completion_tokens: 0,
+                total_tokens: tokens as i32,
+            }),
+            ExtraFields {
+                provider: self.name().to_string(),
+                model_params: request.params.clone(),
+                latency,
+                ..Default::default()
+            },
+        ))
+    }
+}
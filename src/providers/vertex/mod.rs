@@ -1,9 +1,13 @@
 #![allow(unused_variables, unused_imports, dead_code)]
 
+mod auth;
 mod config;
 mod handler;
+mod stream;
 mod types;
 
+pub use auth::*;
 pub use config::*;
 pub use handler::*;
+pub use stream::*;
 pub use types::*;
@@ -0,0 +1,108 @@
+use super::{GeminiGenerateRes, GeminiPart, GeminiUsageMetadata, gemini_finish_reason};
+use crate::http::schemas::completions::{ChatCompletionChunk, ToolCallDelta, ToolCallId};
+use crate::providers::client::decode_str;
+use crate::providers::{ChunkBuilder, ChunkTranslator, ProviderError, SseEvent};
+
+/// Translates `streamGenerateContent` responses into chat completion chunks
+///
+/// Every event is a partial `generateContent` response; usage metadata is
+/// cumulative, so the last one seen is reported once the stream ends.
+/// Function calls arrive whole and are sent as a tool call with all its arguments.
+pub struct VertexStreamTranslator {
+    provider: String,
+    chunks: ChunkBuilder,
+    started: bool,
+    finished: bool,
+    tool_calls: i32,
+    usage: Option<GeminiUsageMetadata>,
+}
+
+impl VertexStreamTranslator {
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            chunks: ChunkBuilder::new(model),
+            started: false,
+            finished: false,
+            tool_calls: 0,
+            usage: None,
+        }
+    }
+}
+
+impl ChunkTranslator for VertexStreamTranslator {
+    type Frame = SseEvent;
+
+    fn translate(&mut self, event: SseEvent) -> Result<Vec<ChatCompletionChunk>, ProviderError> {
+        let response: GeminiGenerateRes = decode_str(&self.provider, &event.data)?;
+        if let Some(model) = &response.model_version {
+            self.chunks.set_model(model);
+        }
+        if response.usage_metadata.total_token_count > 0 {
+            self.usage = Some(response.usage_metadata);
+        }
+
+        let mut chunks = Vec::new();
+        if !self.started {
+            self.started = true;
+            chunks.push(self.chunks.role());
+        }
+
+        match response.candidates.first() {
+            Some(candidate) => {
+                let text: String = candidate
+                    .content
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        GeminiPart::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                if !text.is_empty() {
+                    chunks.push(self.chunks.content(text));
+                }
+                for call in candidate.content.function_calls() {
+                    let index = self.tool_calls;
+                    self.tool_calls += 1;
+                    chunks.push(self.chunks.tool_call(ToolCallDelta::start(
+                        index,
+                        ToolCallId::new().to_string(),
+                        call.name.clone(),
+                    )));
+                    chunks.push(
+                        self.chunks
+                            .tool_call(ToolCallDelta::arguments(index, call.args.to_string())),
+                    );
+                }
+                if candidate.finish_reason.is_some() && !self.finished {
+                    self.finished = true;
+                    let finish_reason = match gemini_finish_reason(candidate.finish_reason.as_deref()) {
+                        reason if reason == "stop" && self.tool_calls > 0 => "tool_calls".to_string(),
+                        reason => reason,
+                    };
+                    chunks.push(self.chunks.finish(finish_reason));
+                }
+            }
+            // A prompt blocked before generation has no candidates
+            None if response
+                .prompt_feedback
+                .as_ref()
+                .is_some_and(|f| f.block_reason.is_some())
+                && !self.finished =>
+            {
+                self.finished = true;
+                chunks.push(self.chunks.finish("content_filter".to_string()));
+            }
+            None => {}
+        }
+        Ok(chunks)
+    }
+
+    fn finish(&mut self) -> Vec<ChatCompletionChunk> {
+        self.usage
+            .take()
+            .map(|usage| vec![self.chunks.usage(usage.into())])
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::schemas::completions::UsageInfo;
+
+// GeminiGenerateReq represents a Vertex AI `generateContent` request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiGenerateReq {
+    pub contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GeminiGenerationConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<GeminiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<GeminiToolConfig>,
+}
+
+// GeminiContent represents a conversation turn made of parts
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GeminiContent {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub role: String,
+    #[serde(default)]
+    pub parts: Vec<GeminiPart>,
+}
+
+impl GeminiContent {
+    /// Function calls requested in this content
+    pub fn function_calls(&self) -> impl Iterator<Item = &GeminiFunctionCall> {
+        self.parts.iter().filter_map(|part| match part {
+            GeminiPart::FunctionCall { function_call } => Some(function_call),
+            _ => None,
+        })
+    }
+}
+
+// GeminiPart represents a single part inside a content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GeminiPart {
+    Text {
+        text: String,
+    },
+    FunctionCall {
+        #[serde(rename = "functionCall")]
+        function_call: GeminiFunctionCall,
+    },
+    FunctionResponse {
+        #[serde(rename = "functionResponse")]
+        function_response: GeminiFunctionResponse,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: GeminiBlob,
+    },
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: GeminiFileData,
+    },
+    Other(Value),
+}
+
+// GeminiBlob represents media passed inline as base64
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiBlob {
+    pub mime_type: String,
+    pub data: String,
+}
+
+// GeminiFileData represents media referenced by a Cloud Storage URI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
+// GeminiFunctionCall represents a function call requested by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeminiFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+// GeminiFunctionResponse represents the result of a function call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeminiFunctionResponse {
+    pub name: String,
+    pub response: Value,
+}
+
+// GeminiTool represents a set of function declarations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiTool {
+    pub function_declarations: Vec<GeminiFunctionDeclaration>,
+}
+
+// GeminiFunctionDeclaration represents a function the model may call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeminiFunctionDeclaration {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Value>,
+}
+
+// GeminiToolConfig represents how the model should use the declared functions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiToolConfig {
+    pub function_calling_config: GeminiFunctionCallingConfig,
+}
+
+// GeminiFunctionCallingConfig represents the function calling mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFunctionCallingConfig {
+    pub mode: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_function_names: Vec<String>,
+}
+
+// GeminiGenerationConfig represents sampling parameters
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
+}
+
+// GeminiGenerateRes represents a Vertex AI `generateContent` response
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiGenerateRes {
+    #[serde(default)]
+    pub candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    pub prompt_feedback: Option<GeminiPromptFeedback>,
+    #[serde(default)]
+    pub usage_metadata: GeminiUsageMetadata,
+    #[serde(default)]
+    pub model_version: Option<String>,
+}
+
+// GeminiCandidate represents a generated candidate
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiCandidate {
+    #[serde(default)]
+    pub content: GeminiContent,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub safety_ratings: Vec<Value>,
+}
+
+// GeminiPromptFeedback represents why a prompt was blocked, if it was
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiPromptFeedback {
+    #[serde(default)]
+    pub block_reason: Option<String>,
+    #[serde(default)]
+    pub safety_ratings: Vec<Value>,
+}
+
+// GeminiUsageMetadata represents token usage reported by Vertex AI
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiUsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: i32,
+    #[serde(default)]
+    pub candidates_token_count: i32,
+    #[serde(default)]
+    pub total_token_count: i32,
+}
+
+// VertexEmbedReq represents a Vertex AI text embedding `predict` request
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VertexEmbedReq {
+    pub instances: Vec<VertexEmbedInstance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<VertexEmbedParameters>,
+}
+
+// VertexEmbedInstance represents a single text to embed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VertexEmbedInstance {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+}
+
+// VertexEmbedParameters represents the embedding parameters
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexEmbedParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dimensionality: Option<u32>,
+}
+
+// VertexEmbedRes represents a Vertex AI text embedding `predict` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VertexEmbedRes {
+    pub predictions: Vec<VertexEmbedPrediction>,
+}
+
+// VertexEmbedPrediction represents the embedding of a single instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VertexEmbedPrediction {
+    pub embeddings: VertexEmbedding,
+}
+
+// VertexEmbedding represents an embedding vector and its statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VertexEmbedding {
+    pub values: Vec<f32>,
+    #[serde(default)]
+    pub statistics: Option<VertexEmbeddingStatistics>,
+}
+
+// VertexEmbeddingStatistics represents token statistics of an embedded instance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct VertexEmbeddingStatistics {
+    #[serde(default)]
+    pub token_count: f64,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl From<GeminiUsageMetadata> for UsageInfo {
+    fn from(usage: GeminiUsageMetadata) -> Self {
+        UsageInfo {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+            total_tokens: if usage.total_token_count > 0 {
+                usage.total_token_count
+            } else {
+                usage.prompt_token_count + usage.candidates_token_count
+            },
+        }
+    }
+}
+
+/// Map a Gemini `finishReason` to an OpenAI-style `finish_reason`
+///
+/// Every safety related block is reported as `content_filter` so clients can
+/// tell it apart from a regular stop.
+pub fn gemini_finish_reason(finish_reason: Option<&str>) -> String {
+    match finish_reason {
+        Some("MAX_TOKENS") => "length",
+        Some("SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY") => {
+            "content_filter"
+        }
+        _ => "stop",
+    }
+    .to_string()
+}
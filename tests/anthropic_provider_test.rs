@@ -0,0 +1,126 @@
+mod common;
+
+#[cfg(test)]
+mod anthropic_provider_tests {
+    use super::common::{spawn_mock, typed_messages};
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::anthropic::{AnthropicConfig, AnthropicProvider};
+    use sorai::providers::{Provider, http_client};
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(HeaderMap, Value)>>>;
+
+    #[test]
+    fn test_build_request_lifts_system_and_merges_turns() {
+        let messages = vec![
+            json!({ "role": "system", "content": "You are Sorai." }),
+            json!({ "role": "developer", "content": "Answer briefly." }),
+            json!({ "role": "user", "content": "Hello" }),
+            json!({ "role": "user", "content": [{ "type": "text", "text": "Who are you?" }] }),
+            json!({ "role": "assistant", "content": "I am Sorai." }),
+            json!({ "role": "user", "content": "Nice" }),
+        ];
+        let params = json!({
+            "max_tokens": 256,
+            "temperature": 0.5,
+            "stop": ["END"],
+            "top_k": 40,
+            "seed": 1
+        });
+
+        let request =
+            AnthropicProvider::build_request("claude-3-5-haiku-latest", &typed_messages(messages), Some(&params));
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["system"], "You are Sorai.\n\nAnswer briefly.");
+        assert_eq!(body["max_tokens"], 256);
+        assert_eq!(body["temperature"], 0.5);
+        assert_eq!(body["stop_sequences"], json!(["END"]));
+        assert_eq!(body["top_k"], 40);
+        assert!(body.get("seed").is_none(), "OpenAI-only params must not be forwarded");
+
+        let turns = body["messages"].as_array().unwrap();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[0]["role"], "user");
+        assert_eq!(
+            turns[0]["content"],
+            json!([{ "type": "text", "text": "Hello" }, { "type": "text", "text": "Who are you?" }])
+        );
+        assert_eq!(turns[1]["role"], "assistant");
+        assert_eq!(turns[2]["role"], "user");
+    }
+
+    #[test]
+    fn test_build_request_defaults_max_tokens() {
+        let messages = vec![json!({ "role": "user", "content": "Hi" })];
+        let request = AnthropicProvider::build_request("claude-3-5-haiku-latest", &typed_messages(messages), None);
+        assert_eq!(request.max_tokens, 4096);
+        assert!(request.system.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_round_trip() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let router = Router::new()
+            .route(
+                "/v1/messages",
+                post(
+                    |State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                        *captured.lock().unwrap() = Some((headers, body));
+                        Json(json!({
+                            "id": "msg_01",
+                            "type": "message",
+                            "role": "assistant",
+                            "model": "claude-3-5-haiku-20241022",
+                            "content": [{ "type": "text", "text": "Hello" }, { "type": "text", "text": ", friend" }],
+                            "stop_reason": "max_tokens",
+                            "stop_sequence": null,
+                            "usage": { "input_tokens": 20, "output_tokens": 5, "cache_read_input_tokens": 4 }
+                        }))
+                    },
+                ),
+            )
+            .with_state(captured.clone());
+
+        let provider = AnthropicProvider::new(
+            AnthropicConfig {
+                api_key: "sk-ant-test".to_string(),
+                base_url: spawn_mock(router).await,
+            },
+            http_client(),
+        );
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [
+                { "role": "system", "content": "Be nice." },
+                { "role": "user", "content": "Hi" }
+            ]
+        }))
+        .unwrap();
+
+        let response = provider
+            .chat_completion("claude-3-5-haiku-latest", &request)
+            .await
+            .unwrap();
+
+        let (headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(headers["x-api-key"], "sk-ant-test");
+        assert_eq!(headers["anthropic-version"], "2023-06-01");
+        assert_eq!(body["system"], "Be nice.");
+
+        assert_eq!(response.choices[0].message.content, "Hello, friend");
+        assert_eq!(response.choices[0].finish_reason, "length");
+        assert_eq!(response.model, "claude-3-5-haiku-20241022");
+
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 24);
+        assert_eq!(usage.completion_tokens, 5);
+        assert_eq!(usage.total_tokens, 29);
+        assert_eq!(response.extra_fields.unwrap().provider, "anthropic");
+    }
+}
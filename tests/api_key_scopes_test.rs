@@ -0,0 +1,307 @@
+mod common;
+
+#[cfg(test)]
+mod api_key_scopes_tests {
+    use super::common::{Captured, app_state, get_json, mock_openai, post_json, reply_hi, send_json};
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::auth::{ApiKeyScopes, CLI_ACTOR, CreateApiKey, Endpoint};
+    use sorai::http::AppState;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{ModelAlias, ModelCatalog, ModelInfo, ProviderRegistry, RoutingTable, http_client};
+    use std::sync::Arc;
+
+    fn scopes(value: Value) -> ApiKeyScopes {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn registry(base_url: String) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        )));
+        let mut catalog = ModelCatalog::new(false);
+        for id in ["gpt-4o", "gpt-4o-mini", "o3-mini"] {
+            catalog.insert(ModelInfo::new("openai", id));
+        }
+        registry.set_catalog(catalog);
+        let aliases: Vec<ModelAlias> = serde_json::from_value(json!([
+            { "name": "small", "targets": [{ "provider": "openai", "model": "gpt-4o-mini" }] },
+            {
+                "name": "smart",
+                "targets": [
+                    { "provider": "openai", "model": "gpt-4o" },
+                    { "provider": "openai", "model": "gpt-4o-mini" }
+                ]
+            }
+        ]))
+        .unwrap();
+        registry.set_routing(RoutingTable::new(aliases));
+        registry
+    }
+
+    /// State around the OpenAI mock, with a key limited to the given scopes
+    async fn scoped_state(calls: Captured, value: Value) -> (AppState, String) {
+        let state = app_state(registry(mock_openai(calls, reply_hi).await));
+        let params = CreateApiKey {
+            name: "scoped".to_string(),
+            scopes: scopes(value),
+            ..Default::default()
+        };
+        let created = state.api_keys.create(CLI_ACTOR, &params).await.unwrap();
+        (state, created.key)
+    }
+
+    fn chat(model: &str) -> Value {
+        json!({
+            "provider": "openai",
+            "model": model,
+            "messages": [{ "role": "user", "content": "Hello" }]
+        })
+    }
+
+    #[test]
+    fn test_scope_matching() {
+        let unrestricted = ApiKeyScopes::default();
+        assert!(unrestricted.permits_endpoint(Endpoint::Admin));
+        assert!(unrestricted.permits_model("openai", "gpt-4o"));
+
+        let scopes = scopes(json!({
+            "providers": { "deny": ["cohere"] },
+            "models": { "allow": ["gpt-4o*", "anthropic/claude-*-haiku-*"], "deny": ["*-audio-*"] },
+            "endpoints": { "allow": ["chat", "embeddings"] }
+        }));
+        assert!(scopes.permits_model("openai", "gpt-4o"));
+        assert!(scopes.permits_model("azure", "gpt-4o-mini"));
+        assert!(!scopes.permits_model("openai", "gpt-4o-audio-preview"));
+        assert!(!scopes.permits_model("openai", "o3-mini"));
+        assert!(scopes.permits_model("anthropic", "claude-3-5-haiku-latest"));
+        assert!(!scopes.permits_model("bedrock", "claude-3-5-haiku-latest"));
+        assert!(!scopes.permits_model("cohere", "gpt-4o"));
+        assert!(scopes.permits_endpoint(Endpoint::Chat));
+        assert!(!scopes.permits_endpoint(Endpoint::Text));
+
+        let error = scopes.check(Endpoint::Text, []).unwrap_err();
+        assert_eq!(error.to_string(), "API key is not allowed to use the 'text' endpoints");
+        let error = scopes
+            .check(Endpoint::Chat, [("openai", "gpt-4o"), ("cohere", "command-r")])
+            .unwrap_err();
+        assert_eq!(error.to_string(), "API key is not allowed to use provider 'cohere'");
+    }
+
+    #[tokio::test]
+    async fn test_out_of_scope_requests_are_refused_before_the_provider_is_called() {
+        let calls = Captured::default();
+        let (state, key) = scoped_state(calls.clone(), json!({ "models": { "allow": ["gpt-4o-mini"] } })).await;
+
+        let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["code"], "AUTHORIZATION_ERROR");
+        assert_eq!(
+            body["error"]["reason"],
+            "API key is not allowed to use model 'openai/gpt-4o'"
+        );
+        assert_eq!(calls.len(), 0);
+
+        // Every fallback the request could be retried with must be in scope too
+        let mut request = chat("gpt-4o-mini");
+        request["fallbacks"] = json!([{ "provider": "openai", "model": "o3-mini" }]);
+        let (status, _) = post_json(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // As must every target of an alias
+        let request = json!({ "model": "smart", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, _) = post_json(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(calls.len(), 0);
+
+        let (status, body) = post_json(state, "/api/v1/chat/completions", &key, chat("gpt-4o-mini")).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scopes_apply_to_every_spelling_of_a_provider() {
+        let calls = Captured::default();
+        let (state, key) = scoped_state(
+            calls.clone(),
+            json!({ "providers": { "deny": ["openai", "azure_openai"] } }),
+        )
+        .await;
+
+        for provider in ["OpenAI", " openai", "azure", "Azure-OpenAI"] {
+            let mut request = chat("gpt-4o");
+            request["provider"] = json!(provider);
+            let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &key, request).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{}", provider);
+            assert_eq!(body["error"]["code"], "AUTHORIZATION_ERROR");
+        }
+
+        let mut request = chat("gpt-4o");
+        request["fallbacks"] = json!([{ "provider": "OPENAI", "model": "gpt-4o-mini" }]);
+        request["provider"] = json!("cohere");
+        let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            body["error"]["reason"],
+            "API key is not allowed to use provider 'openai'"
+        );
+
+        for model in ["OpenAI/gpt-4o", "azure/gpt-4o"] {
+            let request = json!({ "model": model, "messages": [{ "role": "user", "content": "Hello" }] });
+            let (status, _) = post_json(state.clone(), "/v1/chat/completions", &key, request).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{}", model);
+
+            let request = json!({ "model": model, "prompt": "Hello" });
+            let (status, _) = post_json(state.clone(), "/v1/completions", &key, request).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{}", model);
+
+            let request = json!({
+                "model": model,
+                "max_tokens": 16,
+                "messages": [{ "role": "user", "content": "Hello" }]
+            });
+            let (status, _) = post_json(state.clone(), "/v1/messages", &key, request).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{}", model);
+        }
+
+        // Patterns naming a provider match every spelling of it as well
+        let (state, key) = scoped_state(calls.clone(), json!({ "models": { "deny": ["openai/gpt-4o"] } })).await;
+        let mut request = chat("gpt-4o");
+        request["provider"] = json!("OpenAI");
+        let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            body["error"]["reason"],
+            "API key is not allowed to use model 'openai/gpt-4o'"
+        );
+        let request = json!({ "model": "OpenAI/gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, _) = post_json(state, "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(calls.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_scopes_apply_to_every_route_family() {
+        let calls = Captured::default();
+        let (state, key) = scoped_state(calls.clone(), json!({ "endpoints": { "allow": ["embeddings"] } })).await;
+
+        let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            body["error"]["reason"],
+            "API key is not allowed to use the 'chat' endpoints"
+        );
+
+        let request = json!({ "provider": "openai", "model": "gpt-4o", "text": "Hello" });
+        let (status, _) = post_json(state.clone(), "/api/v1/text/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let request = json!({ "model": "openai/gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, body) = post_json(state.clone(), "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["code"], "authorization_error");
+
+        let request = json!({ "model": "openai/gpt-4o", "prompt": "Hello" });
+        let (status, _) = post_json(state.clone(), "/v1/completions", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let request = json!({
+            "model": "openai/gpt-4o",
+            "max_tokens": 16,
+            "messages": [{ "role": "user", "content": "Hello" }]
+        });
+        let (status, _) = post_json(state, "/v1/messages", &key, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(calls.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_model_lists_only_show_models_in_scope() {
+        let calls = Captured::default();
+        let (state, key) = scoped_state(calls, json!({ "models": { "allow": ["gpt-4o*"] } })).await;
+
+        let (status, body) = get_json(state.clone(), "/api/v1/models", &key).await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|model| model["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["gpt-4o", "gpt-4o-mini"]);
+
+        let (_, body) = get_json(state.clone(), "/v1/models", &key).await;
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|model| model["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["openai/gpt-4o", "openai/gpt-4o-mini", "small", "smart"]);
+
+        // Unrestricted keys see the whole catalog
+        let (_, body) = get_json(state, "/v1/models", "sk-1234").await;
+        assert_eq!(body["data"].as_array().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_model_lists_require_the_models_endpoint_scope() {
+        let calls = Captured::default();
+        let (state, key) = scoped_state(calls, json!({ "endpoints": { "allow": ["chat"] } })).await;
+
+        let (status, body) = get_json(state.clone(), "/api/v1/models", &key).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            body["error"]["reason"],
+            "API key is not allowed to use the 'models' endpoints"
+        );
+
+        let (status, body) = get_json(state, "/v1/models", &key).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["code"], "authorization_error");
+    }
+
+    #[tokio::test]
+    async fn test_scopes_are_managed_through_the_admin_api() {
+        let state = app_state(ProviderRegistry::new());
+        let params = CreateApiKey {
+            name: "admin".to_string(),
+            admin: true,
+            ..Default::default()
+        };
+        let admin = state.api_keys.create(CLI_ACTOR, &params).await.unwrap().key;
+
+        let body = json!({ "name": "ci", "admin": true, "scopes": { "endpoints": { "deny": ["admin"] } } });
+        let (status, body) = send_json(state.clone(), "POST", "/api/v1/auth/keys", &admin, Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!(body["data"]["scopes"]["endpoints"]["deny"], json!(["admin"]));
+        let key = body["data"]["key"].as_str().unwrap().to_string();
+        let path = format!("/api/v1/auth/keys/{}", body["data"]["id"].as_str().unwrap());
+
+        // The admin flag alone is not enough when the admin endpoints are out of scope
+        let (status, _) = get_json(state.clone(), "/api/v1/auth/keys", &key).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let changes = json!({ "scopes": { "models": { "allow": ["gpt-4o-mini"] } } });
+        let (status, body) = send_json(state.clone(), "PATCH", &path, &admin, Some(changes)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["scopes"]["endpoints"]["deny"], json!([]));
+        assert_eq!(body["data"]["scopes"]["models"]["allow"], json!(["gpt-4o-mini"]));
+        assert_eq!(body["data"]["name"], "ci");
+
+        let (status, _) = get_json(state.clone(), "/api/v1/auth/keys", &key).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, body) = get_json(state, "/api/v1/auth/audit?limit=1", &admin).await;
+        assert_eq!(body["data"][0]["action"], "key.updated");
+        assert_eq!(
+            body["data"][0]["details"]["scopes"]["models"]["allow"],
+            json!(["gpt-4o-mini"])
+        );
+    }
+}
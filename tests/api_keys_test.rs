@@ -0,0 +1,205 @@
+mod common;
+
+#[cfg(test)]
+mod api_keys_tests {
+    use super::common::{app_state, get_json};
+    use axum::http::StatusCode;
+    use sorai::auth::{ApiKeyStore, AuthError, CLI_ACTOR, CreateApiKey};
+    use sorai::config::{AuthConfig, DatabaseConfig};
+    use sorai::database::{Database, DatabaseError};
+    use sorai::providers::ProviderRegistry;
+
+    fn store(cache_ttl: u64) -> ApiKeyStore {
+        let config = AuthConfig {
+            cache_ttl,
+            ..Default::default()
+        };
+        ApiKeyStore::new(Database::in_memory(), &config)
+    }
+
+    fn params(name: &str, owner: &str) -> CreateApiKey {
+        CreateApiKey {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_key_authenticates() {
+        let store = store(60);
+        let created = store.create(CLI_ACTOR, &params("ci", "platform")).await.unwrap();
+
+        assert!(created.key.starts_with("sk-"));
+        assert_eq!(created.key.len(), 51);
+        assert!(created.key.starts_with(&created.record.prefix));
+        assert_eq!(created.record.prefix.len(), 12);
+        assert_eq!(created.record.last_used_at, None);
+
+        let record = store.authenticate(&created.key).await.unwrap();
+        assert_eq!(record.id, created.record.id);
+        assert_eq!(record.name, "ci");
+        assert_eq!(record.owner, "platform");
+        assert!(record.id.starts_with("key_"));
+
+        // The first use is written back to the database
+        let stored = store.get(&record.id).await.unwrap();
+        assert!(stored.last_used_at.is_some());
+        assert_eq!(store.list().await.unwrap(), vec![stored]);
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_key_is_not_stored() {
+        let store = store(60);
+        let created = store.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+        let other = store.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+        assert_ne!(created.key, other.key);
+
+        let conn = store.database().connect().await.unwrap();
+        let mut rows = conn.query("SELECT key_hash, salt FROM api_keys", ()).await.unwrap();
+        let mut hashes = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            let hash: String = row.get(0).unwrap();
+            let salt: String = row.get(1).unwrap();
+            assert!(!hash.contains(&created.key[3..]) && !hash.contains(&other.key[3..]));
+            assert_eq!(salt.len(), 32);
+            hashes.push(hash);
+        }
+        assert_eq!(hashes.len(), 2);
+        assert_ne!(hashes[0], hashes[1]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_disabled_keys_are_rejected() {
+        let store = store(60);
+        let created = store.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+
+        assert!(matches!(
+            store.authenticate("sk-1234").await,
+            Err(AuthError::InvalidKey)
+        ));
+        // Same prefix, different secret
+        let forged = format!("{}{}", created.record.prefix, "0".repeat(39));
+        assert!(matches!(store.authenticate(&forged).await, Err(AuthError::InvalidKey)));
+
+        store.set_disabled(CLI_ACTOR, &created.record.id, true).await.unwrap();
+        assert!(matches!(
+            store.authenticate(&created.key).await,
+            Err(AuthError::Disabled)
+        ));
+
+        store.set_disabled(CLI_ACTOR, &created.record.id, false).await.unwrap();
+        assert!(store.authenticate(&created.key).await.is_ok());
+
+        assert!(matches!(
+            store.set_disabled(CLI_ACTOR, "key_unknown", true).await,
+            Err(AuthError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lookups_are_cached_for_the_ttl() {
+        let disable = |store: &ApiKeyStore| {
+            let database = store.database().clone();
+            async move {
+                // Written behind the store's back, as another instance would
+                let conn = database.connect().await.unwrap();
+                conn.execute("UPDATE api_keys SET disabled = 1", ()).await.unwrap();
+            }
+        };
+
+        let cached = store(60);
+        let created = cached.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+        cached.authenticate(&created.key).await.unwrap();
+        disable(&cached).await;
+        assert!(cached.authenticate(&created.key).await.is_ok());
+        cached.clear_cache();
+        assert!(matches!(
+            cached.authenticate(&created.key).await,
+            Err(AuthError::Disabled)
+        ));
+
+        let uncached = store(0);
+        let created = uncached.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+        uncached.authenticate(&created.key).await.unwrap();
+        disable(&uncached).await;
+        assert!(matches!(
+            uncached.authenticate(&created.key).await,
+            Err(AuthError::Disabled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_imported_key_is_stored_once() {
+        let database = Database::in_memory();
+        let config = AuthConfig::default();
+
+        let store = ApiKeyStore::new(database.clone(), &config).with_key("sk-1234", "legacy", "ops");
+        let record = store.authenticate("sk-1234").await.unwrap();
+        assert_eq!(record.prefix, "sk-");
+        assert_eq!(record.name, "legacy");
+
+        let store = ApiKeyStore::new(database, &config).with_key("sk-1234", "legacy", "ops");
+        assert_eq!(store.authenticate("sk-1234").await.unwrap().id, record.id);
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_database_location() {
+        let data_dir = std::env::temp_dir().join(format!("sorai-db-{}", std::process::id()));
+        let data_dir = data_dir.to_string_lossy().to_string();
+
+        let database = Database::new(&DatabaseConfig::default(), &data_dir);
+        assert_eq!(database.path(), format!("{}/sorai.db", data_dir));
+        assert_eq!(
+            database.migrate().await.unwrap(),
+            vec![
+                "0001_api_keys",
+                "0002_api_key_lifecycle",
+                "0003_api_key_scopes",
+                "0004_api_key_rate_limits",
+                "0005_api_key_budgets"
+            ]
+        );
+        assert!(database.migrate().await.unwrap().is_empty());
+        assert!(std::path::Path::new(database.path()).exists());
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        let config = DatabaseConfig {
+            url: "file:./data/other.db".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Database::new(&config, &data_dir).path(), "./data/other.db");
+
+        let config = DatabaseConfig {
+            url: "libsql://sorai.turso.io".to_string(),
+            ..Default::default()
+        };
+        let result = Database::new(&config, &data_dir).connect().await;
+        assert!(matches!(result, Err(DatabaseError::Unsupported(_))));
+    }
+
+    #[tokio::test]
+    async fn test_requests_authenticate_against_the_store() {
+        let state = app_state(ProviderRegistry::new());
+        let created = state.api_keys.create(CLI_ACTOR, &params("ci", "")).await.unwrap();
+
+        let (status, _) = get_json(state.clone(), "/api/v1/models", &created.key).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = get_json(state.clone(), "/api/v1/models", "sk-1234").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = get_json(state.clone(), "/api/v1/models", "sk-4321").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "AUTHENTICATION_ERROR");
+
+        state
+            .api_keys
+            .set_disabled(CLI_ACTOR, &created.record.id, true)
+            .await
+            .unwrap();
+        let (status, body) = get_json(state, "/v1/models", &created.key).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["message"], "API key is disabled.");
+    }
+}
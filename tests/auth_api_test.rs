@@ -0,0 +1,231 @@
+mod common;
+
+#[cfg(test)]
+mod auth_api_tests {
+    use super::common::{app_state, get_json, send_json};
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::auth::{CLI_ACTOR, CreateApiKey};
+    use sorai::http::AppState;
+    use sorai::providers::ProviderRegistry;
+
+    /// Application state with an admin key, returned along with its plaintext
+    async fn admin_state() -> (AppState, String, String) {
+        let state = app_state(ProviderRegistry::new());
+        let params = CreateApiKey {
+            name: "admin".to_string(),
+            owner: "ops".to_string(),
+            admin: true,
+            ..Default::default()
+        };
+        let admin = state.api_keys.create(CLI_ACTOR, &params).await.unwrap();
+        (state, admin.key, admin.record.id)
+    }
+
+    async fn create_key(state: &AppState, admin: &str, body: Value) -> Value {
+        let (status, body) = send_json(state.clone(), "POST", "/api/v1/auth/keys", admin, Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        body["data"].clone()
+    }
+
+    async fn models_status(state: &AppState, key: &str) -> StatusCode {
+        get_json(state.clone(), "/api/v1/models", key).await.0
+    }
+
+    #[tokio::test]
+    async fn test_admin_api_requires_an_admin_key() {
+        let (state, _, _) = admin_state().await;
+
+        let (status, body) = get_json(state.clone(), "/api/v1/auth/keys", "sk-1234").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["code"], "AUTHORIZATION_ERROR");
+
+        let (status, body) = get_json(state, "/api/v1/auth/keys", "sk-0000").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "AUTHENTICATION_ERROR");
+    }
+
+    #[tokio::test]
+    async fn test_create_list_and_get_keys() {
+        let (state, admin, _) = admin_state().await;
+
+        let created = create_key(&state, &admin, json!({ "name": "ci", "owner": "platform" })).await;
+        let key = created["key"].as_str().unwrap();
+        let id = created["id"].as_str().unwrap();
+        assert_eq!(created["name"], "ci");
+        assert_eq!(created["admin"], false);
+        assert_eq!(models_status(&state, key).await, StatusCode::OK);
+
+        // The plaintext is never returned again
+        let (status, body) = get_json(state.clone(), "/api/v1/auth/keys", &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        let keys = body["data"].as_array().unwrap();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|key| key.get("key").is_none()));
+
+        let (status, body) = get_json(state.clone(), &format!("/api/v1/auth/keys/{}", id), &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["owner"], "platform");
+        assert_eq!(body["data"]["prefix"], &key[..12]);
+
+        let (status, body) = get_json(state.clone(), "/api/v1/auth/keys/key_unknown", &admin).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+
+        let (status, _) = send_json(state, "POST", "/api/v1/auth/keys", &admin, Some(json!({ "name": " " }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_update_key_metadata() {
+        let (state, admin, _) = admin_state().await;
+        let created = create_key(&state, &admin, json!({ "name": "ci" })).await;
+        let key = created["key"].as_str().unwrap();
+        let path = format!("/api/v1/auth/keys/{}", created["id"].as_str().unwrap());
+
+        let (status, body) = send_json(
+            state.clone(),
+            "PATCH",
+            &path,
+            &admin,
+            Some(json!({ "owner": "data", "disabled": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["name"], "ci");
+        assert_eq!(body["data"]["owner"], "data");
+        assert_eq!(body["data"]["disabled"], true);
+        assert_eq!(models_status(&state, key).await, StatusCode::UNAUTHORIZED);
+
+        let (_, body) = send_json(
+            state.clone(),
+            "PATCH",
+            &path,
+            &admin,
+            Some(json!({ "disabled": false })),
+        )
+        .await;
+        assert_eq!(body["data"]["disabled"], false);
+        assert_eq!(models_status(&state, key).await, StatusCode::OK);
+
+        // Granting admin opens the admin API to the key
+        assert_eq!(
+            get_json(state.clone(), "/api/v1/auth/keys", key).await.0,
+            StatusCode::FORBIDDEN
+        );
+        send_json(state.clone(), "PATCH", &path, &admin, Some(json!({ "admin": true }))).await;
+        assert_eq!(get_json(state, "/api/v1/auth/keys", key).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_the_old_secret_for_the_grace_window() {
+        let (state, admin, _) = admin_state().await;
+        let created = create_key(&state, &admin, json!({ "name": "ci" })).await;
+        let id = created["id"].as_str().unwrap();
+        let first = created["key"].as_str().unwrap();
+        let rotate = format!("/api/v1/auth/keys/{}/rotate", id);
+
+        let (status, body) = send_json(state.clone(), "POST", &rotate, &admin, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let second = body["data"]["key"].as_str().unwrap().to_string();
+        assert_eq!(body["data"]["id"], id);
+        assert_ne!(second, first);
+        assert!(body["data"]["previous_expires_at"].as_i64().unwrap() > chrono::Utc::now().timestamp() + 3600);
+        assert_eq!(models_status(&state, first).await, StatusCode::OK);
+        assert_eq!(models_status(&state, &second).await, StatusCode::OK);
+
+        // Without grace the outgoing secret stops working right away
+        let (status, body) = send_json(
+            state.clone(),
+            "POST",
+            &rotate,
+            &admin,
+            Some(json!({ "grace_seconds": 0 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let third = body["data"]["key"].as_str().unwrap();
+        assert_eq!(models_status(&state, first).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(models_status(&state, &second).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(models_status(&state, third).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_keys_stop_working() {
+        let (state, admin, _) = admin_state().await;
+        let created = create_key(&state, &admin, json!({ "name": "ci" })).await;
+        let key = created["key"].as_str().unwrap();
+        let path = format!("/api/v1/auth/keys/{}", created["id"].as_str().unwrap());
+        send_json(state.clone(), "POST", &format!("{}/rotate", path), &admin, None).await;
+
+        let (status, body) = send_json(state.clone(), "DELETE", &path, &admin, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"]["revoked_at"].is_i64());
+
+        let (status, body) = get_json(state.clone(), "/api/v1/models", key).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["reason"], "API key has been revoked.");
+
+        let (status, _) = send_json(state, "POST", &format!("{}/rotate", path), &admin, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_requests() {
+        let (state, admin, _) = admin_state().await;
+        let created = create_key(&state, &admin, json!({ "name": "ci" })).await;
+        let key = created["key"].as_str().unwrap();
+        let path = format!("/api/v1/auth/keys/{}/usage", created["id"].as_str().unwrap());
+
+        let (_, body) = get_json(state.clone(), &path, &admin).await;
+        assert_eq!(body["data"]["request_count"], 0);
+        assert!(body["data"]["last_used_at"].is_null());
+
+        for _ in 0..3 {
+            models_status(&state, key).await;
+        }
+        let (status, body) = get_json(state, &path, &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["request_count"], 3);
+        assert!(body["data"]["last_used_at"].is_i64());
+    }
+
+    #[tokio::test]
+    async fn test_changes_are_audited() {
+        let (state, admin, admin_id) = admin_state().await;
+        let created = create_key(&state, &admin, json!({ "name": "ci" })).await;
+        let id = created["id"].as_str().unwrap();
+        let path = format!("/api/v1/auth/keys/{}", id);
+        send_json(
+            state.clone(),
+            "PATCH",
+            &path,
+            &admin,
+            Some(json!({ "name": "ci-main" })),
+        )
+        .await;
+        send_json(state.clone(), "POST", &format!("{}/rotate", path), &admin, None).await;
+        send_json(state.clone(), "DELETE", &path, &admin, None).await;
+
+        let (status, body) = get_json(state.clone(), &format!("/api/v1/auth/audit?key_id={}", id), &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        let events = body["data"].as_array().unwrap();
+        let actions: Vec<_> = events.iter().map(|event| event["action"].as_str().unwrap()).collect();
+        assert_eq!(actions, ["key.revoked", "key.rotated", "key.updated", "key.created"]);
+        assert!(events.iter().all(|event| event["actor"] == admin_id.as_str()));
+        assert_eq!(events[2]["details"], json!({ "name": "ci-main" }));
+
+        // The admin key itself was created from the command line
+        let (_, body) = get_json(
+            state.clone(),
+            &format!("/api/v1/auth/audit?key_id={}", admin_id),
+            &admin,
+        )
+        .await;
+        assert_eq!(body["data"][0]["actor"], CLI_ACTOR);
+
+        let (_, body) = get_json(state, "/api/v1/auth/audit?limit=1", &admin).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), 1);
+        assert_eq!(body["data"][0]["action"], "key.revoked");
+    }
+}
@@ -0,0 +1,150 @@
+mod common;
+
+#[cfg(test)]
+mod azure_openai_provider_tests {
+    use super::common::spawn_mock;
+    use axum::extract::{Path, Query, State};
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::{ChatCompletionReq, TextCompletionReq};
+    use sorai::providers::azure_openai::{AzureDeployment, AzureOpenAIConfig, AzureOpenAIProvider};
+    use sorai::providers::{Provider, http_client};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(String, HashMap<String, String>, HeaderMap, Value)>>>;
+
+    async fn mock_azure(captured: Captured) -> String {
+        let handler = |State(captured): State<Captured>,
+                       Path((deployment, operation)): Path<(String, String)>,
+                       Query(query): Query<HashMap<String, String>>,
+                       headers: HeaderMap,
+                       Json(body): Json<Value>| async move {
+            *captured.lock().unwrap() = Some((format!("{}/{}", deployment, operation), query, headers, body));
+            Json(json!({
+                "id": "chatcmpl-azure",
+                "object": "chat.completion",
+                "created": 1_700_000_000,
+                "model": "gpt-4o-2024-08-06",
+                "prompt_filter_results": [{
+                    "prompt_index": 0,
+                    "content_filter_results": { "hate": { "filtered": false, "severity": "safe" } }
+                }],
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "Hello from Azure" },
+                    "text": "Hello from Azure",
+                    "finish_reason": "stop",
+                    "content_filter_results": { "violence": { "filtered": false, "severity": "low" } }
+                }],
+                "usage": { "prompt_tokens": 7, "completion_tokens": 4, "total_tokens": 11 }
+            }))
+        };
+        let router = Router::new()
+            .route("/openai/deployments/{deployment}/{*operation}", post(handler))
+            .with_state(captured);
+        spawn_mock(router).await
+    }
+
+    fn config(endpoint: String) -> AzureOpenAIConfig {
+        AzureOpenAIConfig {
+            api_key: "azure-key".to_string(),
+            endpoint,
+            api_version: String::new(),
+            deployments: AzureOpenAIConfig::parse_deployments("gpt-4o=prod-gpt4o@2025-01-01-preview, gpt-4o-mini=mini"),
+        }
+    }
+
+    #[test]
+    fn test_parse_deployments() {
+        let deployments =
+            AzureOpenAIConfig::parse_deployments("gpt-4o=prod-gpt4o@2025-01-01-preview,gpt-4o-mini=mini,,ada");
+
+        assert_eq!(deployments.len(), 3);
+        assert_eq!(
+            deployments["gpt-4o"],
+            AzureDeployment {
+                deployment: "prod-gpt4o".to_string(),
+                api_version: Some("2025-01-01-preview".to_string()),
+            }
+        );
+        assert_eq!(deployments["gpt-4o-mini"].deployment, "mini");
+        assert_eq!(deployments["gpt-4o-mini"].api_version, None);
+        assert_eq!(deployments["ada"].deployment, "ada");
+    }
+
+    #[test]
+    fn test_resolve_deployment_and_api_version() {
+        let config = config("https://example.openai.azure.com".to_string());
+
+        assert_eq!(
+            config.resolve("gpt-4o"),
+            ("prod-gpt4o".to_string(), "2025-01-01-preview".to_string())
+        );
+        assert_eq!(
+            config.resolve("gpt-4o-mini"),
+            ("mini".to_string(), "2024-10-21".to_string())
+        );
+        assert_eq!(
+            config.resolve("unmapped"),
+            ("unmapped".to_string(), "2024-10-21".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_uses_deployment_and_api_key() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let provider = AzureOpenAIProvider::new(config(mock_azure(captured.clone()).await), http_client());
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "params": { "temperature": 0.1 }
+        }))
+        .unwrap();
+
+        let response = provider.chat_completion("gpt-4o", &request).await.unwrap();
+
+        let (path, query, headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(path, "prod-gpt4o/chat/completions");
+        assert_eq!(query["api-version"], "2025-01-01-preview");
+        assert_eq!(headers["api-key"], "azure-key");
+        assert!(headers.get("authorization").is_none());
+        assert_eq!(body["temperature"], 0.1);
+
+        assert_eq!(response.choices[0].message.content, "Hello from Azure");
+        assert_eq!(response.model, "gpt-4o-2024-08-06");
+        assert_eq!(response.usage.unwrap().total_tokens, 11);
+
+        let extra = response.extra_fields.unwrap();
+        assert_eq!(extra.provider, "azure_openai");
+        let filter = extra.content_filter.unwrap();
+        assert_eq!(
+            filter["prompt_filter_results"][0]["content_filter_results"]["hate"]["severity"],
+            "safe"
+        );
+        assert_eq!(
+            filter["choices"][0]["content_filter_results"]["violence"]["severity"],
+            "low"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_uses_default_api_version() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let provider = AzureOpenAIProvider::new(config(mock_azure(captured.clone()).await), http_client());
+
+        let request: TextCompletionReq = serde_json::from_value(json!({ "text": "Say hi" })).unwrap();
+        let response = provider
+            .text_completion("gpt-35-turbo-instruct", &request)
+            .await
+            .unwrap();
+
+        let (path, query, _, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(path, "gpt-35-turbo-instruct/completions");
+        assert_eq!(query["api-version"], "2024-10-21");
+        assert_eq!(body["prompt"], "Say hi");
+        assert_eq!(response.choices[0].text, "Hello from Azure");
+    }
+}
@@ -0,0 +1,191 @@
+mod common;
+
+#[cfg(test)]
+mod bedrock_provider_tests {
+    use super::common::{spawn_mock, typed_messages};
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, Uri};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use chrono::{NaiveDateTime, TimeZone, Utc};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::bedrock::{BedrockConfig, BedrockProvider, SigV4Signer};
+    use sorai::providers::{Provider, http_client};
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(Uri, HeaderMap, Bytes)>>>;
+
+    fn converse_response() -> Value {
+        json!({
+            "output": {
+                "message": {
+                    "role": "assistant",
+                    "content": [{ "text": "Hello" }, { "text": " there" }]
+                }
+            },
+            "stopReason": "guardrail_intervened",
+            "usage": { "inputTokens": 12, "outputTokens": 3, "totalTokens": 15 },
+            "metrics": { "latencyMs": 120 }
+        })
+    }
+
+    async fn mock_bedrock(captured: Captured) -> String {
+        let router = Router::new()
+            .route(
+                "/model/{model}/converse",
+                post(
+                    |State(captured): State<Captured>, uri: Uri, headers: HeaderMap, body: Bytes| async move {
+                        *captured.lock().unwrap() = Some((uri, headers, body));
+                        Json(converse_response())
+                    },
+                ),
+            )
+            .with_state(captured);
+        spawn_mock(router).await
+    }
+
+    #[test]
+    fn test_build_request_maps_system_and_inference_config() {
+        let messages = vec![
+            json!({ "role": "system", "content": "You are Sorai." }),
+            json!({ "role": "user", "content": "Hello" }),
+            json!({ "role": "user", "content": "Again" }),
+            json!({ "role": "assistant", "content": "Hi" }),
+        ];
+        let params = json!({ "max_tokens": 100, "temperature": 0.2, "stop": "END", "top_k": 5, "seed": 1 });
+
+        let request = BedrockProvider::build_request(&typed_messages(messages), Some(&params));
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["system"], json!([{ "text": "You are Sorai." }]));
+        assert_eq!(
+            body["inferenceConfig"],
+            json!({ "maxTokens": 100, "temperature": 0.2, "stopSequences": ["END"] })
+        );
+        assert_eq!(body["additionalModelRequestFields"], json!({ "top_k": 5 }));
+        assert_eq!(
+            body["messages"],
+            json!([
+                { "role": "user", "content": [{ "text": "Hello" }, { "text": "Again" }] },
+                { "role": "assistant", "content": [{ "text": "Hi" }] }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_with_api_key() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let provider = BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: mock_bedrock(captured.clone()).await,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Hi" }]
+        }))
+        .unwrap();
+
+        let response = provider
+            .chat_completion("anthropic.claude-3-haiku-20240307-v1:0", &request)
+            .await
+            .unwrap();
+
+        let (uri, headers, _) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(uri.path(), "/model/anthropic.claude-3-haiku-20240307-v1%3A0/converse");
+        assert_eq!(headers["authorization"], "Bearer bedrock-key");
+
+        assert_eq!(response.choices[0].message.content, "Hello there");
+        assert_eq!(response.choices[0].finish_reason, "content_filter");
+        assert_eq!(response.model, "anthropic.claude-3-haiku-20240307-v1:0");
+
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 12);
+        assert_eq!(usage.completion_tokens, 3);
+        assert_eq!(usage.total_tokens, 15);
+        assert_eq!(response.extra_fields.unwrap().provider, "bedrock");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_signs_with_sigv4() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let base_url = mock_bedrock(captured.clone()).await;
+        let config = BedrockConfig {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "secret".to_string(),
+            session_token: "token".to_string(),
+            region: "eu-west-1".to_string(),
+            base_url: base_url.clone(),
+            ..Default::default()
+        };
+        let provider = BedrockProvider::new(config, http_client());
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Hi" }]
+        }))
+        .unwrap();
+        provider
+            .chat_completion("amazon.nova-lite-v1:0", &request)
+            .await
+            .unwrap();
+
+        let (uri, headers, body) = captured.lock().unwrap().take().unwrap();
+        let authorization = headers["authorization"].to_str().unwrap();
+        assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(authorization.contains("/eu-west-1/bedrock/aws4_request"));
+        assert!(authorization.contains("SignedHeaders=host;x-amz-date;x-amz-security-token"));
+        assert_eq!(headers["x-amz-security-token"], "token");
+
+        // Recompute the signature for the received request and compare
+        let amz_date = headers["x-amz-date"].to_str().unwrap();
+        let time = Utc.from_utc_datetime(&NaiveDateTime::parse_from_str(amz_date, "%Y%m%dT%H%M%SZ").unwrap());
+        let url = reqwest::Url::parse(&format!("{}{}", base_url, uri.path())).unwrap();
+        let signer = SigV4Signer {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "secret".to_string(),
+            session_token: Some("token".to_string()),
+            region: "eu-west-1".to_string(),
+            service: "bedrock".to_string(),
+        };
+        let expected = signer.sign("POST", &url, &[], &body, time);
+        assert_eq!(authorization, expected[0].1);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_error_is_mapped() {
+        let router = Router::new().route(
+            "/model/{model}/converse",
+            post(|| async {
+                (
+                    axum::http::StatusCode::FORBIDDEN,
+                    Json(json!({ "message": "The security token included in the request is invalid." })),
+                )
+            }),
+        );
+        let provider = BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bad".to_string(),
+                base_url: spawn_mock(router).await,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Hi" }]
+        }))
+        .unwrap();
+        let err = provider
+            .chat_completion("amazon.nova-lite-v1:0", &request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status_code(), axum::http::StatusCode::BAD_GATEWAY);
+        assert!(err.to_string().contains("security token"));
+    }
+}
@@ -0,0 +1,312 @@
+mod common;
+
+#[cfg(test)]
+mod budget_tests {
+    use super::common::{
+        Captured, app_state, get_json, mock_openai, post_json, post_with_headers, reply_hi, send_json,
+    };
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::auth::{AuthError, Budget, BudgetPeriod, BudgetUnit, CLI_ACTOR, CreateApiKey};
+    use sorai::http::AppState;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{ModelCatalog, ModelInfo, ModelPricing, ProviderRegistry, http_client};
+    use std::sync::Arc;
+
+    /// Cost of a request to the mock: 5 prompt tokens at $2.50 and 1 completion token at $10 per million
+    const REQUEST_COST: f64 = 0.0000225;
+
+    async fn budget_state(calls: Captured) -> AppState {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url: mock_openai(calls, reply_hi).await,
+            },
+            http_client(),
+        )));
+        let mut catalog = ModelCatalog::new(false);
+        catalog.insert(ModelInfo {
+            pricing: Some(ModelPricing {
+                input: 2.5,
+                output: 10.0,
+            }),
+            ..ModelInfo::new("openai", "gpt-4o")
+        });
+        registry.set_catalog(catalog);
+        app_state(registry)
+    }
+
+    async fn create_key(state: &AppState, owner: &str, budgets: Value) -> (String, String) {
+        let params = CreateApiKey {
+            name: "budgeted".to_string(),
+            owner: owner.to_string(),
+            budgets: serde_json::from_value(budgets).unwrap(),
+            ..Default::default()
+        };
+        let created = state.api_keys.create(CLI_ACTOR, &params).await.unwrap();
+        (created.record.id, created.key)
+    }
+
+    async fn admin_key(state: &AppState) -> String {
+        let params = CreateApiKey {
+            name: "admin".to_string(),
+            admin: true,
+            ..Default::default()
+        };
+        state.api_keys.create(CLI_ACTOR, &params).await.unwrap().key
+    }
+
+    fn chat() -> Value {
+        json!({
+            "provider": "openai",
+            "model": "gpt-4o",
+            "messages": [{ "role": "user", "content": "Hello" }]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_budgets_default_to_monthly_dollars_and_are_validated() {
+        let budget: Budget = serde_json::from_value(json!({ "limit": 100 })).unwrap();
+        assert_eq!(budget.unit, BudgetUnit::Usd);
+        assert_eq!(budget.period, BudgetPeriod::Monthly);
+        assert_eq!(budget.to_string(), "100 USD per month");
+        let budget: Budget =
+            serde_json::from_value(json!({ "limit": 5000, "unit": "tokens", "period": "rolling", "days": 7 })).unwrap();
+        assert_eq!(budget.to_string(), "5000 tokens per 7 days");
+
+        let state = budget_state(Captured::default()).await;
+        for budgets in [
+            json!([{ "limit": 0 }]),
+            json!([{ "limit": 10, "period": "rolling", "days": 0 }]),
+            json!([{ "limit": 10, "soft_limits": [80, 100] }]),
+        ] {
+            let params = CreateApiKey {
+                name: "invalid".to_string(),
+                budgets: serde_json::from_value(budgets).unwrap(),
+                ..Default::default()
+            };
+            let error = state.api_keys.create(CLI_ACTOR, &params).await.unwrap_err();
+            assert!(matches!(error, AuthError::InvalidInput(_)), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_over_a_key_budget_are_refused_with_quota_error() {
+        let calls = Captured::default();
+        let state = budget_state(calls.clone()).await;
+        let (_, key) = create_key(
+            &state,
+            "",
+            json!([{ "limit": 10, "unit": "tokens", "period": "daily" }]),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat()).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+        let (status, headers, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], "QUOTA_ERROR");
+        let retry_after: u64 = headers["retry-after"].to_str().unwrap().parse().unwrap();
+        assert!((1..=86_400).contains(&retry_after), "{}", retry_after);
+        assert_eq!(
+            body["error"]["reason"],
+            format!(
+                "Budget of 10 tokens per day exceeded for API key 'budgeted' (12 tokens spent). \
+                 Please try again in {}s.",
+                retry_after
+            )
+        );
+        assert_eq!(calls.len(), 2);
+
+        // The compatible routes answer in their own error format
+        let request = json!({ "model": "openai/gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, _, body) = post_with_headers(state.clone(), "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["type"], "insufficient_quota");
+        assert_eq!(body["error"]["code"], "quota_error");
+
+        let request = json!({
+            "model": "openai/gpt-4o",
+            "max_tokens": 16,
+            "messages": [{ "role": "user", "content": "Hello" }]
+        });
+        let (status, _, body) = post_with_headers(state.clone(), "/v1/messages", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["type"], "billing_error");
+        assert_eq!(calls.len(), 2);
+
+        // Keys without budgets are not affected
+        let (status, _, _) = post_with_headers(state.clone(), "/api/v1/chat/completions", "sk-1234", chat()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_usd_budgets_are_charged_whatever_the_provider_spelling() {
+        let calls = Captured::default();
+        let state = budget_state(calls.clone()).await;
+        let (_, key) = create_key(&state, "", json!([{ "limit": 0.00004 }])).await;
+
+        let mut request = chat();
+        request["provider"] = json!("OpenAI");
+        let (status, _, body) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request.clone()).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["extra_fields"]["attempts"][0]["provider"], "openai");
+        let request = json!({ "model": "OPENAI/gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, _, body) = post_with_headers(state.clone(), "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let mut request = chat();
+        request["provider"] = json!(" openai ");
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(
+            body["error"]["reason"]
+                .as_str()
+                .unwrap()
+                .starts_with("Budget of 0.00004 USD per month exceeded for API key 'budgeted' (0.000045 USD spent)."),
+            "{}",
+            body
+        );
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unpriced_models_only_count_their_tokens_under_a_usd_budget() {
+        let calls = Captured::default();
+        let state = budget_state(calls.clone()).await;
+        let admin = admin_key(&state).await;
+        let (id, key) = create_key(&state, "", json!([{ "limit": 0.00004 }])).await;
+
+        let mut request = chat();
+        request["model"] = json!("gpt-4o-mini");
+        for _ in 0..3 {
+            let (status, _, body) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request.clone()).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+        assert_eq!(calls.len(), 3);
+
+        let (_, body) = get_json(state.clone(), &format!("/api/v1/auth/keys/{}/spend", id), &admin).await;
+        assert_eq!(body["data"]["budgets"][0]["spent"], 0.0);
+        assert_eq!(body["data"]["month"]["requests"], 3);
+        assert_eq!(body["data"]["month"]["tokens"], 18);
+    }
+
+    #[tokio::test]
+    async fn test_keys_without_budgets_record_no_spend() {
+        let calls = Captured::default();
+        let state = budget_state(calls.clone()).await;
+        let admin = admin_key(&state).await;
+        let (id, key) = create_key(&state, "", json!([])).await;
+
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat()).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(calls.len(), 1);
+
+        let (_, body) = get_json(state.clone(), &format!("/api/v1/auth/keys/{}/spend", id), &admin).await;
+        assert_eq!(body["data"]["month"]["requests"], 0);
+        assert_eq!(body["data"]["month"]["cost"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_soft_limits_emit_an_event_once_per_window() {
+        let calls = Captured::default();
+        let state = budget_state(calls).await;
+        let budgets = json!([{ "limit": 20, "unit": "tokens", "soft_limits": [50] }]);
+        let (id, key) = create_key(&state, "", budgets).await;
+
+        let mut actions = Vec::new();
+        for _ in 0..4 {
+            let (status, _, _) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat()).await;
+            assert_eq!(status, StatusCode::OK);
+            let events = state.api_keys.audit(Some(&id), 10).await.unwrap();
+            actions.push(events[0].action.clone());
+        }
+        // 6 and 18 tokens leave the last event in place, 12 reach the soft limit and 24 the limit
+        assert_eq!(
+            actions,
+            ["key.created", "budget.threshold", "budget.threshold", "budget.exceeded"]
+        );
+
+        let events = state.api_keys.audit(Some(&id), 10).await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].actor, "budget");
+        assert_eq!(events[1].details["scope"], "key");
+        assert_eq!(events[1].details["percent"], 50);
+        assert_eq!(events[1].details["spent"], 12.0);
+
+        let (status, _, _) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_owner_budgets_cap_every_key_of_the_owner() {
+        let calls = Captured::default();
+        let state = budget_state(calls.clone()).await;
+        let admin = admin_key(&state).await;
+        let (first_id, first) = create_key(&state, "search", json!([])).await;
+        let (_, second) = create_key(&state, "search", json!([])).await;
+
+        let budgets = json!({ "budgets": [{ "limit": 0.00004, "soft_limits": [50] }] });
+        let path = "/api/v1/auth/owners/search/budgets";
+        let (status, body) = send_json(state.clone(), "PUT", path, &admin, Some(budgets)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["budgets"][0]["period"], "monthly");
+        assert_eq!(body["data"]["budgets"][0]["spent"], 0.0);
+
+        // Each key spends under the ceiling, together they reach it
+        for key in [&first, &second] {
+            let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", key, chat()).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+        let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", &first, chat()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(
+            body["error"]["reason"]
+                .as_str()
+                .unwrap()
+                .starts_with("Budget of 0.00004 USD per month exceeded for owner 'search' (0.000045 USD spent)."),
+            "{}",
+            body
+        );
+        assert_eq!(calls.len(), 2);
+
+        let (_, body) = get_json(state.clone(), path, &admin).await;
+        let spent = body["data"]["budgets"][0]["spent"].as_f64().unwrap();
+        assert!((spent - 2.0 * REQUEST_COST).abs() < 1e-12, "{}", spent);
+        assert_eq!(body["data"]["budgets"][0]["remaining"], 0.0);
+        assert_eq!(body["data"]["month"]["requests"], 2);
+        assert_eq!(body["data"]["month"]["tokens"], 12);
+
+        let (status, body) = get_json(state.clone(), &format!("/api/v1/auth/keys/{}/spend", first_id), &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["owner"], "search");
+        assert_eq!(body["data"]["budgets"], json!([]));
+        assert_eq!(body["data"]["month"]["requests"], 1);
+        let cost = body["data"]["month"]["cost"].as_f64().unwrap();
+        assert!((cost - REQUEST_COST).abs() < 1e-12, "{}", cost);
+
+        // Reaching the soft limit and the ceiling of the owner are in the audit trail
+        let (_, body) = get_json(state.clone(), "/api/v1/auth/audit?limit=3", &admin).await;
+        let actions: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["action"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            actions,
+            ["budget.exceeded", "budget.threshold", "owner.budgets_updated"]
+        );
+
+        // Lifting the ceiling lets the keys through again
+        let (status, _) = send_json(state.clone(), "PUT", path, &admin, Some(json!({ "budgets": [] }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_json(state, "/api/v1/chat/completions", &first, chat()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,273 @@
+mod common;
+
+#[cfg(test)]
+mod circuit_breaker_tests {
+    use super::common::{StubProvider, app_state, post_json, spawn_mock};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use serde_json::json;
+    use sorai::config::{CircuitBreakerConfig, Config};
+    use sorai::http::schemas::completions::{ChatCompletionReq, Fallback};
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{
+        CircuitBreaker, CircuitState, ModelProvider, ProviderError, ProviderRegistry, upstream_client, with_fallbacks,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn config(window: u32, open_seconds: u64) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            window,
+            min_requests: window,
+            failure_rate: 50,
+            slow_call_ms: 1_000,
+            open_seconds,
+            half_open_requests: 1,
+            ..Default::default()
+        }
+    }
+
+    fn upstream(status: u16) -> ProviderError {
+        ProviderError::Upstream {
+            provider: "openai".to_string(),
+            status,
+            message: String::new(),
+        }
+    }
+
+    fn call(breaker: &CircuitBreaker, error: Option<ProviderError>) {
+        breaker
+            .acquire()
+            .unwrap()
+            .record(error.as_ref(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_circuit_opens_on_failure_rate() {
+        let breaker = CircuitBreaker::new("openai", config(4, 30));
+
+        call(&breaker, None);
+        call(&breaker, Some(upstream(503)));
+        call(&breaker, None);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        // Request errors say nothing about the provider health
+        call(&breaker, Some(ProviderError::InvalidRequest("bad".to_string())));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        call(
+            &breaker,
+            Some(ProviderError::Timeout {
+                provider: "openai".to_string(),
+            }),
+        );
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let error = breaker.acquire().err().unwrap();
+        assert!(matches!(error, ProviderError::CircuitOpen { .. }));
+        assert!(error.is_retryable());
+        assert_eq!(error.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_client_errors_and_slow_calls() {
+        let breaker = CircuitBreaker::new("openai", config(2, 30));
+
+        // The provider answered a bad request, so it counts as healthy
+        call(&breaker, Some(upstream(400)));
+        call(&breaker, Some(upstream(422)));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.acquire().unwrap().record(None, Duration::from_millis(1_500));
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_half_open_trial_closes_or_reopens() {
+        let breaker = CircuitBreaker::new("openai", config(1, 0));
+        call(&breaker, Some(upstream(500)));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // After the open duration one trial call is let through at a time
+        let trial = breaker.acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.acquire().is_err());
+        trial.record(Some(&upstream(502)), Duration::from_millis(10));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // A permit dropped without an outcome frees its slot
+        let trial = breaker.acquire().unwrap();
+        assert!(breaker.acquire().is_err());
+        drop(trial);
+        let trial = breaker.acquire().unwrap();
+        trial.record(None, Duration::from_millis(10));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_calls_started_before_a_state_change_are_not_counted() {
+        let breaker = CircuitBreaker::new("openai", config(1, 0));
+        let earlier = breaker.acquire().unwrap();
+        call(&breaker, Some(upstream(500)));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        earlier.record(None, Duration::from_millis(10));
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    fn registry(providers: &[Arc<StubProvider>]) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        for provider in providers {
+            registry.register(provider.clone());
+        }
+        registry.set_circuit_breaker(config(2, 30));
+        registry
+    }
+
+    #[test]
+    fn test_open_circuit_goes_straight_to_fallbacks() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 503);
+        let fallback = StubProvider::answering(ModelProvider::Anthropic);
+        let fallbacks = vec![Fallback {
+            provider: "anthropic".to_string(),
+            model: "claude-3-5-haiku-latest".to_string(),
+        }];
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let outcomes = metrics::with_local_recorder(&recorder, || {
+            let registry = registry(&[primary.clone(), fallback.clone()]);
+            (0..3)
+                .map(|_| {
+                    runtime.block_on(with_fallbacks(
+                        &registry,
+                        "openai",
+                        "gpt-4o-mini",
+                        &fallbacks,
+                        |provider, model| {
+                            let request = &request;
+                            async move { provider.chat_completion(&model, request).await }
+                        },
+                    ))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert!(outcomes.iter().all(|outcome| outcome.result.is_ok()));
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(fallback.calls(), 3);
+
+        let skipped = &outcomes[2].attempts[0];
+        assert_eq!(skipped.provider, "openai");
+        assert!(!skipped.success);
+        assert_eq!(
+            skipped.error.as_deref(),
+            Some("Provider 'openai' is unavailable, its circuit breaker is open")
+        );
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"sorai_circuit_breaker_state{provider="openai"} 2"#));
+        assert!(rendered.contains(r#"sorai_circuit_breaker_state{provider="anthropic"} 0"#));
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_without_fallbacks_fails_fast() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 500);
+        let state = app_state(registry(std::slice::from_ref(&primary)));
+        let body = json!({
+            "provider": "openai",
+            "model": "gpt-4o-mini",
+            "messages": [{ "role": "user", "content": "ping" }]
+        });
+
+        for _ in 0..2 {
+            let (status, _) = post_json(state.clone(), "/api/v1/chat/completions", "sk-1234", body.clone()).await;
+            assert_eq!(status, StatusCode::BAD_GATEWAY);
+        }
+
+        let (status, body) = post_json(state, "/api/v1/chat/completions", "sk-1234", body).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{}", body);
+        assert_eq!(primary.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_guards_every_spelling_of_a_provider() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 500);
+        let registry = registry(std::slice::from_ref(&primary));
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .unwrap();
+
+        for spelling in ["OpenAI", " openai ", "OPENAI"] {
+            let outcome = with_fallbacks(&registry, spelling, "gpt-4o-mini", &[], |provider, model| {
+                let request = &request;
+                async move { provider.chat_completion(&model, request).await }
+            })
+            .await;
+            assert!(outcome.result.is_err());
+        }
+
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(registry.circuit_breaker("openai").unwrap().state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_upstream_timeout_defaults_below_the_request_timeout() {
+        let mut config = Config::default().sorai;
+        assert_eq!(
+            config.upstream_timeout("openai"),
+            Duration::from_secs(config.request_timeout / 2)
+        );
+
+        config.upstream_timeout = Some(20);
+        config.provider_timeouts.insert("anthropic".to_string(), 5);
+        assert_eq!(config.upstream_timeout("openai"), Duration::from_secs(20));
+        assert_eq!(config.upstream_timeout("anthropic"), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_hanging_provider_times_out_and_opens_the_circuit() {
+        let router = Router::new().route(
+            "/v1/chat/completions",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Json(json!({}))
+            }),
+        );
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url: format!("{}/v1", spawn_mock(router).await),
+            },
+            upstream_client(Duration::from_millis(100)),
+        )));
+        registry.set_circuit_breaker(config(2, 30));
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .unwrap();
+
+        for _ in 0..2 {
+            let outcome = with_fallbacks(&registry, "openai", "gpt-4o-mini", &[], |provider, model| {
+                let request = &request;
+                async move { provider.chat_completion(&model, request).await }
+            })
+            .await;
+            assert!(
+                matches!(outcome.result, Err(ProviderError::Timeout { .. })),
+                "{:?}",
+                outcome.result.err()
+            );
+        }
+        assert_eq!(registry.circuit_breaker("openai").unwrap().state(), CircuitState::Open);
+    }
+}
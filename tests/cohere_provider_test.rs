@@ -0,0 +1,176 @@
+mod common;
+
+#[cfg(test)]
+mod cohere_provider_tests {
+    use super::common::{app_state, post_json, spawn_mock, typed_messages};
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::config::Config;
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::cohere::{CohereConfig, CohereProvider};
+    use sorai::providers::{Provider, ProviderRegistry, http_client};
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(HeaderMap, Value)>>>;
+
+    async fn mock_cohere(captured: Captured, response: Value) -> String {
+        let router = Router::new()
+            .route(
+                "/v2/chat",
+                post(
+                    |State((captured, response)): State<(Captured, Value)>,
+                     headers: HeaderMap,
+                     Json(body): Json<Value>| async move {
+                        *captured.lock().unwrap() = Some((headers, body));
+                        Json(response)
+                    },
+                ),
+            )
+            .with_state((captured, response));
+        spawn_mock(router).await
+    }
+
+    #[test]
+    fn test_build_request_translates_messages_and_tools() {
+        let messages = vec![
+            json!({ "role": "system", "content": "You are Sorai." }),
+            json!({ "role": "user", "content": "What's the weather in Jakarta?" }),
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "get_weather", "arguments": "{\"location\":\"Jakarta\"}" }
+                }]
+            }),
+            json!({ "role": "tool", "tool_call_id": "call_1", "content": "31C and sunny" }),
+        ];
+        let params = json!({
+            "max_tokens": 200,
+            "top_p": 0.9,
+            "top_k": 10,
+            "seed": 42,
+            "logprobs": true,
+            "tools": [{
+                "type": "function",
+                "function": { "name": "get_weather", "parameters": { "type": "object" } }
+            }],
+            "tool_choice": { "type": "function", "function": { "name": "get_weather" } }
+        });
+
+        let request = CohereProvider::build_request("command-r-plus", &typed_messages(messages), Some(&params));
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["model"], "command-r-plus");
+        assert_eq!(body["max_tokens"], 200);
+        assert_eq!(body["p"], 0.9);
+        assert_eq!(body["k"], 10);
+        assert_eq!(body["seed"], 42);
+        assert!(body.get("logprobs").is_none());
+        assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(body["tool_choice"], "REQUIRED");
+
+        let turns = body["messages"].as_array().unwrap();
+        assert_eq!(turns.len(), 4);
+        assert_eq!(turns[0], json!({ "role": "system", "content": "You are Sorai." }));
+        assert_eq!(turns[2]["role"], "assistant");
+        assert!(turns[2].get("content").is_none());
+        assert_eq!(turns[2]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(
+            turns[3],
+            json!({ "role": "tool", "content": "31C and sunny", "tool_call_id": "call_1" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_maps_tool_calls_and_billed_units() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let response = json!({
+            "id": "c14c80c3",
+            "finish_reason": "TOOL_CALL",
+            "message": {
+                "role": "assistant",
+                "tool_plan": "I will look up the weather.",
+                "tool_calls": [{
+                    "id": "get_weather_1",
+                    "type": "function",
+                    "function": { "name": "get_weather", "arguments": "{\"location\":\"Jakarta\"}" }
+                }]
+            },
+            "usage": {
+                "billed_units": { "input_tokens": 30, "output_tokens": 12 },
+                "tokens": { "input_tokens": 950, "output_tokens": 40 }
+            }
+        });
+        let provider = CohereProvider::new(
+            CohereConfig {
+                api_key: "co-test".to_string(),
+                base_url: mock_cohere(captured.clone(), response).await,
+            },
+            http_client(),
+        );
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "What's the weather in Jakarta?" }]
+        }))
+        .unwrap();
+        let response = provider.chat_completion("command-r-plus", &request).await.unwrap();
+
+        let (headers, _) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(headers["authorization"], "Bearer co-test");
+
+        let choice = &response.choices[0];
+        assert_eq!(choice.finish_reason, "tool_calls");
+        assert_eq!(choice.message.content, "");
+        assert_eq!(
+            choice.message.tool_calls.as_ref().unwrap()[0].function.name,
+            "get_weather"
+        );
+
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 30);
+        assert_eq!(usage.completion_tokens, 12);
+        assert_eq!(usage.total_tokens, 42);
+        assert_eq!(response.extra_fields.unwrap().provider, "cohere");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_through_gateway() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let response = json!({
+            "id": "a1",
+            "finish_reason": "MAX_TOKENS",
+            "message": { "role": "assistant", "content": [{ "type": "text", "text": "Quantum computers use qubits" }] },
+            "usage": { "tokens": { "input_tokens": 8, "output_tokens": 5 } }
+        });
+
+        let mut config = Config::default();
+        config.cohere.api_key = "co-test".to_string();
+        config.cohere.base_url = mock_cohere(captured, response).await;
+        let state = app_state(ProviderRegistry::from_config(&config));
+
+        let (status, body) = post_json(
+            state,
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "cohere",
+                "model": "command",
+                "messages": [{ "role": "user", "content": "Explain quantum computing" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["data"]["choices"][0]["message"]["content"],
+            "Quantum computers use qubits"
+        );
+        assert_eq!(body["data"]["choices"][0]["finish_reason"], "length");
+        assert_eq!(body["data"]["usage"]["total_tokens"], 13);
+    }
+}
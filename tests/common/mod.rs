@@ -0,0 +1,454 @@
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, Request, StatusCode, header};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::{Json, Router};
+use futures_util::stream;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use serde_json::{Value, json};
+use sorai::auth::ApiKeyStore;
+use sorai::config::AuthConfig;
+use sorai::database::Database;
+use sorai::http::schemas::completions::{
+    ChatCompletionChoice, ChatCompletionChunk, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ExtraFields,
+    RequestMessage, TextCompletionChoice, TextCompletionReq, TextCompletionResponse, ToolCall, UsageInfo,
+};
+use sorai::http::{AppState, create_router};
+use sorai::providers::{
+    ChatCompletionStream, ChunkBuilder, ModelProvider, Provider, ProviderCapabilities, ProviderError, ProviderRegistry,
+};
+use tokio::sync::Notify;
+use tower::ServiceExt;
+
+/// Typed request messages from OpenAI-style JSON messages
+pub fn typed_messages(messages: Vec<Value>) -> Vec<RequestMessage> {
+    messages
+        .into_iter()
+        .map(|message| serde_json::from_value(message).expect("Invalid message"))
+        .collect()
+}
+
+/// Spawn a router on a random local port and return its base URL
+pub async fn spawn_mock(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind mock server");
+    let address = listener.local_addr().expect("Failed to read mock address");
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.expect("Mock server failed");
+    });
+    format!("http://{}", address)
+}
+
+/// Bodies of the requests a mock upstream received, in order
+#[derive(Debug, Clone, Default)]
+pub struct Captured(Arc<Mutex<Vec<Value>>>);
+
+impl Captured {
+    /// Record a request body, returning its index
+    pub fn push(&self, body: Value) -> usize {
+        let mut bodies = self.0.lock().unwrap();
+        bodies.push(body);
+        bodies.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Body of the request at `index`
+    pub fn get(&self, index: usize) -> Value {
+        self.0.lock().unwrap()[index].clone()
+    }
+}
+
+/// How the OpenAI mock answers a request
+pub enum MockReply {
+    /// A completion with this content
+    Content(String),
+    /// An error response with this status and message
+    Error(StatusCode, String),
+}
+
+/// Answer every request with `Hi`
+pub fn reply_hi(_index: usize, _body: &Value) -> MockReply {
+    MockReply::Content("Hi".to_string())
+}
+
+/// Answer with each of `contents` in turn, repeating the last one
+pub fn reply_in_turn(contents: Vec<&'static str>) -> impl Fn(usize, &Value) -> MockReply + Send + Sync + 'static {
+    move |index, _| MockReply::Content(contents[index.min(contents.len() - 1)].to_string())
+}
+
+/// Spawn an OpenAI upstream serving chat and text completions under `/v1`, and return its base URL
+///
+/// Request bodies are recorded in `captured`, and `reply` decides the answer from the
+/// index of the request and its body. Completions echo the requested model and report
+/// 5 prompt and 1 completion tokens; streaming requests get the content in one chunk.
+pub async fn mock_openai<F>(captured: Captured, reply: F) -> String
+where
+    F: Fn(usize, &Value) -> MockReply + Send + Sync + 'static,
+{
+    let reply = Arc::new(reply);
+    let handler = move |State(captured): State<Captured>, Json(body): Json<Value>| {
+        let reply = reply.clone();
+        async move {
+            let index = captured.push(body.clone());
+            let content = match reply(index, &body) {
+                MockReply::Content(content) => content,
+                MockReply::Error(status, message) => {
+                    return (status, Json(json!({ "error": { "message": message } }))).into_response();
+                }
+            };
+            if body["stream"] == true {
+                let chunk = json!({
+                    "id": "chatcmpl-mock",
+                    "object": "chat.completion.chunk",
+                    "created": 1700000000,
+                    "model": body["model"],
+                    "choices": [{ "index": 0, "delta": { "role": "assistant", "content": content }, "finish_reason": null }]
+                });
+                let body = format!("data: {}\n\ndata: [DONE]\n\n", chunk);
+                return ([(header::CONTENT_TYPE, "text/event-stream")], body).into_response();
+            }
+            Json(json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion",
+                "created": 1700000000,
+                "model": body["model"],
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": content },
+                    "text": content,
+                    "finish_reason": "stop"
+                }],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+            }))
+            .into_response()
+        }
+    };
+    let router = Router::new()
+        .route("/v1/chat/completions", post(handler.clone()))
+        .route("/v1/completions", post(handler))
+        .with_state(captured);
+    format!("{}/v1", spawn_mock(router).await)
+}
+
+/// Provider answering in process, configured with how its model behaves
+///
+/// Answers `answered by <provider>` unless given a label, fails with an upstream
+/// status when given one, and records the chat requests it receives.
+pub struct StubProvider {
+    kind: ModelProvider,
+    pub label: String,
+    status: Option<u16>,
+    tool_calls: Option<Vec<ToolCall>>,
+    usage: Option<UsageInfo>,
+    /// Chunks streamed instead of the answer split into role and content chunks
+    chunks: Option<Vec<ChatCompletionChunk>>,
+    /// Notified before answering, to keep calls in flight
+    hold: Option<Arc<Notify>>,
+    calls: AtomicUsize,
+    requests: Mutex<Vec<ChatCompletionReq>>,
+}
+
+impl StubProvider {
+    pub fn new(kind: ModelProvider) -> Self {
+        Self {
+            kind,
+            label: format!("answered by {}", kind),
+            status: None,
+            tool_calls: None,
+            usage: None,
+            chunks: None,
+            hold: None,
+            calls: AtomicUsize::new(0),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn answering(kind: ModelProvider) -> Arc<Self> {
+        Arc::new(Self::new(kind))
+    }
+
+    pub fn failing(kind: ModelProvider, status: u16) -> Arc<Self> {
+        Arc::new(Self::new(kind).with_status(status))
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.tool_calls = Some(tool_calls);
+        self
+    }
+
+    pub fn with_usage(mut self, usage: UsageInfo) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    pub fn with_chunks(mut self, chunks: Vec<ChatCompletionChunk>) -> Self {
+        self.chunks = Some(chunks);
+        self
+    }
+
+    pub fn with_hold(mut self, hold: Arc<Notify>) -> Self {
+        self.hold = Some(hold);
+        self
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Last chat request received
+    pub fn last_request(&self) -> Option<ChatCompletionReq> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+
+    async fn respond(&self) -> Result<String, ProviderError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(hold) = &self.hold {
+            hold.notified().await;
+        }
+        match self.status {
+            Some(status) => Err(ProviderError::Upstream {
+                provider: self.name().to_string(),
+                status,
+                message: "upstream failure".to_string(),
+            }),
+            None => Ok(self.label.clone()),
+        }
+    }
+
+    fn finish_reason(&self) -> String {
+        match self.tool_calls {
+            Some(_) => "tool_calls".to_string(),
+            None => "stop".to_string(),
+        }
+    }
+
+    fn extra_fields(&self) -> Option<ExtraFields> {
+        Some(ExtraFields {
+            provider: self.name().to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl Provider for StubProvider {
+    fn kind(&self) -> ModelProvider {
+        self.kind
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            chat_completion: true,
+            text_completion: true,
+            streaming: true,
+            tools: true,
+            ..Default::default()
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionResponse, ProviderError> {
+        self.requests.lock().unwrap().push(request.clone());
+        Ok(ChatCompletionResponse {
+            id: "chatcmpl-stub".to_string(),
+            object: "chat.completion".to_string(),
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: self.respond().await?,
+                    tool_calls: self.tool_calls.clone(),
+                    tool_call_id: None,
+                },
+                finish_reason: self.finish_reason(),
+            }],
+            model: model.to_string(),
+            created: 0,
+            usage: self.usage.clone(),
+            extra_fields: self.extra_fields(),
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        model: &str,
+        request: &ChatCompletionReq,
+    ) -> Result<ChatCompletionStream, ProviderError> {
+        self.requests.lock().unwrap().push(request.clone());
+        let content = self.respond().await?;
+        let items = match &self.chunks {
+            Some(chunks) => chunks.clone(),
+            None => {
+                let chunks = ChunkBuilder::new(model);
+                let mut items = vec![
+                    chunks.role(),
+                    chunks.content(content),
+                    chunks.finish(self.finish_reason()),
+                ];
+                items.extend(self.usage.clone().map(|usage| chunks.usage(usage)));
+                items
+            }
+        };
+        Ok(Box::pin(stream::iter(items.into_iter().map(Ok))))
+    }
+
+    async fn text_completion(
+        &self,
+        model: &str,
+        _request: &TextCompletionReq,
+    ) -> Result<TextCompletionResponse, ProviderError> {
+        Ok(TextCompletionResponse {
+            id: "cmpl-stub".to_string(),
+            object: "text.completion".to_string(),
+            choices: vec![TextCompletionChoice {
+                index: 0,
+                text: self.respond().await?,
+                finish_reason: "stop".to_string(),
+            }],
+            model: model.to_string(),
+            created: 0,
+            usage: self.usage.clone(),
+            extra_fields: self.extra_fields(),
+        })
+    }
+}
+
+/// Build application state around the given provider registry, accepting the key `sk-1234`
+pub fn app_state(providers: ProviderRegistry) -> AppState {
+    let prometheus_handle = PrometheusBuilder::new().build_recorder().handle();
+    let api_keys = ApiKeyStore::new(Database::in_memory(), &AuthConfig::default()).with_key("sk-1234", "test", "tests");
+    AppState::new(prometheus_handle, providers, api_keys)
+}
+
+/// Send a JSON POST request through the application router
+pub async fn post_json(state: AppState, path: &str, api_key: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(path)
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", api_key))
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+/// Send a JSON POST request through the application router, returning the response headers along with the body
+pub async fn post_with_headers(
+    state: AppState,
+    path: &str,
+    api_key: &str,
+    body: Value,
+) -> (StatusCode, HeaderMap, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(path)
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", api_key))
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, headers, json)
+}
+
+/// Send a JSON POST request through the application router and return the raw body
+pub async fn post_raw(state: AppState, path: &str, api_key: &str, body: Value) -> (StatusCode, String, String) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(path)
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", api_key))
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, content_type, String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Send a GET request through the application router
+pub async fn get_json(state: AppState, path: &str, api_key: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(path)
+        .header("authorization", format!("Bearer {}", api_key))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+/// Send a request with any method and an optional JSON body through the application router
+pub async fn send_json(
+    state: AppState,
+    method: &str,
+    path: &str,
+    api_key: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let builder = Request::builder()
+        .method(method)
+        .uri(path)
+        .header("authorization", format!("Bearer {}", api_key));
+    let request = match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => builder.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = create_router(state).oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
@@ -0,0 +1,381 @@
+mod common;
+
+#[cfg(test)]
+mod embeddings_tests {
+    use super::common::{app_state, post_json, spawn_mock};
+    use axum::extract::{Path, State};
+    use axum::http::{StatusCode, Uri};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use serde_json::{Value, json};
+    use sorai::http::schemas::embeddings::EmbeddingReq;
+    use sorai::providers::anthropic::{AnthropicConfig, AnthropicProvider};
+    use sorai::providers::bedrock::{BedrockConfig, BedrockProvider};
+    use sorai::providers::cohere::{CohereConfig, CohereProvider};
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{Provider, ProviderRegistry, http_client};
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Vec<(String, Value)>>>;
+    type Respond = fn(&Value) -> Value;
+
+    /// Mock answering every POST with the response built from the request body
+    async fn mock_upstream(captured: Captured, respond: Respond) -> String {
+        let router = Router::new()
+            .fallback(post(
+                |State((captured, respond)): State<(Captured, Respond)>, uri: Uri, Json(body): Json<Value>| async move {
+                    let response = respond(&body);
+                    captured.lock().unwrap().push((uri.path().to_string(), body));
+                    Json(response)
+                },
+            ))
+            .with_state((captured, respond));
+        spawn_mock(router).await
+    }
+
+    fn openai_response(body: &Value) -> Value {
+        let data: Vec<Value> = body["input"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, _)| json!({ "object": "embedding", "index": index, "embedding": [index as f32, 0.5, -1.0] }))
+            .collect();
+        json!({
+            "object": "list",
+            "model": "text-embedding-3-small",
+            "data": data,
+            "usage": { "prompt_tokens": 8, "total_tokens": 8 }
+        })
+    }
+
+    async fn openai_registry(captured: Captured) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url: mock_upstream(captured, openai_response).await,
+            },
+            http_client(),
+        )));
+        registry
+    }
+
+    fn request(body: Value) -> EmbeddingReq {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_openai_embeddings_in_input_order() {
+        let captured: Captured = Arc::default();
+        let state = app_state(openai_registry(captured.clone()).await);
+
+        let (status, body) = post_json(
+            state,
+            "/api/v1/embeddings",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "text-embedding-3-small",
+                "input": ["first", "second"],
+                "dimensions": 3
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let data = &body["data"];
+        assert_eq!(data["object"], "list");
+        assert_eq!(data["data"][0]["index"], 0);
+        assert_eq!(data["data"][0]["embedding"], json!([0.0, 0.5, -1.0]));
+        assert_eq!(data["data"][1]["embedding"], json!([1.0, 0.5, -1.0]));
+        assert_eq!(data["usage"]["prompt_tokens"], 8);
+        assert_eq!(data["extra_fields"]["provider"], "openai");
+        assert_eq!(data["extra_fields"]["attempts"][0]["success"], true);
+
+        let (path, upstream) = captured.lock().unwrap().pop().unwrap();
+        assert_eq!(path, "/embeddings");
+        assert_eq!(upstream["input"], json!(["first", "second"]));
+        assert_eq!(upstream["dimensions"], 3);
+        assert_eq!(upstream["encoding_format"], "float");
+    }
+
+    #[tokio::test]
+    async fn test_base64_encoding_round_trips() {
+        let state = app_state(openai_registry(Arc::default()).await);
+
+        let (status, body) = post_json(
+            state,
+            "/api/v1/embeddings",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "text-embedding-3-small",
+                "input": "single",
+                "encoding_format": "base64"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let encoded = body["data"]["data"][0]["embedding"].as_str().unwrap();
+        let bytes = STANDARD.decode(encoded).unwrap();
+        let vector: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(vector, vec![0.0, 0.5, -1.0]);
+    }
+
+    #[tokio::test]
+    async fn test_request_validation() {
+        let state = app_state(openai_registry(Arc::default()).await);
+
+        let (status, body) = post_json(
+            state.clone(),
+            "/api/v1/embeddings",
+            "sk-1234",
+            json!({ "provider": "openai", "model": "text-embedding-3-small", "input": [] }),
+        )
+        .await;
+        assert!(!status.is_success());
+        assert_eq!(body["error"]["reason"], "Input is required");
+
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(AnthropicProvider::new(
+            AnthropicConfig {
+                api_key: "sk-ant-test".to_string(),
+                base_url: "http://127.0.0.1:9".to_string(),
+            },
+            http_client(),
+        )));
+        let (status, body) = post_json(
+            app_state(registry),
+            "/api/v1/embeddings",
+            "sk-1234",
+            json!({ "provider": "anthropic", "model": "claude-3-5-haiku-latest", "input": "Hi" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]["reason"].as_str().unwrap().contains("embeddings"));
+    }
+
+    #[test]
+    fn test_token_usage_is_recorded() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let (status, _) = metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let state = app_state(openai_registry(Arc::default()).await);
+                post_json(
+                    state,
+                    "/api/v1/embeddings",
+                    "sk-1234",
+                    json!({ "provider": "openai", "model": "text-embedding-3-small", "input": "Hi" }),
+                )
+                .await
+            })
+        });
+
+        assert_eq!(status, StatusCode::OK);
+        let rendered = handle.render();
+        assert!(
+            rendered
+                .contains(r#"sorai_tokens_total{provider="openai",model="text-embedding-3-small",type="prompt"} 8"#),
+            "{}",
+            rendered
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cohere_embed_request() {
+        let captured: Captured = Arc::default();
+        let provider = CohereProvider::new(
+            CohereConfig {
+                api_key: "co-test".to_string(),
+                base_url: mock_upstream(captured.clone(), |_| {
+                    json!({
+                        "id": "embed-1",
+                        "embeddings": { "float": [[0.1, 0.2], [0.3, 0.4]] },
+                        "meta": { "billed_units": { "input_tokens": 5 } }
+                    })
+                })
+                .await,
+            },
+            http_client(),
+        );
+
+        let response = provider
+            .embeddings(
+                "embed-v4.0",
+                &request(json!({
+                    "input": ["a", "b"],
+                    "dimensions": 256,
+                    "params": { "input_type": "search_query" }
+                })),
+            )
+            .await
+            .unwrap();
+
+        let (path, upstream) = captured.lock().unwrap().pop().unwrap();
+        assert_eq!(path, "/v2/embed");
+        assert_eq!(
+            upstream,
+            json!({
+                "model": "embed-v4.0",
+                "texts": ["a", "b"],
+                "input_type": "search_query",
+                "embedding_types": ["float"],
+                "output_dimension": 256
+            })
+        );
+
+        let body = serde_json::to_value(&response).unwrap();
+        assert_eq!(body["data"][1]["embedding"], json!([0.3_f32, 0.4_f32]));
+        assert_eq!(body["usage"]["prompt_tokens"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_titan_embeds_each_input() {
+        let captured: Captured = Arc::default();
+        let provider = BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: mock_upstream(captured.clone(), |body| {
+                    let length = body["inputText"].as_str().unwrap().len();
+                    json!({ "embedding": [length as f32], "inputTextTokenCount": length })
+                })
+                .await,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let response = provider
+            .embeddings(
+                "amazon.titan-embed-text-v2:0",
+                &request(json!({ "input": ["one", "three"], "dimensions": 512 })),
+            )
+            .await
+            .unwrap();
+
+        let calls = captured.lock().unwrap().clone();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].0, "/model/amazon.titan-embed-text-v2%3A0/invoke");
+        assert_eq!(calls[0].1, json!({ "inputText": "one", "dimensions": 512 }));
+        assert_eq!(calls[1].1["inputText"], "three");
+
+        let body = serde_json::to_value(&response).unwrap();
+        assert_eq!(body["data"][0]["embedding"], json!([3.0]));
+        assert_eq!(body["data"][1]["embedding"], json!([5.0]));
+        assert_eq!(body["usage"]["prompt_tokens"], 8);
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_cohere_embeds_in_one_call() {
+        let captured: Captured = Arc::default();
+        let provider = BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: mock_upstream(captured.clone(), |_| json!({ "embeddings": [[1.0], [2.0]] })).await,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let response = provider
+            .embeddings("cohere.embed-english-v3", &request(json!({ "input": ["a", "b"] })))
+            .await
+            .unwrap();
+
+        let (path, upstream) = captured.lock().unwrap().pop().unwrap();
+        assert_eq!(path, "/model/cohere.embed-english-v3/invoke");
+        assert_eq!(
+            upstream,
+            json!({ "texts": ["a", "b"], "input_type": "search_document" })
+        );
+        assert_eq!(response.data.len(), 2);
+        assert!(response.usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vertex_predict_request() {
+        use sorai::providers::vertex::{VertexConfig, VertexProvider};
+
+        let captured: Captured = Arc::default();
+        let router = Router::new()
+            .route(
+                "/token",
+                post(|| async { Json(json!({ "access_token": "ya29.test-token", "expires_in": 3599 })) }),
+            )
+            .route(
+                "/projects/{project}/locations/{location}/publishers/google/models/{model}",
+                post(
+                    |State(captured): State<Captured>,
+                     Path((_, _, model)): Path<(String, String, String)>,
+                     Json(body): Json<Value>| async move {
+                        captured.lock().unwrap().push((model, body));
+                        Json(json!({
+                            "predictions": [
+                                { "embeddings": { "values": [0.25], "statistics": { "token_count": 2, "truncated": false } } },
+                                { "embeddings": { "values": [0.75], "statistics": { "token_count": 3, "truncated": false } } }
+                            ]
+                        }))
+                    },
+                ),
+            )
+            .with_state(captured.clone());
+        let base_url = spawn_mock(router).await;
+        let provider = VertexProvider::new(
+            VertexConfig {
+                credentials: concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/vertex_service_account.json"
+                )
+                .to_string(),
+                token_url: format!("{}/token", base_url),
+                base_url,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let response = provider
+            .embeddings(
+                "text-embedding-005",
+                &request(json!({
+                    "input": ["a", "b"],
+                    "dimensions": 128,
+                    "params": { "task_type": "RETRIEVAL_QUERY" }
+                })),
+            )
+            .await
+            .unwrap();
+
+        let (model, upstream) = captured.lock().unwrap().pop().unwrap();
+        assert_eq!(model, "text-embedding-005:predict");
+        assert_eq!(
+            upstream,
+            json!({
+                "instances": [
+                    { "content": "a", "task_type": "RETRIEVAL_QUERY" },
+                    { "content": "b", "task_type": "RETRIEVAL_QUERY" }
+                ],
+                "parameters": { "outputDimensionality": 128 }
+            })
+        );
+
+        let body = serde_json::to_value(&response).unwrap();
+        assert_eq!(body["data"][1]["embedding"], json!([0.75]));
+        assert_eq!(body["usage"]["prompt_tokens"], 5);
+    }
+}
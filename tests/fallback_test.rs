@@ -0,0 +1,296 @@
+mod common;
+
+#[cfg(test)]
+mod fallback_tests {
+    use super::common::{StubProvider, app_state, post_json, spawn_mock};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use serde_json::json;
+    use sorai::http::schemas::completions::{ChatCompletionReq, Fallback};
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{ModelProvider, ProviderError, ProviderRegistry, upstream_client, with_fallbacks};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn registry(providers: &[Arc<StubProvider>]) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        for provider in providers {
+            registry.register(provider.clone());
+        }
+        registry
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let upstream = |status| ProviderError::Upstream {
+            provider: "openai".to_string(),
+            status,
+            message: String::new(),
+        };
+
+        assert!(upstream(429).is_retryable());
+        assert!(upstream(500).is_retryable());
+        assert!(upstream(503).is_retryable());
+        assert!(!upstream(400).is_retryable());
+        assert!(!upstream(401).is_retryable());
+        assert!(ProviderError::NotConfigured("anthropic".to_string()).is_retryable());
+        assert!(
+            ProviderError::Timeout {
+                provider: "openai".to_string()
+            }
+            .is_retryable()
+        );
+        assert!(!ProviderError::UnknownProvider("mystery".to_string()).is_retryable());
+        assert!(!ProviderError::InvalidRequest("bad".to_string()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_falls_back_on_server_error() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 503);
+        let fallback = StubProvider::answering(ModelProvider::Anthropic);
+
+        let (status, body) = post_json(
+            app_state(registry(&[primary.clone(), fallback.clone()])),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "ping" }],
+                "fallbacks": [{ "provider": "anthropic", "model": "claude-3-5-haiku-latest" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["data"]["choices"][0]["message"]["content"],
+            "answered by anthropic"
+        );
+        assert_eq!(body["data"]["model"], "claude-3-5-haiku-latest");
+
+        let extra = &body["data"]["extra_fields"];
+        assert_eq!(extra["provider"], "anthropic");
+        let attempts = extra["attempts"].as_array().unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0]["provider"], "openai");
+        assert_eq!(attempts[0]["success"], false);
+        assert!(attempts[0]["error"].as_str().unwrap().contains("503"));
+        assert_eq!(attempts[1]["provider"], "anthropic");
+        assert_eq!(attempts[1]["model"], "claude-3-5-haiku-latest");
+        assert_eq!(attempts[1]["success"], true);
+
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(fallback.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_skips_unconfigured_provider() {
+        let fallback = StubProvider::answering(ModelProvider::Cohere);
+
+        let (status, body) = post_json(
+            app_state(registry(std::slice::from_ref(&fallback))),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "anthropic",
+                "model": "claude-3-5-haiku-latest",
+                "messages": [{ "role": "user", "content": "ping" }],
+                "fallbacks": [{ "provider": "cohere", "model": "command-r" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["extra_fields"]["provider"], "cohere");
+        assert_eq!(body["data"]["extra_fields"]["attempts"][0]["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_does_not_fall_back_on_client_error() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 400);
+        let fallback = StubProvider::answering(ModelProvider::Anthropic);
+
+        let (status, body) = post_json(
+            app_state(registry(&[primary, fallback.clone()])),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "ping" }],
+                "fallbacks": [{ "provider": "anthropic", "model": "claude-3-5-haiku-latest" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "PROVIDER_ERROR");
+        assert_eq!(fallback.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_returns_last_error_when_chain_is_exhausted() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 429);
+        let fallback = StubProvider::failing(ModelProvider::Anthropic, 502);
+
+        let (status, body) = post_json(
+            app_state(registry(&[primary.clone(), fallback.clone()])),
+            "/api/v1/text/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-3.5-turbo-instruct",
+                "text": "Once upon a time",
+                "fallbacks": [{ "provider": "anthropic", "model": "claude-3-5-haiku-latest" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert!(body["error"]["reason"].to_string().contains("anthropic"));
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(fallback.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_falls_back() {
+        let primary = StubProvider::failing(ModelProvider::OpenAI, 500);
+        let fallback = StubProvider::answering(ModelProvider::Bedrock);
+
+        let (status, body) = post_json(
+            app_state(registry(&[primary, fallback])),
+            "/api/v1/text/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-3.5-turbo-instruct",
+                "text": "Once upon a time",
+                "fallbacks": [{ "provider": "bedrock", "model": "anthropic.claude-3-haiku-20240307-v1:0" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["choices"][0]["text"], "answered by bedrock");
+        assert_eq!(body["data"]["extra_fields"]["attempts"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fallback_switch_is_recorded() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let registry = registry(&[
+            StubProvider::failing(ModelProvider::OpenAI, 503),
+            StubProvider::failing(ModelProvider::Anthropic, 503),
+            StubProvider::answering(ModelProvider::Cohere),
+        ]);
+        let fallbacks = vec![
+            Fallback {
+                provider: "anthropic".to_string(),
+                model: "claude-3-5-haiku-latest".to_string(),
+            },
+            Fallback {
+                provider: "cohere".to_string(),
+                model: "command-r".to_string(),
+            },
+        ];
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let outcome = metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(with_fallbacks(
+                &registry,
+                "openai",
+                "gpt-4o-mini",
+                &fallbacks,
+                |provider, model| {
+                    let request = &request;
+                    async move { provider.chat_completion(&model, request).await }
+                },
+            ))
+        });
+
+        assert!(outcome.result.is_ok());
+        assert_eq!(outcome.attempts.len(), 3);
+
+        let rendered = handle.render();
+        assert!(
+            rendered
+                .contains(r#"sorai_fallback_usage_total{primary_provider="openai",fallback_provider="anthropic"} 1"#)
+        );
+        assert!(
+            rendered.contains(r#"sorai_fallback_usage_total{primary_provider="openai",fallback_provider="cohere"} 1"#)
+        );
+    }
+
+    #[test]
+    fn test_hanging_primary_falls_back_after_its_timeout() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let router = Router::new().route(
+            "/v1/chat/completions",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Json(json!({}))
+            }),
+        );
+        let base_url = format!("{}/v1", runtime.block_on(spawn_mock(router)));
+
+        let fallback = StubProvider::answering(ModelProvider::Cohere);
+        let mut registry = registry(std::slice::from_ref(&fallback));
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            upstream_client(Duration::from_millis(100)),
+        )));
+        let fallbacks = vec![Fallback {
+            provider: "Cohere".to_string(),
+            model: "command-r".to_string(),
+        }];
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "ping" }]
+        }))
+        .unwrap();
+
+        let outcome = metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(with_fallbacks(
+                &registry,
+                "OpenAI",
+                "gpt-4o-mini",
+                &fallbacks,
+                |provider, model| {
+                    let request = &request;
+                    async move { provider.chat_completion(&model, request).await }
+                },
+            ))
+        });
+
+        assert!(outcome.result.is_ok());
+        assert_eq!(fallback.calls(), 1);
+        assert_eq!(outcome.attempts[0].provider, "openai");
+        assert_eq!(
+            outcome.attempts[0].error.as_deref(),
+            Some("Request to provider 'openai' timed out")
+        );
+        assert_eq!(outcome.attempts[1].provider, "cohere");
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains(r#"sorai_fallback_usage_total{primary_provider="openai",fallback_provider="cohere"} 1"#),
+            "{}",
+            rendered
+        );
+    }
+}
@@ -0,0 +1,255 @@
+mod common;
+
+#[cfg(test)]
+mod messages_tests {
+    use super::common::{StubProvider, app_state};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use serde_json::{Value, json};
+    use sorai::http::create_router;
+    use sorai::http::schemas::completions::{MessageRole, ToolCall, ToolCallDelta, UsageInfo};
+    use sorai::http::schemas::messages::MessagesReq;
+    use sorai::providers::{ChunkBuilder, FrameDecoder, ModelProvider, ProviderRegistry, SseDecoder};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// OpenAI stub answering with a tool call, whole or streamed in pieces
+    fn tool_calling() -> Arc<StubProvider> {
+        let chunks = ChunkBuilder::new("gpt-4o");
+        Arc::new(
+            StubProvider::new(ModelProvider::OpenAI)
+                .with_label("Let me check.")
+                .with_tool_calls(vec![ToolCall::function(
+                    "call_1",
+                    "get_weather",
+                    "{\"city\":\"Paris\"}",
+                )])
+                .with_usage(UsageInfo {
+                    prompt_tokens: 12,
+                    completion_tokens: 7,
+                    total_tokens: 19,
+                })
+                .with_chunks(vec![
+                    chunks.role(),
+                    chunks.content("Hel"),
+                    chunks.content("lo"),
+                    chunks.tool_call(ToolCallDelta::start(0, "call_1", "get_weather")),
+                    chunks.tool_call(ToolCallDelta::arguments(0, "{\"city\":")),
+                    chunks.tool_call(ToolCallDelta::arguments(0, "\"Paris\"}")),
+                    chunks.finish("tool_calls".to_string()),
+                    chunks.usage(UsageInfo {
+                        prompt_tokens: 12,
+                        completion_tokens: 9,
+                        total_tokens: 21,
+                    }),
+                ]),
+        )
+    }
+
+    /// Send a Messages API request the way the Anthropic SDK does, with `x-api-key`
+    async fn post_messages(provider: Arc<StubProvider>, api_key: &str, body: Value) -> (StatusCode, String) {
+        let mut registry = ProviderRegistry::new();
+        registry.register(provider);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/messages")
+            .header("content-type", "application/json")
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = create_router(app_state(registry)).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    #[test]
+    fn test_into_chat_request_translates_blocks() {
+        let request: MessagesReq = serde_json::from_value(json!({
+            "model": "openai/gpt-4o",
+            "max_tokens": 256,
+            "system": [{ "type": "text", "text": "Be brief." }],
+            "stop_sequences": ["END"],
+            "tools": [{
+                "name": "get_weather",
+                "description": "Current weather",
+                "input_schema": { "type": "object", "properties": { "city": { "type": "string" } } }
+            }],
+            "tool_choice": { "type": "any" },
+            "messages": [
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "text", "text": "Weather here?" },
+                        { "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": "iVBORw0" } }
+                    ]
+                },
+                {
+                    "role": "assistant",
+                    "content": [
+                        { "type": "text", "text": "Checking." },
+                        { "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": { "city": "Paris" } }
+                    ]
+                },
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "tool_result", "tool_use_id": "toolu_1", "content": [{ "type": "text", "text": "18C" }] },
+                        { "type": "text", "text": "Thanks" }
+                    ]
+                }
+            ]
+        }))
+        .unwrap();
+
+        let chat = request.into_chat_request("openai", "gpt-4o").unwrap();
+
+        assert_eq!(chat.provider.as_deref(), Some("openai"));
+        assert_eq!(chat.model.as_deref(), Some("gpt-4o"));
+        let messages = serde_json::to_value(&chat.messages).unwrap();
+        assert_eq!(chat.messages.len(), 5);
+        assert_eq!(messages[0], json!({ "role": "system", "content": "Be brief." }));
+        assert_eq!(messages[1]["content"][0]["text"], "Weather here?");
+        assert_eq!(
+            messages[1]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,iVBORw0"
+        );
+        assert_eq!(messages[2]["content"], "Checking.");
+        assert_eq!(messages[2]["tool_calls"][0]["id"], "toolu_1");
+        assert_eq!(
+            messages[2]["tool_calls"][0]["function"]["arguments"],
+            "{\"city\":\"Paris\"}"
+        );
+        assert_eq!(
+            messages[3],
+            json!({ "role": "tool", "tool_call_id": "toolu_1", "content": "18C" })
+        );
+        assert_eq!(messages[4], json!({ "role": "user", "content": "Thanks" }));
+
+        let params = chat.params.unwrap();
+        assert_eq!(params["max_tokens"], 256);
+        assert_eq!(params["stop"], json!(["END"]));
+        assert_eq!(params["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(params["tools"][0]["function"]["parameters"]["type"], "object");
+        assert_eq!(params["tool_choice"], "required");
+    }
+
+    #[test]
+    fn test_into_chat_request_rejects_unsupported_blocks() {
+        let request: MessagesReq = serde_json::from_value(json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": [{ "type": "server_tool_use" }] }]
+        }))
+        .unwrap();
+
+        assert!(request.into_chat_request("openai", "gpt-4o").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_messages_answer_in_anthropic_format() {
+        let provider = tool_calling();
+
+        let (status, body) = post_messages(
+            provider.clone(),
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o",
+                "max_tokens": 128,
+                "system": "Be brief.",
+                "messages": [{ "role": "user", "content": "Weather in Paris?" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["type"], "message");
+        assert_eq!(body["role"], "assistant");
+        assert!(body["id"].as_str().unwrap().starts_with("msg_"));
+        assert_eq!(body["content"][0], json!({ "type": "text", "text": "Let me check." }));
+        assert_eq!(body["content"][1]["type"], "tool_use");
+        assert_eq!(body["content"][1]["id"], "call_1");
+        assert_eq!(body["content"][1]["input"], json!({ "city": "Paris" }));
+        assert_eq!(body["stop_reason"], "tool_use");
+        assert_eq!(body["usage"], json!({ "input_tokens": 12, "output_tokens": 7 }));
+
+        let captured = provider.last_request().unwrap();
+        assert_eq!(captured.messages[0].role, MessageRole::System);
+        assert_eq!(captured.params.unwrap()["max_tokens"], 128);
+    }
+
+    #[tokio::test]
+    async fn test_messages_stream_events() {
+        let (status, body) = post_messages(
+            tool_calling(),
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o",
+                "max_tokens": 128,
+                "stream": true,
+                "messages": [{ "role": "user", "content": "Weather in Paris?" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let events = SseDecoder::new().decode(body.as_bytes()).unwrap();
+        let names: Vec<&str> = events.iter().filter_map(|event| event.event.as_deref()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "message_start",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "message_delta",
+                "message_stop",
+            ]
+        );
+
+        let data: Vec<Value> = events
+            .iter()
+            .map(|event| serde_json::from_str(&event.data).unwrap())
+            .collect();
+        assert_eq!(data[0]["message"]["model"], "gpt-4o");
+        assert_eq!(data[2]["delta"], json!({ "type": "text_delta", "text": "Hel" }));
+        assert_eq!(data[5]["index"], 1);
+        assert_eq!(data[5]["content_block"]["name"], "get_weather");
+        assert_eq!(data[6]["delta"]["type"], "input_json_delta");
+        assert_eq!(data[7]["delta"]["partial_json"], "\"Paris\"}");
+        assert_eq!(data[9]["delta"]["stop_reason"], "tool_use");
+        assert_eq!(data[9]["usage"]["output_tokens"], 9);
+    }
+
+    #[tokio::test]
+    async fn test_errors_use_anthropic_format() {
+        let (status, body) = post_messages(
+            tool_calling(),
+            "sk-1234",
+            json!({ "model": "gpt-4o", "messages": [{ "role": "user", "content": "Hi" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["type"], "error");
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+
+        let (status, body) = post_messages(
+            tool_calling(),
+            "sk-0000",
+            json!({ "model": "openai/gpt-4o", "messages": [{ "role": "user", "content": "Hi" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"]["type"], "authentication_error");
+    }
+}
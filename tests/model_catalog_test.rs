@@ -0,0 +1,233 @@
+mod common;
+
+#[cfg(test)]
+mod model_catalog_tests {
+    use super::common::{Captured, app_state, get_json, mock_openai, post_json, reply_hi};
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{
+        Modality, ModelCatalog, ModelInfo, ModelKind, ModelPricing, ModelRequirements, ProviderError, ProviderRegistry,
+        http_client,
+    };
+    use std::sync::Arc;
+
+    fn chat(body: Value) -> ModelRequirements {
+        let request: ChatCompletionReq = serde_json::from_value(body).unwrap();
+        ModelRequirements::chat(&request)
+    }
+
+    fn hello() -> Value {
+        json!([{ "role": "user", "content": "Hello" }])
+    }
+
+    fn registry(base_url: String) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        )));
+        registry.set_catalog(ModelCatalog::builtin(true));
+        registry
+    }
+
+    #[test]
+    fn test_builtin_catalog_metadata() {
+        let catalog = ModelCatalog::builtin(true);
+        assert!(catalog.is_strict());
+
+        let model = catalog.get("openai", "gpt-4o-mini").unwrap();
+        assert_eq!(model.kind, ModelKind::Chat);
+        assert_eq!(model.context_window, Some(128_000));
+        assert_eq!(model.max_output_tokens, Some(16_384));
+        assert_eq!(model.tools, Some(true));
+        assert_eq!(model.streaming, Some(true));
+        assert_eq!(
+            model.modalities.as_deref(),
+            Some(&[Modality::Text, Modality::Image, Modality::Document][..])
+        );
+        assert_eq!(
+            model.pricing,
+            Some(ModelPricing {
+                input: 0.15,
+                output: 0.6
+            })
+        );
+
+        let embedding = catalog.get("vertex", "text-embedding-004").unwrap();
+        assert_eq!(embedding.kind, ModelKind::Embedding);
+        assert_eq!(embedding.max_output_tokens, None);
+        assert_eq!(embedding.streaming, Some(false));
+
+        assert!(catalog.get("openai", "claude-3-5-haiku-latest").is_none());
+        assert!(catalog.provider_models("bedrock").count() > 0);
+    }
+
+    #[test]
+    fn test_check_rejects_unknown_models() {
+        let requirements = chat(json!({ "messages": hello() }));
+
+        let error = ModelCatalog::builtin(true)
+            .check("openai", "gpt-5-ultra", &requirements)
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            error.to_string(),
+            "Model 'gpt-5-ultra' is not served by provider 'openai'"
+        );
+
+        // Lenient catalogs and providers without entries accept any model
+        assert!(
+            ModelCatalog::builtin(false)
+                .check("openai", "gpt-5-ultra", &requirements)
+                .is_ok()
+        );
+        assert!(
+            ModelCatalog::builtin(true)
+                .check("groq", "llama-3.1-8b-instant", &requirements)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_rejects_unsupported_features() {
+        let catalog = ModelCatalog::builtin(true);
+        let error = |provider: &str, model: &str, requirements: ModelRequirements| {
+            let error = catalog.check(provider, model, &requirements).unwrap_err();
+            assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+            error.to_string()
+        };
+
+        let tools = chat(json!({
+            "messages": hello(),
+            "params": { "tools": [{ "type": "function", "function": { "name": "get_weather" } }] }
+        }));
+        assert_eq!(
+            error("cohere", "command", tools.clone()),
+            "Provider 'cohere' does not support tool calling with model 'command'"
+        );
+        assert!(catalog.check("cohere", "command-r", &tools).is_ok());
+
+        let image = chat(json!({
+            "messages": [{
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "What is this?" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } }
+                ]
+            }]
+        }));
+        assert_eq!(
+            error("openai", "o3-mini", image.clone()),
+            "Provider 'openai' does not support image input with model 'o3-mini'"
+        );
+        assert!(catalog.check("openai", "gpt-4o", &image).is_ok());
+
+        assert_eq!(
+            error("openai", "text-embedding-3-small", chat(json!({ "messages": hello() }))),
+            "Provider 'openai' does not support completions with model 'text-embedding-3-small'"
+        );
+        assert_eq!(
+            error("openai", "gpt-4o", ModelRequirements::embeddings()),
+            "Provider 'openai' does not support embeddings with model 'gpt-4o'"
+        );
+
+        assert_eq!(
+            error(
+                "anthropic",
+                "claude-3-5-haiku-latest",
+                chat(json!({ "messages": hello(), "params": { "max_tokens": 10000 } }))
+            ),
+            "max_tokens of 10000 exceeds the maximum of 8192 output tokens for model 'claude-3-5-haiku-latest'"
+        );
+    }
+
+    #[test]
+    fn test_custom_entries_and_advertised_models() {
+        let mut catalog = ModelCatalog::new(true);
+        catalog.advertise("azure", vec!["gpt4-prod".to_string()]);
+        assert_eq!(
+            catalog.get("azure", "gpt4-prod"),
+            Some(&ModelInfo::new("azure", "gpt4-prod"))
+        );
+
+        let entry: ModelInfo = serde_json::from_value(json!({
+            "provider": "azure",
+            "id": "gpt4-prod",
+            "max_output_tokens": 4096,
+            "tools": false
+        }))
+        .unwrap();
+        catalog.insert(entry);
+        catalog.advertise("azure", vec!["gpt4-prod".to_string()]);
+
+        assert_eq!(catalog.models().len(), 1);
+        let model = catalog.get("azure", "gpt4-prod").unwrap();
+        assert_eq!(model.kind, ModelKind::Chat);
+        assert_eq!(model.tools, Some(false));
+        assert!(matches!(
+            catalog.check("azure", "gpt35-dev", &ModelRequirements::default()),
+            Err(ProviderError::UnknownModel { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_model_is_rejected_before_the_provider() {
+        let calls = Captured::default();
+        let state = app_state(registry(mock_openai(calls.clone(), reply_hi).await));
+
+        let (status, body) = post_json(
+            state.clone(),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({ "provider": "openai", "model": "gpt-5-ultra", "messages": hello() }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+        assert_eq!(
+            body["error"]["reason"],
+            "Model 'gpt-5-ultra' is not served by provider 'openai'"
+        );
+        assert_eq!(calls.len(), 0);
+
+        let (status, body) = post_json(
+            state,
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({ "provider": "openai", "model": "gpt-4o-mini", "messages": hello() }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_models_endpoints_list_registered_providers() {
+        let state = app_state(registry("http://127.0.0.1:1/v1".to_string()));
+
+        let (status, body) = get_json(state.clone(), "/api/v1/models", "sk-1234").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let models = body["data"].as_array().unwrap();
+        assert!(models.iter().all(|model| model["provider"] == "openai"));
+        let model = models.iter().find(|model| model["id"] == "gpt-4o-mini").unwrap();
+        assert_eq!(model["kind"], "chat");
+        assert_eq!(model["context_window"], 128_000);
+        assert_eq!(model["modalities"], json!(["text", "image", "document"]));
+        assert_eq!(model["pricing"], json!({ "input": 0.15, "output": 0.6 }));
+
+        let (status, body) = get_json(state, "/v1/models", "sk-1234").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|model| model["id"].as_str())
+            .collect();
+        assert!(ids.contains(&"openai/gpt-4o-mini"));
+        assert!(ids.iter().all(|id| id.starts_with("openai/")));
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+#[cfg(test)]
+mod model_routing_tests {
+    use super::common::{Captured, app_state, get_json, mock_openai, post_json, reply_hi};
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use sorai::http::schemas::completions::Fallback;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{ModelAlias, ProviderRegistry, RoutingTable, http_client};
+    use std::sync::Arc;
+
+    fn aliases() -> Vec<ModelAlias> {
+        serde_json::from_value(json!([
+            {
+                "name": "fast",
+                "targets": [
+                    { "provider": "anthropic", "model": "claude-3-5-haiku-latest" },
+                    { "provider": "openai", "model": "gpt-4o-mini" }
+                ],
+                "params": { "temperature": 0.2, "max_tokens": 256 }
+            },
+            {
+                "name": "gpt-4o",
+                "targets": [{ "provider": "openai", "model": "gpt-4o-2024-11-20" }]
+            }
+        ]))
+        .unwrap()
+    }
+
+    fn registry(base_url: String) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        )));
+        registry.set_routing(RoutingTable::new(aliases()));
+        registry
+    }
+
+    #[test]
+    fn test_resolve_rewrites_aliased_requests() {
+        let table = RoutingTable::new(aliases());
+
+        let mut provider = None;
+        let mut model = Some("fast".to_string());
+        let mut params = Some(json!({ "temperature": 0.9 }));
+        let mut fallbacks = Some(vec![Fallback {
+            provider: "cohere".to_string(),
+            model: "command-r".to_string(),
+        }]);
+        let alias = table.resolve(&mut provider, &mut model, &mut params, &mut fallbacks);
+
+        assert_eq!(alias.as_deref(), Some("fast"));
+        assert_eq!(provider.as_deref(), Some("anthropic"));
+        assert_eq!(model.as_deref(), Some("claude-3-5-haiku-latest"));
+        assert_eq!(params, Some(json!({ "temperature": 0.9, "max_tokens": 256 })));
+        let fallbacks: Vec<(String, String)> = fallbacks
+            .unwrap()
+            .into_iter()
+            .map(|fallback| (fallback.provider, fallback.model))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            vec![
+                ("openai".to_string(), "gpt-4o-mini".to_string()),
+                ("cohere".to_string(), "command-r".to_string())
+            ]
+        );
+
+        // A request naming its provider is never aliased
+        let mut provider = Some("openai".to_string());
+        let mut model = Some("gpt-4o".to_string());
+        let (mut params, mut fallbacks) = (None, None);
+        assert_eq!(
+            table.resolve(&mut provider, &mut model, &mut params, &mut fallbacks),
+            None
+        );
+        assert_eq!(model.as_deref(), Some("gpt-4o"));
+        assert!(params.is_none() && fallbacks.is_none());
+
+        let mut provider = None;
+        let mut model = Some("slow".to_string());
+        assert_eq!(
+            table.resolve(&mut provider, &mut model, &mut params, &mut fallbacks),
+            None
+        );
+    }
+
+    #[test]
+    fn test_aliases_without_targets_are_ignored() {
+        let mut table = RoutingTable::new(aliases());
+        table.insert(ModelAlias {
+            name: "empty".to_string(),
+            targets: Vec::new(),
+            params: Default::default(),
+        });
+
+        assert!(table.get("empty").is_none());
+        let names: Vec<&str> = table.aliases().iter().map(|alias| alias.name.as_str()).collect();
+        assert_eq!(names, vec!["fast", "gpt-4o"]);
+    }
+
+    #[tokio::test]
+    async fn test_alias_is_resolved_and_reported() {
+        let captured = Captured::default();
+        let state = app_state(registry(mock_openai(captured.clone(), reply_hi).await));
+
+        let (status, body) = post_json(
+            state,
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "model": "fast",
+                "messages": [{ "role": "user", "content": "Hello" }],
+                "params": { "temperature": 0.7 }
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let extra = &body["data"]["extra_fields"];
+        assert_eq!(
+            extra["alias"],
+            json!({ "name": "fast", "provider": "openai", "model": "gpt-4o-mini" })
+        );
+        assert_eq!(extra["attempts"].as_array().unwrap().len(), 2);
+        assert_eq!(extra["attempts"][0]["provider"], "anthropic");
+
+        let sent = captured.get(0);
+        assert_eq!(sent["model"], "gpt-4o-mini");
+        assert_eq!(sent["temperature"], 0.7);
+        assert_eq!(sent["max_tokens"], 256);
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_endpoints_accept_aliases() {
+        let captured = Captured::default();
+        let state = app_state(registry(mock_openai(captured.clone(), reply_hi).await));
+
+        let (status, body) = post_json(
+            state.clone(),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({ "model": "gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["model"], "gpt-4o-2024-11-20");
+        assert_eq!(captured.get(0)["model"], "gpt-4o-2024-11-20");
+
+        let (status, body) = post_json(
+            state.clone(),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({ "model": "slow", "messages": [{ "role": "user", "content": "Hello" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["error"]["message"],
+            "Model 'slow' must be in the 'provider/model' format or an alias"
+        );
+
+        let (status, body) = get_json(state, "/v1/models", "sk-1234").await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|model| model["id"].as_str())
+            .collect();
+        assert!(ids.contains(&"fast"));
+        assert!(ids.contains(&"gpt-4o"));
+    }
+}
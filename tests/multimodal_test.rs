@@ -0,0 +1,455 @@
+mod common;
+
+#[cfg(test)]
+mod multimodal_tests {
+    use super::common::{app_state, post_json, spawn_mock, typed_messages};
+    use axum::http::StatusCode;
+    use axum::http::header::{CONTENT_TYPE, LOCATION};
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde_json::{Value, json};
+    use sorai::config::StorageConfig;
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::anthropic::AnthropicProvider;
+    use sorai::providers::bedrock::{BedrockConfig, BedrockProvider};
+    use sorai::providers::cohere::{CohereConfig, CohereProvider};
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::vertex::VertexProvider;
+    use sorai::providers::{MediaResolver, ProviderError, ProviderRegistry, http_client, supports_vision};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const PNG: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+    /// A user message with a text part followed by the given media parts
+    fn user_message(parts: Vec<Value>) -> Value {
+        let mut content = vec![json!({ "type": "text", "text": "What is this?" })];
+        content.extend(parts);
+        json!({ "role": "user", "content": content })
+    }
+
+    fn png_data_url() -> String {
+        format!("data:image/png;base64,{}", STANDARD.encode(PNG))
+    }
+
+    fn request(messages: Vec<Value>) -> ChatCompletionReq {
+        serde_json::from_value(json!({ "messages": messages })).unwrap()
+    }
+
+    /// Mock serving a PNG image and a PDF document
+    async fn media_server() -> String {
+        let router = Router::new()
+            .route(
+                "/cat.png",
+                get(|| async { ([(CONTENT_TYPE, "image/png")], PNG.to_vec()) }),
+            )
+            .route(
+                "/report.pdf",
+                get(|| async { ([(CONTENT_TYPE, "application/octet-stream")], b"%PDF-1.7".to_vec()) }),
+            )
+            .route(
+                "/moved.png",
+                get(|| async { (StatusCode::FOUND, [(LOCATION, "/cat.png")]) }),
+            )
+            .route(
+                "/metadata.png",
+                get(|| async {
+                    (
+                        StatusCode::FOUND,
+                        [(LOCATION, "http://169.254.169.254/latest/meta-data/")],
+                    )
+                }),
+            )
+            .route(
+                "/slow.png",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    ([(CONTENT_TYPE, "image/png")], PNG.to_vec())
+                }),
+            );
+        spawn_mock(router).await
+    }
+
+    /// Resolver allowed to download from the local media mock
+    fn local_resolver(max_upload_size: u64) -> MediaResolver {
+        MediaResolver::new(&StorageConfig {
+            max_upload_size,
+            media_allowed_hosts: vec!["127.0.0.1".to_string()],
+            media_fetch_timeout: 1,
+            ..Default::default()
+        })
+    }
+
+    /// Registry whose media resolver may download from the local media mock
+    fn local_registry() -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.set_media(local_resolver(StorageConfig::default().max_upload_size));
+        registry
+    }
+
+    /// A request with a remote image
+    fn remote_image_request(url: &str) -> ChatCompletionReq {
+        request(vec![user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": url } }),
+        ])])
+    }
+
+    /// Bedrock does not fetch image URLs itself, so remote images are downloaded for it
+    fn bedrock_provider() -> BedrockProvider {
+        BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: "http://127.0.0.1:9".to_string(),
+                ..Default::default()
+            },
+            http_client(),
+        )
+    }
+
+    fn openai_provider() -> OpenAIProvider {
+        OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url: "http://127.0.0.1:9".to_string(),
+            },
+            http_client(),
+        )
+    }
+
+    #[test]
+    fn test_anthropic_request_uses_media_blocks() {
+        let message = user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": png_data_url() } }),
+            json!({ "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } }),
+            json!({ "type": "file", "file": { "filename": "report.pdf", "file_data": "JVBERi0xLjc=" } }),
+        ]);
+
+        let request = AnthropicProvider::build_request("claude-3-5-haiku-latest", &typed_messages(vec![message]), None);
+        let content = &serde_json::to_value(&request).unwrap()["messages"][0]["content"];
+
+        assert_eq!(content[0], json!({ "type": "text", "text": "What is this?" }));
+        assert_eq!(
+            content[1],
+            json!({ "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": STANDARD.encode(PNG) } })
+        );
+        assert_eq!(
+            content[2],
+            json!({ "type": "image", "source": { "type": "url", "url": "https://example.com/cat.png" } })
+        );
+        assert_eq!(
+            content[3],
+            json!({ "type": "document", "source": { "type": "base64", "media_type": "application/pdf", "data": "JVBERi0xLjc=" } })
+        );
+    }
+
+    #[test]
+    fn test_bedrock_request_uses_inline_bytes() {
+        let message = user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": png_data_url() } }),
+            json!({ "type": "file", "file": { "filename": "Q3 report_final!.pdf", "file_data": "data:application/pdf;base64,JVBERi0xLjc=" } }),
+        ]);
+
+        let request = BedrockProvider::build_request(&typed_messages(vec![message]), None);
+        let content = &serde_json::to_value(&request).unwrap()["messages"][0]["content"];
+
+        assert_eq!(content[0], json!({ "text": "What is this?" }));
+        assert_eq!(
+            content[1],
+            json!({ "image": { "format": "png", "source": { "bytes": STANDARD.encode(PNG) } } })
+        );
+        assert_eq!(
+            content[2],
+            json!({ "document": { "format": "pdf", "name": "Q3 report-final-", "source": { "bytes": "JVBERi0xLjc=" } } })
+        );
+    }
+
+    #[test]
+    fn test_gemini_request_uses_inline_and_file_data() {
+        let message = user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": png_data_url() } }),
+            json!({ "type": "file", "file": { "file_data": "gs://bucket/report.pdf" } }),
+        ]);
+
+        let request = VertexProvider::build_request(&typed_messages(vec![message]), None);
+        let parts = &serde_json::to_value(&request).unwrap()["contents"][0]["parts"];
+
+        assert_eq!(parts[0], json!({ "text": "What is this?" }));
+        assert_eq!(
+            parts[1],
+            json!({ "inlineData": { "mimeType": "image/png", "data": STANDARD.encode(PNG) } })
+        );
+        assert_eq!(
+            parts[2],
+            json!({ "fileData": { "mimeType": "application/pdf", "fileUri": "gs://bucket/report.pdf" } })
+        );
+    }
+
+    #[test]
+    fn test_text_only_models_are_known() {
+        assert!(supports_vision("gpt-4o-mini"));
+        assert!(supports_vision("claude-3-5-sonnet-latest"));
+        assert!(supports_vision("us.meta.llama3-2-11b-instruct-v1:0"));
+        assert!(!supports_vision("gpt-3.5-turbo"));
+        assert!(!supports_vision("gpt-4"));
+        assert!(!supports_vision("command-r-plus"));
+        assert!(!supports_vision("meta.llama3-70b-instruct-v1:0"));
+    }
+
+    #[tokio::test]
+    async fn test_images_are_rejected_for_text_only_models() {
+        let resolver = MediaResolver::default();
+        let request = request(vec![user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": png_data_url() } }),
+        ])]);
+
+        let err = resolver
+            .prepare(&openai_provider(), "gpt-3.5-turbo", &request)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Unsupported { .. }));
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+
+        assert!(resolver.prepare(&openai_provider(), "gpt-4o", &request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_documents_are_rejected_for_cohere() {
+        let provider = CohereProvider::new(
+            CohereConfig {
+                api_key: "co-test".to_string(),
+                ..Default::default()
+            },
+            http_client(),
+        );
+        let request = request(vec![user_message(vec![
+            json!({ "type": "file", "file": { "filename": "report.pdf", "file_data": "JVBERi0xLjc=" } }),
+        ])]);
+
+        let err = MediaResolver::default()
+            .prepare(&provider, "command-a-03-2025", &request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Provider 'cohere' does not support document input");
+    }
+
+    #[tokio::test]
+    async fn test_inline_media_is_checked() {
+        let resolver = local_resolver(6);
+
+        let too_large = request(vec![user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": png_data_url() } }),
+        ])]);
+        let err = resolver
+            .prepare(&openai_provider(), "gpt-4o", &too_large)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Attachment exceeds the maximum upload size of 6 bytes");
+
+        let wrong_type = request(vec![user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": "data:image/bmp;base64,Qk0=" } }),
+        ])]);
+        let err = resolver
+            .prepare(&openai_provider(), "gpt-4o", &wrong_type)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unsupported image type 'image/bmp'");
+    }
+
+    #[tokio::test]
+    async fn test_remote_media_is_inlined_when_needed() {
+        let media = media_server().await;
+        let request = request(vec![user_message(vec![
+            json!({ "type": "image_url", "image_url": { "url": format!("{}/cat.png", media) } }),
+            json!({ "type": "file", "file": { "file_data": format!("{}/report.pdf?download=1", media) } }),
+        ])]);
+        let resolver = local_resolver(StorageConfig::default().max_upload_size);
+
+        // OpenAI fetches image URLs itself, but documents are always inlined
+        let prepared = resolver.prepare(&openai_provider(), "gpt-4o", &request).await.unwrap();
+        let messages = serde_json::to_value(&prepared.messages).unwrap();
+        let content = &messages[0]["content"];
+        assert_eq!(content[1]["image_url"]["url"], format!("{}/cat.png", media));
+        assert_eq!(
+            content[2]["file"],
+            json!({ "file_data": format!("data:application/pdf;base64,{}", STANDARD.encode(b"%PDF-1.7")), "filename": "report.pdf" })
+        );
+
+        let err = local_resolver(4)
+            .prepare(&openai_provider(), "gpt-4o", &request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Attachment exceeds the maximum upload size of 4 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_receives_downloaded_image() {
+        let media = media_server().await;
+        let captured: Arc<Mutex<Option<Value>>> = Arc::default();
+        let converse = Router::new().fallback(post({
+            let captured = captured.clone();
+            move |Json(body): Json<Value>| async move {
+                *captured.lock().unwrap() = Some(body);
+                Json(json!({
+                    "output": { "message": { "role": "assistant", "content": [{ "text": "A cat." }] } },
+                    "stopReason": "end_turn",
+                    "usage": { "inputTokens": 12, "outputTokens": 3, "totalTokens": 15 }
+                }))
+            }
+        }));
+        let mut registry = local_registry();
+        registry.register(Arc::new(BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: spawn_mock(converse).await,
+                ..Default::default()
+            },
+            http_client(),
+        )));
+
+        let (status, body) = post_json(
+            app_state(registry),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "bedrock",
+                "model": "anthropic.claude-3-haiku-20240307-v1:0",
+                "messages": [user_message(vec![
+                    json!({ "type": "image_url", "image_url": { "url": format!("{}/cat.png", media) } }),
+                ])]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["choices"][0]["message"]["content"], "A cat.");
+        let sent = captured.lock().unwrap().clone().unwrap();
+        assert_eq!(
+            sent["messages"][0]["content"][1],
+            json!({ "image": { "format": "png", "source": { "bytes": STANDARD.encode(PNG) } } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_media_is_a_request_error() {
+        let mut registry = local_registry();
+        registry.register(Arc::new(BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url: "http://127.0.0.1:9".to_string(),
+                ..Default::default()
+            },
+            http_client(),
+        )));
+        let media = media_server().await;
+
+        let (status, body) = post_json(
+            app_state(registry),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "bedrock",
+                "model": "anthropic.claude-3-haiku-20240307-v1:0",
+                "messages": [user_message(vec![
+                    json!({ "type": "image_url", "image_url": { "url": format!("{}/missing.png", media) } }),
+                ])]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["error"]["reason"],
+            format!("Failed to fetch '{}/missing.png': status 404", media)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_media_on_private_networks_is_refused() {
+        let media = media_server().await;
+        let resolver = MediaResolver::default();
+        let port = media.rsplit(':').next().unwrap();
+
+        for (url, reason) in [
+            (format!("{}/cat.png", media), "address 127.0.0.1 is not public"),
+            (
+                format!("http://localhost:{}/cat.png", port),
+                "host 'localhost' resolves to non-public address",
+            ),
+            (
+                "http://169.254.169.254/latest/meta-data/".to_string(),
+                "address 169.254.169.254 is not public",
+            ),
+            ("http://10.0.0.1/cat.png".to_string(), "address 10.0.0.1 is not public"),
+            ("http://[::1]/cat.png".to_string(), "address ::1 is not public"),
+            (
+                "http://[::ffff:192.168.1.1]/cat.png".to_string(),
+                "address ::ffff:192.168.1.1 is not public",
+            ),
+        ] {
+            let err = resolver
+                .prepare(
+                    &bedrock_provider(),
+                    "anthropic.claude-3-haiku-20240307-v1:0",
+                    &remote_image_request(&url),
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ProviderError::InvalidRequest(_)), "{}", url);
+            assert!(
+                err.to_string()
+                    .starts_with(&format!("Failed to fetch '{}': {}", url, reason)),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_checked_at_every_hop() {
+        let media = media_server().await;
+        let resolver = local_resolver(StorageConfig::default().max_upload_size);
+        let model = "anthropic.claude-3-haiku-20240307-v1:0";
+
+        let moved = remote_image_request(&format!("{}/moved.png", media));
+        let prepared = resolver.prepare(&bedrock_provider(), model, &moved).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&prepared.messages).unwrap()[0]["content"][1]["image_url"]["url"],
+            png_data_url()
+        );
+
+        let url = format!("{}/metadata.png", media);
+        let err = resolver
+            .prepare(&bedrock_provider(), model, &remote_image_request(&url))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Failed to fetch '{}': address 169.254.169.254 is not public", url)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_media_downloads_time_out() {
+        let media = media_server().await;
+        let url = format!("{}/slow.png", media);
+
+        let started = std::time::Instant::now();
+        let err = local_resolver(StorageConfig::default().max_upload_size)
+            .prepare(
+                &bedrock_provider(),
+                "anthropic.claude-3-haiku-20240307-v1:0",
+                &remote_image_request(&url),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::InvalidRequest(_)));
+        assert!(
+            err.to_string().starts_with(&format!("Failed to fetch '{}'", url)),
+            "{}",
+            err
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
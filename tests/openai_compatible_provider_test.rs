@@ -0,0 +1,199 @@
+mod common;
+
+#[cfg(test)]
+mod openai_compatible_provider_tests {
+    use super::common::{app_state, post_json, spawn_mock};
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::config::Config;
+    use sorai::http::schemas::completions::{ChatCompletionReq, TextCompletionReq};
+    use sorai::providers::openai_compatible::{OpenAICompatibleConfig, OpenAICompatibleProvider};
+    use sorai::providers::{ModelProvider, Provider, ProviderError, ProviderRegistry, http_client};
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(String, HeaderMap, Value)>>>;
+
+    /// Mock OpenAI-compatible server answering with its own label
+    async fn mock_server(label: &'static str, captured: Captured) -> String {
+        let chat = move |State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+            *captured.lock().unwrap() = Some(("chat/completions".to_string(), headers, body));
+            Json(json!({
+                "id": "chatcmpl-local",
+                "object": "chat.completion",
+                "created": 1_700_000_000,
+                "model": "llama3.2",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": format!("Hello from {}", label) },
+                    "finish_reason": "stop"
+                }],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 3, "total_tokens": 8 }
+            }))
+        };
+        let text = move |State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+            *captured.lock().unwrap() = Some(("completions".to_string(), headers, body));
+            Json(json!({
+                "id": "cmpl-local",
+                "object": "text_completion",
+                "created": 1_700_000_000,
+                "model": "llama3.2",
+                "choices": [{ "index": 0, "text": format!("Once from {}", label), "finish_reason": "length" }]
+            }))
+        };
+        let router = Router::new()
+            .route("/v1/chat/completions", post(chat))
+            .route("/v1/completions", post(text))
+            .with_state(captured);
+        spawn_mock(router).await
+    }
+
+    fn config(name: &str, base_url: String) -> OpenAICompatibleConfig {
+        OpenAICompatibleConfig {
+            name: name.to_string(),
+            base_url: format!("{}/v1", base_url),
+            api_key: String::new(),
+            headers: BTreeMap::new(),
+            models: Vec::new(),
+        }
+    }
+
+    fn chat_request() -> ChatCompletionReq {
+        serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "params": { "temperature": 0.3 }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_headers_and_env_prefix() {
+        let headers = OpenAICompatibleConfig::parse_headers("X-Tenant=acme, X-Trace = on,,broken");
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["X-Tenant"], "acme");
+        assert_eq!(headers["X-Trace"], "on");
+        assert_eq!(
+            OpenAICompatibleConfig::env_prefix("llama-cpp"),
+            "PROVIDER_OPENAI_COMPATIBLE_LLAMA_CPP_"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_sends_key_and_extra_headers() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let mut config = config("vllm", mock_server("vllm", captured.clone()).await);
+        config.api_key = "local-secret".to_string();
+        config.headers = OpenAICompatibleConfig::parse_headers("X-Tenant=acme");
+        let provider = OpenAICompatibleProvider::new(config, http_client());
+
+        let response = provider.chat_completion("llama3.2", &chat_request()).await.unwrap();
+
+        let (path, headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(path, "chat/completions");
+        assert_eq!(headers["authorization"], "Bearer local-secret");
+        assert_eq!(headers["x-tenant"], "acme");
+        assert_eq!(body["model"], "llama3.2");
+        assert_eq!(body["temperature"], 0.3);
+
+        assert_eq!(provider.name(), "vllm");
+        assert_eq!(provider.kind(), ModelProvider::OpenAICompatible);
+        assert_eq!(response.choices[0].message.content, "Hello from vllm");
+        assert_eq!(response.usage.unwrap().total_tokens, 8);
+        assert_eq!(response.extra_fields.unwrap().provider, "vllm");
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_without_api_key() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let provider = OpenAICompatibleProvider::new(
+            config("ollama", mock_server("ollama", captured.clone()).await),
+            http_client(),
+        );
+
+        let request: TextCompletionReq = serde_json::from_value(json!({ "text": "Once upon a time" })).unwrap();
+        let response = provider.text_completion("llama3.2", &request).await.unwrap();
+
+        let (path, headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(path, "completions");
+        assert!(headers.get("authorization").is_none());
+        assert_eq!(body["prompt"], "Once upon a time");
+        assert_eq!(response.choices[0].text, "Once from ollama");
+        assert_eq!(response.choices[0].finish_reason, "length");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_model_outside_model_list() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let mut config = config("jan", mock_server("jan", captured.clone()).await);
+        config.models = vec!["llama3.2".to_string()];
+        let provider = OpenAICompatibleProvider::new(config, http_client());
+
+        let err = provider.chat_completion("gpt-4o", &chat_request()).await.unwrap_err();
+
+        assert!(matches!(err, ProviderError::InvalidRequest(_)));
+        assert!(err.to_string().contains("'gpt-4o' is not served by provider 'jan'"));
+        assert!(captured.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_routes_to_named_providers() {
+        let ollama = mock_server("ollama", Arc::new(Mutex::new(None))).await;
+        let vllm = mock_server("vllm", Arc::new(Mutex::new(None))).await;
+
+        let app_config = Config {
+            openai_compatible: vec![config("ollama", ollama), config("vllm", vllm)],
+            ..Default::default()
+        };
+        let registry = ProviderRegistry::from_config(&app_config);
+        assert_eq!(registry.names(), vec!["ollama", "vllm"]);
+
+        for name in ["ollama", "vllm"] {
+            let (status, body) = post_json(
+                app_state(registry.clone()),
+                "/api/v1/chat/completions",
+                "sk-1234",
+                json!({
+                    "provider": name,
+                    "model": "llama3.2",
+                    "messages": [{ "role": "user", "content": "Hi" }]
+                }),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(
+                body["data"]["choices"][0]["message"]["content"],
+                format!("Hello from {}", name)
+            );
+            assert_eq!(body["data"]["extra_fields"]["provider"], name);
+        }
+    }
+
+    #[test]
+    fn test_registry_skips_unusable_names() {
+        let app_config = Config {
+            openai_compatible: vec![
+                config("openai", "http://localhost:1".to_string()),
+                config("local", "http://localhost:2".to_string()),
+                config("local", "http://localhost:3".to_string()),
+                OpenAICompatibleConfig {
+                    name: "no-url".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let registry = ProviderRegistry::from_config(&app_config);
+
+        assert_eq!(registry.names(), vec!["local"]);
+        assert!(matches!(
+            registry.get("openai").err(),
+            Some(ProviderError::NotConfigured(_))
+        ));
+    }
+}
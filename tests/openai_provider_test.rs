@@ -0,0 +1,173 @@
+mod common;
+
+#[cfg(test)]
+mod openai_provider_tests {
+    use super::common::spawn_mock;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::Config;
+    use sorai::http::schemas::completions::{ChatCompletionReq, MessageRole, RequestMessage, TextCompletionReq};
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{Provider, ProviderError, ProviderRegistry, http_client};
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(HeaderMap, Value)>>>;
+
+    async fn mock_openai() -> (String, Captured) {
+        let captured: Captured = Arc::new(Mutex::new(None));
+
+        let router = Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(
+                    |State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                        *captured.lock().unwrap() = Some((headers, body));
+                        Json(json!({
+                            "id": "chatcmpl-abc",
+                            "object": "chat.completion",
+                            "created": 1700000000,
+                            "model": "gpt-4o-mini-2024-07-18",
+                            "choices": [{
+                                "index": 0,
+                                "message": { "role": "assistant", "content": "Hello there!" },
+                                "finish_reason": "length"
+                            }],
+                            "usage": { "prompt_tokens": 9, "completion_tokens": 3, "total_tokens": 12 }
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/v1/completions",
+                post(
+                    |State(captured): State<Captured>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                        *captured.lock().unwrap() = Some((headers, body));
+                        Json(json!({
+                            "id": "cmpl-abc",
+                            "object": "text_completion",
+                            "created": 1700000000,
+                            "model": "gpt-3.5-turbo-instruct",
+                            "choices": [{ "index": 0, "text": " bright.", "finish_reason": "stop" }],
+                            "usage": { "prompt_tokens": 6, "completion_tokens": 2, "total_tokens": 8 }
+                        }))
+                    },
+                ),
+            )
+            .with_state(captured.clone());
+
+        (spawn_mock(router).await, captured)
+    }
+
+    fn provider(base_url: String) -> OpenAIProvider {
+        OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        )
+    }
+
+    #[test]
+    fn test_registry_registers_keyless_local_server() {
+        let mut config = Config::default();
+        config.openai.base_url = "http://127.0.0.1:1337/v1".to_string();
+
+        let registry = ProviderRegistry::from_config(&config);
+        assert_eq!(registry.get("openai").unwrap().name(), "openai");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_round_trip() {
+        let (base_url, captured) = mock_openai().await;
+        let provider = provider(format!("{}/v1", base_url));
+
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "provider": "openai",
+            "model": "gpt-4o-mini",
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "params": { "max_tokens": 16, "temperature": 0.2, "stop_sequences": "\n\n", "seed": 7 }
+        }))
+        .unwrap();
+
+        let response = provider.chat_completion("gpt-4o-mini", &request).await.unwrap();
+
+        let (headers, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(headers["authorization"], "Bearer sk-test");
+        assert_eq!(body["model"], "gpt-4o-mini");
+        assert_eq!(body["messages"][0]["content"], "Hi");
+        assert_eq!(body["max_tokens"], 16);
+        assert_eq!(body["stop"], json!(["\n\n"]));
+        assert_eq!(body["seed"], 7);
+
+        assert_eq!(response.object, "chat.completion");
+        assert_eq!(response.model, "gpt-4o-mini-2024-07-18");
+        assert_eq!(response.choices[0].message.content, "Hello there!");
+        assert_eq!(response.choices[0].finish_reason, "length");
+
+        let usage = response.usage.unwrap();
+        assert_eq!(
+            (usage.prompt_tokens, usage.completion_tokens, usage.total_tokens),
+            (9, 3, 12)
+        );
+
+        let extra = response.extra_fields.unwrap();
+        assert_eq!(extra.provider, "openai");
+        assert!(extra.latency > 0.0);
+        assert_eq!(extra.raw_response.unwrap()["id"], "chatcmpl-abc");
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_round_trip() {
+        let (base_url, captured) = mock_openai().await;
+        let provider = provider(format!("{}/v1/", base_url));
+
+        let request = TextCompletionReq {
+            text: Some("The future is".to_string()),
+            params: Some(json!({ "max_tokens": 5 })),
+            ..Default::default()
+        };
+
+        let response = provider
+            .text_completion("gpt-3.5-turbo-instruct", &request)
+            .await
+            .unwrap();
+
+        let (_, body) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(body["prompt"], "The future is");
+        assert_eq!(body["max_tokens"], 5);
+
+        assert_eq!(response.choices[0].text, " bright.");
+        assert_eq!(response.usage.unwrap().total_tokens, 8);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_error_is_mapped() {
+        let router = Router::new().route(
+            "/chat/completions",
+            post(|| async {
+                (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    Json(json!({ "error": { "message": "Rate limit reached", "type": "requests" } })),
+                )
+            }),
+        );
+        let provider = provider(spawn_mock(router).await);
+
+        let request = ChatCompletionReq {
+            messages: vec![RequestMessage::new(MessageRole::User, "Hi")],
+            ..Default::default()
+        };
+
+        match provider.chat_completion("gpt-4o-mini", &request).await {
+            Err(ProviderError::Upstream { status, message, .. }) => {
+                assert_eq!(status, 429);
+                assert_eq!(message, "Rate limit reached");
+            }
+            other => panic!("unexpected result: {:?}", other.map(|r| r.id)),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+mod common;
+
+#[cfg(test)]
+mod openai_routes_tests {
+    use super::common::{Captured, MockReply, app_state, get_json, mock_openai, post_json, post_raw};
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::http::schemas::openai::split_model;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::openai_compatible::{OpenAICompatibleConfig, OpenAICompatibleProvider};
+    use sorai::providers::{ProviderRegistry, http_client};
+    use std::sync::Arc;
+
+    /// Answer chat and text completions with `Hello there`, or fail with `status`
+    fn reply(status: StatusCode) -> impl Fn(usize, &Value) -> MockReply + Send + Sync + 'static {
+        move |_, _| {
+            if status.is_success() {
+                MockReply::Content("Hello there".to_string())
+            } else {
+                MockReply::Error(status, "slow down".to_string())
+            }
+        }
+    }
+
+    fn openai(base_url: String) -> Arc<OpenAIProvider> {
+        Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-upstream".to_string(),
+                base_url,
+            },
+            http_client(),
+        ))
+    }
+
+    async fn registry(status: StatusCode, captured: Captured) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(openai(mock_openai(captured, reply(status)).await));
+        registry
+    }
+
+    #[test]
+    fn test_split_model() {
+        assert_eq!(split_model("openai/gpt-4o"), Some(("openai", "gpt-4o")));
+        assert_eq!(
+            split_model("ollama/library/llama3.2"),
+            Some(("ollama", "library/llama3.2"))
+        );
+        assert_eq!(split_model("gpt-4o"), None);
+        assert_eq!(split_model("/gpt-4o"), None);
+        assert_eq!(split_model("openai/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_returns_raw_openai_json() {
+        let captured = Captured::default();
+        let state = app_state(registry(StatusCode::OK, captured.clone()).await);
+
+        let (status, body) = post_json(
+            state,
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Hi" }],
+                "temperature": 0.2,
+                "max_tokens": 32
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["object"], "chat.completion");
+        assert_eq!(body["choices"][0]["message"]["content"], "Hello there");
+        assert_eq!(body["usage"]["total_tokens"], 6);
+        assert!(body.get("data").is_none());
+        assert!(body.get("extra_fields").is_none());
+
+        let upstream = captured.get(0);
+        assert_eq!(upstream["model"], "gpt-4o-mini");
+        assert_eq!(upstream["temperature"], 0.2);
+        assert_eq!(upstream["max_tokens"], 32);
+    }
+
+    #[tokio::test]
+    async fn test_completion_accepts_single_prompt_list() {
+        let captured = Captured::default();
+        let state = app_state(registry(StatusCode::OK, captured.clone()).await);
+
+        let (status, body) = post_json(
+            state,
+            "/v1/completions",
+            "sk-1234",
+            json!({ "model": "openai/gpt-3.5-turbo-instruct", "prompt": ["Say hi"] }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["object"], "text_completion");
+        assert_eq!(body["choices"][0]["text"], "Hello there");
+        assert_eq!(captured.get(0)["prompt"], "Say hi");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_chat_completion() {
+        let captured = Captured::default();
+        let state = app_state(registry(StatusCode::OK, captured).await);
+
+        let (status, content_type, body) = post_raw(
+            state,
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Hi" }],
+                "stream": true
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("text/event-stream"));
+        assert!(body.contains(r#""content":"Hello there""#));
+        assert!(!body.contains("extra_fields"));
+        assert!(body.trim_end().ends_with("data: [DONE]"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_use_openai_format() {
+        let captured = Captured::default();
+        let registry = registry(StatusCode::TOO_MANY_REQUESTS, captured).await;
+
+        let (status, body) = post_json(
+            app_state(registry.clone()),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({ "model": "gpt-4o-mini", "messages": [{ "role": "user", "content": "Hi" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+        assert_eq!(body["error"]["param"], "model");
+
+        let (status, body) = post_json(
+            app_state(registry.clone()),
+            "/v1/chat/completions",
+            "sk-0000",
+            json!({ "model": "openai/gpt-4o-mini", "messages": [{ "role": "user", "content": "Hi" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["type"], "authentication_error");
+
+        let (status, body) = post_json(
+            app_state(registry.clone()),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({ "model": "openai/gpt-4o-mini", "messages": [{ "role": "user", "content": "Hi" }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["type"], "rate_limit_error");
+        assert_eq!(body["error"]["code"], "rate_limit_error");
+        assert!(body["error"]["message"].as_str().unwrap().contains("slow down"));
+
+        let (status, body) = get_json(app_state(registry), "/v1/embeddings/unknown", "sk-1234").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_messages_are_rejected() {
+        let captured = Captured::default();
+        let registry = registry(StatusCode::OK, captured.clone()).await;
+
+        let (status, body) = post_json(
+            app_state(registry),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o-mini",
+                "messages": [{ "role": "user", "content": [{ "type": "image_url", "image_url": "not-an-object" }] }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+        assert_eq!(body["error"]["param"], "messages");
+        assert!(captured.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_fields_are_passed_through() {
+        let captured = Captured::default();
+        let registry = registry(StatusCode::OK, captured.clone()).await;
+        let messages = json!([
+            { "role": "user", "content": "Weather?", "name": "alice" },
+            {
+                "role": "assistant",
+                "content": null,
+                "refusal": null,
+                "tool_calls": [{ "id": "call_1", "type": "function", "function": { "name": "get_weather", "arguments": "{}" } }]
+            },
+            { "role": "tool", "tool_call_id": "call_1", "content": "18C" }
+        ]);
+
+        let (status, _) = post_json(
+            app_state(registry),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({ "model": "openai/gpt-4o-mini", "messages": messages }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let sent = captured.get(0)["messages"].clone();
+        assert_eq!(sent[0], messages[0]);
+        assert_eq!(sent[1]["refusal"], Value::Null);
+        assert_eq!(sent[1]["tool_calls"], messages[1]["tool_calls"]);
+        assert_eq!(sent[2], messages[2]);
+    }
+
+    #[tokio::test]
+    async fn test_fallbacks_use_provider_model_ids() {
+        let failing = mock_openai(Captured::default(), reply(StatusCode::SERVICE_UNAVAILABLE)).await;
+        let captured = Captured::default();
+        let answering = mock_openai(captured.clone(), reply(StatusCode::OK)).await;
+
+        let mut registry = ProviderRegistry::new();
+        registry.register(openai(failing));
+        registry.register(Arc::new(OpenAICompatibleProvider::new(
+            OpenAICompatibleConfig {
+                name: "local".to_string(),
+                base_url: answering,
+                ..Default::default()
+            },
+            http_client(),
+        )));
+
+        let (status, body) = post_json(
+            app_state(registry),
+            "/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "model": "openai/gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Hi" }],
+                "fallbacks": ["local/llama3.2"]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["choices"][0]["message"]["content"], "Hello there");
+        let upstream = captured.get(0);
+        assert_eq!(upstream["model"], "llama3.2");
+        assert!(upstream.get("fallbacks").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_models_lists_advertised_models() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAICompatibleProvider::new(
+            OpenAICompatibleConfig {
+                name: "local".to_string(),
+                base_url: "http://localhost:1".to_string(),
+                models: vec!["llama3.2".to_string(), "qwen2.5".to_string()],
+                ..Default::default()
+            },
+            http_client(),
+        )));
+
+        let (status, body) = get_json(app_state(registry), "/v1/models", "sk-1234").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["object"], "list");
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|model| model["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["local/llama3.2", "local/qwen2.5"]);
+        assert_eq!(body["data"][0]["owned_by"], "local");
+    }
+}
@@ -0,0 +1,260 @@
+mod common;
+
+#[cfg(test)]
+mod provider_pool_tests {
+    use super::common::{Captured, StubProvider, app_state, mock_openai, post_json, reply_in_turn, spawn_mock};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::json;
+    use sorai::config::{Config, PoolConfig, PoolMemberConfig, PoolStrategy};
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::{ModelProvider, PoolMember, Provider, ProviderError, ProviderPool, ProviderRegistry};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    /// OpenAI stub answering with its label
+    fn stub(label: &str) -> StubProvider {
+        StubProvider::new(ModelProvider::OpenAI).with_label(label)
+    }
+
+    fn pool(members: &[(&Arc<StubProvider>, u32)], strategy: PoolStrategy, cooldown: Duration) -> Arc<ProviderPool> {
+        let members = members
+            .iter()
+            .map(|(provider, weight)| PoolMember::new(provider.label.clone(), *weight, (*provider).clone()))
+            .collect();
+        Arc::new(ProviderPool::new(members, strategy, cooldown).unwrap())
+    }
+
+    fn request() -> ChatCompletionReq {
+        serde_json::from_value(json!({ "messages": [{ "role": "user", "content": "Hello" }] })).unwrap()
+    }
+
+    async fn answer(pool: &ProviderPool) -> Result<String, ProviderError> {
+        let response = pool.chat_completion("gpt-4o-mini", &request()).await?;
+        Ok(response.choices[0].message.content.to_string())
+    }
+
+    #[test]
+    fn test_pool_strategy_names() {
+        assert_eq!("round_robin".parse(), Ok(PoolStrategy::RoundRobin));
+        assert_eq!("weighted-round-robin".parse(), Ok(PoolStrategy::RoundRobin));
+        assert_eq!("LEAST_IN_FLIGHT".parse(), Ok(PoolStrategy::LeastInFlight));
+        assert!("random".parse::<PoolStrategy>().is_err());
+        assert!(ProviderPool::new(Vec::new(), PoolStrategy::RoundRobin, Duration::from_secs(30)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_follows_weights() {
+        let primary = Arc::new(stub("primary"));
+        let secondary = Arc::new(stub("secondary"));
+        let pool = pool(
+            &[(&primary, 3), (&secondary, 1)],
+            PoolStrategy::RoundRobin,
+            Duration::from_secs(30),
+        );
+
+        let mut answers = Vec::new();
+        for _ in 0..8 {
+            answers.push(answer(&pool).await.unwrap());
+        }
+
+        assert_eq!(primary.calls(), 6);
+        assert_eq!(secondary.calls(), 2);
+        // Smooth weighted round-robin interleaves the lighter member
+        assert_eq!(answers[..4], ["primary", "primary", "secondary", "primary"]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_member_is_ejected_until_cooldown() {
+        let limited = Arc::new(stub("limited").with_status(429));
+        let healthy = Arc::new(stub("healthy"));
+        let pool = pool(
+            &[(&limited, 1), (&healthy, 1)],
+            PoolStrategy::RoundRobin,
+            Duration::from_millis(200),
+        );
+
+        // The request is retried on the other member
+        assert_eq!(answer(&pool).await.unwrap(), "healthy");
+        assert_eq!(limited.calls(), 1);
+        assert!(pool.members()[0].is_ejected());
+
+        for _ in 0..3 {
+            assert_eq!(answer(&pool).await.unwrap(), "healthy");
+        }
+        assert_eq!(limited.calls(), 1);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(!pool.members()[0].is_ejected());
+        for _ in 0..2 {
+            answer(&pool).await.unwrap();
+        }
+        assert_eq!(limited.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_request_errors_are_not_retried() {
+        let rejecting = Arc::new(stub("rejecting").with_status(400));
+        let healthy = Arc::new(stub("healthy"));
+        let pool = pool(
+            &[(&rejecting, 1), (&healthy, 1)],
+            PoolStrategy::RoundRobin,
+            Duration::from_secs(30),
+        );
+
+        let error = answer(&pool).await.unwrap_err();
+        assert!(matches!(error, ProviderError::Upstream { status: 400, .. }));
+        assert_eq!(healthy.calls(), 0);
+        assert!(!pool.members()[0].is_ejected());
+    }
+
+    #[tokio::test]
+    async fn test_ejected_members_are_tried_when_none_remain() {
+        let unauthorized = Arc::new(stub("unauthorized").with_status(401));
+        let unavailable = Arc::new(stub("unavailable").with_status(503));
+        let pool = pool(
+            &[(&unauthorized, 1), (&unavailable, 1)],
+            PoolStrategy::RoundRobin,
+            Duration::from_secs(30),
+        );
+
+        let error = answer(&pool).await.unwrap_err();
+        assert!(error.is_retryable());
+        assert!(pool.members().iter().all(|member| member.is_ejected()));
+
+        // With every member out of rotation the pool still tries them all
+        assert!(answer(&pool).await.is_err());
+        assert_eq!(unauthorized.calls(), 2);
+        assert_eq!(unavailable.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_least_in_flight_prefers_idle_member() {
+        let hold = Arc::new(Notify::new());
+        let busy = Arc::new(stub("busy").with_hold(hold.clone()));
+        let idle = Arc::new(stub("idle"));
+        let pool = pool(
+            &[(&busy, 1), (&idle, 1)],
+            PoolStrategy::LeastInFlight,
+            Duration::from_secs(30),
+        );
+
+        let pending = tokio::spawn({
+            let pool = pool.clone();
+            async move { answer(&pool).await }
+        });
+        while pool.members()[0].in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        for _ in 0..3 {
+            assert_eq!(answer(&pool).await.unwrap(), "idle");
+        }
+        assert_eq!(busy.calls(), 1);
+
+        hold.notify_one();
+        assert_eq!(pending.await.unwrap().unwrap(), "busy");
+        assert_eq!(pool.members()[0].in_flight(), 0);
+    }
+
+    fn member(name: &str, base_url: String) -> PoolMemberConfig {
+        PoolMemberConfig {
+            name: name.to_string(),
+            weight: 1,
+            overrides: BTreeMap::from([
+                ("api_key".to_string(), format!("sk-{}", name)),
+                ("base_url".to_string(), base_url),
+            ]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_builds_pools_from_config() {
+        let east_calls = Captured::default();
+        let west_calls = Captured::default();
+        let mut config = Config::default();
+        config.pools.push(PoolConfig {
+            provider: "openai".to_string(),
+            strategy: PoolStrategy::RoundRobin,
+            cooldown: 30,
+            members: vec![
+                member(
+                    "east",
+                    mock_openai(east_calls.clone(), reply_in_turn(vec!["east"])).await,
+                ),
+                member(
+                    "west",
+                    mock_openai(west_calls.clone(), reply_in_turn(vec!["west"])).await,
+                ),
+            ],
+        });
+        let state = app_state(ProviderRegistry::from_config(&config));
+
+        let mut answers = Vec::new();
+        for _ in 0..2 {
+            let (status, body) = post_json(
+                state.clone(),
+                "/api/v1/chat/completions",
+                "sk-1234",
+                json!({
+                    "provider": "openai",
+                    "model": "gpt-4o-mini",
+                    "messages": [{ "role": "user", "content": "Hello" }]
+                }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            assert_eq!(body["data"]["extra_fields"]["provider"], "openai");
+            answers.push(body["data"]["choices"][0]["message"]["content"].clone());
+        }
+
+        assert_eq!(answers, vec![json!("east"), json!("west")]);
+        assert_eq!(east_calls.len(), 1);
+        assert_eq!(west_calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_member_timing_out_is_ejected() {
+        let hanging = Router::new().route(
+            "/v1/chat/completions",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Json(json!({}))
+            }),
+        );
+        let west_calls = Captured::default();
+        let mut config = Config::default();
+        config.sorai.provider_timeouts.insert("openai".to_string(), 1);
+        config.pools.push(PoolConfig {
+            provider: "openai".to_string(),
+            strategy: PoolStrategy::RoundRobin,
+            cooldown: 30,
+            members: vec![
+                member("east", format!("{}/v1", spawn_mock(hanging).await)),
+                member(
+                    "west",
+                    mock_openai(west_calls.clone(), reply_in_turn(vec!["west"])).await,
+                ),
+            ],
+        });
+        let state = app_state(ProviderRegistry::from_config(&config));
+        let request = json!({
+            "provider": "openai",
+            "model": "gpt-4o-mini",
+            "messages": [{ "role": "user", "content": "Hello" }]
+        });
+
+        // Only the first request waits for the hanging member, which is then out of rotation
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            let (status, body) = post_json(state.clone(), "/api/v1/chat/completions", "sk-1234", request.clone()).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            assert_eq!(body["data"]["choices"][0]["message"]["content"], "west");
+        }
+        assert!(start.elapsed() < Duration::from_secs(2), "{:?}", start.elapsed());
+        assert_eq!(west_calls.len(), 3);
+    }
+}
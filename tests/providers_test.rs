@@ -0,0 +1,179 @@
+mod common;
+
+#[cfg(test)]
+mod providers_tests {
+    use super::common::{app_state, post_json};
+    use async_trait::async_trait;
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use sorai::Config;
+    use sorai::http::schemas::completions::{
+        ChatCompletionChoice, ChatCompletionReq, ChatCompletionResponse, ChatMessage, ExtraFields,
+    };
+    use sorai::providers::{ModelProvider, Provider, ProviderCapabilities, ProviderError, ProviderRegistry};
+    use std::sync::Arc;
+
+    /// Provider that echoes the last message back
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        fn kind(&self) -> ModelProvider {
+            ModelProvider::OpenAI
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                chat_completion: true,
+                ..Default::default()
+            }
+        }
+
+        async fn chat_completion(
+            &self,
+            model: &str,
+            request: &ChatCompletionReq,
+        ) -> Result<ChatCompletionResponse, ProviderError> {
+            let content = request.messages.last().map(|m| m.text()).unwrap_or_default();
+
+            Ok(ChatCompletionResponse {
+                id: "chatcmpl-echo".to_string(),
+                object: "chat.completion".to_string(),
+                choices: vec![ChatCompletionChoice {
+                    index: 0,
+                    message: ChatMessage {
+                        role: "assistant".to_string(),
+                        content,
+                        ..Default::default()
+                    },
+                    finish_reason: "stop".to_string(),
+                }],
+                model: model.to_string(),
+                created: 0,
+                usage: None,
+                extra_fields: Some(ExtraFields {
+                    provider: self.name().to_string(),
+                    model_params: None,
+                    latency: 0.0,
+                    ..Default::default()
+                }),
+            })
+        }
+    }
+
+    #[test]
+    fn test_model_provider_parse() {
+        assert_eq!("openai".parse::<ModelProvider>().unwrap(), ModelProvider::OpenAI);
+        assert_eq!(
+            "Azure-OpenAI".parse::<ModelProvider>().unwrap(),
+            ModelProvider::AzureOpenAI
+        );
+        assert_eq!(ModelProvider::Bedrock.to_string(), "bedrock");
+        assert!(matches!(
+            "mystery".parse::<ModelProvider>(),
+            Err(ProviderError::UnknownProvider(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_from_default_config_is_empty() {
+        let registry = ProviderRegistry::from_config(&Config::default());
+        assert!(registry.is_empty());
+        assert!(matches!(
+            registry.get("anthropic"),
+            Err(ProviderError::NotConfigured(_))
+        ));
+        assert!(matches!(
+            registry.get("mystery"),
+            Err(ProviderError::UnknownProvider(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_resolves_registered_provider() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(EchoProvider));
+
+        assert_eq!(registry.names(), vec!["openai".to_string()]);
+        assert!(registry.get("openai").is_ok());
+        assert!(registry.get("OpenAI").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_dispatches_to_provider() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(EchoProvider));
+
+        let (status, body) = post_json(
+            app_state(registry),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "ping" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["choices"][0]["message"]["content"], "ping");
+        assert_eq!(body["data"]["model"], "gpt-4o-mini");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_unconfigured_provider() {
+        let (status, body) = post_json(
+            app_state(ProviderRegistry::new()),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "anthropic",
+                "model": "claude-3-5-haiku-latest",
+                "messages": [{ "role": "user", "content": "ping" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error"]["code"], "PROVIDER_ERROR");
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_unknown_provider() {
+        let (status, body) = post_json(
+            app_state(ProviderRegistry::new()),
+            "/api/v1/text/completions",
+            "sk-1234",
+            json!({
+                "provider": "mystery",
+                "model": "some-model",
+                "text": "Once upon a time"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+    }
+
+    #[tokio::test]
+    async fn test_text_completion_unsupported_by_provider() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(EchoProvider));
+
+        let (status, _) = post_json(
+            app_state(registry),
+            "/api/v1/text/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "text": "Once upon a time"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,256 @@
+mod common;
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::common::{Captured, MockReply, app_state, mock_openai, post_with_headers, reply_hi};
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+    use sorai::auth::{CLI_ACTOR, CreateApiKey, RateLimiter, estimate_tokens, text_len};
+    use sorai::config::AuthConfig;
+    use sorai::http::AppState;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::{ProviderRegistry, http_client};
+    use std::sync::Arc;
+
+    /// State with an OpenAI mock counting requests, where `gpt-4o-down` is unavailable
+    async fn limited_state(calls: Captured, rate_limits: Value) -> (AppState, String) {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url: mock_openai(calls, |index, body| match body["model"].as_str() {
+                    Some("gpt-4o-down") => MockReply::Error(StatusCode::SERVICE_UNAVAILABLE, "unavailable".to_string()),
+                    _ => reply_hi(index, body),
+                })
+                .await,
+            },
+            http_client(),
+        )));
+        let state = app_state(registry);
+        let params = CreateApiKey {
+            name: "limited".to_string(),
+            rate_limits: serde_json::from_value(rate_limits).unwrap(),
+            ..Default::default()
+        };
+        let created = state.api_keys.create(CLI_ACTOR, &params).await.unwrap();
+        (state, created.key)
+    }
+
+    fn chat(model: &str, max_tokens: u32) -> Value {
+        json!({
+            "provider": "openai",
+            "model": model,
+            "messages": [{ "role": "user", "content": "Hello" }],
+            "params": { "max_tokens": max_tokens }
+        })
+    }
+
+    #[test]
+    fn test_token_estimates_leave_media_out() {
+        let message = json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "What is in this picture?" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUg" } }
+            ]
+        });
+        assert_eq!(text_len(&serde_json::from_value(message).unwrap()), 24);
+        assert_eq!(estimate_tokens(10, Some(100)), 103);
+        assert_eq!(estimate_tokens(0, None), 0);
+    }
+
+    #[tokio::test]
+    async fn test_requests_over_the_limit_are_refused_with_headers() {
+        let calls = Captured::default();
+        let (state, key) = limited_state(calls.clone(), json!({ "rpm": 2 })).await;
+
+        for _ in 0..2 {
+            let (status, _, body) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 16)).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+        let (status, headers, body) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 16)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], "RATE_LIMIT_ERROR");
+        assert_eq!(calls.len(), 2);
+
+        // The bucket refills at 2 requests a minute, one every 30 seconds
+        let retry_after: u64 = headers["retry-after"].to_str().unwrap().parse().unwrap();
+        assert!((29..=30).contains(&retry_after), "{}", retry_after);
+        assert_eq!(headers["x-ratelimit-limit-requests"], "2");
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "0");
+        assert!(headers["x-ratelimit-reset-requests"].to_str().unwrap().ends_with('s'));
+        assert!(headers.get("x-ratelimit-limit-tokens").is_none());
+
+        // The compatible routes answer in their own error format
+        let request = json!({ "model": "openai/gpt-4o", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, headers, body) = post_with_headers(state.clone(), "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], "rate_limit_error");
+        assert!(headers.contains_key("retry-after"));
+
+        let request = json!({
+            "model": "openai/gpt-4o",
+            "max_tokens": 16,
+            "messages": [{ "role": "user", "content": "Hello" }]
+        });
+        let (status, _, body) = post_with_headers(state.clone(), "/v1/messages", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["type"], "rate_limit_error");
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_token_reservations_are_corrected_with_real_usage() {
+        let calls = Captured::default();
+        let (state, key) = limited_state(calls.clone(), json!({ "tpm": 100 })).await;
+
+        // Each request reserves 83 tokens but only uses 6, so the next one still fits
+        for _ in 0..3 {
+            let (status, _, body) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 80)).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+
+        // The 18 tokens used still count, leaving 82 for a request reserving 98
+        let (status, headers, body) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 95)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            body["error"]["reason"],
+            format!(
+                "Rate limit of 100 tokens per minute exceeded. Please try again in {}s.",
+                headers["retry-after"].to_str().unwrap()
+            )
+        );
+        assert_eq!(headers["x-ratelimit-limit-tokens"], "100");
+        assert_eq!(headers["x-ratelimit-remaining-tokens"], "82");
+        assert_eq!(calls.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_model_limits_apply_on_top_of_the_key_limits() {
+        let calls = Captured::default();
+        let limits = json!({ "rpm": 3, "models": [{ "model": "openai/gpt-4o", "rpm": 1 }] });
+        let (state, key) = limited_state(calls.clone(), limits).await;
+
+        let (status, _, _) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 16)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, headers, _) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 16)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(headers["x-ratelimit-limit-requests"], "1");
+
+        // Other models only count against the key limit
+        for _ in 0..2 {
+            let (status, _, _) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o-mini", 16)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, headers, _) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o-mini", 16)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(headers["x-ratelimit-limit-requests"], "3");
+        assert_eq!(calls.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_model_limits_are_charged_to_the_target_that_runs() {
+        let calls = Captured::default();
+        let limits = json!({ "models": [{ "model": "openai/gpt-4o-mini", "rpm": 1 }] });
+        let (state, key) = limited_state(calls.clone(), limits.clone()).await;
+
+        // The fallback answering for an unavailable primary is charged
+        let mut request = chat("gpt-4o-down", 16);
+        request["fallbacks"] = json!([{ "provider": "openai", "model": "gpt-4o-mini" }]);
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["model"], "gpt-4o-mini");
+
+        // Whatever the spelling of its provider
+        let mut request = chat("gpt-4o-mini", 16);
+        request["provider"] = json!("OpenAI");
+        let (status, headers, _) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(headers["x-ratelimit-limit-requests"], "1");
+        let request = json!({ "model": "OpenAI/gpt-4o-mini", "messages": [{ "role": "user", "content": "Hello" }] });
+        let (status, _, _) = post_with_headers(state.clone(), "/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(calls.len(), 2);
+
+        // A fallback that is never tried is not charged
+        let (state, key) = limited_state(calls.clone(), limits).await;
+        let mut request = chat("gpt-4o", 16);
+        request["fallbacks"] = json!([{ "provider": "openai", "model": "gpt-4o-mini" }]);
+        let (status, _, _) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _, _) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o-mini", 16)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(calls.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_limits_are_checked_when_the_fallback_is_tried() {
+        let calls = Captured::default();
+        let limits = json!({ "models": [{ "model": "openai/gpt-4o-mini", "rpm": 1 }] });
+        let (state, key) = limited_state(calls.clone(), limits).await;
+        let (status, _, _) =
+            post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o-mini", 16)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // A primary with capacity is served even though its fallback is over its limit
+        let mut request = chat("gpt-4o", 16);
+        request["fallbacks"] = json!([{ "provider": "openai", "model": "gpt-4o-mini" }]);
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["model"], "gpt-4o");
+
+        // A fallback over its limit is skipped without being sent
+        let mut request = chat("gpt-4o-down", 16);
+        request["fallbacks"] = json!([
+            { "provider": "openai", "model": "gpt-4o-mini" },
+            { "provider": "openai", "model": "gpt-4o" }
+        ]);
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["model"], "gpt-4o");
+        let attempts = &body["data"]["extra_fields"]["attempts"];
+        assert_eq!(attempts[1]["model"], "gpt-4o-mini");
+        assert_eq!(attempts[1]["success"], false);
+        assert_eq!(calls.len(), 4);
+
+        // The last target being limited refuses the request
+        let mut request = chat("gpt-4o-down", 16);
+        request["fallbacks"] = json!([{ "provider": "openai", "model": "gpt-4o-mini" }]);
+        let (status, _, body) = post_with_headers(state.clone(), "/api/v1/chat/completions", &key, request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], "RATE_LIMIT_ERROR");
+        assert_eq!(calls.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_default_limits_apply_to_keys_without_their_own() {
+        let calls = Captured::default();
+        let (mut state, key) = limited_state(calls, json!({ "rpm": 0 })).await;
+        state.rate_limiter = RateLimiter::new(&AuthConfig {
+            rate_limit_rpm: 1,
+            ..Default::default()
+        });
+
+        for expected in [StatusCode::OK, StatusCode::TOO_MANY_REQUESTS] {
+            let (status, _, _) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", "sk-1234", chat("gpt-4o", 16)).await;
+            assert_eq!(status, expected);
+        }
+
+        // A limit of 0 set on the key lifts the default
+        for _ in 0..3 {
+            let (status, _, _) =
+                post_with_headers(state.clone(), "/api/v1/chat/completions", &key, chat("gpt-4o", 16)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,502 @@
+mod common;
+
+#[cfg(test)]
+mod streaming_tests {
+    use super::common::{app_state, post_raw, spawn_mock};
+    use axum::extract::{RawQuery, State};
+    use axum::http::{HeaderMap, StatusCode, header};
+    use axum::response::IntoResponse;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use futures_util::StreamExt;
+    use serde_json::{Value, json};
+    use sorai::config::Config;
+    use sorai::http::schemas::completions::{ChatCompletionChunk, ChatCompletionReq};
+    use sorai::providers::anthropic::{AnthropicConfig, AnthropicProvider};
+    use sorai::providers::bedrock::{BedrockConfig, BedrockProvider, crc32};
+    use sorai::providers::vertex::{VertexConfig, VertexProvider};
+    use sorai::providers::{
+        FrameDecoder, Provider, ProviderError, ProviderRegistry, SseDecoder, SseEvent, http_client,
+    };
+    use std::sync::{Arc, Mutex};
+
+    type Captured = Arc<Mutex<Option<(HeaderMap, Value)>>>;
+
+    /// Serve a fixed streaming body on `path`, capturing the request
+    async fn mock_stream(path: &str, content_type: &'static str, body: Vec<u8>, captured: Captured) -> String {
+        let router = Router::new()
+            .route(
+                path,
+                post(
+                    move |State((captured, body)): State<(Captured, Vec<u8>)>,
+                          headers: HeaderMap,
+                          Json(request): Json<Value>| async move {
+                        *captured.lock().unwrap() = Some((headers, request));
+                        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+                    },
+                ),
+            )
+            .with_state((captured, body));
+        spawn_mock(router).await
+    }
+
+    fn sse(events: &[(&str, Value)]) -> Vec<u8> {
+        events
+            .iter()
+            .map(|(event, data)| format!("event: {}\ndata: {}\n\n", event, data))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// Parse the `data:` payloads of an SSE body
+    fn sse_data(body: &str) -> Vec<String> {
+        let mut decoder = SseDecoder::new();
+        decoder
+            .decode(body.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|event| event.data)
+            .collect()
+    }
+
+    fn chat_request() -> ChatCompletionReq {
+        serde_json::from_value(json!({
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Say hello" }
+            ],
+            "params": { "stream": true, "max_tokens": 16 }
+        }))
+        .unwrap()
+    }
+
+    async fn collect(provider: &dyn Provider, model: &str) -> Vec<Result<ChatCompletionChunk, ProviderError>> {
+        provider
+            .chat_completion_stream(model, &chat_request())
+            .await
+            .unwrap()
+            .collect()
+            .await
+    }
+
+    fn content(chunks: &[Result<ChatCompletionChunk, ProviderError>]) -> String {
+        chunks
+            .iter()
+            .filter_map(|chunk| chunk.as_ref().ok())
+            .flat_map(|chunk| chunk.choices.iter())
+            .filter_map(|choice| choice.delta.content.as_deref())
+            .collect()
+    }
+
+    fn anthropic_events() -> Vec<(&'static str, Value)> {
+        vec![
+            (
+                "message_start",
+                json!({
+                    "type": "message_start",
+                    "message": {
+                        "id": "msg_1",
+                        "model": "claude-3-5-haiku-20241022",
+                        "usage": { "input_tokens": 12, "output_tokens": 1 }
+                    }
+                }),
+            ),
+            (
+                "content_block_start",
+                json!({ "type": "content_block_start", "index": 0, "content_block": { "type": "text", "text": "" } }),
+            ),
+            ("ping", json!({ "type": "ping" })),
+            (
+                "content_block_delta",
+                json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "text_delta", "text": "Hello" } }),
+            ),
+            (
+                "content_block_delta",
+                json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "text_delta", "text": " there" } }),
+            ),
+            (
+                "content_block_stop",
+                json!({ "type": "content_block_stop", "index": 0 }),
+            ),
+            (
+                "message_delta",
+                json!({ "type": "message_delta", "delta": { "stop_reason": "max_tokens" }, "usage": { "output_tokens": 5 } }),
+            ),
+            ("message_stop", json!({ "type": "message_stop" })),
+        ]
+    }
+
+    #[test]
+    fn test_sse_decoder_handles_split_and_multiline_events() {
+        let mut decoder = SseDecoder::new();
+
+        let mut events = decoder
+            .decode(b": keep-alive\r\nevent: delta\r\ndata: {\"a\":")
+            .unwrap();
+        assert!(events.is_empty());
+        events.extend(
+            decoder
+                .decode(b"1}\r\n\r\ndata: line one\ndata: line two\n\ndata:[DONE]\n\n")
+                .unwrap(),
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("delta".to_string()),
+                    data: "{\"a\":1}".to_string(),
+                },
+                SseEvent {
+                    event: None,
+                    data: "line one\nline two".to_string(),
+                },
+                SseEvent {
+                    event: None,
+                    data: "[DONE]".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openai_stream_through_gateway() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let body = [
+            json!({ "id": "c1", "model": "gpt-4o-mini-2024-07-18", "choices": [{ "index": 0, "delta": { "role": "assistant", "content": "" }, "finish_reason": null }] }),
+            json!({ "id": "c1", "model": "gpt-4o-mini-2024-07-18", "choices": [{ "index": 0, "delta": { "content": "Hi" }, "finish_reason": null }] }),
+            json!({ "id": "c1", "model": "gpt-4o-mini-2024-07-18", "choices": [{ "index": 0, "delta": {}, "finish_reason": "stop" }] }),
+            json!({ "id": "c1", "model": "gpt-4o-mini-2024-07-18", "choices": [], "usage": { "prompt_tokens": 9, "completion_tokens": 1, "total_tokens": 10 } }),
+        ]
+        .iter()
+        .map(|chunk| format!("data: {}\n\n", chunk))
+        .chain(std::iter::once("data: [DONE]\n\n".to_string()))
+        .collect::<String>();
+
+        let base_url = mock_stream(
+            "/v1/chat/completions",
+            "text/event-stream",
+            body.into_bytes(),
+            captured.clone(),
+        )
+        .await;
+        let mut config = Config::default();
+        config.openai.api_key = "sk-test".to_string();
+        config.openai.base_url = format!("{}/v1", base_url);
+
+        let (status, content_type, body) = post_raw(
+            app_state(ProviderRegistry::from_config(&config)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Say hi" }],
+                "params": { "stream": true }
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("text/event-stream"));
+
+        let (_, request) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(request["stream"], true);
+        assert_eq!(request["stream_options"]["include_usage"], true);
+
+        let data = sse_data(&body);
+        assert_eq!(data.last().unwrap(), "[DONE]");
+        let chunks: Vec<Value> = data[..data.len() - 1]
+            .iter()
+            .map(|d| serde_json::from_str(d).unwrap())
+            .collect();
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|c| c["object"] == "chat.completion.chunk"));
+        assert!(chunks.iter().all(|c| c["id"] == chunks[0]["id"]));
+        assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+        assert_eq!(chunks[0]["extra_fields"]["provider"], "openai");
+        assert!(chunks[1].get("extra_fields").is_none());
+        assert_eq!(chunks[1]["choices"][0]["delta"]["content"], "Hi");
+        assert_eq!(chunks[2]["choices"][0]["finish_reason"], "stop");
+        assert_eq!(chunks[3]["choices"], json!([]));
+        assert_eq!(chunks[3]["usage"]["total_tokens"], 10);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream_is_normalised() {
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let base_url = mock_stream(
+            "/v1/messages",
+            "text/event-stream",
+            sse(&anthropic_events()),
+            captured.clone(),
+        )
+        .await;
+        let provider = AnthropicProvider::new(
+            AnthropicConfig {
+                api_key: "sk-ant-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        );
+
+        let chunks = collect(&provider, "claude-3-5-haiku-latest").await;
+
+        let (_, request) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(request["stream"], true);
+        assert_eq!(request["system"], "Be brief.");
+
+        assert_eq!(content(&chunks), "Hello there");
+        let chunks: Vec<_> = chunks.into_iter().map(Result::unwrap).collect();
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[0].choices[0].delta.role.as_deref(), Some("assistant"));
+        assert_eq!(chunks[0].model, "claude-3-5-haiku-20241022");
+        assert_eq!(chunks[3].choices[0].finish_reason.as_deref(), Some("length"));
+
+        let usage = chunks[4].usage.as_ref().unwrap();
+        assert!(chunks[4].choices.is_empty());
+        assert_eq!(usage.prompt_tokens, 12);
+        assert_eq!(usage.completion_tokens, 5);
+        assert_eq!(usage.total_tokens, 17);
+    }
+
+    #[tokio::test]
+    async fn test_stream_error_is_sent_as_final_event() {
+        let mut events = anthropic_events();
+        events.truncate(4);
+        events.push((
+            "error",
+            json!({ "type": "error", "error": { "type": "overloaded_error", "message": "Overloaded" } }),
+        ));
+
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let mut config = Config::default();
+        config.anthropic.api_key = "sk-ant-test".to_string();
+        config.anthropic.base_url = mock_stream("/v1/messages", "text/event-stream", sse(&events), captured).await;
+
+        let (status, _, body) = post_raw(
+            app_state(ProviderRegistry::from_config(&config)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "anthropic",
+                "model": "claude-3-5-haiku-latest",
+                "messages": [{ "role": "user", "content": "Say hello" }],
+                "params": { "stream": true }
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let data = sse_data(&body);
+        assert_eq!(data.len(), 4);
+        assert_eq!(data[3], "[DONE]");
+
+        let error: Value = serde_json::from_str(&data[2]).unwrap();
+        assert_eq!(error["error"]["type"], "external");
+        assert!(error["error"]["reason"].as_str().unwrap().contains("Overloaded"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_falls_back_before_first_chunk() {
+        let router = Router::new().route(
+            "/v1/chat/completions",
+            post(|| async {
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(json!({ "error": { "message": "down" } })),
+                )
+            }),
+        );
+        let captured: Captured = Arc::new(Mutex::new(None));
+
+        let mut config = Config::default();
+        config.openai.api_key = "sk-test".to_string();
+        config.openai.base_url = format!("{}/v1", spawn_mock(router).await);
+        config.anthropic.api_key = "sk-ant-test".to_string();
+        config.anthropic.base_url =
+            mock_stream("/v1/messages", "text/event-stream", sse(&anthropic_events()), captured).await;
+
+        let (status, _, body) = post_raw(
+            app_state(ProviderRegistry::from_config(&config)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Say hello" }],
+                "params": { "stream": true },
+                "fallbacks": [{ "provider": "anthropic", "model": "claude-3-5-haiku-latest" }]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let first: Value = serde_json::from_str(&sse_data(&body)[0]).unwrap();
+        assert_eq!(first["extra_fields"]["provider"], "anthropic");
+        let attempts = first["extra_fields"]["attempts"].as_array().unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0]["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_event_stream_is_normalised() {
+        fn frame(event_type: &str, payload: Value) -> Vec<u8> {
+            let mut headers = Vec::new();
+            for (name, value) in [
+                (":message-type", "event"),
+                (":event-type", event_type),
+                (":content-type", "application/json"),
+            ] {
+                headers.push(name.len() as u8);
+                headers.extend_from_slice(name.as_bytes());
+                headers.push(7);
+                headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+                headers.extend_from_slice(value.as_bytes());
+            }
+            let payload = payload.to_string().into_bytes();
+
+            let mut message = Vec::new();
+            message.extend_from_slice(&((16 + headers.len() + payload.len()) as u32).to_be_bytes());
+            message.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+            message.extend_from_slice(&crc32(&message).to_be_bytes());
+            message.extend_from_slice(&headers);
+            message.extend_from_slice(&payload);
+            message.extend_from_slice(&crc32(&message).to_be_bytes());
+            message
+        }
+
+        let body = [
+            frame("messageStart", json!({ "role": "assistant" })),
+            frame(
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 0, "delta": { "text": "Hello" } }),
+            ),
+            frame(
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 0, "delta": { "text": " world" } }),
+            ),
+            frame("contentBlockStop", json!({ "contentBlockIndex": 0 })),
+            frame("messageStop", json!({ "stopReason": "end_turn" })),
+            frame(
+                "metadata",
+                json!({ "usage": { "inputTokens": 7, "outputTokens": 2, "totalTokens": 9 }, "metrics": { "latencyMs": 80 } }),
+            ),
+        ]
+        .concat();
+
+        let captured: Captured = Arc::new(Mutex::new(None));
+        let base_url = mock_stream(
+            "/model/{model}/converse-stream",
+            "application/vnd.amazon.eventstream",
+            body,
+            captured.clone(),
+        )
+        .await;
+        let provider = BedrockProvider::new(
+            BedrockConfig {
+                api_key: "bedrock-key".to_string(),
+                base_url,
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let chunks = collect(&provider, "anthropic.claude-3-haiku-20240307-v1:0").await;
+
+        let (headers, request) = captured.lock().unwrap().take().unwrap();
+        assert_eq!(headers["accept"], "application/vnd.amazon.eventstream");
+        assert_eq!(request["system"][0]["text"], "Be brief.");
+
+        assert_eq!(content(&chunks), "Hello world");
+        let chunks: Vec<_> = chunks.into_iter().map(Result::unwrap).collect();
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[3].choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(chunks[4].usage.as_ref().unwrap().total_tokens, 9);
+    }
+
+    #[tokio::test]
+    async fn test_vertex_stream_is_normalised() {
+        type Query = Arc<Mutex<Option<String>>>;
+        let query: Query = Arc::new(Mutex::new(None));
+        let events = [
+            json!({
+                "candidates": [{ "content": { "role": "model", "parts": [{ "text": "Hel" }] } }],
+                "usageMetadata": { "promptTokenCount": 4 },
+                "modelVersion": "gemini-1.5-flash-002"
+            }),
+            json!({
+                "candidates": [{ "content": { "role": "model", "parts": [{ "text": "lo" }] }, "finishReason": "STOP" }],
+                "usageMetadata": { "promptTokenCount": 4, "candidatesTokenCount": 2, "totalTokenCount": 6 },
+                "modelVersion": "gemini-1.5-flash-002"
+            }),
+        ]
+        .iter()
+        .map(|event| format!("data: {}\r\n\r\n", event))
+        .collect::<String>();
+
+        let router = Router::new()
+            .route(
+                "/token",
+                post(|| async { Json(json!({ "access_token": "ya29.test-token", "expires_in": 3599 })) }),
+            )
+            .route(
+                "/projects/{project}/locations/{location}/publishers/google/models/{model}",
+                post(
+                    |State((query, events)): State<(Query, String)>, RawQuery(raw): RawQuery| async move {
+                        *query.lock().unwrap() = raw;
+                        ([(header::CONTENT_TYPE, "text/event-stream")], events).into_response()
+                    },
+                ),
+            )
+            .with_state((query.clone(), events));
+        let base_url = spawn_mock(router).await;
+        let provider = VertexProvider::new(
+            VertexConfig {
+                credentials: concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/vertex_service_account.json"
+                )
+                .to_string(),
+                base_url: base_url.clone(),
+                token_url: format!("{}/token", base_url),
+                ..Default::default()
+            },
+            http_client(),
+        );
+
+        let chunks = collect(&provider, "gemini-1.5-flash").await;
+
+        assert_eq!(query.lock().unwrap().as_deref(), Some("alt=sse"));
+        assert_eq!(content(&chunks), "Hello");
+        let chunks: Vec<_> = chunks.into_iter().map(Result::unwrap).collect();
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[0].choices[0].delta.role.as_deref(), Some("assistant"));
+        assert_eq!(chunks[3].choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(chunks[4].model, "gemini-1.5-flash-002");
+        assert_eq!(chunks[4].usage.as_ref().unwrap().completion_tokens, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stream_unsupported_by_provider() {
+        let mut config = Config::default();
+        config.cohere.api_key = "co-test".to_string();
+
+        let (status, content_type, body) = post_raw(
+            app_state(ProviderRegistry::from_config(&config)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "cohere",
+                "model": "command-r",
+                "messages": [{ "role": "user", "content": "Say hello" }],
+                "params": { "stream": true }
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(content_type.starts_with("application/json"));
+        assert!(body.contains("streaming"));
+    }
+}
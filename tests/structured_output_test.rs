@@ -0,0 +1,306 @@
+mod common;
+
+#[cfg(test)]
+mod structured_output_tests {
+    use super::common::{Captured, app_state, mock_openai, post_json, reply_in_turn, spawn_mock};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::{ChatCompletionChunk, ChatCompletionReq};
+    use sorai::providers::anthropic::{AnthropicConfig, AnthropicProvider, AnthropicStreamTranslator};
+    use sorai::providers::cohere::CohereProvider;
+    use sorai::providers::openai::{OpenAIConfig, OpenAIProvider};
+    use sorai::providers::vertex::VertexProvider;
+    use sorai::providers::{
+        ChunkTranslator, ModelParams, Provider, ProviderRegistry, ResponseFormat, SseEvent, http_client,
+        validate_schema,
+    };
+    use std::sync::Arc;
+
+    fn city_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string" },
+                "population": { "type": ["integer", "null"], "minimum": 0 }
+            },
+            "required": ["city", "population"],
+            "additionalProperties": false
+        })
+    }
+
+    fn params() -> Value {
+        json!({
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": { "name": "city", "schema": city_schema(), "strict": true }
+            }
+        })
+    }
+
+    fn request() -> ChatCompletionReq {
+        serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Largest city in Japan?" }],
+            "params": params()
+        }))
+        .unwrap()
+    }
+
+    fn registry(base_url: String, retries: u32) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.set_structured_output_retries(retries);
+        registry.register(Arc::new(OpenAIProvider::new(
+            OpenAIConfig {
+                api_key: "sk-test".to_string(),
+                base_url,
+            },
+            http_client(),
+        )));
+        registry
+    }
+
+    #[test]
+    fn test_response_format_is_parsed() {
+        let format =
+            |value: Value| ModelParams::from_value(Some(&json!({ "response_format": value }))).response_format();
+
+        let nested = format(params()["response_format"].clone()).unwrap();
+        let flat = format(json!({ "type": "json_schema", "name": "city", "schema": city_schema(), "strict": true }));
+        assert_eq!(Some(nested.clone()), flat);
+        assert_eq!(nested.name(), "city");
+        assert_eq!(nested.schema(), Some(city_schema()));
+        assert_eq!(nested.to_openai(), params()["response_format"]);
+
+        let json_mode = format(json!({ "type": "json_object" })).unwrap();
+        assert_eq!(json_mode, ResponseFormat::JsonObject);
+        assert_eq!(json_mode.name(), ResponseFormat::DEFAULT_NAME);
+        assert_eq!(json_mode.schema(), Some(json!({ "type": "object" })));
+
+        assert_eq!(format(json!({ "type": "text" })), Some(ResponseFormat::Text));
+        assert_eq!(ResponseFormat::Text.schema(), None);
+        assert_eq!(format(json!({ "type": "json_schema" })), None);
+        assert_eq!(format(json!({ "type": "xml" })), None);
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let schema = city_schema();
+        assert!(validate_schema(&schema, &json!({ "city": "Tokyo", "population": 14000000 })).is_ok());
+        assert!(validate_schema(&schema, &json!({ "city": "Tokyo", "population": null })).is_ok());
+
+        let error = |instance: Value| validate_schema(&schema, &instance).unwrap_err();
+        assert_eq!(error(json!([])), "expected object, got array");
+        assert_eq!(
+            error(json!({ "city": "Tokyo" })),
+            "missing required property 'population'"
+        );
+        assert_eq!(
+            error(json!({ "city": 1, "population": 2 })),
+            "/city: expected string, got number"
+        );
+        assert_eq!(
+            error(json!({ "city": "Tokyo", "population": -1 })),
+            "/population: -1 is less than 0"
+        );
+        assert_eq!(
+            error(json!({ "city": "Tokyo", "population": 1, "country": "Japan" })),
+            "unexpected property 'country'"
+        );
+
+        let schema = json!({
+            "type": "array",
+            "items": { "$ref": "#/$defs/color" },
+            "minItems": 1,
+            "$defs": { "color": { "enum": ["red", "green"] } }
+        });
+        assert!(validate_schema(&schema, &json!(["red", "green"])).is_ok());
+        assert_eq!(
+            validate_schema(&schema, &json!(["blue"])).unwrap_err(),
+            "/0: \"blue\" is not one of [\"red\",\"green\"]"
+        );
+        assert_eq!(
+            validate_schema(&schema, &json!([])).unwrap_err(),
+            "expected at least 1 items, got 0"
+        );
+    }
+
+    #[test]
+    fn test_openai_request_uses_nested_response_format() {
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [],
+            "params": { "response_format": { "type": "json_schema", "name": "city", "schema": city_schema() } }
+        }))
+        .unwrap();
+
+        let body = serde_json::to_value(OpenAIProvider::build_chat_request("gpt-4o-mini", &request)).unwrap();
+        assert_eq!(
+            body["response_format"],
+            json!({ "type": "json_schema", "json_schema": { "name": "city", "schema": city_schema() } })
+        );
+    }
+
+    #[test]
+    fn test_native_mappings() {
+        let anthropic = serde_json::to_value(AnthropicProvider::build_request(
+            "claude-3-5-haiku-latest",
+            &[],
+            Some(&params()),
+        ))
+        .unwrap();
+        assert_eq!(anthropic["tools"][0]["name"], "city");
+        assert_eq!(anthropic["tools"][0]["input_schema"], city_schema());
+        assert_eq!(anthropic["tool_choice"], json!({ "type": "tool", "name": "city" }));
+
+        let gemini = serde_json::to_value(VertexProvider::build_request(&[], Some(&params()))).unwrap();
+        assert_eq!(gemini["generationConfig"]["responseMimeType"], "application/json");
+        assert_eq!(
+            gemini["generationConfig"]["responseSchema"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "population": { "type": "integer", "nullable": true, "minimum": 0 }
+                },
+                "required": ["city", "population"]
+            })
+        );
+
+        let cohere =
+            serde_json::to_value(CohereProvider::build_request("command-a-03-2025", &[], Some(&params()))).unwrap();
+        assert_eq!(
+            cohere["response_format"],
+            json!({ "type": "json_object", "json_schema": city_schema() })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_response_tool_becomes_content() {
+        let router = Router::new().route(
+            "/v1/messages",
+            post(|| async {
+                Json(json!({
+                    "id": "msg_1",
+                    "model": "claude-3-5-haiku-latest",
+                    "role": "assistant",
+                    "content": [
+                        { "type": "tool_use", "id": "toolu_1", "name": "city", "input": { "city": "Tokyo", "population": 14000000 } }
+                    ],
+                    "stop_reason": "tool_use",
+                    "usage": { "input_tokens": 20, "output_tokens": 10 }
+                }))
+            }),
+        );
+        let provider = AnthropicProvider::new(
+            AnthropicConfig {
+                api_key: "sk-ant-test".to_string(),
+                base_url: spawn_mock(router).await,
+            },
+            http_client(),
+        );
+
+        let response = provider
+            .chat_completion("claude-3-5-haiku-latest", &request())
+            .await
+            .unwrap();
+
+        let choice = &response.choices[0];
+        assert_eq!(choice.finish_reason, "stop");
+        assert!(choice.message.tool_calls.is_none());
+        assert_eq!(
+            serde_json::from_str::<Value>(&choice.message.content).unwrap(),
+            json!({ "city": "Tokyo", "population": 14000000 })
+        );
+    }
+
+    #[test]
+    fn test_anthropic_stream_response_tool_becomes_content() {
+        let mut translator = AnthropicStreamTranslator::new("anthropic", "claude-3-5-haiku-latest")
+            .with_response_tool(Some("city".to_string()));
+        let events = [
+            json!({ "type": "message_start", "message": { "model": "claude-3-5-haiku-latest", "usage": { "input_tokens": 5 } } }),
+            json!({ "type": "content_block_start", "index": 0, "content_block": { "type": "tool_use", "id": "toolu_1", "name": "city", "input": {} } }),
+            json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "input_json_delta", "partial_json": "{\"city\":" } }),
+            json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "input_json_delta", "partial_json": "\"Tokyo\"}" } }),
+            json!({ "type": "message_delta", "delta": { "stop_reason": "tool_use" }, "usage": { "output_tokens": 9 } }),
+            json!({ "type": "message_stop" }),
+        ];
+
+        let chunks: Vec<ChatCompletionChunk> = events
+            .iter()
+            .flat_map(|event| {
+                translator
+                    .translate(SseEvent {
+                        event: None,
+                        data: event.to_string(),
+                    })
+                    .unwrap()
+            })
+            .collect();
+        let choices = || chunks.iter().flat_map(|chunk| chunk.choices.iter());
+
+        let content: String = choices().filter_map(|choice| choice.delta.content.clone()).collect();
+        assert_eq!(content, "{\"city\":\"Tokyo\"}");
+        assert!(choices().all(|choice| choice.delta.tool_calls.is_none()));
+        assert_eq!(
+            choices().find_map(|choice| choice.finish_reason.clone()).as_deref(),
+            Some("stop")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_output_is_retried() {
+        let calls = Captured::default();
+        let base_url = mock_openai(
+            calls.clone(),
+            reply_in_turn(vec!["Tokyo", "{\"city\": \"Tokyo\", \"population\": 14000000}"]),
+        )
+        .await;
+
+        let (status, body) = post_json(
+            app_state(registry(base_url, 1)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Largest city in Japan?" }],
+                "params": params()
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            body["data"]["choices"][0]["message"]["content"],
+            "{\"city\": \"Tokyo\", \"population\": 14000000}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_mismatch_after_retries() {
+        let calls = Captured::default();
+        let base_url = mock_openai(calls.clone(), reply_in_turn(vec!["{\"city\": \"Tokyo\"}"])).await;
+
+        let (status, body) = post_json(
+            app_state(registry(base_url, 2)),
+            "/api/v1/chat/completions",
+            "sk-1234",
+            json!({
+                "provider": "openai",
+                "model": "gpt-4o-mini",
+                "messages": [{ "role": "user", "content": "Largest city in Japan?" }],
+                "params": params()
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(
+            body["error"]["reason"],
+            "Response from provider 'openai' does not match the requested schema: missing required property 'population'"
+        );
+    }
+}
@@ -0,0 +1,332 @@
+mod common;
+
+#[cfg(test)]
+mod tool_calling_tests {
+    use super::common::{spawn_mock, typed_messages};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::{ChatCompletionChunk, ChatCompletionReq, RequestMessage, ToolCallDelta};
+    use sorai::providers::anthropic::{AnthropicConfig, AnthropicProvider, AnthropicStreamTranslator};
+    use sorai::providers::bedrock::{BedrockProvider, BedrockStreamTranslator, EventStreamMessage};
+    use sorai::providers::vertex::{VertexProvider, VertexStreamTranslator};
+    use sorai::providers::{ChunkTranslator, ModelParams, Provider, SseEvent, ToolChoice, http_client};
+
+    /// A conversation where the assistant called a tool and the result was sent back
+    fn messages() -> Vec<RequestMessage> {
+        typed_messages(vec![
+            json!({ "role": "system", "content": "Be brief." }),
+            json!({ "role": "user", "content": "Weather in Paris and Rome?" }),
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [
+                    { "id": "call_1", "type": "function", "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" } },
+                    { "id": "call_2", "type": "function", "function": { "name": "get_time", "arguments": "{\"city\":\"Rome\"}" } }
+                ]
+            }),
+            json!({ "role": "tool", "tool_call_id": "call_1", "content": "18C" }),
+            json!({ "role": "tool", "tool_call_id": "call_2", "content": "{\"time\":\"10:00\"}" }),
+        ])
+    }
+
+    fn params(tool_choice: Value) -> Value {
+        json!({
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "get_weather",
+                    "description": "Current weather",
+                    "parameters": { "type": "object", "properties": { "city": { "type": "string" } } }
+                }
+            }],
+            "tool_choice": tool_choice
+        })
+    }
+
+    /// Collect the tool call deltas of the given chunks
+    fn tool_deltas(chunks: &[ChatCompletionChunk]) -> Vec<ToolCallDelta> {
+        chunks
+            .iter()
+            .flat_map(|chunk| chunk.choices.iter())
+            .flat_map(|choice| choice.delta.tool_calls.clone().unwrap_or_default())
+            .collect()
+    }
+
+    fn finish_reason(chunks: &[ChatCompletionChunk]) -> Option<String> {
+        chunks
+            .iter()
+            .flat_map(|chunk| chunk.choices.iter())
+            .find_map(|choice| choice.finish_reason.clone())
+    }
+
+    #[test]
+    fn test_tool_choice_is_parsed() {
+        let choice = |value: Value| ModelParams::from_value(Some(&json!({ "tool_choice": value }))).tool_choice();
+
+        assert_eq!(choice(json!("auto")), Some(ToolChoice::Auto));
+        assert_eq!(choice(json!("none")), Some(ToolChoice::None));
+        assert_eq!(choice(json!("required")), Some(ToolChoice::Required));
+        assert_eq!(
+            choice(json!({ "type": "function", "function": { "name": "get_weather" } })),
+            Some(ToolChoice::Function("get_weather".to_string()))
+        );
+        assert_eq!(choice(json!(42)), None);
+
+        let tools = ModelParams::from_value(Some(&params(json!("auto")))).tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].function.name, "get_weather");
+    }
+
+    #[test]
+    fn test_anthropic_request_uses_tool_blocks() {
+        let request =
+            AnthropicProvider::build_request("claude-3-5-haiku-latest", &messages(), Some(&params(json!("required"))));
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["tools"][0]["name"], "get_weather");
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+        assert_eq!(body["tool_choice"], json!({ "type": "any" }));
+
+        let turns = body["messages"].as_array().unwrap();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(
+            turns[1]["content"][0],
+            json!({ "type": "tool_use", "id": "call_1", "name": "get_weather", "input": { "city": "Paris" } })
+        );
+        assert_eq!(turns[1]["content"][1]["name"], "get_time");
+        assert_eq!(turns[2]["role"], "user");
+        assert_eq!(
+            turns[2]["content"],
+            json!([
+                { "type": "tool_result", "tool_use_id": "call_1", "content": "18C" },
+                { "type": "tool_result", "tool_use_id": "call_2", "content": "{\"time\":\"10:00\"}" }
+            ])
+        );
+
+        let request = AnthropicProvider::build_request(
+            "claude-3-5-haiku-latest",
+            &[],
+            Some(&params(
+                json!({ "type": "function", "function": { "name": "get_weather" } }),
+            )),
+        );
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["tool_choice"],
+            json!({ "type": "tool", "name": "get_weather" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_tool_use_response() {
+        let router = Router::new().route(
+            "/v1/messages",
+            post(|| async {
+                Json(json!({
+                    "id": "msg_1",
+                    "model": "claude-3-5-haiku-latest",
+                    "role": "assistant",
+                    "content": [
+                        { "type": "text", "text": "Checking." },
+                        { "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": { "city": "Paris" } }
+                    ],
+                    "stop_reason": "tool_use",
+                    "usage": { "input_tokens": 20, "output_tokens": 10 }
+                }))
+            }),
+        );
+        let provider = AnthropicProvider::new(
+            AnthropicConfig {
+                api_key: "sk-ant-test".to_string(),
+                base_url: spawn_mock(router).await,
+            },
+            http_client(),
+        );
+        let request: ChatCompletionReq = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "Weather in Paris?" }],
+            "params": params(json!("auto"))
+        }))
+        .unwrap();
+
+        let response = provider
+            .chat_completion("claude-3-5-haiku-latest", &request)
+            .await
+            .unwrap();
+
+        let choice = &response.choices[0];
+        assert_eq!(choice.finish_reason, "tool_calls");
+        assert_eq!(choice.message.content, "Checking.");
+        let calls = choice.message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].id, "toolu_1");
+        assert_eq!(calls[0].kind, "function");
+        assert_eq!(calls[0].function.name, "get_weather");
+        assert_eq!(calls[0].function.parsed_arguments(), json!({ "city": "Paris" }));
+    }
+
+    #[test]
+    fn test_anthropic_stream_tool_use() {
+        let mut translator = AnthropicStreamTranslator::new("anthropic", "claude-3-5-haiku-latest");
+        let events = [
+            json!({ "type": "message_start", "message": { "model": "claude-3-5-haiku-latest", "usage": { "input_tokens": 5 } } }),
+            json!({ "type": "content_block_start", "index": 0, "content_block": { "type": "text", "text": "" } }),
+            json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "text_delta", "text": "Checking." } }),
+            json!({ "type": "content_block_start", "index": 1, "content_block": { "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {} } }),
+            json!({ "type": "content_block_delta", "index": 1, "delta": { "type": "input_json_delta", "partial_json": "" } }),
+            json!({ "type": "content_block_delta", "index": 1, "delta": { "type": "input_json_delta", "partial_json": "{\"city\":" } }),
+            json!({ "type": "content_block_delta", "index": 1, "delta": { "type": "input_json_delta", "partial_json": "\"Paris\"}" } }),
+            json!({ "type": "message_delta", "delta": { "stop_reason": "tool_use" }, "usage": { "output_tokens": 9 } }),
+            json!({ "type": "message_stop" }),
+        ];
+
+        let chunks: Vec<ChatCompletionChunk> = events
+            .iter()
+            .flat_map(|event| {
+                translator
+                    .translate(SseEvent {
+                        event: None,
+                        data: event.to_string(),
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            tool_deltas(&chunks),
+            vec![
+                ToolCallDelta::start(0, "toolu_1", "get_weather"),
+                ToolCallDelta::arguments(0, "{\"city\":"),
+                ToolCallDelta::arguments(0, "\"Paris\"}"),
+            ]
+        );
+        assert_eq!(finish_reason(&chunks).as_deref(), Some("tool_calls"));
+    }
+
+    #[test]
+    fn test_gemini_request_uses_function_parts() {
+        let request = VertexProvider::build_request(
+            &messages(),
+            Some(&params(
+                json!({ "type": "function", "function": { "name": "get_weather" } }),
+            )),
+        );
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["tools"][0]["functionDeclarations"][0]["name"], "get_weather");
+        assert_eq!(
+            body["toolConfig"],
+            json!({ "functionCallingConfig": { "mode": "ANY", "allowedFunctionNames": ["get_weather"] } })
+        );
+
+        let contents = body["contents"].as_array().unwrap();
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(
+            contents[1]["parts"][0],
+            json!({ "functionCall": { "name": "get_weather", "args": { "city": "Paris" } } })
+        );
+        assert_eq!(contents[2]["role"], "user");
+        assert_eq!(
+            contents[2]["parts"],
+            json!([
+                { "functionResponse": { "name": "get_weather", "response": { "content": "18C" } } },
+                { "functionResponse": { "name": "get_time", "response": { "time": "10:00" } } }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_gemini_stream_function_call() {
+        let mut translator = VertexStreamTranslator::new("vertex", "gemini-2.0-flash");
+        let event = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{ "functionCall": { "name": "get_weather", "args": { "city": "Paris" } } }]
+                },
+                "finishReason": "STOP"
+            }]
+        });
+
+        let chunks = translator
+            .translate(SseEvent {
+                event: None,
+                data: event.to_string(),
+            })
+            .unwrap();
+
+        let deltas = tool_deltas(&chunks);
+        assert_eq!(deltas.len(), 2);
+        assert!(deltas[0].id.as_deref().unwrap().starts_with("call_"));
+        assert_eq!(
+            deltas[0].function.as_ref().unwrap().name.as_deref(),
+            Some("get_weather")
+        );
+        assert_eq!(deltas[1], ToolCallDelta::arguments(0, "{\"city\":\"Paris\"}"));
+        assert_eq!(finish_reason(&chunks).as_deref(), Some("tool_calls"));
+    }
+
+    #[test]
+    fn test_bedrock_request_uses_tool_blocks() {
+        let request = BedrockProvider::build_request(&messages(), Some(&params(json!("required"))));
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            body["toolConfig"]["tools"][0]["toolSpec"]["inputSchema"]["json"]["type"],
+            "object"
+        );
+        assert_eq!(body["toolConfig"]["toolChoice"], json!({ "any": {} }));
+
+        let turns = body["messages"].as_array().unwrap();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(
+            turns[1]["content"][0],
+            json!({ "toolUse": { "toolUseId": "call_1", "name": "get_weather", "input": { "city": "Paris" } } })
+        );
+        assert_eq!(
+            turns[2]["content"][0],
+            json!({ "toolResult": { "toolUseId": "call_1", "content": [{ "text": "18C" }] } })
+        );
+        assert_eq!(turns[2]["content"][1]["toolResult"]["toolUseId"], "call_2");
+    }
+
+    #[test]
+    fn test_bedrock_stream_tool_use() {
+        let mut translator = BedrockStreamTranslator::new("bedrock", "anthropic.claude-3-haiku-20240307-v1:0");
+        let events = [
+            ("messageStart", json!({ "role": "assistant" })),
+            (
+                "contentBlockStart",
+                json!({ "contentBlockIndex": 1, "start": { "toolUse": { "toolUseId": "tooluse_1", "name": "get_weather" } } }),
+            ),
+            (
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 1, "delta": { "toolUse": { "input": "{\"city\":\"Paris\"}" } } }),
+            ),
+            ("messageStop", json!({ "stopReason": "tool_use" })),
+        ];
+
+        let chunks: Vec<ChatCompletionChunk> = events
+            .iter()
+            .flat_map(|(event_type, payload)| {
+                translator
+                    .translate(EventStreamMessage {
+                        headers: vec![
+                            (":message-type".to_string(), "event".to_string()),
+                            (":event-type".to_string(), event_type.to_string()),
+                        ],
+                        payload: payload.to_string().into_bytes(),
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            tool_deltas(&chunks),
+            vec![
+                ToolCallDelta::start(0, "tooluse_1", "get_weather"),
+                ToolCallDelta::arguments(0, "{\"city\":\"Paris\"}"),
+            ]
+        );
+        assert_eq!(finish_reason(&chunks).as_deref(), Some("tool_calls"));
+    }
+}
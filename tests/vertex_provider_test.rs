@@ -0,0 +1,264 @@
+mod common;
+
+#[cfg(test)]
+mod vertex_provider_tests {
+    use super::common::spawn_mock;
+    use axum::extract::{Path, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use base64::Engine;
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    use ring::signature::{KeyPair, RSA_PKCS1_2048_8192_SHA256, RsaKeyPair, UnparsedPublicKey};
+    use serde_json::{Value, json};
+    use sorai::http::schemas::completions::ChatCompletionReq;
+    use sorai::providers::vertex::{ServiceAccountKey, VertexConfig, VertexProvider};
+    use sorai::providers::{Provider, ProviderError, http_client};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    const CREDENTIALS: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/vertex_service_account.json"
+    );
+
+    #[derive(Clone, Default)]
+    struct MockState {
+        token_calls: Arc<AtomicUsize>,
+        assertion: Arc<Mutex<Option<String>>>,
+        generate: Arc<Mutex<Option<(String, HeaderMap, Value)>>>,
+        response: Value,
+    }
+
+    async fn mock_vertex(state: MockState) -> String {
+        let router = Router::new()
+            .route(
+                "/token",
+                post(|State(state): State<MockState>, body: String| async move {
+                    state.token_calls.fetch_add(1, Ordering::SeqCst);
+                    let assertion = body
+                        .split('&')
+                        .find_map(|pair| pair.strip_prefix("assertion="))
+                        .map(str::to_string);
+                    assert!(body.contains("grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer"));
+                    *state.assertion.lock().unwrap() = assertion;
+                    Json(json!({ "access_token": "ya29.test-token", "expires_in": 3599, "token_type": "Bearer" }))
+                }),
+            )
+            .route(
+                "/projects/{project}/locations/{location}/publishers/google/models/{model}",
+                post(
+                    |State(state): State<MockState>,
+                     Path((project, location, model)): Path<(String, String, String)>,
+                     headers: HeaderMap,
+                     Json(body): Json<Value>| async move {
+                        *state.generate.lock().unwrap() =
+                            Some((format!("{}/{}/{}", project, location, model), headers, body));
+                        Json(state.response.clone())
+                    },
+                ),
+            )
+            .with_state(state);
+        spawn_mock(router).await
+    }
+
+    fn provider(base_url: &str) -> VertexProvider {
+        VertexProvider::new(
+            VertexConfig {
+                credentials: CREDENTIALS.to_string(),
+                base_url: base_url.to_string(),
+                token_url: format!("{}/token", base_url),
+                ..Default::default()
+            },
+            http_client(),
+        )
+    }
+
+    fn chat_request() -> ChatCompletionReq {
+        serde_json::from_value(json!({
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Hi" },
+                { "role": "assistant", "content": "Hello" },
+                { "role": "user", "content": "How are you?" }
+            ],
+            "params": { "max_tokens": 64, "top_k": 20 }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_request_maps_roles_and_generation_config() {
+        let request = chat_request();
+        let body = serde_json::to_value(VertexProvider::build_request(
+            &request.messages,
+            request.params.as_ref(),
+        ))
+        .unwrap();
+
+        assert_eq!(body["systemInstruction"], json!({ "parts": [{ "text": "Be brief." }] }));
+        assert_eq!(body["generationConfig"], json!({ "maxOutputTokens": 64, "topK": 20 }));
+        assert_eq!(
+            body["contents"],
+            json!([
+                { "role": "user", "parts": [{ "text": "Hi" }] },
+                { "role": "model", "parts": [{ "text": "Hello" }] },
+                { "role": "user", "parts": [{ "text": "How are you?" }] }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_mints_and_caches_token() {
+        let state = MockState {
+            response: json!({
+                "candidates": [{
+                    "content": { "role": "model", "parts": [{ "text": "I am " }, { "text": "fine." }] },
+                    "finishReason": "STOP",
+                    "safetyRatings": [{ "category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE" }]
+                }],
+                "usageMetadata": { "promptTokenCount": 9, "candidatesTokenCount": 4, "totalTokenCount": 13 },
+                "modelVersion": "gemini-2.0-flash-001"
+            }),
+            ..Default::default()
+        };
+        let base_url = mock_vertex(state.clone()).await;
+        let provider = provider(&base_url);
+
+        let response = provider
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap();
+        provider
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap();
+
+        assert_eq!(state.token_calls.load(Ordering::SeqCst), 1, "token must be cached");
+
+        let (path, headers, _) = state.generate.lock().unwrap().take().unwrap();
+        assert_eq!(path, "sorai-test/us-central1/gemini-2.0-flash:generateContent");
+        assert_eq!(headers["authorization"], "Bearer ya29.test-token");
+
+        assert_eq!(response.choices[0].message.content, "I am fine.");
+        assert_eq!(response.choices[0].finish_reason, "stop");
+        assert_eq!(response.model, "gemini-2.0-flash-001");
+
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 9);
+        assert_eq!(usage.completion_tokens, 4);
+        assert_eq!(usage.total_tokens, 13);
+
+        let extra = response.extra_fields.unwrap();
+        assert_eq!(extra.provider, "vertex");
+        assert_eq!(
+            extra.content_filter.unwrap()["safety_ratings"][0]["category"],
+            "HARM_CATEGORY_HARASSMENT"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_assertion_is_signed_by_service_account() {
+        let state = MockState {
+            response: json!({ "candidates": [] }),
+            ..Default::default()
+        };
+        let base_url = mock_vertex(state.clone()).await;
+        provider(&base_url)
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap();
+
+        let assertion = state.assertion.lock().unwrap().take().unwrap();
+        let (signing_input, signature) = assertion.rsplit_once('.').unwrap();
+        let claims: Value = serde_json::from_slice(
+            &URL_SAFE_NO_PAD
+                .decode(signing_input.split('.').nth(1).unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(claims["iss"], "sorai@sorai-test.iam.gserviceaccount.com");
+        assert_eq!(claims["aud"], format!("{}/token", base_url));
+        assert_eq!(claims["scope"], "https://www.googleapis.com/auth/cloud-platform");
+
+        let key = ServiceAccountKey::from_file(CREDENTIALS).unwrap();
+        let der: String = key.private_key.lines().filter(|l| !l.starts_with("-----")).collect();
+        let key_pair = RsaKeyPair::from_pkcs8(&STANDARD.decode(der).unwrap()).unwrap();
+        UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, key_pair.public_key().as_ref())
+            .verify(signing_input.as_bytes(), &URL_SAFE_NO_PAD.decode(signature).unwrap())
+            .expect("assertion signature must verify");
+    }
+
+    #[tokio::test]
+    async fn test_safety_block_maps_to_content_filter() {
+        let state = MockState {
+            response: json!({
+                "candidates": [{
+                    "content": { "role": "model", "parts": [] },
+                    "finishReason": "SAFETY",
+                    "safetyRatings": [{ "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true }]
+                }],
+                "usageMetadata": { "promptTokenCount": 5 }
+            }),
+            ..Default::default()
+        };
+        let base_url = mock_vertex(state).await;
+
+        let response = provider(&base_url)
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap();
+
+        assert_eq!(response.choices[0].finish_reason, "content_filter");
+        assert_eq!(response.choices[0].message.content, "");
+        assert_eq!(
+            response.extra_fields.unwrap().content_filter.unwrap()["safety_ratings"][0]["blocked"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blocked_prompt_maps_to_content_filter() {
+        let state = MockState {
+            response: json!({
+                "promptFeedback": { "blockReason": "SAFETY", "safetyRatings": [] },
+                "usageMetadata": { "promptTokenCount": 5, "totalTokenCount": 5 }
+            }),
+            ..Default::default()
+        };
+        let base_url = mock_vertex(state).await;
+
+        let response = provider(&base_url)
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap();
+
+        assert_eq!(response.choices[0].finish_reason, "content_filter");
+        assert_eq!(
+            response.extra_fields.unwrap().content_filter.unwrap()["prompt_feedback"]["blockReason"],
+            "SAFETY"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint_failure_is_authentication_error() {
+        let router = Router::new().route(
+            "/token",
+            post(|| async {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({ "error": "invalid_grant", "error_description": "Invalid JWT Signature." })),
+                )
+            }),
+        );
+        let base_url = spawn_mock(router).await;
+
+        let err = provider(&base_url)
+            .chat_completion("gemini-2.0-flash", &chat_request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::Authentication { .. }));
+        assert_eq!(err.status_code(), StatusCode::BAD_GATEWAY);
+    }
+}